@@ -12,11 +12,14 @@ pub mod types;
 // Re-exports
 pub use error::{Error, Result};
 pub use notifications::{
-    GotifyBackend, NotificationAction, NotificationBackend, NotificationManager,
-    NotificationMessage, NtfyBackend,
+    DiscordBackend, GotifyBackend, MatrixBackend, NotificationAction, NotificationBackend,
+    NotificationBackendConfig, NotificationBackendFactory, NotificationBackendRegistry,
+    NotificationManager, NotificationMessage, NtfyBackend, SlackBackend, SmtpBackend, SmtpTls,
+    WebhookBackend,
 };
 pub use plugin::{
-    Plugin, PluginContext, PluginInfo, PluginMetadata, PluginRegistry, PluginResult, ScheduledTask,
+    server_metric_key, split_server_metric_key, Plugin, PluginContext, PluginInfo, PluginMetadata,
+    PluginRegistry, PluginResult, ScheduledTask, SERVER_METRIC_SEPARATOR,
 };
 pub use remote::RemoteExecutor;
 pub use types::{MetricValue, Server, ServerStatus};
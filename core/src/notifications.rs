@@ -1,14 +1,36 @@
-//! Notification system with Gotify and ntfy.sh backends
+//! Notification system with Gotify, ntfy.sh, email, Slack, Discord, Matrix and
+//! generic webhook backends
+//!
+//! Backends are built from their stored configuration by a
+//! [`NotificationBackendRegistry`], which maps each backend type to a
+//! factory, and collected in a [`NotificationManager`] that sends every
+//! message to all of them.
 
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
 use std::collections::HashMap;
 use std::env;
+use std::fmt;
 use tracing::{debug, warn};
 
 use crate::{Error, Result};
 
+mod discord;
+#[cfg(test)]
+mod http_stub;
+mod matrix;
+mod slack;
+mod smtp;
+mod webhook;
+
+pub use discord::DiscordBackend;
+pub use matrix::MatrixBackend;
+pub use slack::SlackBackend;
+pub use smtp::{SmtpBackend, SmtpTls};
+pub use webhook::WebhookBackend;
+
 /// Notification message
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NotificationMessage {
@@ -28,6 +50,19 @@ fn default_priority() -> u8 {
     3
 }
 
+impl NotificationMessage {
+    /// Name of the message's priority, as ntfy uses them
+    pub fn priority_label(&self) -> &'static str {
+        match self.priority {
+            0 | 1 => "min",
+            2 => "low",
+            3 => "default",
+            4 => "high",
+            _ => "urgent",
+        }
+    }
+}
+
 /// Notification action button
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NotificationAction {
@@ -62,10 +97,119 @@ pub trait NotificationBackend: Send + Sync {
     /// Send a notification
     async fn send(&self, message: &NotificationMessage) -> Result<()>;
 
+    /// Send a notification on behalf of a service (plugin), for backends
+    /// that route services differently (per-service keys or topics)
+    async fn send_for_service(&self, _service: &str, message: &NotificationMessage) -> Result<()> {
+        self.send(message).await
+    }
+
     /// Backend name
     fn name(&self) -> &str;
 }
 
+/// Everything a [`NotificationBackendFactory`] gets to build a backend
+pub struct NotificationBackendConfig<'a> {
+    /// Name of the configured backend
+    pub name: &'a str,
+    /// Backend-specific JSON configuration
+    pub config: &'a JsonValue,
+    /// Shared HTTP client
+    pub client: &'a Client,
+    /// Services (plugins) that send notifications, for per-service settings
+    pub services: &'a [&'a str],
+}
+
+impl<'a> NotificationBackendConfig<'a> {
+    /// Get a non-empty string setting
+    pub fn get_str(&self, key: &str) -> Option<&'a str> {
+        self.config
+            .get(key)
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+    }
+
+    /// Get a required string setting
+    pub fn require_str(&self, key: &str) -> Result<&'a str> {
+        self.get_str(key).ok_or_else(|| {
+            Error::ConfigError(format!(
+                "Notification backend '{}' is missing '{}'",
+                self.name, key
+            ))
+        })
+    }
+}
+
+/// Builds a backend of one type from its configuration
+pub type NotificationBackendFactory =
+    fn(&NotificationBackendConfig<'_>) -> Result<Box<dyn NotificationBackend>>;
+
+/// Notification backend types, by the name stored with each configured
+/// backend
+pub struct NotificationBackendRegistry {
+    factories: HashMap<String, NotificationBackendFactory>,
+}
+
+impl NotificationBackendRegistry {
+    /// Create a registry with the built-in backend types
+    pub fn new() -> Self {
+        let mut registry = Self {
+            factories: HashMap::new(),
+        };
+        registry.register("gotify", GotifyBackend::from_config);
+        registry.register("ntfy", NtfyBackend::from_config);
+        registry.register("email", SmtpBackend::from_config);
+        registry.register("slack", SlackBackend::from_config);
+        registry.register("discord", DiscordBackend::from_config);
+        registry.register("matrix", MatrixBackend::from_config);
+        registry.register("webhook", WebhookBackend::from_config);
+        registry
+    }
+
+    /// Register a backend type, replacing any existing factory for it
+    pub fn register(
+        &mut self,
+        backend_type: impl Into<String>,
+        factory: NotificationBackendFactory,
+    ) {
+        self.factories.insert(backend_type.into(), factory);
+    }
+
+    /// Whether a backend type is registered
+    pub fn contains(&self, backend_type: &str) -> bool {
+        self.factories.contains_key(backend_type)
+    }
+
+    /// Registered backend types, sorted
+    pub fn types(&self) -> Vec<&str> {
+        let mut types: Vec<&str> = self.factories.keys().map(|t| t.as_str()).collect();
+        types.sort_unstable();
+        types
+    }
+
+    /// Build a backend of the given type
+    pub fn create(
+        &self,
+        backend_type: &str,
+        config: &NotificationBackendConfig<'_>,
+    ) -> Result<Box<dyn NotificationBackend>> {
+        let factory = self.factories.get(backend_type).ok_or_else(|| {
+            Error::ConfigError(format!(
+                "Unknown notification backend type '{}' (known types: {})",
+                backend_type,
+                self.types().join(", ")
+            ))
+        })?;
+        factory(config)
+    }
+}
+
+impl Default for NotificationBackendRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 // ============================================================================
 // Gotify Backend
 // ============================================================================
@@ -73,6 +217,7 @@ pub trait NotificationBackend: Send + Sync {
 /// Gotify notification backend
 #[derive(Debug, Clone)]
 pub struct GotifyBackend {
+    name: String,
     client: Client,
     base_url: String,
     /// Service-specific keys: service_name -> api_key
@@ -96,6 +241,7 @@ impl GotifyBackend {
         let fallback_key = Self::load_key_from_env("GOTIFY_KEY");
 
         Ok(Self {
+            name: "gotify".to_string(),
             client,
             base_url,
             keys: HashMap::new(),
@@ -105,8 +251,13 @@ impl GotifyBackend {
     }
 
     /// Create a new Gotify backend with explicit URL and key
-    pub fn with_url_and_key(client: Client, url: impl Into<String>, key: impl Into<String>) -> Result<Self> {
+    pub fn with_url_and_key(
+        client: Client,
+        url: impl Into<String>,
+        key: impl Into<String>,
+    ) -> Result<Self> {
         Ok(Self {
+            name: "gotify".to_string(),
             client,
             base_url: url.into(),
             keys: HashMap::new(),
@@ -115,6 +266,22 @@ impl GotifyBackend {
         })
     }
 
+    /// Build a Gotify backend from its `url` and `token` settings
+    ///
+    /// Service-specific keys are loaded from `<SERVICE>_GOTIFY_KEY`.
+    pub fn from_config(
+        config: &NotificationBackendConfig<'_>,
+    ) -> Result<Box<dyn NotificationBackend>> {
+        let mut backend = Self::with_url_and_key(
+            config.client.clone(),
+            config.require_str("url")?,
+            config.require_str("token")?,
+        )?;
+        backend.name = config.name.to_string();
+        backend.load_service_keys(config.services);
+        Ok(Box::new(backend))
+    }
+
     /// Register a service-specific key
     pub fn register_service(&mut self, service: impl Into<String>, key: impl Into<String>) {
         self.keys.insert(service.into(), key.into());
@@ -179,13 +346,16 @@ impl GotifyBackend {
             "***".to_string()
         }
     }
+}
 
-    /// Send notification for a specific service
-    pub async fn send_for_service(
-        &self,
-        service: &str,
-        message: &NotificationMessage,
-    ) -> Result<()> {
+#[async_trait]
+impl NotificationBackend for GotifyBackend {
+    async fn send(&self, message: &NotificationMessage) -> Result<()> {
+        // Use fallback key if available
+        self.send_for_service("default", message).await
+    }
+
+    async fn send_for_service(&self, service: &str, message: &NotificationMessage) -> Result<()> {
         let key = match self.get_key(service) {
             Some(k) => k,
             None => {
@@ -207,7 +377,7 @@ impl GotifyBackend {
 
         // Gotify requires /message endpoint
         let url = format!("{}/message", self.base_url.trim_end_matches('/'));
-        
+
         let response = self
             .client
             .post(&url)
@@ -227,17 +397,9 @@ impl GotifyBackend {
 
         Ok(())
     }
-}
-
-#[async_trait]
-impl NotificationBackend for GotifyBackend {
-    async fn send(&self, message: &NotificationMessage) -> Result<()> {
-        // Use fallback key if available
-        self.send_for_service("default", message).await
-    }
 
     fn name(&self) -> &str {
-        "gotify"
+        &self.name
     }
 }
 
@@ -248,6 +410,7 @@ impl NotificationBackend for GotifyBackend {
 /// ntfy.sh notification backend
 #[derive(Debug, Clone)]
 pub struct NtfyBackend {
+    name: String,
     client: Client,
     base_url: String,
     /// Service-specific topics: service_name -> topic
@@ -269,6 +432,7 @@ impl NtfyBackend {
             .unwrap_or(false);
 
         Ok(Self {
+            name: "ntfy".to_string(),
             client,
             base_url,
             topics: HashMap::new(),
@@ -278,11 +442,16 @@ impl NtfyBackend {
     }
 
     /// Create a new ntfy backend with explicit URL and default topic
-    pub fn with_url_and_topic(client: Client, url: impl Into<String>, topic: impl Into<String>) -> Result<Self> {
+    pub fn with_url_and_topic(
+        client: Client,
+        url: impl Into<String>,
+        topic: impl Into<String>,
+    ) -> Result<Self> {
         let mut topics = HashMap::new();
         topics.insert("default".to_string(), topic.into());
-        
+
         Ok(Self {
+            name: "ntfy".to_string(),
             client,
             base_url: url.into(),
             topics,
@@ -291,6 +460,24 @@ impl NtfyBackend {
         })
     }
 
+    /// Build an ntfy backend from its `url`, `topic` and optional `token`
+    /// settings
+    ///
+    /// Service-specific topics are loaded from `<SERVICE>_NTFY_TOPIC`.
+    pub fn from_config(
+        config: &NotificationBackendConfig<'_>,
+    ) -> Result<Box<dyn NotificationBackend>> {
+        let mut backend = Self::with_url_and_topic(
+            config.client.clone(),
+            config.require_str("url")?,
+            config.require_str("topic")?,
+        )?;
+        backend.name = config.name.to_string();
+        backend.auth_token = config.get_str("token").map(str::to_string);
+        backend.load_service_topics(config.services);
+        Ok(Box::new(backend))
+    }
+
     /// Register a service-specific topic
     pub fn register_service(&mut self, service: impl Into<String>, topic: impl Into<String>) {
         self.topics.insert(service.into(), topic.into());
@@ -313,13 +500,16 @@ impl NtfyBackend {
     fn get_topic(&self, service: &str) -> Option<&str> {
         self.topics.get(service).map(|s| s.as_str())
     }
+}
 
-    /// Send notification for a specific service
-    pub async fn send_for_service(
-        &self,
-        service: &str,
-        message: &NotificationMessage,
-    ) -> Result<()> {
+#[async_trait]
+impl NotificationBackend for NtfyBackend {
+    async fn send(&self, message: &NotificationMessage) -> Result<()> {
+        // Use the default topic if there is one
+        self.send_for_service("default", message).await
+    }
+
+    async fn send_for_service(&self, service: &str, message: &NotificationMessage) -> Result<()> {
         let topic = match self.get_topic(service) {
             Some(t) => t,
             None => {
@@ -348,10 +538,24 @@ impl NtfyBackend {
             "markdown": true,
         });
 
-        // Add actions if provided
+        // Add actions if provided; ntfy needs each action's type
         if !message.actions.is_empty() {
-            json_body["actions"] =
-                serde_json::to_value(&message.actions).map_err(Error::SerializationError)?;
+            let actions: Vec<serde_json::Value> = message
+                .actions
+                .iter()
+                .map(|action| {
+                    let mut value = serde_json::json!({
+                        "action": if action.method.is_some() { "http" } else { "view" },
+                        "label": action.label,
+                        "url": action.url,
+                    });
+                    if let Some(method) = &action.method {
+                        value["method"] = serde_json::Value::String(method.clone());
+                    }
+                    value
+                })
+                .collect();
+            json_body["actions"] = serde_json::Value::Array(actions);
         }
 
         if self.debug {
@@ -382,59 +586,79 @@ impl NtfyBackend {
 
         Ok(())
     }
-}
 
-#[async_trait]
-impl NotificationBackend for NtfyBackend {
-    async fn send(&self, _message: &NotificationMessage) -> Result<()> {
-        // ntfy requires a topic, so we can't send without one
-        warn!("ntfy backend called without service context; skipping");
-        Ok(())
+    fn name(&self) -> &str {
+        &self.name
     }
+}
 
-    fn name(&self) -> &str {
-        "ntfy"
+/// Cut text to at most `max_chars` characters, ending with an ellipsis if it
+/// was cut
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
     }
+    let mut cut: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    cut.push('…');
+    cut
+}
+
+/// Escape text for HTML message bodies
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
 }
 
 // ============================================================================
 // Notification Manager
 // ============================================================================
 
-/// Manages multiple notification backends
-#[derive(Debug, Clone)]
+/// Sends notifications to every configured backend
+///
+/// Backends are tried in the order they were added, so add them highest
+/// priority first.
+#[derive(Default)]
 pub struct NotificationManager {
-    gotify: Option<GotifyBackend>,
-    ntfy: Option<NtfyBackend>,
+    backends: Vec<Box<dyn NotificationBackend>>,
 }
 
 impl NotificationManager {
     /// Create a new notification manager with auto-detected backends from environment
     pub fn new(client: Client, services: &[&str]) -> Result<Self> {
-        let mut gotify = None;
-        let mut ntfy = None;
+        let mut manager = Self::default();
 
         // Try to initialize Gotify
         if let Ok(mut backend) = GotifyBackend::new(client.clone()) {
             backend.load_service_keys(services);
-            gotify = Some(backend);
+            manager.add(Box::new(backend));
         }
 
         // Try to initialize ntfy
         if let Ok(mut backend) = NtfyBackend::new(client.clone()) {
             backend.load_service_topics(services);
-            ntfy = Some(backend);
+            manager.add(Box::new(backend));
         }
 
-        Ok(Self { gotify, ntfy })
+        Ok(manager)
     }
 
     /// Create a new notification manager from pre-configured backends
-    pub fn from_backends(gotify: Option<GotifyBackend>, ntfy: Option<NtfyBackend>) -> Self {
-        Self { gotify, ntfy }
+    pub fn from_backends(backends: Vec<Box<dyn NotificationBackend>>) -> Self {
+        Self { backends }
+    }
+
+    /// Add a backend after the existing ones
+    pub fn add(&mut self, backend: Box<dyn NotificationBackend>) {
+        self.backends.push(backend);
     }
 
     /// Send notification via all configured backends for a service
+    ///
+    /// A failing backend doesn't stop the others; all failures are returned
+    /// together.
     pub async fn send_for_service(
         &self,
         service: &str,
@@ -442,19 +666,10 @@ impl NotificationManager {
     ) -> Result<()> {
         let mut errors = Vec::new();
 
-        // Send to Gotify
-        if let Some(backend) = &self.gotify {
-            if let Err(e) = backend.send_for_service(service, message).await {
-                warn!(service = %service, backend = "gotify", error = %e, "Notification failed");
-                errors.push(format!("Gotify: {}", e));
-            }
-        }
-
-        // Send to ntfy
-        if let Some(backend) = &self.ntfy {
+        for backend in &self.backends {
             if let Err(e) = backend.send_for_service(service, message).await {
-                warn!(service = %service, backend = "ntfy", error = %e, "Notification failed");
-                errors.push(format!("ntfy: {}", e));
+                warn!(service = %service, backend = backend.name(), error = %e, "Notification failed");
+                errors.push(format!("{}: {}", backend.name(), e));
             }
         }
 
@@ -465,13 +680,141 @@ impl NotificationManager {
         Ok(())
     }
 
-    /// Get Gotify backend reference
-    pub fn gotify(&self) -> Option<&GotifyBackend> {
-        self.gotify.as_ref()
+    /// Configured backends, in sending order
+    pub fn backends(&self) -> impl Iterator<Item = &dyn NotificationBackend> {
+        self.backends.iter().map(|b| b.as_ref())
     }
 
-    /// Get ntfy backend reference
-    pub fn ntfy(&self) -> Option<&NtfyBackend> {
-        self.ntfy.as_ref()
+    /// Whether no backends are configured
+    pub fn is_empty(&self) -> bool {
+        self.backends.is_empty()
+    }
+}
+
+impl fmt::Debug for NotificationManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.backends.iter().map(|b| b.name()))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Records which backends were sent to, in order
+    struct RecordingBackend {
+        name: String,
+        sent: Arc<Mutex<Vec<String>>>,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl NotificationBackend for RecordingBackend {
+        async fn send(&self, _message: &NotificationMessage) -> Result<()> {
+            self.sent.lock().unwrap().push(self.name.clone());
+            if self.fail {
+                return Err(Error::NotificationError("unreachable".to_string()));
+            }
+            Ok(())
+        }
+
+        fn name(&self) -> &str {
+            &self.name
+        }
+    }
+
+    fn message() -> NotificationMessage {
+        NotificationMessage {
+            title: "Test".to_string(),
+            body: "Body".to_string(),
+            priority: 3,
+            actions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_registry_builds_registered_types() {
+        let client = Client::new();
+        let config = serde_json::json!({"url": "http://gotify.local", "token": "abc"});
+        let backend_config = NotificationBackendConfig {
+            name: "ops",
+            config: &config,
+            client: &client,
+            services: &[],
+        };
+
+        let registry = NotificationBackendRegistry::new();
+        assert_eq!(
+            registry.types(),
+            vec!["discord", "email", "gotify", "matrix", "ntfy", "slack", "webhook"]
+        );
+        assert_eq!(
+            registry.create("gotify", &backend_config).unwrap().name(),
+            "ops"
+        );
+
+        // ntfy needs a topic, and unknown types are rejected
+        assert!(registry.create("ntfy", &backend_config).is_err());
+        assert!(registry.create("pager", &backend_config).is_err());
+    }
+
+    #[test]
+    fn test_registry_accepts_new_types() {
+        fn recording(
+            config: &NotificationBackendConfig<'_>,
+        ) -> Result<Box<dyn NotificationBackend>> {
+            Ok(Box::new(RecordingBackend {
+                name: config.name.to_string(),
+                sent: Arc::default(),
+                fail: false,
+            }))
+        }
+
+        let client = Client::new();
+        let config = serde_json::json!({});
+        let mut registry = NotificationBackendRegistry::new();
+        registry.register("recording", recording);
+
+        assert!(registry.contains("recording"));
+        let backend = registry
+            .create(
+                "recording",
+                &NotificationBackendConfig {
+                    name: "rec",
+                    config: &config,
+                    client: &client,
+                    services: &[],
+                },
+            )
+            .unwrap();
+        assert_eq!(backend.name(), "rec");
+    }
+
+    #[tokio::test]
+    async fn test_manager_sends_to_every_backend_in_order() {
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let backend = |name: &str, fail: bool| -> Box<dyn NotificationBackend> {
+            Box::new(RecordingBackend {
+                name: name.to_string(),
+                sent: sent.clone(),
+                fail,
+            })
+        };
+        let manager = NotificationManager::from_backends(vec![
+            backend("first", false),
+            backend("broken", true),
+            backend("second", false),
+        ]);
+
+        // A failing backend is reported but doesn't stop the rest
+        let err = manager
+            .send_for_service("docker", &message())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("broken: "));
+        assert_eq!(*sent.lock().unwrap(), vec!["first", "broken", "second"]);
     }
 }
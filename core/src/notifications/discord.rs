@@ -0,0 +1,186 @@
+//! Discord notification backend
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::{json, Value as JsonValue};
+use tracing::debug;
+
+use super::{truncate, NotificationBackend, NotificationBackendConfig, NotificationMessage};
+use crate::{Error, Result};
+
+/// Longest embed title Discord accepts
+const TITLE_MAX_CHARS: usize = 256;
+/// Longest embed description Discord accepts
+const DESCRIPTION_MAX_CHARS: usize = 4096;
+
+/// Discord notification backend
+///
+/// Posts each notification to a channel webhook as an embed, coloured by
+/// priority.
+#[derive(Debug, Clone)]
+pub struct DiscordBackend {
+    name: String,
+    client: Client,
+    webhook_url: String,
+    /// Overrides the webhook's own display name
+    username: Option<String>,
+}
+
+impl DiscordBackend {
+    /// Build a Discord backend from its `url` (channel webhook URL) and
+    /// optional `username` settings
+    pub fn from_config(
+        config: &NotificationBackendConfig<'_>,
+    ) -> Result<Box<dyn NotificationBackend>> {
+        Ok(Box::new(Self {
+            name: config.name.to_string(),
+            client: config.client.clone(),
+            webhook_url: config.require_str("url")?.to_string(),
+            username: config.get_str("username").map(str::to_string),
+        }))
+    }
+
+    /// Webhook message with one embed
+    fn payload(&self, message: &NotificationMessage) -> JsonValue {
+        let mut description = message.body.clone();
+        if !message.actions.is_empty() {
+            description.push_str("\n\n");
+            for action in &message.actions {
+                match &action.method {
+                    Some(method) => description
+                        .push_str(&format!("{}: `{} {}`\n", action.label, method, action.url)),
+                    None => description.push_str(&format!("[{}]({})\n", action.label, action.url)),
+                }
+            }
+        }
+
+        let mut payload = json!({
+            "embeds": [{
+                "title": truncate(&message.title, TITLE_MAX_CHARS),
+                "description": truncate(description.trim(), DESCRIPTION_MAX_CHARS),
+                "color": priority_color(message.priority),
+                "footer": { "text": format!("Priority: {}", message.priority_label()) },
+            }],
+            // Never ping @everyone or roles mentioned in a message
+            "allowed_mentions": { "parse": [] },
+        });
+        if let Some(username) = &self.username {
+            payload["username"] = JsonValue::String(username.clone());
+        }
+        payload
+    }
+}
+
+#[async_trait]
+impl NotificationBackend for DiscordBackend {
+    async fn send(&self, message: &NotificationMessage) -> Result<()> {
+        debug!(backend = %self.name, title = %message.title, "Sending Discord notification");
+
+        let response = self
+            .client
+            .post(&self.webhook_url)
+            .json(&self.payload(message))
+            .send()
+            .await
+            .map_err(|e| Error::HttpError(format!("Discord request failed: {}", e)))?;
+
+        response
+            .error_for_status()
+            .map_err(|e| Error::NotificationError(format!("Discord error: {}", e)))?;
+
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Embed colour: red for urgent, orange for high, blue for default and grey
+/// below that
+fn priority_color(priority: u8) -> u32 {
+    match priority {
+        0..=2 => 0x95A5A6,
+        3 => 0x3498DB,
+        4 => 0xE67E22,
+        _ => 0xE74C3C,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notifications::http_stub::HttpStub;
+    use crate::NotificationAction;
+
+    fn backend(config: JsonValue) -> Box<dyn NotificationBackend> {
+        let client = Client::new();
+        DiscordBackend::from_config(&NotificationBackendConfig {
+            name: "team-chat",
+            config: &config,
+            client: &client,
+            services: &[],
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_send_embed() {
+        let stub = HttpStub::start(204).await;
+        let backend = backend(json!({
+            "url": format!("{}/api/webhooks/1/abc", stub.url),
+            "username": "SvrCtlRS",
+        }));
+
+        backend
+            .send(&NotificationMessage {
+                title: "Disk almost full".to_string(),
+                body: "web1: / at 95%".to_string(),
+                priority: 5,
+                actions: vec![
+                    NotificationAction::view("Open", "https://svrctlrs.local/servers/1"),
+                    NotificationAction::http_post("Clean up", "https://svrctlrs.local/cleanup"),
+                ],
+            })
+            .await
+            .unwrap();
+
+        let requests = stub.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].path, "/api/webhooks/1/abc");
+
+        let body = requests[0].json();
+        assert_eq!(body["username"], "SvrCtlRS");
+        assert_eq!(body["allowed_mentions"]["parse"], json!([]));
+        let embed = &body["embeds"][0];
+        assert_eq!(embed["title"], "Disk almost full");
+        assert_eq!(embed["color"], 0xE74C3C);
+        assert_eq!(embed["footer"]["text"], "Priority: urgent");
+        assert_eq!(
+            embed["description"],
+            "web1: / at 95%\n\n[Open](https://svrctlrs.local/servers/1)\nClean up: `POST https://svrctlrs.local/cleanup`"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_reports_http_errors() {
+        let stub = HttpStub::start(401).await;
+        let backend = backend(json!({ "url": stub.url }));
+
+        let message = NotificationMessage {
+            title: "Test".to_string(),
+            body: "Body".to_string(),
+            priority: 3,
+            actions: Vec::new(),
+        };
+        let err = backend.send(&message).await.unwrap_err();
+        assert!(err.to_string().contains("Discord error"));
+    }
+
+    #[test]
+    fn test_priority_colors() {
+        assert_eq!(priority_color(1), priority_color(2));
+        assert_ne!(priority_color(3), priority_color(4));
+        assert_eq!(priority_color(5), 0xE74C3C);
+    }
+}
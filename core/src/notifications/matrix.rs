@@ -0,0 +1,271 @@
+//! Matrix notification backend
+
+use async_trait::async_trait;
+use reqwest::{Client, Url};
+use serde_json::{json, Value as JsonValue};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::debug;
+
+use super::{escape_html, NotificationBackend, NotificationBackendConfig, NotificationMessage};
+use crate::{Error, Result};
+
+/// Matrix notification backend
+///
+/// Sends each notification to a room as a message with a plaintext and an
+/// HTML body, using the Client-Server API with a (bot) user's access token.
+#[derive(Debug)]
+pub struct MatrixBackend {
+    name: String,
+    client: Client,
+    homeserver: Url,
+    access_token: String,
+    room_id: String,
+    /// Makes transaction IDs unique within this process
+    txn_counter: AtomicU64,
+}
+
+impl MatrixBackend {
+    /// Build a Matrix backend from its `url` (homeserver), `token` (access
+    /// token) and `room_id` settings
+    pub fn from_config(
+        config: &NotificationBackendConfig<'_>,
+    ) -> Result<Box<dyn NotificationBackend>> {
+        let url = config.require_str("url")?;
+        let homeserver = Url::parse(url)
+            .ok()
+            .filter(|u| !u.cannot_be_a_base())
+            .ok_or_else(|| {
+                Error::ConfigError(format!(
+                    "Invalid homeserver URL '{}' for '{}'",
+                    url, config.name
+                ))
+            })?;
+
+        let room_id = config.require_str("room_id")?;
+        if !room_id.starts_with('!') || !room_id.contains(':') {
+            return Err(Error::ConfigError(format!(
+                "Invalid room ID '{}' for '{}'. Use the room's internal ID, like !abc123:example.org.",
+                room_id, config.name
+            )));
+        }
+
+        Ok(Box::new(Self {
+            name: config.name.to_string(),
+            client: config.client.clone(),
+            homeserver,
+            access_token: config.require_str("token")?.to_string(),
+            room_id: room_id.to_string(),
+            txn_counter: AtomicU64::new(0),
+        }))
+    }
+
+    /// URL to send a message event with a new transaction ID
+    ///
+    /// Matrix deduplicates sends by transaction ID, so each message needs
+    /// its own.
+    fn send_url(&self) -> Url {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default();
+        let txn_id = format!(
+            "svrctlrs-{}-{}",
+            nanos,
+            self.txn_counter.fetch_add(1, Ordering::Relaxed)
+        );
+
+        let mut url = self.homeserver.clone();
+        url.path_segments_mut()
+            .expect("homeserver URL can be a base")
+            .pop_if_empty()
+            .extend([
+                "_matrix",
+                "client",
+                "v3",
+                "rooms",
+                &self.room_id,
+                "send",
+                "m.room.message",
+                &txn_id,
+            ]);
+        url
+    }
+}
+
+#[async_trait]
+impl NotificationBackend for MatrixBackend {
+    async fn send(&self, message: &NotificationMessage) -> Result<()> {
+        debug!(backend = %self.name, room = %self.room_id, title = %message.title, "Sending Matrix notification");
+
+        let response = self
+            .client
+            .put(self.send_url())
+            .bearer_auth(&self.access_token)
+            .json(&content(message))
+            .send()
+            .await
+            .map_err(|e| Error::HttpError(format!("Matrix request failed: {}", e)))?;
+
+        response
+            .error_for_status()
+            .map_err(|e| Error::NotificationError(format!("Matrix error: {}", e)))?;
+
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// `m.room.message` content
+///
+/// High and urgent messages are sent as `m.text` so clients notify about
+/// them; the rest are `m.notice`, which clients treat as bot chatter.
+fn content(message: &NotificationMessage) -> JsonValue {
+    let mut plain = format!("{}\n\n{}", message.title, message.body);
+    let mut html = format!(
+        "<strong>{}</strong><br>{}",
+        escape_html(&message.title),
+        escape_html(&message.body).replace('\n', "<br>")
+    );
+
+    if !message.actions.is_empty() {
+        plain.push('\n');
+        html.push_str("<ul>");
+        for action in &message.actions {
+            match &action.method {
+                Some(method) => {
+                    plain.push_str(&format!("\n{}: {} {}", action.label, method, action.url));
+                    html.push_str(&format!(
+                        "<li>{}: <code>{} {}</code></li>",
+                        escape_html(&action.label),
+                        escape_html(method),
+                        escape_html(&action.url)
+                    ));
+                }
+                None => {
+                    plain.push_str(&format!("\n{}: {}", action.label, action.url));
+                    html.push_str(&format!(
+                        "<li><a href=\"{}\">{}</a></li>",
+                        escape_html(&action.url),
+                        escape_html(&action.label)
+                    ));
+                }
+            }
+        }
+        html.push_str("</ul>");
+    }
+
+    json!({
+        "msgtype": if message.priority >= 4 { "m.text" } else { "m.notice" },
+        "body": plain,
+        "format": "org.matrix.custom.html",
+        "formatted_body": html,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notifications::http_stub::HttpStub;
+    use crate::NotificationAction;
+
+    fn backend(config: JsonValue) -> Result<Box<dyn NotificationBackend>> {
+        let client = Client::new();
+        MatrixBackend::from_config(&NotificationBackendConfig {
+            name: "ops-room",
+            config: &config,
+            client: &client,
+            services: &[],
+        })
+    }
+
+    #[tokio::test]
+    async fn test_send_room_message() {
+        let stub = HttpStub::start(200).await;
+        let backend = backend(json!({
+            "url": stub.url,
+            "token": "syt_secret",
+            "room_id": "!ops:example.org",
+        }))
+        .unwrap();
+
+        let message = NotificationMessage {
+            title: "Backup failed".to_string(),
+            body: "db1: exit 1 <stderr>".to_string(),
+            priority: 5,
+            actions: vec![NotificationAction::view(
+                "Logs",
+                "https://svrctlrs.local/tasks",
+            )],
+        };
+        backend.send(&message).await.unwrap();
+        backend.send(&message).await.unwrap();
+
+        let requests = stub.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].method, "PUT");
+        assert!(requests[0]
+            .path
+            .starts_with("/_matrix/client/v3/rooms/!ops:example.org/send/m.room.message/"));
+        assert_ne!(requests[0].path, requests[1].path);
+        assert_eq!(
+            requests[0].header("authorization"),
+            Some("Bearer syt_secret")
+        );
+
+        let body = requests[0].json();
+        assert_eq!(body["msgtype"], "m.text");
+        assert_eq!(
+            body["body"],
+            "Backup failed\n\ndb1: exit 1 <stderr>\n\nLogs: https://svrctlrs.local/tasks"
+        );
+        assert_eq!(body["format"], "org.matrix.custom.html");
+        assert_eq!(
+            body["formatted_body"],
+            "<strong>Backup failed</strong><br>db1: exit 1 &lt;stderr&gt;<ul><li><a href=\"https://svrctlrs.local/tasks\">Logs</a></li></ul>"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_reports_http_errors() {
+        let stub = HttpStub::start(403).await;
+        let backend = backend(json!({
+            "url": stub.url,
+            "token": "syt_secret",
+            "room_id": "!ops:example.org",
+        }))
+        .unwrap();
+
+        let message = NotificationMessage {
+            title: "Test".to_string(),
+            body: "Body".to_string(),
+            priority: 3,
+            actions: Vec::new(),
+        };
+        let err = backend.send(&message).await.unwrap_err();
+        assert!(err.to_string().contains("Matrix error"));
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let valid = json!({
+            "url": "https://matrix.example.org",
+            "token": "syt_secret",
+            "room_id": "!ops:example.org",
+        });
+        assert!(backend(valid.clone()).is_ok());
+
+        for (key, value) in [
+            ("url", json!("matrix.example.org")),
+            ("token", JsonValue::Null),
+            ("room_id", json!("#ops:example.org")),
+        ] {
+            let mut config = valid.clone();
+            config[key] = value;
+            assert!(backend(config).is_err(), "{} should be rejected", key);
+        }
+    }
+}
@@ -0,0 +1,227 @@
+//! Slack notification backend
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::{json, Value as JsonValue};
+use tracing::debug;
+
+use super::{truncate, NotificationBackend, NotificationBackendConfig, NotificationMessage};
+use crate::{Error, Result};
+
+/// Longest header block text Slack accepts
+const HEADER_MAX_CHARS: usize = 150;
+/// Longest section block text Slack accepts
+const SECTION_MAX_CHARS: usize = 3000;
+/// Longest button label Slack accepts
+const BUTTON_MAX_CHARS: usize = 75;
+/// Most elements an actions block can hold
+const MAX_BUTTONS: usize = 25;
+
+/// Slack notification backend
+///
+/// Posts each notification to an incoming webhook as Block Kit blocks.
+#[derive(Debug, Clone)]
+pub struct SlackBackend {
+    name: String,
+    client: Client,
+    webhook_url: String,
+}
+
+impl SlackBackend {
+    /// Build a Slack backend from its `url` (incoming webhook URL) setting
+    pub fn from_config(
+        config: &NotificationBackendConfig<'_>,
+    ) -> Result<Box<dyn NotificationBackend>> {
+        Ok(Box::new(Self {
+            name: config.name.to_string(),
+            client: config.client.clone(),
+            webhook_url: config.require_str("url")?.to_string(),
+        }))
+    }
+}
+
+#[async_trait]
+impl NotificationBackend for SlackBackend {
+    async fn send(&self, message: &NotificationMessage) -> Result<()> {
+        debug!(backend = %self.name, title = %message.title, "Sending Slack notification");
+
+        let response = self
+            .client
+            .post(&self.webhook_url)
+            .json(&payload(message))
+            .send()
+            .await
+            .map_err(|e| Error::HttpError(format!("Slack request failed: {}", e)))?;
+
+        response
+            .error_for_status()
+            .map_err(|e| Error::NotificationError(format!("Slack error: {}", e)))?;
+
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Block Kit message: a header with the title, the body as a section, the
+/// priority as context, and view actions as link buttons
+///
+/// Incoming webhooks can't receive button clicks, so HTTP actions are listed
+/// in the context instead.
+fn payload(message: &NotificationMessage) -> JsonValue {
+    let mut blocks = vec![json!({
+        "type": "header",
+        "text": {
+            "type": "plain_text",
+            "text": truncate(&message.title, HEADER_MAX_CHARS),
+        },
+    })];
+
+    if !message.body.trim().is_empty() {
+        blocks.push(json!({
+            "type": "section",
+            "text": {
+                "type": "mrkdwn",
+                "text": truncate(&escape_mrkdwn(&message.body), SECTION_MAX_CHARS),
+            },
+        }));
+    }
+
+    let mut context = vec![json!({
+        "type": "mrkdwn",
+        "text": format!("Priority: *{}*", message.priority_label()),
+    })];
+    let mut buttons = Vec::new();
+    for action in &message.actions {
+        match &action.method {
+            Some(method) => context.push(json!({
+                "type": "mrkdwn",
+                "text": format!(
+                    "{}: `{} {}`",
+                    escape_mrkdwn(&action.label),
+                    method,
+                    action.url
+                ),
+            })),
+            None => buttons.push(json!({
+                "type": "button",
+                "text": {
+                    "type": "plain_text",
+                    "text": truncate(&action.label, BUTTON_MAX_CHARS),
+                },
+                "url": action.url,
+            })),
+        }
+    }
+    blocks.push(json!({ "type": "context", "elements": context }));
+
+    if !buttons.is_empty() {
+        buttons.truncate(MAX_BUTTONS);
+        blocks.push(json!({ "type": "actions", "elements": buttons }));
+    }
+
+    json!({
+        // Shown in push notifications and clients without Block Kit
+        "text": message.title,
+        "blocks": blocks,
+    })
+}
+
+/// Escape the characters Slack treats as markup in mrkdwn text
+fn escape_mrkdwn(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notifications::http_stub::HttpStub;
+    use crate::NotificationAction;
+
+    fn backend(url: &str) -> Box<dyn NotificationBackend> {
+        let client = Client::new();
+        let config = json!({ "url": url });
+        SlackBackend::from_config(&NotificationBackendConfig {
+            name: "team-chat",
+            config: &config,
+            client: &client,
+            services: &[],
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_send_block_kit_message() {
+        let stub = HttpStub::start(200).await;
+        let backend = backend(&format!("{}/services/T000/B000/XXX", stub.url));
+
+        backend
+            .send(&NotificationMessage {
+                title: "Updates available".to_string(),
+                body: "web1: 3 updates <security>".to_string(),
+                priority: 4,
+                actions: vec![
+                    NotificationAction::view("Open", "https://svrctlrs.local/servers"),
+                    NotificationAction::http_post("Approve", "https://svrctlrs.local/approve"),
+                ],
+            })
+            .await
+            .unwrap();
+
+        let requests = stub.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/services/T000/B000/XXX");
+
+        let body = requests[0].json();
+        assert_eq!(body["text"], "Updates available");
+        let blocks = body["blocks"].as_array().unwrap();
+        assert_eq!(blocks[0]["type"], "header");
+        assert_eq!(blocks[0]["text"]["text"], "Updates available");
+        assert_eq!(
+            blocks[1]["text"]["text"],
+            "web1: 3 updates &lt;security&gt;"
+        );
+        assert_eq!(blocks[2]["elements"][0]["text"], "Priority: *high*");
+        assert_eq!(
+            blocks[2]["elements"][1]["text"],
+            "Approve: `POST https://svrctlrs.local/approve`"
+        );
+        assert_eq!(blocks[3]["type"], "actions");
+        assert_eq!(
+            blocks[3]["elements"][0]["url"],
+            "https://svrctlrs.local/servers"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_reports_http_errors() {
+        let stub = HttpStub::start(404).await;
+        let backend = backend(&stub.url);
+
+        let message = NotificationMessage {
+            title: "Test".to_string(),
+            body: String::new(),
+            priority: 3,
+            actions: Vec::new(),
+        };
+        let err = backend.send(&message).await.unwrap_err();
+        assert!(err.to_string().contains("Slack error"));
+    }
+
+    #[test]
+    fn test_long_titles_are_truncated() {
+        let message = NotificationMessage {
+            title: "x".repeat(200),
+            body: "Body".to_string(),
+            priority: 3,
+            actions: Vec::new(),
+        };
+        let header = &payload(&message)["blocks"][0]["text"]["text"];
+        assert_eq!(header.as_str().unwrap().chars().count(), HEADER_MAX_CHARS);
+    }
+}
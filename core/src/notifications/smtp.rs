@@ -0,0 +1,440 @@
+//! Email (SMTP) notification backend
+
+use async_trait::async_trait;
+use lettre::{
+    message::{Mailbox, MultiPart},
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use serde_json::Value as JsonValue;
+use std::time::Duration;
+use tracing::debug;
+
+use super::{escape_html, NotificationBackend, NotificationBackendConfig, NotificationMessage};
+use crate::{Error, Result};
+
+/// How long to wait for the SMTP server before giving up
+const SMTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How the connection to the SMTP server is encrypted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpTls {
+    /// Plain connection upgraded with `STARTTLS` (port 587)
+    StartTls,
+    /// TLS from the start (port 465)
+    Implicit,
+    /// No encryption, for local relays (port 25)
+    None,
+}
+
+impl SmtpTls {
+    /// Parse a TLS mode from its name
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim() {
+            "starttls" => Some(Self::StartTls),
+            "tls" => Some(Self::Implicit),
+            "none" => Some(Self::None),
+            _ => None,
+        }
+    }
+
+    /// Port used when none is configured
+    pub fn default_port(&self) -> u16 {
+        match self {
+            Self::StartTls => 587,
+            Self::Implicit => 465,
+            Self::None => 25,
+        }
+    }
+}
+
+/// Email notification backend
+///
+/// Sends each notification as a plaintext + HTML email to every recipient.
+#[derive(Clone)]
+pub struct SmtpBackend {
+    name: String,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    to: Vec<Mailbox>,
+}
+
+impl SmtpBackend {
+    /// Build an email backend from its settings
+    ///
+    /// `host` (required), `port`, `tls` (`starttls` (default), `tls` or
+    /// `none`), `username` and `password` (optional, for SMTP AUTH), `from`
+    /// (required), and `to` (required; a list or a comma-separated string).
+    pub fn from_config(
+        config: &NotificationBackendConfig<'_>,
+    ) -> Result<Box<dyn NotificationBackend>> {
+        let host = config.require_str("host")?;
+        let tls = match config.get_str("tls") {
+            Some(tls) => SmtpTls::parse(tls).ok_or_else(|| {
+                Error::ConfigError(format!(
+                    "Invalid TLS mode '{}' for '{}'. Use starttls, tls or none.",
+                    tls, config.name
+                ))
+            })?,
+            None => SmtpTls::StartTls,
+        };
+        let port = match config.config.get("port") {
+            None | Some(JsonValue::Null) => tls.default_port(),
+            Some(JsonValue::String(s)) if s.trim().is_empty() => tls.default_port(),
+            Some(JsonValue::Number(n)) => n
+                .as_u64()
+                .and_then(|p| u16::try_from(p).ok())
+                .ok_or_else(|| invalid_port(config))?,
+            Some(JsonValue::String(s)) => s.trim().parse().map_err(|_| invalid_port(config))?,
+            Some(_) => return Err(invalid_port(config)),
+        };
+
+        let from = parse_mailbox(config.require_str("from")?)?;
+        let to = recipients(config.config.get("to"))?;
+        if to.is_empty() {
+            return Err(Error::ConfigError(format!(
+                "Notification backend '{}' is missing 'to'",
+                config.name
+            )));
+        }
+
+        let mut builder = match tls {
+            SmtpTls::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host),
+            SmtpTls::Implicit => AsyncSmtpTransport::<Tokio1Executor>::relay(host),
+            SmtpTls::None => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(
+                host,
+            )),
+        }
+        .map_err(|e| Error::ConfigError(format!("Invalid SMTP host '{}': {}", host, e)))?
+        .port(port)
+        .timeout(Some(SMTP_TIMEOUT));
+
+        if let Some(username) = config.get_str("username") {
+            let password = config.get_str("password").unwrap_or_default();
+            builder =
+                builder.credentials(Credentials::new(username.to_string(), password.to_string()));
+        }
+
+        Ok(Box::new(Self {
+            name: config.name.to_string(),
+            transport: builder.build(),
+            from,
+            to,
+        }))
+    }
+
+    /// Build the email for a notification
+    fn email(&self, message: &NotificationMessage) -> Result<Message> {
+        let mut builder = Message::builder()
+            .from(self.from.clone())
+            .subject(&message.title);
+        for to in &self.to {
+            builder = builder.to(to.clone());
+        }
+        builder
+            .multipart(MultiPart::alternative_plain_html(
+                render_plain(message),
+                render_html(message),
+            ))
+            .map_err(|e| Error::NotificationError(format!("Failed to build email: {}", e)))
+    }
+}
+
+impl std::fmt::Debug for SmtpBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SmtpBackend")
+            .field("name", &self.name)
+            .field("from", &self.from.to_string())
+            .field(
+                "to",
+                &self.to.iter().map(|m| m.to_string()).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+#[async_trait]
+impl NotificationBackend for SmtpBackend {
+    async fn send(&self, message: &NotificationMessage) -> Result<()> {
+        debug!(backend = %self.name, recipients = self.to.len(), title = %message.title, "Sending email notification");
+
+        let email = self.email(message)?;
+        self.transport
+            .send(email)
+            .await
+            .map_err(|e| Error::NotificationError(format!("SMTP error: {}", e)))?;
+
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+fn invalid_port(config: &NotificationBackendConfig<'_>) -> Error {
+    Error::ConfigError(format!("Invalid SMTP port for '{}'", config.name))
+}
+
+fn parse_mailbox(address: &str) -> Result<Mailbox> {
+    address.trim().parse().map_err(|e| {
+        Error::ConfigError(format!("Invalid email address '{}': {}", address.trim(), e))
+    })
+}
+
+/// Recipients from a list or a comma-separated string
+fn recipients(value: Option<&JsonValue>) -> Result<Vec<Mailbox>> {
+    let addresses: Vec<&str> = match value {
+        Some(JsonValue::Array(items)) => items.iter().filter_map(|v| v.as_str()).collect(),
+        Some(JsonValue::String(s)) => s.split(',').collect(),
+        _ => Vec::new(),
+    };
+    addresses
+        .into_iter()
+        .filter(|a| !a.trim().is_empty())
+        .map(parse_mailbox)
+        .collect()
+}
+
+/// Plaintext body: the message, then one line per action
+fn render_plain(message: &NotificationMessage) -> String {
+    let mut text = message.body.clone();
+    if !message.actions.is_empty() {
+        text.push_str("\n\n");
+        for action in &message.actions {
+            match &action.method {
+                Some(method) => {
+                    text.push_str(&format!("{}: {} {}\n", action.label, method, action.url))
+                }
+                None => text.push_str(&format!("{}: {}\n", action.label, action.url)),
+            }
+        }
+    }
+    text
+}
+
+/// HTML body: the escaped message with line breaks kept, and view actions as
+/// links (HTTP actions can't be sent from an email, so they are listed)
+fn render_html(message: &NotificationMessage) -> String {
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html><body style=\"font-family: sans-serif;\">\n<h2>{}</h2>\n<p>{}</p>\n",
+        escape_html(&message.title),
+        escape_html(&message.body).replace('\n', "<br>\n")
+    );
+    if !message.actions.is_empty() {
+        html.push_str("<ul>\n");
+        for action in &message.actions {
+            match &action.method {
+                Some(method) => html.push_str(&format!(
+                    "<li>{}: <code>{} {}</code></li>\n",
+                    escape_html(&action.label),
+                    escape_html(method),
+                    escape_html(&action.url)
+                )),
+                None => html.push_str(&format!(
+                    "<li><a href=\"{}\">{}</a></li>\n",
+                    escape_html(&action.url),
+                    escape_html(&action.label)
+                )),
+            }
+        }
+        html.push_str("</ul>\n");
+    }
+    html.push_str("</body></html>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NotificationAction;
+    use reqwest::Client;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    /// What the mock SMTP server received
+    #[derive(Debug, Default)]
+    struct Received {
+        auth: Vec<String>,
+        mail_from: Vec<String>,
+        rcpt_to: Vec<String>,
+        data: String,
+    }
+
+    /// Accept one SMTP session on a local port and record it
+    async fn mock_smtp_server() -> (u16, Arc<Mutex<Received>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let received = Arc::new(Mutex::new(Received::default()));
+
+        let record = received.clone();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (read, mut write) = stream.into_split();
+            let mut lines = BufReader::new(read).lines();
+
+            write.write_all(b"220 mock ESMTP\r\n").await.unwrap();
+            while let Ok(Some(line)) = lines.next_line().await {
+                let command = line.to_uppercase();
+                let reply: &[u8] = if command.starts_with("EHLO") {
+                    b"250-mock\r\n250-AUTH PLAIN LOGIN\r\n250 8BITMIME\r\n"
+                } else if command.starts_with("AUTH") {
+                    record.lock().unwrap().auth.push(line.clone());
+                    b"235 2.7.0 Authentication successful\r\n"
+                } else if command.starts_with("MAIL FROM") {
+                    record.lock().unwrap().mail_from.push(line.clone());
+                    b"250 OK\r\n"
+                } else if command.starts_with("RCPT TO") {
+                    record.lock().unwrap().rcpt_to.push(line.clone());
+                    b"250 OK\r\n"
+                } else if command == "DATA" {
+                    write
+                        .write_all(b"354 End data with <CR><LF>.<CR><LF>\r\n")
+                        .await
+                        .unwrap();
+                    while let Ok(Some(data)) = lines.next_line().await {
+                        if data == "." {
+                            break;
+                        }
+                        let mut received = record.lock().unwrap();
+                        received.data.push_str(&data);
+                        received.data.push('\n');
+                    }
+                    b"250 OK queued\r\n"
+                } else if command == "QUIT" {
+                    write.write_all(b"221 Bye\r\n").await.unwrap();
+                    break;
+                } else {
+                    b"250 OK\r\n"
+                };
+                write.write_all(reply).await.unwrap();
+            }
+        });
+
+        (port, received)
+    }
+
+    fn backend(config: JsonValue) -> Result<Box<dyn NotificationBackend>> {
+        let client = Client::new();
+        SmtpBackend::from_config(&NotificationBackendConfig {
+            name: "on-call",
+            config: &config,
+            client: &client,
+            services: &[],
+        })
+    }
+
+    #[tokio::test]
+    async fn test_send_to_mock_server() {
+        let (port, received) = mock_smtp_server().await;
+        let backend = backend(serde_json::json!({
+            "host": "127.0.0.1",
+            "port": port,
+            "tls": "none",
+            "username": "svrctlrs",
+            "password": "hunter2",
+            "from": "SvrCtlRS <svrctlrs@example.com>",
+            "to": "ops@example.com, oncall@example.com",
+        }))
+        .unwrap();
+
+        backend
+            .send(&NotificationMessage {
+                title: "Updates <available>".to_string(),
+                body: "web1: 3 updates\nweb2: 1 update".to_string(),
+                priority: 4,
+                actions: vec![
+                    NotificationAction::view("Open", "https://svrctlrs.local/servers"),
+                    NotificationAction::http_post("Approve", "https://svrctlrs.local/approve"),
+                ],
+            })
+            .await
+            .unwrap();
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.auth.len(), 1);
+        assert!(received.mail_from[0].contains("<svrctlrs@example.com>"));
+        assert_eq!(received.rcpt_to.len(), 2);
+        assert!(received.rcpt_to[0].contains("<ops@example.com>"));
+        assert!(received.rcpt_to[1].contains("<oncall@example.com>"));
+
+        let data = received.data.replace("=\n", "");
+        assert!(data.contains("Subject: Updates <available>"));
+        assert!(data.contains("multipart/alternative"));
+        assert!(data.contains("text/plain"));
+        assert!(data.contains("text/html"));
+        assert!(data.contains("Approve: POST https://svrctlrs.local/approve"));
+        assert!(data.contains("Updates &lt;available&gt;"));
+    }
+
+    #[tokio::test]
+    async fn test_send_reports_server_errors() {
+        // Nothing listens on the port
+        let port = {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            listener.local_addr().unwrap().port()
+        };
+        let backend = backend(serde_json::json!({
+            "host": "127.0.0.1",
+            "port": port,
+            "tls": "none",
+            "from": "svrctlrs@example.com",
+            "to": ["ops@example.com"],
+        }))
+        .unwrap();
+
+        let message = NotificationMessage {
+            title: "Test".to_string(),
+            body: "Body".to_string(),
+            priority: 3,
+            actions: Vec::new(),
+        };
+        assert!(backend.send(&message).await.is_err());
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let valid = serde_json::json!({
+            "host": "smtp.example.com",
+            "from": "svrctlrs@example.com",
+            "to": ["ops@example.com"],
+        });
+        assert!(backend(valid.clone()).is_ok());
+
+        for (key, value) in [
+            ("host", JsonValue::Null),
+            ("from", serde_json::json!("not an address")),
+            ("to", serde_json::json!([])),
+            ("tls", serde_json::json!("ssl")),
+            ("port", serde_json::json!(70000)),
+        ] {
+            let mut config = valid.clone();
+            config[key] = value;
+            assert!(backend(config).is_err(), "{} should be rejected", key);
+        }
+    }
+
+    #[test]
+    fn test_render_bodies() {
+        let message = NotificationMessage {
+            title: "Disk <full>".to_string(),
+            body: "web1 & web2\nat 95%".to_string(),
+            priority: 5,
+            actions: vec![NotificationAction::view(
+                "Details",
+                "https://x.local/?a=1&b=2",
+            )],
+        };
+
+        assert_eq!(
+            render_plain(&message),
+            "web1 & web2\nat 95%\n\nDetails: https://x.local/?a=1&b=2\n"
+        );
+
+        let html = render_html(&message);
+        assert!(html.contains("<h2>Disk &lt;full&gt;</h2>"));
+        assert!(html.contains("web1 &amp; web2<br>\nat 95%"));
+        assert!(html.contains("<a href=\"https://x.local/?a=1&amp;b=2\">Details</a>"));
+    }
+}
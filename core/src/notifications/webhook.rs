@@ -0,0 +1,341 @@
+//! Generic JSON webhook notification backend
+
+use async_trait::async_trait;
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE},
+    Client, Method,
+};
+use serde_json::{json, Value as JsonValue};
+use tracing::debug;
+
+use super::{NotificationBackend, NotificationBackendConfig, NotificationMessage};
+use crate::{Error, Result};
+
+/// Placeholders a body template can use
+const PLACEHOLDERS: &[&str] = &[
+    "title",
+    "body",
+    "priority",
+    "priority_label",
+    "service",
+    "actions",
+];
+
+/// Generic webhook notification backend
+///
+/// Sends each notification as JSON to any URL. Without a body template the
+/// JSON is the message itself plus the sending service. A template is JSON
+/// with `{{placeholder}}`s (see [`PLACEHOLDERS`]): text values are inserted
+/// JSON-escaped, to go inside quotes (`"{{title}}"`), while `priority` and
+/// `actions` are inserted as a number and an array.
+#[derive(Debug, Clone)]
+pub struct WebhookBackend {
+    name: String,
+    client: Client,
+    url: String,
+    method: Method,
+    headers: HeaderMap,
+    body_template: Option<String>,
+}
+
+impl WebhookBackend {
+    /// Build a webhook backend from its `url` (required), `method` (`POST`
+    /// (default), `PUT` or `PATCH`), `headers` (name to value) and
+    /// `body_template` settings
+    pub fn from_config(
+        config: &NotificationBackendConfig<'_>,
+    ) -> Result<Box<dyn NotificationBackend>> {
+        let url = config.require_str("url")?.to_string();
+
+        let method = match config.get_str("method") {
+            None => Method::POST,
+            Some(method) => match method.to_uppercase().as_str() {
+                "POST" => Method::POST,
+                "PUT" => Method::PUT,
+                "PATCH" => Method::PATCH,
+                _ => {
+                    return Err(Error::ConfigError(format!(
+                        "Invalid method '{}' for '{}'. Use POST, PUT or PATCH.",
+                        method, config.name
+                    )))
+                }
+            },
+        };
+
+        let mut headers = HeaderMap::new();
+        match config.config.get("headers") {
+            None | Some(JsonValue::Null) => {}
+            Some(JsonValue::Object(map)) => {
+                for (name, value) in map {
+                    let header_name = HeaderName::from_bytes(name.trim().as_bytes());
+                    let header_value = value.as_str().map(HeaderValue::from_str);
+                    match (header_name, header_value) {
+                        (Ok(name), Some(Ok(value))) => {
+                            headers.insert(name, value);
+                        }
+                        _ => {
+                            return Err(Error::ConfigError(format!(
+                                "Invalid header '{}' for '{}'",
+                                name, config.name
+                            )))
+                        }
+                    }
+                }
+            }
+            Some(_) => {
+                return Err(Error::ConfigError(format!(
+                    "Headers for '{}' must be an object of names to values",
+                    config.name
+                )))
+            }
+        }
+
+        let body_template = config.get_str("body_template").map(str::to_string);
+        if let Some(template) = &body_template {
+            // Catch mistakes now rather than on the first notification
+            let sample = NotificationMessage {
+                title: "Title".to_string(),
+                body: "Body".to_string(),
+                priority: 3,
+                actions: Vec::new(),
+            };
+            let rendered = render_template(template, "service", &sample).map_err(|e| {
+                Error::ConfigError(format!("Body template for '{}': {}", config.name, e))
+            })?;
+            serde_json::from_str::<JsonValue>(&rendered).map_err(|e| {
+                Error::ConfigError(format!(
+                    "Body template for '{}' isn't valid JSON: {}",
+                    config.name, e
+                ))
+            })?;
+        }
+
+        Ok(Box::new(Self {
+            name: config.name.to_string(),
+            client: config.client.clone(),
+            url,
+            method,
+            headers,
+            body_template,
+        }))
+    }
+
+    /// Request body for a message
+    fn body(&self, service: &str, message: &NotificationMessage) -> Result<String> {
+        match &self.body_template {
+            Some(template) => render_template(template, service, message).map_err(|e| {
+                Error::NotificationError(format!("Body template for '{}': {}", self.name, e))
+            }),
+            None => Ok(json!({
+                "title": message.title,
+                "body": message.body,
+                "priority": message.priority,
+                "priority_label": message.priority_label(),
+                "service": service,
+                "actions": message.actions,
+            })
+            .to_string()),
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationBackend for WebhookBackend {
+    async fn send(&self, message: &NotificationMessage) -> Result<()> {
+        self.send_for_service("default", message).await
+    }
+
+    async fn send_for_service(&self, service: &str, message: &NotificationMessage) -> Result<()> {
+        debug!(backend = %self.name, service = %service, method = %self.method, title = %message.title, "Sending webhook notification");
+
+        let response = self
+            .client
+            .request(self.method.clone(), &self.url)
+            .header(CONTENT_TYPE, "application/json")
+            .headers(self.headers.clone())
+            .body(self.body(service, message)?)
+            .send()
+            .await
+            .map_err(|e| Error::HttpError(format!("Webhook request failed: {}", e)))?;
+
+        response
+            .error_for_status()
+            .map_err(|e| Error::NotificationError(format!("Webhook error: {}", e)))?;
+
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Fill in a body template's placeholders
+///
+/// Fails on unknown or unclosed placeholders. Placeholders are replaced in
+/// one pass, so `{{...}}` inside a message is sent as is.
+fn render_template(
+    template: &str,
+    service: &str,
+    message: &NotificationMessage,
+) -> std::result::Result<String, String> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| "unclosed '{{' placeholder".to_string())?;
+        let placeholder = after[..end].trim();
+
+        let value = match placeholder {
+            "title" => escape_json(&message.title),
+            "body" => escape_json(&message.body),
+            "priority" => message.priority.to_string(),
+            "priority_label" => message.priority_label().to_string(),
+            "service" => escape_json(service),
+            "actions" => serde_json::to_string(&message.actions).unwrap_or_else(|_| "[]".into()),
+            _ => {
+                return Err(format!(
+                    "unknown placeholder '{{{{{}}}}}' (known: {})",
+                    placeholder,
+                    PLACEHOLDERS.join(", ")
+                ))
+            }
+        };
+        rendered.push_str(&value);
+        rest = &after[end + 2..];
+    }
+    rendered.push_str(rest);
+
+    Ok(rendered)
+}
+
+/// Text escaped for use inside a JSON string
+fn escape_json(text: &str) -> String {
+    let quoted = JsonValue::String(text.to_string()).to_string();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notifications::http_stub::HttpStub;
+    use crate::NotificationAction;
+
+    fn backend(config: JsonValue) -> Result<Box<dyn NotificationBackend>> {
+        let client = Client::new();
+        WebhookBackend::from_config(&NotificationBackendConfig {
+            name: "pager",
+            config: &config,
+            client: &client,
+            services: &[],
+        })
+    }
+
+    fn message() -> NotificationMessage {
+        NotificationMessage {
+            title: "Container \"web\" stopped".to_string(),
+            body: "exit code 137\nOOM killed".to_string(),
+            priority: 4,
+            actions: vec![NotificationAction::view("Open", "https://svrctlrs.local/")],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_default_body() {
+        let stub = HttpStub::start(200).await;
+        let backend = backend(json!({ "url": format!("{}/hooks/svrctlrs", stub.url) })).unwrap();
+
+        backend
+            .send_for_service("docker", &message())
+            .await
+            .unwrap();
+
+        let requests = stub.requests();
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/hooks/svrctlrs");
+        assert_eq!(requests[0].header("content-type"), Some("application/json"));
+        assert_eq!(
+            requests[0].json(),
+            json!({
+                "title": "Container \"web\" stopped",
+                "body": "exit code 137\nOOM killed",
+                "priority": 4,
+                "priority_label": "high",
+                "service": "docker",
+                "actions": [{"label": "Open", "url": "https://svrctlrs.local/", "method": null}],
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_templated_body_with_headers() {
+        let stub = HttpStub::start(200).await;
+        let backend = backend(json!({
+            "url": stub.url,
+            "method": "put",
+            "headers": { "X-Api-Key": "k3y", "Content-Type": "application/vnd.pager+json" },
+            "body_template": "{\"summary\": \"[{{service}}] {{ title }}\", \"details\": \"{{body}}\", \"severity\": {{priority}}, \"links\": {{actions}}}",
+        }))
+        .unwrap();
+
+        backend
+            .send_for_service("docker", &message())
+            .await
+            .unwrap();
+
+        let requests = stub.requests();
+        assert_eq!(requests[0].method, "PUT");
+        assert_eq!(requests[0].header("x-api-key"), Some("k3y"));
+        assert_eq!(
+            requests[0].header("content-type"),
+            Some("application/vnd.pager+json")
+        );
+        let body = requests[0].json();
+        assert_eq!(body["summary"], "[docker] Container \"web\" stopped");
+        assert_eq!(body["details"], "exit code 137\nOOM killed");
+        assert_eq!(body["severity"], 4);
+        assert_eq!(body["links"][0]["url"], "https://svrctlrs.local/");
+    }
+
+    #[tokio::test]
+    async fn test_send_reports_http_errors() {
+        let stub = HttpStub::start(500).await;
+        let backend = backend(json!({ "url": stub.url })).unwrap();
+
+        let err = backend.send(&message()).await.unwrap_err();
+        assert!(err.to_string().contains("Webhook error"));
+    }
+
+    #[test]
+    fn test_placeholders_in_messages_are_not_expanded() {
+        let mut message = message();
+        message.title = "{{body}}".to_string();
+        let rendered = render_template("{\"t\": \"{{title}}\"}", "docker", &message).unwrap();
+        assert_eq!(rendered, "{\"t\": \"{{body}}\"}");
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(backend(json!({ "url": "https://hooks.local" })).is_ok());
+
+        for config in [
+            json!({}),
+            json!({ "url": "https://hooks.local", "method": "DELETE" }),
+            json!({ "url": "https://hooks.local", "headers": ["X-Key: 1"] }),
+            json!({ "url": "https://hooks.local", "headers": { "Bad Header": "1" } }),
+            json!({ "url": "https://hooks.local", "body_template": "{\"t\": \"{{titel}}\"}" }),
+            json!({ "url": "https://hooks.local", "body_template": "{\"t\": \"{{title\"}" }),
+            json!({ "url": "https://hooks.local", "body_template": "{\"t\": {{title}}}" }),
+        ] {
+            assert!(
+                backend(config.clone()).is_err(),
+                "{} should be rejected",
+                config
+            );
+        }
+    }
+}
@@ -43,7 +43,7 @@ pub struct ScheduledTask {
 }
 
 /// Plugin execution context
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct PluginContext {
     /// Servers to monitor/operate on
     pub servers: Vec<Server>,
@@ -53,6 +53,25 @@ pub struct PluginContext {
     pub notification_manager: NotificationManager,
 }
 
+/// Separator between the server name and metric name in per-server metric keys
+pub const SERVER_METRIC_SEPARATOR: &str = "::";
+
+/// Build a metric key scoped to a server (e.g. `web01::cpu_usage_percent`)
+///
+/// Plugins that collect metrics from several servers use this for the keys
+/// of [`PluginResult::metrics`] so each value can be attributed to its server.
+pub fn server_metric_key(server: &str, metric: &str) -> String {
+    format!("{}{}{}", server, SERVER_METRIC_SEPARATOR, metric)
+}
+
+/// Split a metric key into its server name (if scoped) and metric name
+pub fn split_server_metric_key(key: &str) -> (Option<&str>, &str) {
+    match key.split_once(SERVER_METRIC_SEPARATOR) {
+        Some((server, metric)) => (Some(server), metric),
+        None => (None, key),
+    }
+}
+
 /// Plugin execution result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PluginResult {
@@ -63,6 +82,9 @@ pub struct PluginResult {
     /// Structured data (optional)
     pub data: Option<serde_json::Value>,
     /// Metrics collected (optional)
+    ///
+    /// Keys are plain metric names, or [`server_metric_key`] keys when the
+    /// plugin reports values for more than one server.
     pub metrics: Option<HashMap<String, f64>>,
 }
 
@@ -190,3 +212,26 @@ impl Default for PluginRegistry {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_server_metric_key_roundtrip() {
+        let key = server_metric_key("web01", "disk_used_percent:/var");
+        assert_eq!(key, "web01::disk_used_percent:/var");
+        assert_eq!(
+            split_server_metric_key(&key),
+            (Some("web01"), "disk_used_percent:/var")
+        );
+    }
+
+    #[test]
+    fn test_split_unscoped_metric_key() {
+        assert_eq!(
+            split_server_metric_key("total_updates"),
+            (None, "total_updates")
+        );
+    }
+}
@@ -1,6 +1,7 @@
 //! Remote execution via SSH
 
 use crate::{Error, Result, Server};
+use std::process::Output;
 use tokio::process::Command;
 use tokio::time::{timeout, Duration};
 use tracing::{debug, info, instrument};
@@ -59,11 +60,29 @@ impl RemoteExecutor {
     /// Command output (stdout)
     #[instrument(skip(self), fields(server = %self.server.name, cmd = %cmd))]
     pub async fn execute_command(&self, cmd: &str, args: &[&str]) -> Result<String> {
-        if self.server.is_local() {
-            self.execute_local(cmd, args).await
-        } else {
-            self.execute_ssh(cmd, args).await
+        let output = self.output(&self.server, cmd, args).await?;
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// Execute a command on the configured server, failing on a non-zero exit code
+    ///
+    /// The error includes the command's stderr.
+    #[instrument(skip(self), fields(server = %self.server.name, cmd = %cmd))]
+    pub async fn execute_command_checked(&self, cmd: &str, args: &[&str]) -> Result<String> {
+        let output = self.output(&self.server, cmd, args).await?;
+        if !output.status.success() {
+            let code = output
+                .status
+                .code()
+                .map_or_else(|| "a signal".to_string(), |c| c.to_string());
+            return Err(Error::RemoteExecutionError(format!(
+                "{} exited with {}: {}",
+                cmd,
+                code,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
         }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
     }
 
     /// Execute a command on a specific server (overrides configured server)
@@ -79,6 +98,12 @@ impl RemoteExecutor {
     /// Command output (stdout)
     #[instrument(skip(self), fields(server = %server.name, cmd = %cmd))]
     pub async fn execute(&self, server: &Server, cmd: &str, args: &[&str]) -> Result<String> {
+        let output = self.output(server, cmd, args).await?;
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// Run a command on a server, locally or via SSH
+    async fn output(&self, server: &Server, cmd: &str, args: &[&str]) -> Result<Output> {
         if server.is_local() {
             self.execute_local(cmd, args).await
         } else {
@@ -87,7 +112,7 @@ impl RemoteExecutor {
     }
 
     /// Execute command locally
-    async fn execute_local(&self, cmd: &str, args: &[&str]) -> Result<String> {
+    async fn execute_local(&self, cmd: &str, args: &[&str]) -> Result<Output> {
         info!(cmd = %cmd, args = ?args, "Executing command locally");
 
         let output = timeout(
@@ -109,19 +134,12 @@ impl RemoteExecutor {
         // (e.g., dnf check-update returns 100 if updates exist)
         // So we don't fail on non-zero exit here
 
-        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-
+        let stderr = String::from_utf8_lossy(&output.stderr);
         if !stderr.is_empty() {
             debug!(cmd = %cmd, stderr = %stderr, "Command produced stderr output");
         }
 
-        Ok(stdout)
-    }
-
-    /// Execute command via SSH on configured server
-    async fn execute_ssh(&self, cmd: &str, args: &[&str]) -> Result<String> {
-        self.execute_ssh_on_server(&self.server, cmd, args).await
+        Ok(output)
     }
 
     /// Execute command via SSH on a specific server
@@ -130,7 +148,7 @@ impl RemoteExecutor {
         server: &Server,
         cmd: &str,
         args: &[&str],
-    ) -> Result<String> {
+    ) -> Result<Output> {
         let ssh_host = server
             .ssh_host
             .as_ref()
@@ -160,7 +178,13 @@ impl RemoteExecutor {
             ssh_cmd.arg("-i").arg(key_path);
         }
 
-        ssh_cmd.arg(ssh_host).arg(&remote_cmd);
+        // ssh does not understand host:port, pass the port separately
+        let (destination, port) = split_ssh_port(ssh_host);
+        if let Some(port) = port {
+            ssh_cmd.arg("-p").arg(port);
+        }
+
+        ssh_cmd.arg(destination).arg(&remote_cmd);
 
         // Execute with timeout
         let output = timeout(Duration::from_secs(self.timeout_secs), ssh_cmd.output())
@@ -186,8 +210,7 @@ impl RemoteExecutor {
             }
         }
 
-        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-        Ok(stdout)
+        Ok(output)
     }
 
     /// Build remote command string with proper quoting
@@ -229,6 +252,20 @@ impl RemoteExecutor {
     }
 }
 
+/// Split an optional `:port` suffix off an SSH destination (`user@host:2222`)
+pub fn split_ssh_port(ssh_host: &str) -> (&str, Option<&str>) {
+    match ssh_host.rsplit_once(':') {
+        Some((destination, port))
+            if !destination.contains(':')
+                && !port.is_empty()
+                && port.chars().all(|c| c.is_ascii_digit()) =>
+        {
+            (destination, Some(port))
+        }
+        _ => (ssh_host, None),
+    }
+}
+
 impl Default for RemoteExecutor {
     fn default() -> Self {
         Self::new(None)
@@ -267,6 +304,19 @@ mod tests {
         assert_eq!(cmd, "echo 'it'\\''s working'");
     }
 
+    #[test]
+    fn test_split_ssh_port() {
+        assert_eq!(
+            split_ssh_port("root@example.com:2222"),
+            ("root@example.com", Some("2222"))
+        );
+        assert_eq!(
+            split_ssh_port("root@example.com"),
+            ("root@example.com", None)
+        );
+        assert_eq!(split_ssh_port("root@fe80::1"), ("root@fe80::1", None));
+    }
+
     #[tokio::test]
     async fn test_execute_local_simple() {
         let executor = RemoteExecutor::new(None);
@@ -275,6 +325,27 @@ mod tests {
         assert_eq!(result.unwrap().trim(), "hello");
     }
 
+    #[tokio::test]
+    async fn test_execute_checked_fails_on_exit_code() {
+        let executor = RemoteExecutor::new(None);
+        assert!(executor
+            .execute_command("sh", &["-c", "exit 3"])
+            .await
+            .is_ok());
+
+        let err = executor
+            .execute_command_checked("sh", &["-c", "echo broken >&2; exit 3"])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("exited with 3: broken"));
+
+        let output = executor
+            .execute_command_checked("echo", &["fine"])
+            .await
+            .unwrap();
+        assert_eq!(output.trim(), "fine");
+    }
+
     #[tokio::test]
     async fn test_timeout_configuration() {
         let executor = RemoteExecutor::new(None).with_timeout(5);
@@ -10,6 +10,26 @@ pub struct Server {
     pub name: String,
     /// SSH connection string (user@host) or None for localhost
     pub ssh_host: Option<String>,
+    /// SSH private key for this server; None = the SSH agent / default keys
+    #[serde(default)]
+    pub ssh_key_path: Option<String>,
+    /// Docker daemon endpoint (`unix://`, `tcp://`, `https://` or `ssh://`);
+    /// None = local socket, or the socket tunnelled over SSH for remote servers
+    #[serde(default)]
+    pub docker_endpoint: Option<String>,
+    /// Directory with ca.pem, cert.pem and key.pem for `https://` Docker endpoints
+    #[serde(default)]
+    pub docker_cert_path: Option<String>,
+    /// Shell script run before applying updates (snapshot, stop services);
+    /// updates are not applied if it fails
+    #[serde(default)]
+    pub pre_update_hook: Option<String>,
+    /// Shell script run after applying updates, e.g. a health probe
+    #[serde(default)]
+    pub post_update_hook: Option<String>,
+    /// Shell script run when the post-update hook fails
+    #[serde(default)]
+    pub rollback_hook: Option<String>,
 }
 
 impl Server {
@@ -18,6 +38,12 @@ impl Server {
         Self {
             name: name.into(),
             ssh_host: None,
+            ssh_key_path: None,
+            docker_endpoint: None,
+            docker_cert_path: None,
+            pre_update_hook: None,
+            post_update_hook: None,
+            rollback_hook: None,
         }
     }
 
@@ -26,6 +52,12 @@ impl Server {
         Self {
             name: name.into(),
             ssh_host: Some(ssh_host.into()),
+            ssh_key_path: None,
+            docker_endpoint: None,
+            docker_cert_path: None,
+            pre_update_hook: None,
+            post_update_hook: None,
+            rollback_hook: None,
         }
     }
 
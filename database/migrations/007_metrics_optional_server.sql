@@ -0,0 +1,28 @@
+-- Allow metrics without a server (plugins that run on the svrctlrs host itself)
+-- SQLite cannot drop NOT NULL in place, so the table is rebuilt
+
+CREATE TABLE metrics_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_id INTEGER,  -- NULL = local / not tied to a registered server
+    plugin_id TEXT NOT NULL,
+    metric_name TEXT NOT NULL,
+    metric_value REAL NOT NULL,
+    metric_unit TEXT,
+    metadata TEXT,  -- JSON (e.g. {"server": "name"} for unregistered hosts)
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (server_id) REFERENCES servers(id) ON DELETE CASCADE
+);
+
+INSERT INTO metrics_new (id, server_id, plugin_id, metric_name, metric_value, metric_unit, metadata, timestamp)
+SELECT id, server_id, plugin_id, metric_name, metric_value, metric_unit, metadata, timestamp
+FROM metrics;
+
+DROP TABLE metrics;
+ALTER TABLE metrics_new RENAME TO metrics;
+
+CREATE INDEX IF NOT EXISTS idx_metrics_server_plugin
+ON metrics(server_id, plugin_id, timestamp DESC);
+
+-- Time-series lookups by metric name
+CREATE INDEX IF NOT EXISTS idx_metrics_name_timestamp
+ON metrics(metric_name, timestamp);
@@ -0,0 +1,68 @@
+-- Threshold-based alert rules evaluated against stored metrics
+
+CREATE TABLE IF NOT EXISTS alert_rules (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    description TEXT,
+    enabled BOOLEAN NOT NULL DEFAULT 1,
+
+    -- What to match
+    plugin_id TEXT,  -- NULL = any plugin
+    metric_name TEXT NOT NULL,  -- Exact name, base name (matches all ":qualifier" variants) or "prefix*"
+    server_id INTEGER,  -- NULL = any server
+    server_tag TEXT,  -- NULL = any tag
+
+    -- Condition
+    operator TEXT NOT NULL CHECK (operator IN ('>', '>=', '<', '<=', '==', '!=')),
+    threshold REAL NOT NULL,
+    duration_secs INTEGER NOT NULL DEFAULT 0,  -- Condition must hold this long before firing
+
+    -- Notification
+    severity TEXT NOT NULL DEFAULT 'warning' CHECK (severity IN ('info', 'warning', 'critical')),
+    notify_resolved BOOLEAN NOT NULL DEFAULT 1,
+
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY (server_id) REFERENCES servers(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_alert_rules_enabled ON alert_rules(enabled);
+
+-- Current state of each (rule, server, metric) combination
+CREATE TABLE IF NOT EXISTS alert_states (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    rule_id INTEGER NOT NULL,
+    server_id INTEGER,  -- NULL = local / unregistered host
+    server_name TEXT NOT NULL,
+    metric_name TEXT NOT NULL,
+    state TEXT NOT NULL CHECK (state IN ('ok', 'pending', 'firing', 'resolved')),
+    value REAL,
+    pending_since DATETIME,
+    firing_since DATETIME,
+    resolved_at DATETIME,
+    last_evaluated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    UNIQUE (rule_id, server_name, metric_name),
+    FOREIGN KEY (rule_id) REFERENCES alert_rules(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_alert_states_state ON alert_states(state);
+
+-- History of firing / resolved transitions
+CREATE TABLE IF NOT EXISTS alert_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    rule_id INTEGER NOT NULL,
+    server_name TEXT NOT NULL,
+    metric_name TEXT NOT NULL,
+    event TEXT NOT NULL CHECK (event IN ('firing', 'resolved')),
+    severity TEXT NOT NULL,
+    value REAL NOT NULL,
+    threshold REAL NOT NULL,
+    message TEXT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY (rule_id) REFERENCES alert_rules(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_alert_events_created ON alert_events(created_at DESC);
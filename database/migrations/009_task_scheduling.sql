@@ -0,0 +1,9 @@
+-- Scheduler bookkeeping for tasks
+-- next_run_at already exists and is now kept up to date by the scheduler
+
+-- What to do with runs missed while the scheduler was down: skip, once, all
+ALTER TABLE tasks ADD COLUMN catch_up TEXT NOT NULL DEFAULT 'skip';
+
+-- Total number of scheduled runs that were missed
+ALTER TABLE tasks ADD COLUMN missed_runs INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE tasks ADD COLUMN last_missed_at DATETIME;
@@ -0,0 +1,5 @@
+-- Concurrency control for tasks
+
+-- What to do when a task comes due while its previous run is still active:
+-- skip, queue, kill
+ALTER TABLE tasks ADD COLUMN overlap_policy TEXT NOT NULL DEFAULT 'skip';
@@ -0,0 +1,7 @@
+-- Retries for transient task failures
+
+-- Retries after a transient failure; NULL = use the server's retry_attempts
+ALTER TABLE tasks ADD COLUMN retry_attempts INTEGER;
+
+-- Attempt number of each execution (1 = first try)
+ALTER TABLE task_history ADD COLUMN attempt INTEGER NOT NULL DEFAULT 1;
@@ -0,0 +1,46 @@
+-- Workflows: DAGs of task / remote command steps with their own run history
+
+CREATE TABLE IF NOT EXISTS workflows (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    description TEXT,
+    enabled BOOLEAN NOT NULL DEFAULT 1,
+    schedule TEXT,  -- Cron expression; NULL = run manually or via webhook only
+    steps TEXT NOT NULL,  -- JSON array of steps (id, task_id | server_id + command, depends_on, condition)
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_run_at DATETIME,
+    next_run_at DATETIME
+);
+
+CREATE TABLE IF NOT EXISTS workflow_runs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    workflow_id INTEGER NOT NULL,
+    status TEXT NOT NULL DEFAULT 'running' CHECK (status IN ('running', 'success', 'failed')),
+    triggered_by TEXT NOT NULL DEFAULT 'manual',  -- schedule, manual, webhook
+    started_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    finished_at DATETIME,
+    error_message TEXT,
+
+    FOREIGN KEY (workflow_id) REFERENCES workflows(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_workflow_runs_workflow ON workflow_runs(workflow_id, started_at DESC);
+CREATE INDEX IF NOT EXISTS idx_workflow_runs_status ON workflow_runs(status);
+
+-- State of every step of a run; all steps are inserted as pending when the run starts
+CREATE TABLE IF NOT EXISTS workflow_step_runs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    run_id INTEGER NOT NULL,
+    step_id TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'running', 'success', 'failed', 'skipped')),
+    started_at DATETIME,
+    finished_at DATETIME,
+    duration_ms INTEGER,
+    message TEXT,
+    result TEXT,  -- JSON PluginResult of the step
+
+    UNIQUE (run_id, step_id),
+    FOREIGN KEY (run_id) REFERENCES workflow_runs(id) ON DELETE CASCADE
+);
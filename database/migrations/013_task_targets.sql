@@ -0,0 +1,14 @@
+-- Target selection for tasks
+
+-- Where a task runs:
+--   local  - the plugin runs on this host (server_id NULL)
+--   server - the command runs on server_id
+--   all    - the command runs on every enabled server
+--   tags   - the command runs on every enabled server with one of target_tags
+ALTER TABLE tasks ADD COLUMN target TEXT NOT NULL DEFAULT 'local';
+ALTER TABLE tasks ADD COLUMN target_tags TEXT;  -- JSON array
+
+UPDATE tasks SET target = 'server' WHERE server_id IS NOT NULL;
+
+-- Aggregated outcome of the last run: success, partial, failed, cancelled
+ALTER TABLE tasks ADD COLUMN last_status TEXT;
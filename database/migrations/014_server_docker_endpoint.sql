@@ -0,0 +1,9 @@
+-- How the Docker plugin reaches each server's Docker daemon
+
+-- NULL = local socket for local servers, SSH tunnel to /var/run/docker.sock
+-- for remote ones; otherwise unix://, tcp://, https:// or ssh:// (with an
+-- optional remote socket path)
+ALTER TABLE servers ADD COLUMN docker_endpoint TEXT;
+
+-- Directory holding ca.pem, cert.pem and key.pem for https:// endpoints
+ALTER TABLE servers ADD COLUMN docker_cert_path TEXT;
@@ -0,0 +1,22 @@
+-- Package updates found by the most recent updates check of each server,
+-- so they can be reviewed before updates are applied
+
+CREATE TABLE IF NOT EXISTS pending_updates (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_id INTEGER,  -- NULL = local / unregistered host
+    server_name TEXT NOT NULL,
+
+    name TEXT NOT NULL,
+    arch TEXT,
+    current_version TEXT,  -- NULL when the package manager does not report it
+    new_version TEXT,
+    repository TEXT,  -- Flatpak / snap updates are prefixed "flatpak/" / "snap"
+    is_security BOOLEAN NOT NULL DEFAULT 0,
+    severity TEXT,  -- Advisory severity, e.g. Critical, Important, Moderate, Low
+
+    checked_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY (server_id) REFERENCES servers(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_pending_updates_server ON pending_updates(server_name);
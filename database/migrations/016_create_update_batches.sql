@@ -0,0 +1,30 @@
+-- Update batches awaiting approval before updates_apply may install them
+--
+-- In approval mode every updates check opens a batch per server with the
+-- packages it found. A batch is approved or deferred (from the API or a
+-- notification action), and updates_apply only runs on servers whose
+-- latest batch was approved.
+
+CREATE TABLE IF NOT EXISTS update_batches (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_id INTEGER,  -- NULL = local / unregistered host
+    server_name TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'approved', 'deferred', 'superseded', 'applied', 'failed')),
+    packages TEXT NOT NULL,  -- JSON array of the package updates found by the check
+    package_count INTEGER NOT NULL DEFAULT 0,
+    security_count INTEGER NOT NULL DEFAULT 0,
+    token TEXT NOT NULL,  -- Secret embedded in notification action URLs
+
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    decided_at DATETIME,
+    decided_by TEXT,  -- Who approved / deferred the batch
+    decided_via TEXT,  -- api, notification
+    applied_at DATETIME,
+    result TEXT,  -- Summary of the updates_apply run
+
+    FOREIGN KEY (server_id) REFERENCES servers(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_update_batches_server ON update_batches(server_name, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_update_batches_status ON update_batches(status);
@@ -0,0 +1,33 @@
+-- Update hooks and package transactions
+--
+-- Servers can run a shell script before applying updates (snapshot, stop
+-- compose stacks), after applying them (health probe), and when the
+-- post-update hook fails (rollback). Every updates_apply run that changed
+-- packages records the package manager's transaction so it can be rolled
+-- back later.
+
+ALTER TABLE servers ADD COLUMN pre_update_hook TEXT;
+ALTER TABLE servers ADD COLUMN post_update_hook TEXT;
+ALTER TABLE servers ADD COLUMN rollback_hook TEXT;
+
+CREATE TABLE IF NOT EXISTS update_transactions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_id INTEGER,  -- NULL = local / unregistered host
+    server_name TEXT NOT NULL,
+    package_manager TEXT NOT NULL,  -- apt, dnf, yum
+    transaction_id TEXT NOT NULL,  -- dnf/yum history ID, apt history.log Start-Date
+    packages TEXT NOT NULL DEFAULT '[]',  -- JSON array of {name, from_version, to_version}
+    status TEXT NOT NULL DEFAULT 'applied'
+        CHECK (status IN ('applied', 'rolling_back', 'rolled_back', 'rollback_failed')),
+    post_hook_failed BOOLEAN NOT NULL DEFAULT 0,  -- Health probe failed after applying
+    rollback_hook_ran BOOLEAN NOT NULL DEFAULT 0,  -- The server's rollback hook ran successfully
+
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    rolled_back_at DATETIME,
+    rolled_back_by TEXT,
+    rollback_output TEXT,  -- Output or error of the package downgrade
+
+    FOREIGN KEY (server_id) REFERENCES servers(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_update_transactions_server ON update_transactions(server_name, created_at DESC);
@@ -0,0 +1,16 @@
+-- User accounts for logging in to the web UI and API
+--
+-- Passwords are stored as argon2 PHC strings. The first account is created
+-- by the server's first-run bootstrap; sessions live in the
+-- tower_sessions table managed by the session store.
+
+CREATE TABLE IF NOT EXISTS users (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    username TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    password_hash TEXT NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT 1,
+
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_login_at DATETIME
+);
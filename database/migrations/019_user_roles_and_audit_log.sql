@@ -0,0 +1,26 @@
+-- User roles, per-user server scopes, and the audit log
+--
+-- viewer: read-only; operator: runs tasks, workflows, and update actions;
+-- admin: also manages servers, plugins, notifications, tasks, and users.
+-- A user with server_tags may only act on servers carrying one of them.
+
+ALTER TABLE users ADD COLUMN role TEXT NOT NULL DEFAULT 'viewer'
+    CHECK (role IN ('viewer', 'operator', 'admin'));
+ALTER TABLE users ADD COLUMN server_tags TEXT;  -- JSON array; NULL = all servers
+
+-- Accounts created before roles existed had full access
+UPDATE users SET role = 'admin';
+
+CREATE TABLE IF NOT EXISTS audit_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    actor TEXT NOT NULL,  -- Username, or 'webhook' for token-authenticated webhooks
+    action TEXT NOT NULL,  -- e.g. server.create, task.run, webhook.updates_apply
+    target TEXT,  -- What the action was applied to, e.g. 'server 3 (web1)'
+    outcome TEXT NOT NULL CHECK (outcome IN ('success', 'failure', 'denied')),
+    message TEXT,  -- Reason an action was denied or failed
+    source_ip TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_created ON audit_log(created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_audit_log_actor ON audit_log(actor, created_at DESC);
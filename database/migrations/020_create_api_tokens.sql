@@ -0,0 +1,22 @@
+-- Per-user API tokens for the REST API and the svrctl CLI
+--
+-- Only a SHA-256 hash of each token is stored; the token itself is shown
+-- once when it is created. A token acts as its user, limited by its scopes:
+-- read (GET requests), operate (operator actions), admin (admin actions).
+
+CREATE TABLE IF NOT EXISTS api_tokens (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,  -- Hex SHA-256 of the token
+    token_prefix TEXT NOT NULL,  -- Start of the token, to tell tokens apart
+    scopes TEXT NOT NULL,  -- JSON array of scopes
+
+    expires_at DATETIME,  -- NULL = never expires
+    last_used_at DATETIME,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_tokens_user ON api_tokens(user_id);
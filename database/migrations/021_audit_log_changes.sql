@@ -0,0 +1,8 @@
+-- Before/after diff of audited changes
+--
+-- JSON object mapping each changed field to {"before": ..., "after": ...};
+-- NULL for actions that don't change a stored record (runs, denials).
+
+ALTER TABLE audit_log ADD COLUMN changes TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_action ON audit_log(action, created_at DESC);
@@ -0,0 +1,25 @@
+-- Backend types come from the server's backend registry, so the type is no
+-- longer restricted to gotify/ntfy
+-- SQLite cannot drop a CHECK constraint in place, so the table is rebuilt
+
+CREATE TABLE notification_backends_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    type TEXT NOT NULL,  -- Registered backend type ('gotify', 'ntfy', ...)
+    name TEXT NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT 1,
+    config TEXT NOT NULL,  -- JSON configuration
+    priority INTEGER NOT NULL DEFAULT 5,  -- Higher priorities are notified first
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    
+    CHECK (priority >= 1 AND priority <= 10)
+);
+
+INSERT INTO notification_backends_new (id, type, name, enabled, config, priority, created_at, updated_at)
+SELECT id, type, name, enabled, config, priority, created_at, updated_at
+FROM notification_backends;
+
+DROP TABLE notification_backends;
+ALTER TABLE notification_backends_new RENAME TO notification_backends;
+
+CREATE INDEX IF NOT EXISTS idx_notification_backends_enabled ON notification_backends(enabled);
@@ -0,0 +1,504 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Alert rule model
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AlertRule {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub enabled: bool,
+    pub plugin_id: Option<String>, // NULL = any plugin
+    pub metric_name: String,
+    pub server_id: Option<i64>,     // NULL = any server
+    pub server_tag: Option<String>, // NULL = any tag
+    pub operator: String,           // >, >=, <, <=, ==, !=
+    pub threshold: f64,
+    pub duration_secs: i64,
+    pub severity: String, // info, warning, critical
+    pub notify_resolved: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Create alert rule input
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateAlertRule {
+    pub name: String,
+    pub description: Option<String>,
+    pub plugin_id: Option<String>,
+    pub metric_name: String,
+    pub server_id: Option<i64>,
+    pub server_tag: Option<String>,
+    pub operator: String,
+    pub threshold: f64,
+    #[serde(default)]
+    pub duration_secs: i64,
+    #[serde(default = "default_severity")]
+    pub severity: String,
+    #[serde(default = "default_true")]
+    pub notify_resolved: bool,
+}
+
+/// Update alert rule input
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateAlertRule {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub enabled: Option<bool>,
+    pub plugin_id: Option<String>,
+    pub metric_name: Option<String>,
+    pub server_id: Option<i64>,
+    pub server_tag: Option<String>,
+    pub operator: Option<String>,
+    pub threshold: Option<f64>,
+    pub duration_secs: Option<i64>,
+    pub severity: Option<String>,
+    pub notify_resolved: Option<bool>,
+}
+
+/// Current state of one (rule, server, metric) combination
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AlertState {
+    pub id: i64,
+    pub rule_id: i64,
+    pub rule_name: String,
+    pub severity: String,
+    pub server_id: Option<i64>,
+    pub server_name: String,
+    pub metric_name: String,
+    pub state: String, // ok, pending, firing, resolved
+    pub value: Option<f64>,
+    pub pending_since: Option<DateTime<Utc>>,
+    pub firing_since: Option<DateTime<Utc>>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub last_evaluated_at: DateTime<Utc>,
+}
+
+/// Alert state to upsert after an evaluation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertStateUpdate {
+    pub rule_id: i64,
+    pub server_id: Option<i64>,
+    pub server_name: String,
+    pub metric_name: String,
+    pub status: AlertStatus,
+    pub value: f64,
+    pub pending_since: Option<DateTime<Utc>>,
+    pub firing_since: Option<DateTime<Utc>>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+/// Alert history entry
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AlertEvent {
+    pub id: i64,
+    pub rule_id: i64,
+    pub rule_name: String,
+    pub server_name: String,
+    pub metric_name: String,
+    pub event: String, // firing, resolved
+    pub severity: String,
+    pub value: f64,
+    pub threshold: f64,
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// New alert history entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewAlertEvent {
+    pub rule_id: i64,
+    pub server_name: String,
+    pub metric_name: String,
+    pub event: String,
+    pub severity: String,
+    pub value: f64,
+    pub threshold: f64,
+    pub message: String,
+}
+
+fn default_severity() -> String {
+    "warning".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Comparison operator of an alert rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AlertOperator {
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Eq,
+    Ne,
+}
+
+impl AlertOperator {
+    /// Parse an operator from its symbol
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim() {
+            ">" => Some(Self::Gt),
+            ">=" => Some(Self::Ge),
+            "<" => Some(Self::Lt),
+            "<=" => Some(Self::Le),
+            "==" | "=" => Some(Self::Eq),
+            "!=" => Some(Self::Ne),
+            _ => None,
+        }
+    }
+
+    /// Operator symbol as stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Gt => ">",
+            Self::Ge => ">=",
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Eq => "==",
+            Self::Ne => "!=",
+        }
+    }
+
+    /// Compare a value against a threshold
+    pub fn compare(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            Self::Gt => value > threshold,
+            Self::Ge => value >= threshold,
+            Self::Lt => value < threshold,
+            Self::Le => value <= threshold,
+            Self::Eq => (value - threshold).abs() < f64::EPSILON,
+            Self::Ne => (value - threshold).abs() >= f64::EPSILON,
+        }
+    }
+}
+
+/// Valid alert severities
+pub const ALERT_SEVERITIES: &[&str] = &["info", "warning", "critical"];
+
+/// State of an alert for one server/metric
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertStatus {
+    Ok,
+    Pending,
+    Firing,
+    Resolved,
+}
+
+impl AlertStatus {
+    /// Parse a status as stored in the database (unknown values are treated as ok)
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "pending" => Self::Pending,
+            "firing" => Self::Firing,
+            "resolved" => Self::Resolved,
+            _ => Self::Ok,
+        }
+    }
+
+    /// Status as stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::Pending => "pending",
+            Self::Firing => "firing",
+            Self::Resolved => "resolved",
+        }
+    }
+}
+
+/// What changed as the result of evaluating a sample
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertTransition {
+    /// Nothing worth reporting (includes repeated samples of a firing alert)
+    None,
+    /// Condition started holding, waiting for `duration_secs`
+    Pending,
+    /// Alert started firing - notify
+    Fired,
+    /// Firing alert recovered - notify if `notify_resolved`
+    Resolved,
+    /// Pending alert recovered before it fired
+    Cleared,
+}
+
+/// Outcome of evaluating a rule against one sample
+#[derive(Debug, Clone)]
+pub struct AlertEvaluation {
+    pub status: AlertStatus,
+    pub pending_since: Option<DateTime<Utc>>,
+    pub firing_since: Option<DateTime<Utc>>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub transition: AlertTransition,
+}
+
+impl AlertRule {
+    /// Parsed comparison operator
+    pub fn operator(&self) -> Option<AlertOperator> {
+        AlertOperator::parse(&self.operator)
+    }
+
+    /// Check whether a value breaches the rule's threshold
+    pub fn condition_met(&self, value: f64) -> bool {
+        self.operator()
+            .map(|op| op.compare(value, self.threshold))
+            .unwrap_or(false)
+    }
+
+    /// Check whether a metric name matches this rule
+    ///
+    /// Matches the exact name, a trailing `*` prefix, or the base name of a
+    /// qualified metric (`disk_used_percent` matches `disk_used_percent:/var`).
+    pub fn matches_metric(&self, metric_name: &str) -> bool {
+        if let Some(prefix) = self.metric_name.strip_suffix('*') {
+            return metric_name.starts_with(prefix);
+        }
+        metric_name == self.metric_name
+            || metric_name
+                .split_once(':')
+                .is_some_and(|(base, _)| base == self.metric_name)
+    }
+
+    /// Check whether a sample is in scope for this rule
+    pub fn matches(
+        &self,
+        plugin_id: &str,
+        metric_name: &str,
+        server_id: Option<i64>,
+        server_tags: &[String],
+    ) -> bool {
+        if self.plugin_id.as_deref().is_some_and(|p| p != plugin_id) {
+            return false;
+        }
+        if self.server_id.is_some() && self.server_id != server_id {
+            return false;
+        }
+        if let Some(tag) = &self.server_tag {
+            if !server_tags.iter().any(|t| t == tag) {
+                return false;
+            }
+        }
+        self.matches_metric(metric_name)
+    }
+
+    /// Advance the alert state machine with a new sample
+    ///
+    /// A breach moves ok → pending → firing once it has held for
+    /// `duration_secs`; further breaches while firing are suppressed.
+    /// Recovery moves firing → resolved, or pending → ok silently.
+    pub fn evaluate(
+        &self,
+        current: Option<&AlertState>,
+        value: f64,
+        now: DateTime<Utc>,
+    ) -> AlertEvaluation {
+        let status = current
+            .map(|s| AlertStatus::parse(&s.state))
+            .unwrap_or(AlertStatus::Ok);
+        let pending_since = current.and_then(|s| s.pending_since);
+        let firing_since = current.and_then(|s| s.firing_since);
+        let resolved_at = current.and_then(|s| s.resolved_at);
+
+        if self.condition_met(value) {
+            if status == AlertStatus::Firing {
+                return AlertEvaluation {
+                    status,
+                    pending_since,
+                    firing_since,
+                    resolved_at,
+                    transition: AlertTransition::None,
+                };
+            }
+
+            let since = match (status, pending_since) {
+                (AlertStatus::Pending, Some(since)) => since,
+                _ => now,
+            };
+
+            if (now - since).num_seconds() >= self.duration_secs {
+                AlertEvaluation {
+                    status: AlertStatus::Firing,
+                    pending_since: Some(since),
+                    firing_since: Some(now),
+                    resolved_at: None,
+                    transition: AlertTransition::Fired,
+                }
+            } else {
+                AlertEvaluation {
+                    status: AlertStatus::Pending,
+                    pending_since: Some(since),
+                    firing_since: None,
+                    resolved_at,
+                    transition: if status == AlertStatus::Pending {
+                        AlertTransition::None
+                    } else {
+                        AlertTransition::Pending
+                    },
+                }
+            }
+        } else {
+            match status {
+                AlertStatus::Firing => AlertEvaluation {
+                    status: AlertStatus::Resolved,
+                    pending_since: None,
+                    firing_since,
+                    resolved_at: Some(now),
+                    transition: AlertTransition::Resolved,
+                },
+                AlertStatus::Pending => AlertEvaluation {
+                    status: AlertStatus::Ok,
+                    pending_since: None,
+                    firing_since: None,
+                    resolved_at,
+                    transition: AlertTransition::Cleared,
+                },
+                _ => AlertEvaluation {
+                    status,
+                    pending_since: None,
+                    firing_since,
+                    resolved_at,
+                    transition: AlertTransition::None,
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn rule(operator: &str, threshold: f64, duration_secs: i64) -> AlertRule {
+        AlertRule {
+            id: 1,
+            name: "High CPU".to_string(),
+            description: None,
+            enabled: true,
+            plugin_id: Some("health".to_string()),
+            metric_name: "cpu_usage_percent".to_string(),
+            server_id: None,
+            server_tag: None,
+            operator: operator.to_string(),
+            threshold,
+            duration_secs,
+            severity: "warning".to_string(),
+            notify_resolved: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn state_from(rule: &AlertRule, eval: &AlertEvaluation, now: DateTime<Utc>) -> AlertState {
+        AlertState {
+            id: 1,
+            rule_id: rule.id,
+            rule_name: rule.name.clone(),
+            severity: rule.severity.clone(),
+            server_id: None,
+            server_name: "localhost".to_string(),
+            metric_name: rule.metric_name.clone(),
+            state: eval.status.as_str().to_string(),
+            value: None,
+            pending_since: eval.pending_since,
+            firing_since: eval.firing_since,
+            resolved_at: eval.resolved_at,
+            last_evaluated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_operators() {
+        assert!(AlertOperator::parse(">").unwrap().compare(91.0, 90.0));
+        assert!(!AlertOperator::parse(">").unwrap().compare(90.0, 90.0));
+        assert!(AlertOperator::parse(">=").unwrap().compare(90.0, 90.0));
+        assert!(AlertOperator::parse("<").unwrap().compare(1.0, 2.0));
+        assert!(AlertOperator::parse("==").unwrap().compare(0.0, 0.0));
+        assert!(AlertOperator::parse("!=").unwrap().compare(1.0, 0.0));
+        assert!(AlertOperator::parse("~").is_none());
+    }
+
+    #[test]
+    fn test_matches() {
+        let mut r = rule(">", 90.0, 0);
+        assert!(r.matches("health", "cpu_usage_percent", None, &[]));
+        assert!(!r.matches("docker", "cpu_usage_percent", None, &[]));
+
+        r.metric_name = "disk_used_percent".to_string();
+        assert!(r.matches("health", "disk_used_percent:/var", None, &[]));
+        assert!(!r.matches("health", "disk_used_bytes:/var", None, &[]));
+
+        r.metric_name = "net_*".to_string();
+        assert!(r.matches("health", "net_rx_errors:eth0", None, &[]));
+
+        r.server_tag = Some("prod".to_string());
+        assert!(!r.matches("health", "net_rx_errors:eth0", Some(1), &[]));
+        assert!(r.matches(
+            "health",
+            "net_rx_errors:eth0",
+            Some(1),
+            &["prod".to_string()]
+        ));
+
+        r.server_id = Some(2);
+        assert!(!r.matches(
+            "health",
+            "net_rx_errors:eth0",
+            Some(1),
+            &["prod".to_string()]
+        ));
+    }
+
+    #[test]
+    fn test_fires_after_duration_and_dedupes() {
+        let r = rule(">", 90.0, 300);
+        let t0 = Utc::now();
+
+        let eval = r.evaluate(None, 95.0, t0);
+        assert_eq!(eval.status, AlertStatus::Pending);
+        assert_eq!(eval.transition, AlertTransition::Pending);
+
+        let state = state_from(&r, &eval, t0);
+        let eval = r.evaluate(Some(&state), 95.0, t0 + Duration::seconds(120));
+        assert_eq!(eval.status, AlertStatus::Pending);
+        assert_eq!(eval.transition, AlertTransition::None);
+        assert_eq!(eval.pending_since, Some(t0));
+
+        let eval = r.evaluate(Some(&state), 95.0, t0 + Duration::seconds(300));
+        assert_eq!(eval.status, AlertStatus::Firing);
+        assert_eq!(eval.transition, AlertTransition::Fired);
+
+        let state = state_from(&r, &eval, t0);
+        let eval = r.evaluate(Some(&state), 99.0, t0 + Duration::seconds(360));
+        assert_eq!(eval.status, AlertStatus::Firing);
+        assert_eq!(eval.transition, AlertTransition::None);
+
+        let eval = r.evaluate(Some(&state), 50.0, t0 + Duration::seconds(420));
+        assert_eq!(eval.status, AlertStatus::Resolved);
+        assert_eq!(eval.transition, AlertTransition::Resolved);
+    }
+
+    #[test]
+    fn test_pending_clears_silently() {
+        let r = rule(">", 90.0, 300);
+        let t0 = Utc::now();
+
+        let eval = r.evaluate(None, 95.0, t0);
+        let state = state_from(&r, &eval, t0);
+        let eval = r.evaluate(Some(&state), 10.0, t0 + Duration::seconds(60));
+        assert_eq!(eval.status, AlertStatus::Ok);
+        assert_eq!(eval.transition, AlertTransition::Cleared);
+    }
+
+    #[test]
+    fn test_zero_duration_fires_immediately() {
+        let r = rule("<", 10.0, 0);
+        let eval = r.evaluate(None, 5.0, Utc::now());
+        assert_eq!(eval.transition, AlertTransition::Fired);
+    }
+}
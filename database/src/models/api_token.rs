@@ -0,0 +1,84 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// API token model
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ApiToken {
+    pub id: i64,
+    pub user_id: i64,
+    pub name: String,
+    /// Hex SHA-256 of the token; never serialized
+    #[serde(skip_serializing)]
+    pub token_hash: String,
+    /// Start of the token, to tell tokens apart
+    pub token_prefix: String,
+    /// JSON array of scopes
+    pub scopes: String,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Create API token input
+#[derive(Debug, Clone)]
+pub struct CreateApiToken {
+    pub user_id: i64,
+    pub name: String,
+    pub token_hash: String,
+    pub token_prefix: String,
+    pub scopes: Vec<ApiTokenScope>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// What an API token may be used for, on top of its user's role
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiTokenScope {
+    /// Read requests (`GET`)
+    Read,
+    /// Operator actions: running tasks and workflows, update actions
+    Operate,
+    /// Admin actions: managing servers, plugins, tasks, and users
+    Admin,
+}
+
+impl ApiTokenScope {
+    /// All scopes, in order
+    pub const ALL: [Self; 3] = [Self::Read, Self::Operate, Self::Admin];
+
+    /// Parse a scope from its name
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim() {
+            "read" => Some(Self::Read),
+            "operate" => Some(Self::Operate),
+            "admin" => Some(Self::Admin),
+            _ => None,
+        }
+    }
+
+    /// Scope as stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Operate => "operate",
+            Self::Admin => "admin",
+        }
+    }
+}
+
+impl ApiToken {
+    /// Get the token's scopes (unknown names are ignored)
+    pub fn get_scopes(&self) -> Vec<ApiTokenScope> {
+        serde_json::from_str::<Vec<String>>(&self.scopes)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|s| ApiTokenScope::parse(s))
+            .collect()
+    }
+
+    /// Whether the token has expired
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.is_some_and(|at| at <= Utc::now())
+    }
+}
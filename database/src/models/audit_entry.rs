@@ -0,0 +1,85 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sqlx::FromRow;
+
+/// Audit log entry
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub created_at: DateTime<Utc>,
+    pub actor: String,
+    pub action: String,
+    pub target: Option<String>,
+    /// `success`, `failure` or `denied`
+    pub outcome: String,
+    pub message: Option<String>,
+    pub source_ip: Option<String>,
+    /// JSON object of changed fields: `{"field": {"before": ..., "after": ...}}`
+    pub changes: Option<String>,
+}
+
+/// Audit log entry to record
+#[derive(Debug, Clone)]
+pub struct NewAuditEntry {
+    pub actor: String,
+    pub action: String,
+    pub target: Option<String>,
+    pub outcome: AuditOutcome,
+    pub message: Option<String>,
+    pub source_ip: Option<String>,
+    pub changes: Option<JsonValue>,
+}
+
+/// Audit log search; unset fields match everything
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    pub actor: Option<String>,
+    /// Exact action, or a prefix ending in `.` (e.g. `server.`)
+    pub action: Option<String>,
+    /// Substring of the target
+    pub target: Option<String>,
+    pub outcome: Option<AuditOutcome>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// How an audited action ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditOutcome {
+    Success,
+    Failure,
+    /// Refused because the actor lacks the role or server scope
+    Denied,
+}
+
+impl AuditOutcome {
+    /// Parse an outcome from its name
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim() {
+            "success" => Some(Self::Success),
+            "failure" => Some(Self::Failure),
+            "denied" => Some(Self::Denied),
+            _ => None,
+        }
+    }
+
+    /// Outcome as stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Failure => "failure",
+            Self::Denied => "denied",
+        }
+    }
+}
+
+impl AuditEntry {
+    /// Get the changed fields as JSON
+    pub fn get_changes(&self) -> Option<JsonValue> {
+        self.changes
+            .as_ref()
+            .and_then(|c| serde_json::from_str(c).ok())
+    }
+}
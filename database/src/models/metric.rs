@@ -0,0 +1,85 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sqlx::FromRow;
+
+/// Metric sample model
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Metric {
+    pub id: i64,
+    pub server_id: Option<i64>, // NULL = local / unregistered host
+    pub plugin_id: String,
+    pub metric_name: String,
+    pub metric_value: f64,
+    pub metric_unit: Option<String>,
+    pub metadata: Option<String>, // JSON string
+    pub timestamp: DateTime<Utc>,
+}
+
+/// New metric sample to record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewMetric {
+    pub server_id: Option<i64>,
+    pub plugin_id: String,
+    pub metric_name: String,
+    pub metric_value: f64,
+    pub metric_unit: Option<String>,
+    pub metadata: Option<JsonValue>,
+    /// Sample time (defaults to now)
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+/// Time-series query filter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricQuery {
+    pub plugin_id: Option<String>,
+    pub server_id: Option<i64>,
+    /// Exact metric name, or a prefix ending in `*` (e.g. `disk_used_percent:*`)
+    pub metric_name: Option<String>,
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    /// Down-sampling bucket width in seconds (1 = raw samples)
+    pub bucket_secs: i64,
+    pub limit: i64,
+}
+
+/// Down-sampled time-series bucket
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct MetricBucket {
+    pub server_id: Option<i64>,
+    pub plugin_id: String,
+    pub metric_name: String,
+    pub bucket_start: i64, // Unix timestamp (seconds)
+    pub avg_value: f64,
+    pub min_value: f64,
+    pub max_value: f64,
+    pub sample_count: i64,
+}
+
+/// A distinct metric series and when it was last reported
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct MetricSeriesInfo {
+    pub server_id: Option<i64>,
+    pub plugin_id: String,
+    pub metric_name: String,
+    pub metric_unit: Option<String>,
+    pub sample_count: i64,
+    pub last_seen: DateTime<Utc>,
+}
+
+impl Metric {
+    /// Get metadata as JSON value
+    pub fn get_metadata(&self) -> JsonValue {
+        self.metadata
+            .as_ref()
+            .and_then(|m| serde_json::from_str(m).ok())
+            .unwrap_or(JsonValue::Object(serde_json::Map::new()))
+    }
+}
+
+impl MetricBucket {
+    /// Bucket start as a UTC timestamp
+    pub fn start_time(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp(self.bucket_start, 0).unwrap_or_default()
+    }
+}
@@ -1,14 +1,27 @@
 // Database models
 
-pub mod server;
-pub mod plugin;
+pub mod alert;
+pub mod api_token;
+pub mod audit_entry;
+pub mod metric;
 pub mod notification;
-pub mod task;
+pub mod plugin;
+pub mod server;
 pub mod setting;
+pub mod task;
+pub mod update;
+pub mod user;
+pub mod workflow;
 
-pub use server::*;
-pub use plugin::*;
+pub use alert::*;
+pub use api_token::*;
+pub use audit_entry::*;
+pub use metric::*;
 pub use notification::*;
-pub use task::*;
+pub use plugin::*;
+pub use server::*;
 pub use setting::*;
-
+pub use task::*;
+pub use update::*;
+pub use user::*;
+pub use workflow::*;
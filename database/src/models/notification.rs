@@ -8,10 +8,10 @@ use sqlx::FromRow;
 pub struct NotificationBackend {
     pub id: i64,
     #[sqlx(rename = "type")]
-    pub backend_type: String,  // 'gotify', 'ntfy'
+    pub backend_type: String, // Registered backend type ('gotify', 'ntfy', ...)
     pub name: String,
     pub enabled: bool,
-    pub config: String,  // JSON string
+    pub config: String, // JSON string
     pub priority: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -26,6 +26,8 @@ pub struct CreateNotificationBackend {
     pub config: JsonValue,
     #[serde(default = "default_priority")]
     pub priority: i32,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
 }
 
 /// Update notification backend input
@@ -41,11 +43,13 @@ fn default_priority() -> i32 {
     5
 }
 
+fn default_true() -> bool {
+    true
+}
+
 impl NotificationBackend {
     /// Get config as JSON value
     pub fn get_config(&self) -> JsonValue {
-        serde_json::from_str(&self.config)
-            .unwrap_or(JsonValue::Object(serde_json::Map::new()))
+        serde_json::from_str(&self.config).unwrap_or(JsonValue::Object(serde_json::Map::new()))
     }
 }
-
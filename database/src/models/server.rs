@@ -13,7 +13,7 @@ pub struct Server {
     pub ssh_key_path: Option<String>,
     pub enabled: bool,
     pub description: Option<String>,
-    pub tags: Option<String>,  // JSON array
+    pub tags: Option<String>, // JSON array
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub last_seen_at: Option<DateTime<Utc>>,
@@ -22,6 +22,16 @@ pub struct Server {
     pub docker_installed: bool,
     pub connection_timeout: i32,
     pub retry_attempts: i32,
+    /// How to reach the Docker daemon; `None` = local socket or SSH tunnel
+    pub docker_endpoint: Option<String>,
+    /// Directory with the TLS client certificates for `https://` endpoints
+    pub docker_cert_path: Option<String>,
+    /// Shell script run before applying updates
+    pub pre_update_hook: Option<String>,
+    /// Shell script run after applying updates (health probe)
+    pub post_update_hook: Option<String>,
+    /// Shell script run when the post-update hook fails
+    pub rollback_hook: Option<String>,
 }
 
 /// Create server input
@@ -36,6 +46,16 @@ pub struct CreateServer {
     pub ssh_key_path: Option<String>,
     pub description: Option<String>,
     pub tags: Option<Vec<String>>,
+    #[serde(default)]
+    pub docker_endpoint: Option<String>,
+    #[serde(default)]
+    pub docker_cert_path: Option<String>,
+    #[serde(default)]
+    pub pre_update_hook: Option<String>,
+    #[serde(default)]
+    pub post_update_hook: Option<String>,
+    #[serde(default)]
+    pub rollback_hook: Option<String>,
 }
 
 /// Update server input
@@ -51,6 +71,16 @@ pub struct UpdateServer {
     pub tags: Option<Vec<String>>,
     pub connection_timeout: Option<i32>,
     pub retry_attempts: Option<i32>,
+    /// Empty string = clear
+    pub docker_endpoint: Option<String>,
+    /// Empty string = clear
+    pub docker_cert_path: Option<String>,
+    /// Empty string = clear
+    pub pre_update_hook: Option<String>,
+    /// Empty string = clear
+    pub post_update_hook: Option<String>,
+    /// Empty string = clear
+    pub rollback_hook: Option<String>,
 }
 
 fn default_port() -> i32 {
@@ -70,4 +100,3 @@ impl Server {
             .unwrap_or_default()
     }
 }
-
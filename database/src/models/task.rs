@@ -10,17 +10,25 @@ pub struct Task {
     pub name: String,
     pub description: Option<String>,
     pub plugin_id: String,
-    pub server_id: Option<i64>,
-    pub schedule: String,  // Cron expression
+    pub server_id: Option<i64>, // Set when target = server
+    pub schedule: String,       // Cron expression
     pub enabled: bool,
     pub command: String,
-    pub args: Option<String>,  // JSON string
+    pub args: Option<String>, // JSON string
     pub timeout: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub last_run_at: Option<DateTime<Utc>>,
     pub next_run_at: Option<DateTime<Utc>>,
     pub run_count: i32,
+    pub catch_up: String, // skip, once, all
+    pub missed_runs: i64,
+    pub last_missed_at: Option<DateTime<Utc>>,
+    pub overlap_policy: String,      // skip, queue, kill
+    pub retry_attempts: Option<i32>, // None = use the server's setting
+    pub target: String,              // local, server, all, tags
+    pub target_tags: Option<String>, // JSON array, for target = tags
+    pub last_status: Option<String>, // success, partial, failed, cancelled
 }
 
 /// Create task input
@@ -35,10 +43,34 @@ pub struct CreateTask {
     pub args: Option<JsonValue>,
     #[serde(default = "default_timeout")]
     pub timeout: i32,
+    #[serde(default = "default_catch_up")]
+    pub catch_up: String,
+    #[serde(default = "default_overlap_policy")]
+    pub overlap_policy: String,
+    #[serde(default)]
+    pub retry_attempts: Option<i32>,
+    /// `local`, `server`, `all` or `tags`; defaults to `server` when
+    /// `server_id` is set and `local` otherwise
+    #[serde(default)]
+    pub target: Option<String>,
+    /// Servers with any of these tags, for target `tags`
+    #[serde(default)]
+    pub target_tags: Option<Vec<String>>,
+}
+
+impl CreateTask {
+    /// Target name to store, filling in the default
+    pub fn target_name(&self) -> &str {
+        match (&self.target, self.server_id) {
+            (Some(target), _) => target,
+            (None, Some(_)) => "server",
+            (None, None) => "local",
+        }
+    }
 }
 
 /// Update task input
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct UpdateTask {
     pub name: Option<String>,
     pub description: Option<String>,
@@ -47,6 +79,14 @@ pub struct UpdateTask {
     pub command: Option<String>,
     pub args: Option<JsonValue>,
     pub timeout: Option<i32>,
+    pub catch_up: Option<String>,
+    pub overlap_policy: Option<String>,
+    /// Negative = clear the override and use the server's setting
+    pub retry_attempts: Option<i32>,
+    /// 0 = clear
+    pub server_id: Option<i64>,
+    pub target: Option<String>,
+    pub target_tags: Option<Vec<String>>,
 }
 
 /// Task history model
@@ -68,6 +108,7 @@ pub struct TaskHistory {
     pub success: bool,
     pub message: Option<String>,
     pub timestamp: DateTime<Utc>,
+    pub attempt: i32,
 }
 
 /// Task history entry for recording execution results
@@ -81,13 +122,130 @@ pub struct TaskHistoryEntry {
     pub error: Option<String>,
     pub duration_ms: u64,
     pub executed_at: DateTime<Utc>,
+    /// Attempt number (1 = first try)
+    pub attempt: i32,
+}
+
+/// Start of a task execution attempt, recorded with status `running`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewTaskExecution {
+    pub task_id: i64,
+    pub plugin_id: String,
+    pub server_id: Option<i64>,
+    /// Attempt number (1 = first try)
+    pub attempt: i32,
+    /// What started the execution (`schedule` or `manual`)
+    pub triggered_by: String,
+    pub started_at: DateTime<Utc>,
+}
+
+/// Outcome of a task execution attempt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskExecutionOutcome {
+    /// `success`, `failed` or `cancelled`
+    pub status: String,
+    pub output: String,
+    pub error: Option<String>,
+    /// Exit code of the remote command, if it ran to completion
+    pub exit_code: Option<i32>,
+    /// Full stdout/stderr of the remote command (`None` for plugin tasks)
+    pub stdout: Option<String>,
+    pub stderr: Option<String>,
+    pub duration_ms: u64,
+    pub finished_at: DateTime<Utc>,
 }
 
 fn default_timeout() -> i32 {
     300
 }
 
+fn default_catch_up() -> String {
+    "skip".to_string()
+}
+
+fn default_overlap_policy() -> String {
+    "skip".to_string()
+}
+
+/// Where a task runs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskTarget {
+    /// The plugin runs on this host
+    Local,
+    /// The command runs on one server
+    Server(i64),
+    /// The command runs on every enabled server
+    AllServers,
+    /// The command runs on every enabled server with any of the tags
+    Tags(Vec<String>),
+}
+
+impl TaskTarget {
+    /// Build a target from its stored parts, checking they fit together
+    pub fn from_parts(
+        target: &str,
+        server_id: Option<i64>,
+        tags: &[String],
+    ) -> Result<Self, String> {
+        match target {
+            "local" => Ok(Self::Local),
+            "server" => server_id
+                .map(Self::Server)
+                .ok_or_else(|| "Target 'server' needs a server_id".to_string()),
+            "all" => Ok(Self::AllServers),
+            "tags" => {
+                let tags: Vec<String> = tags
+                    .iter()
+                    .map(|t| t.trim().to_string())
+                    .filter(|t| !t.is_empty())
+                    .collect();
+                if tags.is_empty() {
+                    return Err("Target 'tags' needs at least one tag".to_string());
+                }
+                Ok(Self::Tags(tags))
+            }
+            other => Err(format!(
+                "Invalid target '{}' (expected local, server, all or tags)",
+                other
+            )),
+        }
+    }
+
+    /// Whether the target runs a command on remote servers rather than a local plugin
+    pub fn is_remote(&self) -> bool {
+        !matches!(self, Self::Local)
+    }
+
+    /// Whether a server is one of the target's servers
+    ///
+    /// Disabled servers are never targeted.
+    pub fn includes(&self, server: &crate::models::Server) -> bool {
+        if !server.enabled {
+            return false;
+        }
+        match self {
+            Self::Local => false,
+            Self::Server(id) => server.id == *id,
+            Self::AllServers => true,
+            Self::Tags(tags) => server.get_tags().iter().any(|t| tags.contains(t)),
+        }
+    }
+}
+
 impl Task {
+    /// Where the task runs
+    pub fn target(&self) -> Result<TaskTarget, String> {
+        TaskTarget::from_parts(&self.target, self.server_id, &self.get_target_tags())
+    }
+
+    /// Get target tags as a vector
+    pub fn get_target_tags(&self) -> Vec<String> {
+        self.target_tags
+            .as_ref()
+            .and_then(|t| serde_json::from_str(t).ok())
+            .unwrap_or_default()
+    }
+
     /// Get args as JSON value
     pub fn get_args(&self) -> JsonValue {
         self.args
@@ -97,3 +255,78 @@ impl Task {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Server;
+
+    fn server(id: i64, enabled: bool, tags: Option<&str>) -> Server {
+        Server {
+            id,
+            name: format!("server-{}", id),
+            host: Some("10.0.0.1".to_string()),
+            port: 22,
+            username: "root".to_string(),
+            ssh_key_path: None,
+            enabled,
+            description: None,
+            tags: tags.map(String::from),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            last_seen_at: None,
+            os_type: None,
+            os_version: None,
+            docker_installed: false,
+            connection_timeout: 30,
+            retry_attempts: 3,
+            docker_endpoint: None,
+            docker_cert_path: None,
+            pre_update_hook: None,
+            post_update_hook: None,
+            rollback_hook: None,
+        }
+    }
+
+    #[test]
+    fn test_target_from_parts() {
+        assert_eq!(
+            TaskTarget::from_parts("local", None, &[]),
+            Ok(TaskTarget::Local)
+        );
+        assert_eq!(
+            TaskTarget::from_parts("server", Some(3), &[]),
+            Ok(TaskTarget::Server(3))
+        );
+        assert!(TaskTarget::from_parts("server", None, &[]).is_err());
+        assert_eq!(
+            TaskTarget::from_parts("all", None, &[]),
+            Ok(TaskTarget::AllServers)
+        );
+        assert_eq!(
+            TaskTarget::from_parts("tags", None, &[" web ".to_string(), String::new()]),
+            Ok(TaskTarget::Tags(vec!["web".to_string()]))
+        );
+        assert!(TaskTarget::from_parts("tags", None, &[]).is_err());
+        assert!(TaskTarget::from_parts("everywhere", None, &[]).is_err());
+    }
+
+    #[test]
+    fn test_target_includes() {
+        let web = server(1, true, Some(r#"["web","prod"]"#));
+        let db = server(2, true, Some(r#"["db"]"#));
+        let untagged = server(3, true, None);
+        let disabled = server(4, false, Some(r#"["web"]"#));
+
+        let tags = TaskTarget::Tags(vec!["web".to_string(), "staging".to_string()]);
+        assert!(tags.includes(&web));
+        assert!(!tags.includes(&db));
+        assert!(!tags.includes(&untagged));
+        assert!(!tags.includes(&disabled));
+
+        assert!(TaskTarget::AllServers.includes(&untagged));
+        assert!(!TaskTarget::AllServers.includes(&disabled));
+        assert!(TaskTarget::Server(2).includes(&db));
+        assert!(!TaskTarget::Server(2).includes(&web));
+        assert!(!TaskTarget::Local.includes(&web));
+    }
+}
@@ -0,0 +1,151 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Package update found by a server's most recent updates check
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PendingUpdate {
+    pub id: i64,
+    pub server_id: Option<i64>, // NULL = local / unregistered host
+    pub server_name: String,
+    pub name: String,
+    pub arch: Option<String>,
+    pub current_version: Option<String>,
+    pub new_version: Option<String>,
+    pub repository: Option<String>,
+    pub is_security: bool,
+    pub severity: Option<String>,
+    pub checked_at: DateTime<Utc>,
+}
+
+/// Package update to record, as reported by the updates plugin
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NewPendingUpdate {
+    pub name: String,
+    #[serde(default)]
+    pub arch: Option<String>,
+    #[serde(default)]
+    pub current_version: Option<String>,
+    #[serde(default)]
+    pub new_version: Option<String>,
+    #[serde(default)]
+    pub repository: Option<String>,
+    #[serde(default)]
+    pub is_security: bool,
+    #[serde(default)]
+    pub severity: Option<String>,
+}
+
+/// Updates found on a server that wait for approval before being applied
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UpdateBatch {
+    pub id: i64,
+    pub server_id: Option<i64>, // NULL = local / unregistered host
+    pub server_name: String,
+    pub status: String, // pending, approved, deferred, superseded, applied, failed
+    #[serde(skip_serializing)]
+    pub packages: String, // JSON array of NewPendingUpdate, see get_packages()
+    pub package_count: i64,
+    pub security_count: i64,
+    #[serde(skip_serializing)]
+    pub token: String,
+    pub created_at: DateTime<Utc>,
+    pub decided_at: Option<DateTime<Utc>>,
+    pub decided_by: Option<String>,
+    pub decided_via: Option<String>, // api, notification
+    pub applied_at: Option<DateTime<Utc>>,
+    pub result: Option<String>,
+}
+
+impl UpdateBatch {
+    /// Parse the stored package list
+    pub fn get_packages(&self) -> Vec<NewPendingUpdate> {
+        serde_json::from_str(&self.packages).unwrap_or_default()
+    }
+
+    /// Whether the batch can still be approved or deferred
+    pub fn is_open(&self) -> bool {
+        matches!(self.status.as_str(), "pending" | "approved" | "deferred")
+    }
+}
+
+/// Package manager transaction recorded by an updates_apply run
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UpdateTransaction {
+    pub id: i64,
+    pub server_id: Option<i64>, // NULL = local / unregistered host
+    pub server_name: String,
+    pub package_manager: String, // apt, dnf, yum
+    pub transaction_id: String,  // dnf/yum history ID, apt history.log Start-Date
+    #[serde(skip_serializing)]
+    pub packages: String, // JSON array of TransactionPackage, see get_packages()
+    pub status: String,          // applied, rolling_back, rolled_back, rollback_failed
+    pub post_hook_failed: bool,
+    pub rollback_hook_ran: bool,
+    pub created_at: DateTime<Utc>,
+    pub rolled_back_at: Option<DateTime<Utc>>,
+    pub rolled_back_by: Option<String>,
+    pub rollback_output: Option<String>,
+}
+
+impl UpdateTransaction {
+    /// Parse the stored package list
+    pub fn get_packages(&self) -> Vec<TransactionPackage> {
+        serde_json::from_str(&self.packages).unwrap_or_default()
+    }
+
+    /// Whether the transaction can be rolled back (again)
+    pub fn can_roll_back(&self) -> bool {
+        matches!(self.status.as_str(), "applied" | "rollback_failed")
+    }
+}
+
+/// Package changed by a transaction
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TransactionPackage {
+    pub name: String,
+    #[serde(default)]
+    pub from_version: Option<String>,
+    #[serde(default)]
+    pub to_version: Option<String>,
+}
+
+/// Transaction to record, as reported by the updates plugin
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NewUpdateTransaction {
+    pub package_manager: String,
+    pub transaction_id: String,
+    #[serde(default)]
+    pub packages: Vec<TransactionPackage>,
+    #[serde(default)]
+    pub post_hook_failed: bool,
+    #[serde(default)]
+    pub rollback_hook_ran: bool,
+}
+
+/// Decision taken on an update batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateBatchDecision {
+    Approve,
+    Defer,
+}
+
+impl UpdateBatchDecision {
+    /// Parse a decision from its name (`approve` / `defer`)
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "approve" => Some(Self::Approve),
+            "defer" => Some(Self::Defer),
+            _ => None,
+        }
+    }
+
+    /// Batch status the decision leads to
+    pub fn status(&self) -> &'static str {
+        match self {
+            Self::Approve => "approved",
+            Self::Defer => "deferred",
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// User account model
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct User {
+    pub id: i64,
+    pub username: String,
+    /// argon2 PHC string; never serialized
+    #[serde(skip_serializing)]
+    pub password_hash: String,
+    pub enabled: bool,
+    /// `viewer`, `operator` or `admin`
+    pub role: String,
+    /// Tags of the servers the user may act on (JSON array); `None` = all
+    pub server_tags: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub last_login_at: Option<DateTime<Utc>>,
+}
+
+/// Create user input
+#[derive(Debug, Clone)]
+pub struct CreateUser {
+    pub username: String,
+    /// Already hashed password
+    pub password_hash: String,
+    pub role: UserRole,
+    /// Empty = all servers
+    pub server_tags: Vec<String>,
+}
+
+/// Update user input
+#[derive(Debug, Clone, Default)]
+pub struct UpdateUser {
+    /// Already hashed password
+    pub password_hash: Option<String>,
+    pub role: Option<UserRole>,
+    /// Empty = all servers
+    pub server_tags: Option<Vec<String>>,
+    pub enabled: Option<bool>,
+}
+
+/// What a user is allowed to do; each role includes the ones before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UserRole {
+    /// Read-only access
+    Viewer,
+    /// Runs tasks, workflows, and update actions
+    Operator,
+    /// Manages servers, plugins, notifications, tasks, and users
+    Admin,
+}
+
+impl UserRole {
+    /// Parse a role from its name
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim() {
+            "viewer" => Some(Self::Viewer),
+            "operator" => Some(Self::Operator),
+            "admin" => Some(Self::Admin),
+            _ => None,
+        }
+    }
+
+    /// Role as stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Viewer => "viewer",
+            Self::Operator => "operator",
+            Self::Admin => "admin",
+        }
+    }
+}
+
+impl User {
+    /// The user's role (unknown values are treated as viewer)
+    pub fn role(&self) -> UserRole {
+        UserRole::parse(&self.role).unwrap_or(UserRole::Viewer)
+    }
+
+    /// Get the server tags the user is scoped to (empty = all servers)
+    pub fn get_server_tags(&self) -> Vec<String> {
+        self.server_tags
+            .as_ref()
+            .and_then(|t| serde_json::from_str(t).ok())
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,805 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sqlx::FromRow;
+use std::collections::{HashMap, HashSet};
+use svrctlrs_core::PluginResult;
+
+/// Workflow model: a DAG of task / remote command steps
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Workflow {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub enabled: bool,
+    pub schedule: Option<String>, // NULL = run manually or via webhook only
+    pub steps: String,            // JSON array of WorkflowStep
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub next_run_at: Option<DateTime<Utc>>,
+}
+
+impl Workflow {
+    /// Parse the stored step definitions
+    pub fn get_steps(&self) -> Result<Vec<WorkflowStep>, String> {
+        serde_json::from_str(&self.steps)
+            .map_err(|e| format!("Invalid steps for workflow {}: {}", self.id, e))
+    }
+}
+
+/// One step of a workflow
+///
+/// A step runs either a stored task (`task_id`) or a shell command on a
+/// server (`server_id` + `command`). It starts once every step it depends
+/// on has finished and its condition holds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkflowStep {
+    /// Unique name of the step within the workflow
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_id: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+    /// When to run, see [`StepCondition`] (default: all dependencies succeeded)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+}
+
+/// Create workflow input
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateWorkflow {
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub schedule: Option<String>,
+    pub steps: Vec<WorkflowStep>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+/// Update workflow input
+///
+/// An empty `schedule` or `description` clears it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateWorkflow {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub enabled: Option<bool>,
+    pub schedule: Option<String>,
+    pub steps: Option<Vec<WorkflowStep>>,
+}
+
+/// A single run of a workflow
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WorkflowRun {
+    pub id: i64,
+    pub workflow_id: i64,
+    pub workflow_name: String,
+    pub status: String,       // running, success, failed
+    pub triggered_by: String, // schedule, manual, webhook
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub error_message: Option<String>,
+}
+
+/// State of one step within a workflow run
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WorkflowStepRun {
+    pub id: i64,
+    pub run_id: i64,
+    pub step_id: String,
+    pub status: String, // pending, running, success, failed, skipped
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub duration_ms: Option<i64>,
+    pub message: Option<String>,
+    pub result: Option<String>, // JSON PluginResult of the step
+}
+
+/// Outcome of a step, recorded when it finishes or is skipped
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowStepOutcome {
+    pub status: StepStatus,
+    pub message: Option<String>,
+    pub duration_ms: Option<i64>,
+    pub result: Option<PluginResult>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Status of a step within a workflow run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StepStatus {
+    Pending,
+    Running,
+    Success,
+    Failed,
+    Skipped,
+}
+
+impl StepStatus {
+    /// Status as stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Running => "running",
+            Self::Success => "success",
+            Self::Failed => "failed",
+            Self::Skipped => "skipped",
+        }
+    }
+
+    /// Whether the step has stopped (successfully or not)
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Success | Self::Failed | Self::Skipped)
+    }
+}
+
+/// Fields of a step's `PluginResult` a condition can test
+const RESULT_FIELDS: &[&str] = &["success", "message", "data", "metrics"];
+
+/// When a step runs, given how its dependencies finished
+///
+/// - *(none)* or `success`: every dependency succeeded
+/// - `failure`: at least one dependency failed
+/// - `always`: once every dependency has finished, whatever the outcome
+/// - an expression over the dependencies' results, e.g.
+///   `data.total_updates > 0` or `check.metrics.cpu_usage_percent >= 90 && message contains "ok"`.
+///   Paths start with `success`, `message`, `data` or `metrics` (the only
+///   dependency's result) or with a dependency's step ID. Clauses are joined
+///   with `&&` / `||` (`&&` binds tighter); a bare path tests truthiness.
+///   Expressions require every dependency to have succeeded.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StepCondition {
+    Success,
+    Failure,
+    Always,
+    /// OR of AND-ed comparisons
+    Expr(Vec<Vec<Comparison>>),
+}
+
+/// A single `path op value` test
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    /// Dependency whose result is tested (`None` = the only dependency)
+    pub step: Option<String>,
+    /// Field of the result followed by a path into it
+    pub path: Vec<String>,
+    /// Operator and literal (`None` = truthiness test)
+    pub test: Option<(CompareOp, JsonValue)>,
+}
+
+/// Comparison operator in a step condition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Contains,
+}
+
+impl CompareOp {
+    /// Operator symbols; at the same position the longest wins, so `>=` is not read as `>`
+    const SYMBOLS: &'static [(&'static str, CompareOp)] = &[
+        (">=", Self::Ge),
+        ("<=", Self::Le),
+        ("==", Self::Eq),
+        ("!=", Self::Ne),
+        (">", Self::Gt),
+        ("<", Self::Lt),
+        (" contains ", Self::Contains),
+    ];
+}
+
+impl StepCondition {
+    /// Parse a condition; `None` or blank means [`StepCondition::Success`]
+    pub fn parse(condition: Option<&str>) -> Result<Self, String> {
+        let condition = condition.map(str::trim).unwrap_or_default();
+        match condition {
+            "" | "success" => return Ok(Self::Success),
+            "failure" => return Ok(Self::Failure),
+            "always" => return Ok(Self::Always),
+            _ => {}
+        }
+
+        let mut any = Vec::new();
+        for clause in condition.split("||") {
+            let mut all = Vec::new();
+            for term in clause.split("&&") {
+                all.push(parse_comparison(term.trim())?);
+            }
+            any.push(all);
+        }
+        Ok(Self::Expr(any))
+    }
+
+    /// Steps named explicitly in the condition
+    pub fn referenced_steps(&self) -> Vec<&str> {
+        match self {
+            Self::Expr(any) => any
+                .iter()
+                .flatten()
+                .filter_map(|c| c.step.as_deref())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Whether any comparison refers to "the only dependency"
+    fn uses_implicit_step(&self) -> bool {
+        match self {
+            Self::Expr(any) => any.iter().flatten().any(|c| c.step.is_none()),
+            _ => false,
+        }
+    }
+
+    /// Decide whether a step runs once all of its dependencies have finished
+    ///
+    /// `results` holds the result of every dependency that ran.
+    pub fn should_run(
+        &self,
+        depends_on: &[String],
+        statuses: &HashMap<String, StepStatus>,
+        results: &HashMap<String, PluginResult>,
+    ) -> bool {
+        let status = |id: &String| statuses.get(id).copied().unwrap_or(StepStatus::Pending);
+        let all_succeeded = depends_on
+            .iter()
+            .all(|id| status(id) == StepStatus::Success);
+
+        match self {
+            Self::Success => all_succeeded,
+            Self::Failure => depends_on.iter().any(|id| status(id) == StepStatus::Failed),
+            Self::Always => true,
+            Self::Expr(any) => {
+                all_succeeded
+                    && any.iter().any(|all| {
+                        all.iter().all(|comparison| {
+                            let step = comparison.step.as_ref().or_else(|| depends_on.first());
+                            step.and_then(|id| results.get(id))
+                                .is_some_and(|result| comparison.matches(result))
+                        })
+                    })
+            }
+        }
+    }
+}
+
+impl Comparison {
+    /// Test the comparison against a step result; missing values never match
+    pub fn matches(&self, result: &PluginResult) -> bool {
+        let Some(value) = lookup(result, &self.path) else {
+            return false;
+        };
+
+        match &self.test {
+            None => is_truthy(&value),
+            Some((op, expected)) => compare(&value, *op, expected),
+        }
+    }
+}
+
+fn parse_comparison(term: &str) -> Result<Comparison, String> {
+    if term.is_empty() {
+        return Err("Empty clause in condition".to_string());
+    }
+
+    // The first operator in the term wins, so literals may contain operators
+    let (path, test) = match CompareOp::SYMBOLS
+        .iter()
+        .filter_map(|(symbol, op)| term.find(symbol).map(|pos| (pos, symbol, op)))
+        .min_by_key(|(pos, symbol, _)| (*pos, std::cmp::Reverse(symbol.len())))
+    {
+        Some((pos, symbol, op)) => {
+            let literal = term[pos + symbol.len()..].trim();
+            if literal.is_empty() {
+                return Err(format!("Missing value in condition '{}'", term));
+            }
+            (term[..pos].trim(), Some((*op, parse_literal(literal))))
+        }
+        None => (term, None),
+    };
+
+    let mut segments: Vec<String> = path.split('.').map(|s| s.trim().to_string()).collect();
+    if segments.iter().any(|s| s.is_empty()) {
+        return Err(format!("Invalid path '{}' in condition", path));
+    }
+
+    let step = if RESULT_FIELDS.contains(&segments[0].as_str()) {
+        None
+    } else {
+        Some(segments.remove(0))
+    };
+    match segments.first().map(String::as_str) {
+        Some(field) if RESULT_FIELDS.contains(&field) => {}
+        _ => {
+            return Err(format!(
+                "Invalid path '{}' in condition (expected {} after an optional step ID)",
+                path,
+                RESULT_FIELDS.join(", ")
+            ));
+        }
+    }
+
+    Ok(Comparison {
+        step,
+        path: segments,
+        test,
+    })
+}
+
+/// Parse a literal: number, `true`/`false`, quoted or bare string
+fn parse_literal(literal: &str) -> JsonValue {
+    if let Ok(number) = literal.parse::<f64>() {
+        return serde_json::json!(number);
+    }
+    match literal {
+        "true" => return JsonValue::Bool(true),
+        "false" => return JsonValue::Bool(false),
+        _ => {}
+    }
+    let unquoted = literal
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .or_else(|| {
+            literal
+                .strip_prefix('\'')
+                .and_then(|s| s.strip_suffix('\''))
+        })
+        .unwrap_or(literal);
+    JsonValue::String(unquoted.to_string())
+}
+
+/// Resolve `field.path...` within a plugin result
+fn lookup(result: &PluginResult, path: &[String]) -> Option<JsonValue> {
+    let (field, rest) = path.split_first()?;
+    let root = match field.as_str() {
+        "success" => JsonValue::Bool(result.success),
+        "message" => JsonValue::String(result.message.clone()),
+        "data" => result.data.clone()?,
+        "metrics" => {
+            // Metric names may themselves contain dots; take the rest as one key
+            let metrics = result.metrics.as_ref()?;
+            return match rest {
+                [] => serde_json::to_value(metrics).ok(),
+                _ => metrics.get(&rest.join(".")).map(|v| serde_json::json!(v)),
+            };
+        }
+        _ => return None,
+    };
+
+    rest.iter().try_fold(root, |value, key| match value {
+        JsonValue::Object(mut map) => map.remove(key),
+        JsonValue::Array(mut items) => {
+            let index: usize = key.parse().ok()?;
+            (index < items.len()).then(|| items.swap_remove(index))
+        }
+        _ => None,
+    })
+}
+
+fn as_number(value: &JsonValue) -> Option<f64> {
+    match value {
+        JsonValue::Number(n) => n.as_f64(),
+        JsonValue::String(s) => s.trim().parse().ok(),
+        JsonValue::Bool(b) => Some(if *b { 1.0 } else { 0.0 }),
+        _ => None,
+    }
+}
+
+fn is_truthy(value: &JsonValue) -> bool {
+    match value {
+        JsonValue::Null => false,
+        JsonValue::Bool(b) => *b,
+        JsonValue::Number(n) => n.as_f64().is_some_and(|n| n != 0.0),
+        JsonValue::String(s) => !s.is_empty(),
+        JsonValue::Array(items) => !items.is_empty(),
+        JsonValue::Object(map) => !map.is_empty(),
+    }
+}
+
+fn compare(value: &JsonValue, op: CompareOp, expected: &JsonValue) -> bool {
+    if op == CompareOp::Contains {
+        return match (value, expected) {
+            (JsonValue::String(s), JsonValue::String(needle)) => s.contains(needle.as_str()),
+            (JsonValue::Array(items), needle) => items.iter().any(|item| {
+                item == needle
+                    || matches!((as_number(item), as_number(needle)), (Some(a), Some(b)) if a == b)
+            }),
+            (JsonValue::Object(map), JsonValue::String(key)) => map.contains_key(key),
+            _ => false,
+        };
+    }
+
+    let ordering = match (as_number(value), as_number(expected)) {
+        (Some(a), Some(b)) if !matches!(value, JsonValue::String(_)) || expected.is_number() => {
+            a.partial_cmp(&b)
+        }
+        _ => match (value, expected) {
+            (JsonValue::String(a), JsonValue::String(b)) => Some(a.cmp(b)),
+            (JsonValue::Bool(a), JsonValue::Bool(b)) => Some(a.cmp(b)),
+            _ => None,
+        },
+    };
+    let Some(ordering) = ordering else {
+        // Incomparable values are only ever "not equal"
+        return op == CompareOp::Ne;
+    };
+
+    use std::cmp::Ordering;
+    match op {
+        CompareOp::Eq => ordering == Ordering::Equal,
+        CompareOp::Ne => ordering != Ordering::Equal,
+        CompareOp::Gt => ordering == Ordering::Greater,
+        CompareOp::Ge => ordering != Ordering::Less,
+        CompareOp::Lt => ordering == Ordering::Less,
+        CompareOp::Le => ordering != Ordering::Greater,
+        CompareOp::Contains => unreachable!("handled above"),
+    }
+}
+
+/// Check a workflow's steps form a valid DAG
+///
+/// Every step needs a unique ID and either a `task_id` or a `server_id` and
+/// `command`; dependencies must exist and not form a cycle, and conditions
+/// must parse and only refer to the step's own dependencies.
+pub fn validate_workflow_steps(steps: &[WorkflowStep]) -> Result<(), String> {
+    if steps.is_empty() {
+        return Err("A workflow needs at least one step".to_string());
+    }
+
+    let mut ids = HashSet::new();
+    for step in steps {
+        if step.id.is_empty()
+            || !step
+                .id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(format!(
+                "Invalid step ID '{}' (use letters, digits, '_' or '-')",
+                step.id
+            ));
+        }
+        if RESULT_FIELDS.contains(&step.id.as_str()) {
+            return Err(format!("Step ID '{}' is reserved", step.id));
+        }
+        if !ids.insert(step.id.as_str()) {
+            return Err(format!("Duplicate step ID '{}'", step.id));
+        }
+    }
+
+    for step in steps {
+        let has_command = step
+            .command
+            .as_deref()
+            .is_some_and(|c| !c.trim().is_empty());
+        match (step.task_id, step.server_id, has_command) {
+            (Some(_), None, false) => {}
+            (None, Some(_), true) => {}
+            (Some(_), _, _) => {
+                return Err(format!(
+                    "Step '{}' must run either a task or a command, not both",
+                    step.id
+                ));
+            }
+            (None, _, _) => {
+                return Err(format!(
+                    "Step '{}' needs a task_id, or a server_id and command",
+                    step.id
+                ));
+            }
+        }
+
+        for dependency in &step.depends_on {
+            if dependency == &step.id {
+                return Err(format!("Step '{}' cannot depend on itself", step.id));
+            }
+            if !ids.contains(dependency.as_str()) {
+                return Err(format!(
+                    "Step '{}' depends on unknown step '{}'",
+                    step.id, dependency
+                ));
+            }
+        }
+
+        let condition = StepCondition::parse(step.condition.as_deref())
+            .map_err(|e| format!("Step '{}': {}", step.id, e))?;
+        if condition.uses_implicit_step() && step.depends_on.len() != 1 {
+            return Err(format!(
+                "Step '{}': prefix condition paths with a step ID when the step does not have exactly one dependency",
+                step.id
+            ));
+        }
+        if let Some(unknown) = condition
+            .referenced_steps()
+            .into_iter()
+            .find(|id| !step.depends_on.iter().any(|d| d == id))
+        {
+            return Err(format!(
+                "Step '{}': condition refers to '{}', which is not one of its dependencies",
+                step.id, unknown
+            ));
+        }
+        if matches!(condition, StepCondition::Failure) && step.depends_on.is_empty() {
+            return Err(format!(
+                "Step '{}': a 'failure' condition needs a dependency",
+                step.id
+            ));
+        }
+    }
+
+    workflow_levels(steps).map(|_| ())
+}
+
+/// Group steps into levels: each step comes after every step it depends on
+///
+/// Steps in the same level can run at the same time. Fails if the
+/// dependencies contain a cycle.
+pub fn workflow_levels(steps: &[WorkflowStep]) -> Result<Vec<Vec<&WorkflowStep>>, String> {
+    let mut level_of: HashMap<&str, usize> = HashMap::new();
+    let mut levels: Vec<Vec<&WorkflowStep>> = Vec::new();
+
+    while level_of.len() < steps.len() {
+        let ready: Vec<&WorkflowStep> = steps
+            .iter()
+            .filter(|step| !level_of.contains_key(step.id.as_str()))
+            .filter(|step| {
+                step.depends_on
+                    .iter()
+                    .all(|d| level_of.contains_key(d.as_str()))
+            })
+            .collect();
+
+        if ready.is_empty() {
+            let mut stuck: Vec<&str> = steps
+                .iter()
+                .map(|s| s.id.as_str())
+                .filter(|id| !level_of.contains_key(id))
+                .collect();
+            stuck.sort_unstable();
+            return Err(format!(
+                "Workflow steps contain a dependency cycle (involving {})",
+                stuck.join(", ")
+            ));
+        }
+
+        for step in &ready {
+            level_of.insert(step.id.as_str(), levels.len());
+        }
+        levels.push(ready);
+    }
+
+    Ok(levels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task_step(id: &str, depends_on: &[&str], condition: Option<&str>) -> WorkflowStep {
+        WorkflowStep {
+            id: id.to_string(),
+            task_id: Some(1),
+            server_id: None,
+            command: None,
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+            condition: condition.map(String::from),
+        }
+    }
+
+    fn result(success: bool, data: JsonValue) -> PluginResult {
+        PluginResult {
+            success,
+            message: "Updates check: 3 packages available on web".to_string(),
+            data: Some(data),
+            metrics: Some(HashMap::from([("total_updates".to_string(), 3.0)])),
+        }
+    }
+
+    #[test]
+    fn test_levels_and_validation() {
+        let steps = vec![
+            task_step("check", &[], None),
+            task_step("apply", &["check"], Some("data.total_updates > 0")),
+            task_step("cleanup", &["apply"], Some("always")),
+            task_step("notify", &["apply", "cleanup"], Some("always")),
+        ];
+        validate_workflow_steps(&steps).unwrap();
+
+        let levels = workflow_levels(&steps).unwrap();
+        let ids: Vec<Vec<&str>> = levels
+            .iter()
+            .map(|level| level.iter().map(|s| s.id.as_str()).collect())
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                vec!["check"],
+                vec!["apply"],
+                vec!["cleanup"],
+                vec!["notify"]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_workflows() {
+        let cycle = vec![task_step("a", &["b"], None), task_step("b", &["a"], None)];
+        assert!(validate_workflow_steps(&cycle)
+            .unwrap_err()
+            .contains("cycle"));
+
+        let unknown = vec![task_step("a", &["missing"], None)];
+        assert!(validate_workflow_steps(&unknown)
+            .unwrap_err()
+            .contains("unknown step"));
+
+        let duplicate = vec![task_step("a", &[], None), task_step("a", &[], None)];
+        assert!(validate_workflow_steps(&duplicate)
+            .unwrap_err()
+            .contains("Duplicate"));
+
+        // Unprefixed paths are ambiguous with two dependencies
+        let ambiguous = vec![
+            task_step("a", &[], None),
+            task_step("b", &[], None),
+            task_step("c", &["a", "b"], Some("data.count > 1")),
+        ];
+        assert!(validate_workflow_steps(&ambiguous).is_err());
+
+        // Conditions may only look at dependencies
+        let foreign = vec![
+            task_step("a", &[], None),
+            task_step("b", &[], None),
+            task_step("c", &["a"], Some("b.success == true")),
+        ];
+        assert!(validate_workflow_steps(&foreign).is_err());
+
+        let mut both = task_step("a", &[], None);
+        both.server_id = Some(1);
+        both.command = Some("uptime".to_string());
+        assert!(validate_workflow_steps(&[both]).is_err());
+
+        assert!(validate_workflow_steps(&[]).is_err());
+    }
+
+    #[test]
+    fn test_condition_parse() {
+        assert_eq!(StepCondition::parse(None).unwrap(), StepCondition::Success);
+        assert_eq!(
+            StepCondition::parse(Some(" always ")).unwrap(),
+            StepCondition::Always
+        );
+        assert_eq!(
+            StepCondition::parse(Some("failure")).unwrap(),
+            StepCondition::Failure
+        );
+
+        let StepCondition::Expr(any) = StepCondition::parse(Some(
+            "check.data.total_updates >= 1 && message contains \"web\" || success",
+        ))
+        .unwrap() else {
+            panic!("expected an expression");
+        };
+        assert_eq!(any.len(), 2);
+        assert_eq!(any[0].len(), 2);
+        assert_eq!(any[0][0].step.as_deref(), Some("check"));
+        assert_eq!(any[0][0].path, vec!["data", "total_updates"]);
+        assert_eq!(
+            any[0][0].test,
+            Some((CompareOp::Ge, serde_json::json!(1.0)))
+        );
+        assert_eq!(
+            any[0][1].test,
+            Some((CompareOp::Contains, serde_json::json!("web")))
+        );
+        assert_eq!(any[1][0].test, None);
+
+        assert!(StepCondition::parse(Some("data.count >")).is_err());
+        assert!(StepCondition::parse(Some("check.output == 1")).is_err());
+        assert!(StepCondition::parse(Some("data..count == 1")).is_err());
+    }
+
+    #[test]
+    fn test_condition_evaluation() {
+        let deps = vec!["check".to_string()];
+        let statuses = HashMap::from([("check".to_string(), StepStatus::Success)]);
+        let results = HashMap::from([(
+            "check".to_string(),
+            result(
+                true,
+                serde_json::json!({
+                    "total_updates": 3,
+                    "package_manager": "apt",
+                    "packages": ["curl", "openssl"],
+                }),
+            ),
+        )]);
+        let runs = |condition: &str| {
+            StepCondition::parse(Some(condition))
+                .unwrap()
+                .should_run(&deps, &statuses, &results)
+        };
+
+        assert!(runs("data.total_updates > 0"));
+        assert!(!runs("data.total_updates > 3"));
+        assert!(runs(
+            "data.total_updates == 3 && data.package_manager == apt"
+        ));
+        assert!(runs("data.package_manager == 'apt'"));
+        assert!(runs("data.packages contains openssl"));
+        assert!(runs("data.packages.1 == openssl"));
+        assert!(runs("metrics.total_updates >= 3"));
+        assert!(runs("message contains \"3 packages\""));
+        assert!(!runs("message contains \"a > b\""));
+        assert!(runs("check.success"));
+        assert!(runs("data.total_updates < 1 || data.packages"));
+        // Missing values never match
+        assert!(!runs("data.reboot_required"));
+        assert!(!runs("data.reboot_required == false"));
+
+        assert!(runs("success"));
+        assert!(!runs("failure"));
+        assert!(runs("always"));
+    }
+
+    #[test]
+    fn test_condition_after_failure_or_skip() {
+        let deps = vec!["a".to_string(), "b".to_string()];
+        let results =
+            HashMap::from([("a".to_string(), result(true, serde_json::json!({ "n": 1 })))]);
+        let statuses = HashMap::from([
+            ("a".to_string(), StepStatus::Success),
+            ("b".to_string(), StepStatus::Failed),
+        ]);
+        let check = |condition: Option<&str>| {
+            StepCondition::parse(condition)
+                .unwrap()
+                .should_run(&deps, &statuses, &results)
+        };
+
+        assert!(!check(None));
+        assert!(check(Some("failure")));
+        assert!(check(Some("always")));
+        // Expressions need every dependency to have succeeded
+        assert!(!check(Some("a.data.n == 1")));
+
+        let skipped = HashMap::from([
+            ("a".to_string(), StepStatus::Success),
+            ("b".to_string(), StepStatus::Skipped),
+        ]);
+        let condition = StepCondition::parse(None).unwrap();
+        assert!(!condition.should_run(&deps, &skipped, &results));
+        assert!(!StepCondition::Failure.should_run(&deps, &skipped, &results));
+    }
+
+    #[test]
+    fn test_steps_roundtrip() {
+        let json = r#"[
+            {"id": "check", "task_id": 4},
+            {"id": "reboot", "server_id": 2, "command": "reboot", "depends_on": ["check"],
+             "condition": "data.reboot_required"}
+        ]"#;
+        let steps: Vec<WorkflowStep> = serde_json::from_str(json).unwrap();
+        validate_workflow_steps(&steps).unwrap();
+        assert_eq!(steps[1].depends_on, vec!["check"]);
+
+        let stored = serde_json::to_string(&steps).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Vec<WorkflowStep>>(&stored).unwrap(),
+            steps
+        );
+    }
+}
@@ -0,0 +1,381 @@
+use sqlx::{Pool, Sqlite};
+use svrctlrs_core::{Error, Result};
+
+use super::metrics::sql_timestamp;
+use crate::models::{
+    AlertEvent, AlertRule, AlertState, AlertStateUpdate, CreateAlertRule, NewAlertEvent,
+    UpdateAlertRule,
+};
+
+const RULE_COLUMNS: &str = r#"
+    id, name, description, enabled, plugin_id, metric_name, server_id, server_tag,
+    operator, threshold, duration_secs, severity, notify_resolved, created_at, updated_at
+"#;
+
+const STATE_SELECT: &str = r#"
+    SELECT s.id, s.rule_id, r.name AS rule_name, r.severity, s.server_id, s.server_name,
+           s.metric_name, s.state, s.value, s.pending_since, s.firing_since, s.resolved_at,
+           s.last_evaluated_at
+    FROM alert_states s
+    JOIN alert_rules r ON r.id = s.rule_id
+"#;
+
+/// List all alert rules
+pub async fn list_alert_rules(pool: &Pool<Sqlite>) -> Result<Vec<AlertRule>> {
+    sqlx::query_as::<_, AlertRule>(&format!(
+        "SELECT {} FROM alert_rules ORDER BY name",
+        RULE_COLUMNS
+    ))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to list alert rules: {}", e)))
+}
+
+/// List enabled alert rules
+pub async fn list_enabled_alert_rules(pool: &Pool<Sqlite>) -> Result<Vec<AlertRule>> {
+    sqlx::query_as::<_, AlertRule>(&format!(
+        "SELECT {} FROM alert_rules WHERE enabled = 1 ORDER BY id",
+        RULE_COLUMNS
+    ))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to list enabled alert rules: {}", e)))
+}
+
+/// Get alert rule by ID
+pub async fn get_alert_rule(pool: &Pool<Sqlite>, id: i64) -> Result<AlertRule> {
+    sqlx::query_as::<_, AlertRule>(&format!(
+        "SELECT {} FROM alert_rules WHERE id = ?",
+        RULE_COLUMNS
+    ))
+    .bind(id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to get alert rule: {}", e)))
+}
+
+/// Create a new alert rule
+pub async fn create_alert_rule(pool: &Pool<Sqlite>, rule: &CreateAlertRule) -> Result<i64> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO alert_rules (name, description, plugin_id, metric_name, server_id, server_tag,
+                                 operator, threshold, duration_secs, severity, notify_resolved)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&rule.name)
+    .bind(&rule.description)
+    .bind(&rule.plugin_id)
+    .bind(&rule.metric_name)
+    .bind(rule.server_id)
+    .bind(&rule.server_tag)
+    .bind(&rule.operator)
+    .bind(rule.threshold)
+    .bind(rule.duration_secs)
+    .bind(&rule.severity)
+    .bind(rule.notify_resolved)
+    .execute(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to create alert rule: {}", e)))?;
+
+    Ok(result.last_insert_rowid())
+}
+
+/// Update an alert rule
+///
+/// Empty strings clear the optional fields (`description`, `plugin_id`,
+/// `server_tag`) and a `server_id` of 0 clears the server filter.
+pub async fn update_alert_rule(
+    pool: &Pool<Sqlite>,
+    id: i64,
+    update: &UpdateAlertRule,
+) -> Result<()> {
+    let mut qb =
+        sqlx::QueryBuilder::<Sqlite>::new("UPDATE alert_rules SET updated_at = CURRENT_TIMESTAMP");
+
+    if let Some(name) = &update.name {
+        qb.push(", name = ").push_bind(name.clone());
+    }
+    if let Some(description) = &update.description {
+        qb.push(", description = ")
+            .push_bind(Some(description.clone()).filter(|d| !d.is_empty()));
+    }
+    if let Some(enabled) = update.enabled {
+        qb.push(", enabled = ").push_bind(enabled);
+    }
+    if let Some(plugin_id) = &update.plugin_id {
+        qb.push(", plugin_id = ")
+            .push_bind(Some(plugin_id.clone()).filter(|p| !p.is_empty()));
+    }
+    if let Some(metric_name) = &update.metric_name {
+        qb.push(", metric_name = ").push_bind(metric_name.clone());
+    }
+    if let Some(server_id) = update.server_id {
+        qb.push(", server_id = ")
+            .push_bind(Some(server_id).filter(|id| *id > 0));
+    }
+    if let Some(server_tag) = &update.server_tag {
+        qb.push(", server_tag = ")
+            .push_bind(Some(server_tag.clone()).filter(|t| !t.is_empty()));
+    }
+    if let Some(operator) = &update.operator {
+        qb.push(", operator = ").push_bind(operator.clone());
+    }
+    if let Some(threshold) = update.threshold {
+        qb.push(", threshold = ").push_bind(threshold);
+    }
+    if let Some(duration_secs) = update.duration_secs {
+        qb.push(", duration_secs = ").push_bind(duration_secs);
+    }
+    if let Some(severity) = &update.severity {
+        qb.push(", severity = ").push_bind(severity.clone());
+    }
+    if let Some(notify_resolved) = update.notify_resolved {
+        qb.push(", notify_resolved = ").push_bind(notify_resolved);
+    }
+
+    qb.push(" WHERE id = ").push_bind(id);
+
+    qb.build()
+        .execute(pool)
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to update alert rule: {}", e)))?;
+
+    Ok(())
+}
+
+/// Delete an alert rule (its states and events are removed with it)
+pub async fn delete_alert_rule(pool: &Pool<Sqlite>, id: i64) -> Result<()> {
+    sqlx::query("DELETE FROM alert_rules WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to delete alert rule: {}", e)))?;
+
+    Ok(())
+}
+
+/// Get the state of a rule for one server/metric
+pub async fn get_alert_state(
+    pool: &Pool<Sqlite>,
+    rule_id: i64,
+    server_name: &str,
+    metric_name: &str,
+) -> Result<Option<AlertState>> {
+    sqlx::query_as::<_, AlertState>(&format!(
+        "{} WHERE s.rule_id = ? AND s.server_name = ? AND s.metric_name = ?",
+        STATE_SELECT
+    ))
+    .bind(rule_id)
+    .bind(server_name)
+    .bind(metric_name)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to get alert state: {}", e)))
+}
+
+/// Insert or update the state of a rule for one server/metric
+pub async fn upsert_alert_state(pool: &Pool<Sqlite>, update: &AlertStateUpdate) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO alert_states (rule_id, server_id, server_name, metric_name, state, value,
+                                  pending_since, firing_since, resolved_at, last_evaluated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP)
+        ON CONFLICT (rule_id, server_name, metric_name) DO UPDATE SET
+            server_id = excluded.server_id,
+            state = excluded.state,
+            value = excluded.value,
+            pending_since = excluded.pending_since,
+            firing_since = excluded.firing_since,
+            resolved_at = excluded.resolved_at,
+            last_evaluated_at = excluded.last_evaluated_at
+        "#,
+    )
+    .bind(update.rule_id)
+    .bind(update.server_id)
+    .bind(&update.server_name)
+    .bind(&update.metric_name)
+    .bind(update.status.as_str())
+    .bind(update.value)
+    .bind(update.pending_since.as_ref().map(sql_timestamp))
+    .bind(update.firing_since.as_ref().map(sql_timestamp))
+    .bind(update.resolved_at.as_ref().map(sql_timestamp))
+    .execute(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to update alert state: {}", e)))?;
+
+    Ok(())
+}
+
+/// List alerts that are currently pending or firing
+pub async fn list_active_alerts(pool: &Pool<Sqlite>) -> Result<Vec<AlertState>> {
+    sqlx::query_as::<_, AlertState>(&format!(
+        "{} WHERE s.state IN ('pending', 'firing') \
+         ORDER BY s.state = 'firing' DESC, s.firing_since DESC, s.pending_since DESC",
+        STATE_SELECT
+    ))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to list active alerts: {}", e)))
+}
+
+/// Record an alert event in the history
+pub async fn record_alert_event(pool: &Pool<Sqlite>, event: &NewAlertEvent) -> Result<i64> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO alert_events (rule_id, server_name, metric_name, event, severity, value, threshold, message)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(event.rule_id)
+    .bind(&event.server_name)
+    .bind(&event.metric_name)
+    .bind(&event.event)
+    .bind(&event.severity)
+    .bind(event.value)
+    .bind(event.threshold)
+    .bind(&event.message)
+    .execute(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to record alert event: {}", e)))?;
+
+    Ok(result.last_insert_rowid())
+}
+
+/// List recent alert events, optionally for one rule
+pub async fn list_alert_events(
+    pool: &Pool<Sqlite>,
+    rule_id: Option<i64>,
+    limit: i64,
+) -> Result<Vec<AlertEvent>> {
+    sqlx::query_as::<_, AlertEvent>(
+        r#"
+        SELECT e.id, e.rule_id, r.name AS rule_name, e.server_name, e.metric_name, e.event,
+               e.severity, e.value, e.threshold, e.message, e.created_at
+        FROM alert_events e
+        JOIN alert_rules r ON r.id = e.rule_id
+        WHERE (?1 IS NULL OR e.rule_id = ?1)
+        ORDER BY e.created_at DESC, e.id DESC
+        LIMIT ?2
+        "#,
+    )
+    .bind(rule_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to list alert events: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::AlertStatus;
+    use crate::Database;
+    use chrono::Utc;
+
+    async fn test_db(name: &str) -> Database {
+        let path =
+            std::env::temp_dir().join(format!("svrctlrs-{}-{}.db", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let db = Database::new(&format!("sqlite:{}", path.display()))
+            .await
+            .unwrap();
+        db.migrate().await.unwrap();
+        db
+    }
+
+    #[tokio::test]
+    async fn test_alert_rule_state_roundtrip() {
+        let db = test_db("alerts").await;
+        let pool = db.pool();
+
+        let rule_id = create_alert_rule(
+            pool,
+            &CreateAlertRule {
+                name: "Disk almost full".to_string(),
+                description: None,
+                plugin_id: Some("health".to_string()),
+                metric_name: "disk_used_percent".to_string(),
+                server_id: None,
+                server_tag: None,
+                operator: ">=".to_string(),
+                threshold: 90.0,
+                duration_secs: 0,
+                severity: "critical".to_string(),
+                notify_resolved: true,
+            },
+        )
+        .await
+        .unwrap();
+
+        update_alert_rule(
+            pool,
+            rule_id,
+            &UpdateAlertRule {
+                threshold: Some(85.0),
+                plugin_id: Some(String::new()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let rule = get_alert_rule(pool, rule_id).await.unwrap();
+        assert_eq!(rule.threshold, 85.0);
+        assert!(rule.plugin_id.is_none());
+        assert_eq!(list_enabled_alert_rules(pool).await.unwrap().len(), 1);
+
+        let now = Utc::now();
+        let mut state = AlertStateUpdate {
+            rule_id,
+            server_id: None,
+            server_name: "localhost".to_string(),
+            metric_name: "disk_used_percent:/var".to_string(),
+            status: AlertStatus::Firing,
+            value: 95.0,
+            pending_since: Some(now),
+            firing_since: Some(now),
+            resolved_at: None,
+        };
+        upsert_alert_state(pool, &state).await.unwrap();
+        assert_eq!(list_active_alerts(pool).await.unwrap().len(), 1);
+
+        state.status = AlertStatus::Resolved;
+        state.resolved_at = Some(now);
+        upsert_alert_state(pool, &state).await.unwrap();
+        assert!(list_active_alerts(pool).await.unwrap().is_empty());
+
+        let stored = get_alert_state(pool, rule_id, "localhost", "disk_used_percent:/var")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.state, "resolved");
+        assert_eq!(stored.rule_name, "Disk almost full");
+        assert!(stored.resolved_at.is_some());
+
+        record_alert_event(
+            pool,
+            &NewAlertEvent {
+                rule_id,
+                server_name: "localhost".to_string(),
+                metric_name: "disk_used_percent:/var".to_string(),
+                event: "firing".to_string(),
+                severity: "critical".to_string(),
+                value: 95.0,
+                threshold: 85.0,
+                message: "disk_used_percent:/var = 95 (>= 85)".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            list_alert_events(pool, Some(rule_id), 10)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        delete_alert_rule(pool, rule_id).await.unwrap();
+        assert!(list_alert_events(pool, None, 10).await.unwrap().is_empty());
+    }
+}
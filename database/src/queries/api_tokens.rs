@@ -0,0 +1,195 @@
+use sqlx::{Pool, Sqlite};
+use svrctlrs_core::{Error, Result};
+
+use crate::models::{ApiToken, CreateApiToken};
+
+const API_TOKEN_COLUMNS: &str = r#"
+    id, user_id, name, token_hash, token_prefix, scopes, expires_at, last_used_at,
+    created_at
+"#;
+
+/// List a user's API tokens, newest first
+pub async fn list_api_tokens(pool: &Pool<Sqlite>, user_id: i64) -> Result<Vec<ApiToken>> {
+    sqlx::query_as::<_, ApiToken>(&format!(
+        "SELECT {} FROM api_tokens WHERE user_id = ? ORDER BY created_at DESC, id DESC",
+        API_TOKEN_COLUMNS
+    ))
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to list API tokens: {}", e)))
+}
+
+/// Get an API token by the hash of the token
+pub async fn get_api_token_by_hash(
+    pool: &Pool<Sqlite>,
+    token_hash: &str,
+) -> Result<Option<ApiToken>> {
+    sqlx::query_as::<_, ApiToken>(&format!(
+        "SELECT {} FROM api_tokens WHERE token_hash = ?",
+        API_TOKEN_COLUMNS
+    ))
+    .bind(token_hash)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to get API token: {}", e)))
+}
+
+/// Create an API token
+pub async fn create_api_token(pool: &Pool<Sqlite>, token: &CreateApiToken) -> Result<i64> {
+    let scopes: Vec<&str> = token.scopes.iter().map(|s| s.as_str()).collect();
+    let result = sqlx::query(
+        r#"
+        INSERT INTO api_tokens (user_id, name, token_hash, token_prefix, scopes, expires_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(token.user_id)
+    .bind(&token.name)
+    .bind(&token.token_hash)
+    .bind(&token.token_prefix)
+    .bind(serde_json::to_string(&scopes).unwrap_or_else(|_| "[]".to_string()))
+    .bind(token.expires_at)
+    .execute(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to create API token: {}", e)))?;
+
+    Ok(result.last_insert_rowid())
+}
+
+/// Record that an API token was just used
+pub async fn record_api_token_use(pool: &Pool<Sqlite>, id: i64) -> Result<()> {
+    sqlx::query("UPDATE api_tokens SET last_used_at = CURRENT_TIMESTAMP WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to record API token use: {}", e)))?;
+
+    Ok(())
+}
+
+/// Revoke (delete) one of a user's API tokens
+///
+/// Returns `false` if the user has no such token.
+pub async fn delete_api_token(pool: &Pool<Sqlite>, user_id: i64, id: i64) -> Result<bool> {
+    let result = sqlx::query("DELETE FROM api_tokens WHERE id = ? AND user_id = ?")
+        .bind(id)
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to delete API token: {}", e)))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ApiTokenScope, CreateUser, UserRole};
+    use crate::queries::users;
+    use crate::Database;
+    use chrono::{Duration, Utc};
+
+    async fn test_db(name: &str) -> Database {
+        let path =
+            std::env::temp_dir().join(format!("svrctlrs-{}-{}.db", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let db = Database::new(&format!("sqlite:{}", path.display()))
+            .await
+            .unwrap();
+        db.migrate().await.unwrap();
+        db
+    }
+
+    #[tokio::test]
+    async fn test_api_token_roundtrip() {
+        let db = test_db("api-tokens").await;
+        let pool = db.pool();
+
+        let user_id = users::create_user(
+            pool,
+            &CreateUser {
+                username: "ci".to_string(),
+                password_hash: "hash".to_string(),
+                role: UserRole::Operator,
+                server_tags: Vec::new(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let id = create_api_token(
+            pool,
+            &CreateApiToken {
+                user_id,
+                name: "deploy".to_string(),
+                token_hash: "abc123".to_string(),
+                token_prefix: "svrctlrs_abc".to_string(),
+                scopes: vec![ApiTokenScope::Read, ApiTokenScope::Operate],
+                expires_at: Some(Utc::now() - Duration::days(1)),
+            },
+        )
+        .await
+        .unwrap();
+
+        let token = get_api_token_by_hash(pool, "abc123")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(token.id, id);
+        assert_eq!(
+            token.get_scopes(),
+            vec![ApiTokenScope::Read, ApiTokenScope::Operate]
+        );
+        assert!(token.is_expired());
+        assert!(token.last_used_at.is_none());
+        assert!(get_api_token_by_hash(pool, "nope").await.unwrap().is_none());
+
+        record_api_token_use(pool, id).await.unwrap();
+        let tokens = list_api_tokens(pool, user_id).await.unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert!(tokens[0].last_used_at.is_some());
+
+        // Only the owner can revoke a token
+        assert!(!delete_api_token(pool, user_id + 1, id).await.unwrap());
+        assert!(delete_api_token(pool, user_id, id).await.unwrap());
+        assert!(list_api_tokens(pool, user_id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_api_tokens_deleted_with_user() {
+        let db = test_db("api-tokens-cascade").await;
+        let pool = db.pool();
+
+        let user_id = users::create_user(
+            pool,
+            &CreateUser {
+                username: "gone".to_string(),
+                password_hash: "hash".to_string(),
+                role: UserRole::Viewer,
+                server_tags: Vec::new(),
+            },
+        )
+        .await
+        .unwrap();
+        create_api_token(
+            pool,
+            &CreateApiToken {
+                user_id,
+                name: "old".to_string(),
+                token_hash: "def456".to_string(),
+                token_prefix: "svrctlrs_def".to_string(),
+                scopes: vec![ApiTokenScope::Read],
+                expires_at: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        users::delete_user(pool, user_id).await.unwrap();
+        assert!(get_api_token_by_hash(pool, "def456")
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,210 @@
+use sqlx::{Pool, QueryBuilder, Sqlite};
+use svrctlrs_core::{Error, Result};
+
+use crate::models::{AuditEntry, AuditFilter, NewAuditEntry};
+use crate::queries::metrics::sql_timestamp;
+
+/// Record an audit log entry
+pub async fn record_audit_entry(pool: &Pool<Sqlite>, entry: &NewAuditEntry) -> Result<i64> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO audit_log (actor, action, target, outcome, message, source_ip, changes)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&entry.actor)
+    .bind(&entry.action)
+    .bind(&entry.target)
+    .bind(entry.outcome.as_str())
+    .bind(&entry.message)
+    .bind(&entry.source_ip)
+    .bind(entry.changes.as_ref().map(|c| c.to_string()))
+    .execute(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to record audit entry: {}", e)))?;
+
+    Ok(result.last_insert_rowid())
+}
+
+/// List the most recent audit log entries matching a filter, newest first
+pub async fn list_audit_entries(
+    pool: &Pool<Sqlite>,
+    filter: &AuditFilter,
+    limit: i64,
+) -> Result<Vec<AuditEntry>> {
+    let mut qb: QueryBuilder<Sqlite> = QueryBuilder::new(
+        "SELECT id, created_at, actor, action, target, outcome, message, source_ip, changes \
+         FROM audit_log WHERE 1 = 1",
+    );
+
+    if let Some(actor) = &filter.actor {
+        qb.push(" AND actor = ").push_bind(actor.clone());
+    }
+    if let Some(action) = &filter.action {
+        if action.ends_with('.') {
+            qb.push(" AND action LIKE ")
+                .push_bind(format!("{}%", escape_like(action)))
+                .push(" ESCAPE '\\'");
+        } else {
+            qb.push(" AND action = ").push_bind(action.clone());
+        }
+    }
+    if let Some(target) = &filter.target {
+        qb.push(" AND target LIKE ")
+            .push_bind(format!("%{}%", escape_like(target)))
+            .push(" ESCAPE '\\'");
+    }
+    if let Some(outcome) = filter.outcome {
+        qb.push(" AND outcome = ").push_bind(outcome.as_str());
+    }
+    if let Some(since) = &filter.since {
+        qb.push(" AND created_at >= ")
+            .push_bind(sql_timestamp(since));
+    }
+    if let Some(until) = &filter.until {
+        qb.push(" AND created_at <= ")
+            .push_bind(sql_timestamp(until));
+    }
+
+    qb.push(" ORDER BY created_at DESC, id DESC LIMIT ")
+        .push_bind(limit);
+
+    qb.build_query_as::<AuditEntry>()
+        .fetch_all(pool)
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to list audit entries: {}", e)))
+}
+
+/// Escape `LIKE` wildcards so a value matches literally
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::AuditOutcome;
+    use crate::Database;
+
+    async fn test_db(name: &str) -> Database {
+        let path =
+            std::env::temp_dir().join(format!("svrctlrs-{}-{}.db", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let db = Database::new(&format!("sqlite:{}", path.display()))
+            .await
+            .unwrap();
+        db.migrate().await.unwrap();
+        db
+    }
+
+    #[tokio::test]
+    async fn test_record_audit_entry() {
+        let db = test_db("audit").await;
+        let pool = db.pool();
+
+        for action in ["task.run", "server.delete"] {
+            record_audit_entry(
+                pool,
+                &NewAuditEntry {
+                    actor: "viewer".to_string(),
+                    action: action.to_string(),
+                    target: Some("server 3 (web1)".to_string()),
+                    outcome: AuditOutcome::Denied,
+                    message: Some("Requires the admin role".to_string()),
+                    source_ip: None,
+                    changes: None,
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        let all = AuditFilter::default();
+        let entries = list_audit_entries(pool, &all, 10).await.unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].action, "server.delete");
+        assert_eq!(entries[0].outcome, "denied");
+        assert_eq!(list_audit_entries(pool, &all, 1).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_filter_audit_entries() {
+        let db = test_db("audit-filter").await;
+        let pool = db.pool();
+
+        let entries = [
+            ("admin", "server.create", "web_1", AuditOutcome::Success),
+            ("admin", "server.update", "web1", AuditOutcome::Failure),
+            ("ops", "servers.sync", "all", AuditOutcome::Success),
+            ("ops", "task.run", "task 4", AuditOutcome::Denied),
+        ];
+        for (actor, action, target, outcome) in entries {
+            record_audit_entry(
+                pool,
+                &NewAuditEntry {
+                    actor: actor.to_string(),
+                    action: action.to_string(),
+                    target: Some(target.to_string()),
+                    outcome,
+                    message: None,
+                    source_ip: Some("10.0.0.5".to_string()),
+                    changes: Some(serde_json::json!({"name": {"before": null, "after": target}})),
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        let list = |filter: AuditFilter| async move {
+            list_audit_entries(pool, &filter, 10)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|e| e.action)
+                .collect::<Vec<_>>()
+        };
+
+        // A trailing dot matches a whole group of actions, not similar names
+        let by_prefix = list(AuditFilter {
+            action: Some("server.".to_string()),
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(by_prefix, vec!["server.update", "server.create"]);
+
+        let by_actor_and_outcome = list(AuditFilter {
+            actor: Some("ops".to_string()),
+            outcome: Some(AuditOutcome::Denied),
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(by_actor_and_outcome, vec!["task.run"]);
+
+        // Wildcards in the target search match literally
+        let by_target = list(AuditFilter {
+            target: Some("_1".to_string()),
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(by_target, vec!["server.create"]);
+
+        let future = list(AuditFilter {
+            since: Some(chrono::Utc::now() + chrono::Duration::hours(1)),
+            ..Default::default()
+        })
+        .await;
+        assert!(future.is_empty());
+
+        let entry = &list_audit_entries(pool, &AuditFilter::default(), 1)
+            .await
+            .unwrap()[0];
+        assert_eq!(entry.source_ip.as_deref(), Some("10.0.0.5"));
+        assert_eq!(
+            entry.get_changes().unwrap()["name"]["after"],
+            serde_json::json!("task 4")
+        );
+    }
+}
@@ -0,0 +1,229 @@
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, QueryBuilder, Sqlite};
+use svrctlrs_core::{Error, Result};
+
+use crate::models::{MetricBucket, MetricQuery, MetricSeriesInfo, NewMetric};
+
+/// Format a timestamp the way SQLite's CURRENT_TIMESTAMP stores it,
+/// so stored and bound values compare correctly as text
+pub(crate) fn sql_timestamp(dt: &DateTime<Utc>) -> String {
+    dt.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// Record a batch of metric samples in a single transaction
+pub async fn record_metrics(pool: &Pool<Sqlite>, metrics: &[NewMetric]) -> Result<u64> {
+    if metrics.is_empty() {
+        return Ok(0);
+    }
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to begin transaction: {}", e)))?;
+
+    for metric in metrics {
+        sqlx::query(
+            r#"
+            INSERT INTO metrics (server_id, plugin_id, metric_name, metric_value, metric_unit, metadata, timestamp)
+            VALUES (?, ?, ?, ?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP))
+            "#,
+        )
+        .bind(metric.server_id)
+        .bind(&metric.plugin_id)
+        .bind(&metric.metric_name)
+        .bind(metric.metric_value)
+        .bind(&metric.metric_unit)
+        .bind(metric.metadata.as_ref().map(|m| m.to_string()))
+        .bind(metric.timestamp.as_ref().map(sql_timestamp))
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to record metric: {}", e)))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to commit metrics: {}", e)))?;
+
+    Ok(metrics.len() as u64)
+}
+
+/// Query metrics down-sampled into fixed-width time buckets
+///
+/// Results are ordered by series (server, plugin, metric) then by time.
+pub async fn query_metric_buckets(
+    pool: &Pool<Sqlite>,
+    query: &MetricQuery,
+) -> Result<Vec<MetricBucket>> {
+    let bucket_secs = query.bucket_secs.max(1);
+
+    let mut qb: QueryBuilder<Sqlite> = QueryBuilder::new(
+        "SELECT server_id, plugin_id, metric_name, (CAST(strftime('%s', timestamp) AS INTEGER) / ",
+    );
+    qb.push_bind(bucket_secs)
+        .push(") * ")
+        .push_bind(bucket_secs)
+        .push(
+            " AS bucket_start, AVG(metric_value) AS avg_value, MIN(metric_value) AS min_value, \
+             MAX(metric_value) AS max_value, COUNT(*) AS sample_count \
+             FROM metrics WHERE timestamp >= ",
+        )
+        .push_bind(sql_timestamp(&query.since))
+        .push(" AND timestamp <= ")
+        .push_bind(sql_timestamp(&query.until));
+
+    if let Some(plugin_id) = &query.plugin_id {
+        qb.push(" AND plugin_id = ").push_bind(plugin_id.clone());
+    }
+    if let Some(server_id) = query.server_id {
+        qb.push(" AND server_id = ").push_bind(server_id);
+    }
+    if let Some(metric_name) = &query.metric_name {
+        match metric_name.strip_suffix('*') {
+            Some(prefix) => {
+                let escaped = prefix
+                    .replace('\\', "\\\\")
+                    .replace('%', "\\%")
+                    .replace('_', "\\_");
+                qb.push(" AND metric_name LIKE ")
+                    .push_bind(format!("{}%", escaped))
+                    .push(" ESCAPE '\\'");
+            }
+            None => {
+                qb.push(" AND metric_name = ")
+                    .push_bind(metric_name.clone());
+            }
+        }
+    }
+
+    qb.push(
+        " GROUP BY server_id, plugin_id, metric_name, bucket_start \
+         ORDER BY server_id, plugin_id, metric_name, bucket_start LIMIT ",
+    )
+    .push_bind(query.limit);
+
+    qb.build_query_as::<MetricBucket>()
+        .fetch_all(pool)
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to query metrics: {}", e)))
+}
+
+/// List distinct metric series, optionally filtered by plugin and server
+pub async fn list_metric_series(
+    pool: &Pool<Sqlite>,
+    plugin_id: Option<&str>,
+    server_id: Option<i64>,
+) -> Result<Vec<MetricSeriesInfo>> {
+    sqlx::query_as::<_, MetricSeriesInfo>(
+        r#"
+        SELECT server_id, plugin_id, metric_name, MAX(metric_unit) AS metric_unit,
+               COUNT(*) AS sample_count, MAX(timestamp) AS last_seen
+        FROM metrics
+        WHERE (?1 IS NULL OR plugin_id = ?1)
+          AND (?2 IS NULL OR server_id = ?2)
+        GROUP BY server_id, plugin_id, metric_name
+        ORDER BY plugin_id, metric_name, server_id
+        "#,
+    )
+    .bind(plugin_id)
+    .bind(server_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to list metric series: {}", e)))
+}
+
+/// Delete metrics older than the given number of days
+pub async fn clean_old_metrics(pool: &Pool<Sqlite>, days: i64) -> Result<u64> {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM metrics
+        WHERE timestamp < datetime('now', '-' || ? || ' days')
+        "#,
+    )
+    .bind(days)
+    .execute(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to clean old metrics: {}", e)))?;
+
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Database;
+    use chrono::{Duration, TimeZone};
+
+    async fn test_db(name: &str) -> Database {
+        let path =
+            std::env::temp_dir().join(format!("svrctlrs-{}-{}.db", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let db = Database::new(&format!("sqlite:{}", path.display()))
+            .await
+            .unwrap();
+        db.migrate().await.unwrap();
+        db
+    }
+
+    fn sample(name: &str, value: f64, at: DateTime<Utc>) -> NewMetric {
+        NewMetric {
+            server_id: None,
+            plugin_id: "health".to_string(),
+            metric_name: name.to_string(),
+            metric_value: value,
+            metric_unit: None,
+            metadata: None,
+            timestamp: Some(at),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_query_metric_buckets() {
+        let db = test_db("metrics-buckets").await;
+        let base = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+
+        let samples = vec![
+            sample("cpu_usage_percent", 10.0, base),
+            sample("cpu_usage_percent", 30.0, base + Duration::seconds(30)),
+            sample("cpu_usage_percent", 50.0, base + Duration::seconds(60)),
+            sample("disk_used_percent:/", 40.0, base),
+            sample("disk_used_percent:/var", 60.0, base),
+        ];
+        assert_eq!(record_metrics(db.pool(), &samples).await.unwrap(), 5);
+
+        let mut query = MetricQuery {
+            plugin_id: Some("health".to_string()),
+            server_id: None,
+            metric_name: Some("cpu_usage_percent".to_string()),
+            since: base - Duration::minutes(1),
+            until: base + Duration::minutes(5),
+            bucket_secs: 60,
+            limit: 100,
+        };
+        let buckets = query_metric_buckets(db.pool(), &query).await.unwrap();
+
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(buckets[0].start_time(), base);
+        assert_eq!(buckets[0].sample_count, 2);
+        assert!((buckets[0].avg_value - 20.0).abs() < f64::EPSILON);
+        assert_eq!(buckets[0].min_value, 10.0);
+        assert_eq!(buckets[0].max_value, 30.0);
+        assert_eq!(buckets[1].avg_value, 50.0);
+
+        query.metric_name = Some("disk_used_percent:*".to_string());
+        let buckets = query_metric_buckets(db.pool(), &query).await.unwrap();
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(buckets[1].metric_name, "disk_used_percent:/var");
+
+        query.since = base + Duration::minutes(10);
+        query.until = base + Duration::minutes(20);
+        assert!(query_metric_buckets(db.pool(), &query)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let series = list_metric_series(db.pool(), Some("health"), None)
+            .await
+            .unwrap();
+        assert_eq!(series.len(), 3);
+    }
+}
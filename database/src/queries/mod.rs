@@ -1,14 +1,27 @@
 // Database query modules
 
-pub mod servers;
-pub mod plugins;
+pub mod alerts;
+pub mod api_tokens;
+pub mod audit_log;
+pub mod metrics;
 pub mod notifications;
-pub mod tasks;
+pub mod plugins;
+pub mod servers;
 pub mod settings;
+pub mod tasks;
+pub mod updates;
+pub mod users;
+pub mod workflows;
 
-pub use servers::*;
-pub use plugins::*;
+pub use alerts::*;
+pub use api_tokens::*;
+pub use audit_log::*;
+pub use metrics::*;
 pub use notifications::*;
-pub use tasks::*;
+pub use plugins::*;
+pub use servers::*;
 pub use settings::*;
-
+pub use tasks::*;
+pub use updates::*;
+pub use users::*;
+pub use workflows::*;
@@ -42,14 +42,15 @@ pub async fn create_notification_backend(
 
     let result = sqlx::query(
         r#"
-        INSERT INTO notification_backends (type, name, config, priority)
-        VALUES (?, ?, ?, ?)
+        INSERT INTO notification_backends (type, name, config, priority, enabled)
+        VALUES (?, ?, ?, ?, ?)
         "#,
     )
     .bind(&backend.backend_type)
     .bind(&backend.name)
     .bind(config_json)
     .bind(backend.priority)
+    .bind(backend.enabled)
     .execute(pool)
     .await
     .map_err(|e| Error::DatabaseError(format!("Failed to create notification backend: {}", e)))?;
@@ -91,9 +92,9 @@ pub async fn update_notification_backend(
         q = q.bind(binding);
     }
 
-    q.execute(pool)
-        .await
-        .map_err(|e| Error::DatabaseError(format!("Failed to update notification backend: {}", e)))?;
+    q.execute(pool).await.map_err(|e| {
+        Error::DatabaseError(format!("Failed to update notification backend: {}", e))
+    })?;
 
     Ok(())
 }
@@ -104,7 +105,9 @@ pub async fn delete_notification_backend(pool: &Pool<Sqlite>, id: i64) -> Result
         .bind(id)
         .execute(pool)
         .await
-        .map_err(|e| Error::DatabaseError(format!("Failed to delete notification backend: {}", e)))?;
+        .map_err(|e| {
+            Error::DatabaseError(format!("Failed to delete notification backend: {}", e))
+        })?;
 
     Ok(())
 }
@@ -123,7 +126,12 @@ pub async fn list_enabled_notification_backends(
     )
     .fetch_all(pool)
     .await
-    .map_err(|e| Error::DatabaseError(format!("Failed to list enabled notification backends: {}", e)))
+    .map_err(|e| {
+        Error::DatabaseError(format!(
+            "Failed to list enabled notification backends: {}",
+            e
+        ))
+    })
 }
 
 /// List notification backends by type
@@ -142,6 +150,10 @@ pub async fn list_notification_backends_by_type(
     .bind(backend_type)
     .fetch_all(pool)
     .await
-    .map_err(|e| Error::DatabaseError(format!("Failed to list notification backends by type: {}", e)))
+    .map_err(|e| {
+        Error::DatabaseError(format!(
+            "Failed to list notification backends by type: {}",
+            e
+        ))
+    })
 }
-
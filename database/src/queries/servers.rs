@@ -9,7 +9,8 @@ pub async fn list_servers(pool: &Pool<Sqlite>) -> Result<Vec<Server>> {
         r#"
         SELECT id, name, host, port, username, ssh_key_path, enabled, description, tags,
                created_at, updated_at, last_seen_at, os_type, os_version, docker_installed,
-               connection_timeout, retry_attempts
+               connection_timeout, retry_attempts, docker_endpoint, docker_cert_path,
+               pre_update_hook, post_update_hook, rollback_hook
         FROM servers
         ORDER BY name
         "#,
@@ -25,7 +26,8 @@ pub async fn get_server(pool: &Pool<Sqlite>, id: i64) -> Result<Server> {
         r#"
         SELECT id, name, host, port, username, ssh_key_path, enabled, description, tags,
                created_at, updated_at, last_seen_at, os_type, os_version, docker_installed,
-               connection_timeout, retry_attempts
+               connection_timeout, retry_attempts, docker_endpoint, docker_cert_path,
+               pre_update_hook, post_update_hook, rollback_hook
         FROM servers
         WHERE id = ?
         "#,
@@ -42,7 +44,8 @@ pub async fn get_server_by_name(pool: &Pool<Sqlite>, name: &str) -> Result<Serve
         r#"
         SELECT id, name, host, port, username, ssh_key_path, enabled, description, tags,
                created_at, updated_at, last_seen_at, os_type, os_version, docker_installed,
-               connection_timeout, retry_attempts
+               connection_timeout, retry_attempts, docker_endpoint, docker_cert_path,
+               pre_update_hook, post_update_hook, rollback_hook
         FROM servers
         WHERE name = ?
         "#,
@@ -62,8 +65,10 @@ pub async fn create_server(pool: &Pool<Sqlite>, server: &CreateServer) -> Result
 
     let result = sqlx::query(
         r#"
-        INSERT INTO servers (name, host, port, username, ssh_key_path, description, tags)
-        VALUES (?, ?, ?, ?, ?, ?, ?)
+        INSERT INTO servers (name, host, port, username, ssh_key_path, description, tags,
+                             docker_endpoint, docker_cert_path, pre_update_hook,
+                             post_update_hook, rollback_hook)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#,
     )
     .bind(&server.name)
@@ -73,6 +78,11 @@ pub async fn create_server(pool: &Pool<Sqlite>, server: &CreateServer) -> Result
     .bind(&server.ssh_key_path)
     .bind(&server.description)
     .bind(tags_json)
+    .bind(&server.docker_endpoint)
+    .bind(&server.docker_cert_path)
+    .bind(&server.pre_update_hook)
+    .bind(&server.post_update_hook)
+    .bind(&server.rollback_hook)
     .execute(pool)
     .await
     .map_err(|e| Error::DatabaseError(format!("Failed to create server: {}", e)))?;
@@ -81,11 +91,7 @@ pub async fn create_server(pool: &Pool<Sqlite>, server: &CreateServer) -> Result
 }
 
 /// Update a server
-pub async fn update_server(
-    pool: &Pool<Sqlite>,
-    id: i64,
-    update: &UpdateServer,
-) -> Result<()> {
+pub async fn update_server(pool: &Pool<Sqlite>, id: i64, update: &UpdateServer) -> Result<()> {
     let mut query = String::from("UPDATE servers SET updated_at = CURRENT_TIMESTAMP");
     let mut bindings: Vec<String> = Vec::new();
 
@@ -129,6 +135,26 @@ pub async fn update_server(
         query.push_str(", retry_attempts = ?");
         bindings.push(retry.to_string());
     }
+    // Empty strings clear the Docker settings
+    if let Some(endpoint) = &update.docker_endpoint {
+        query.push_str(", docker_endpoint = NULLIF(?, '')");
+        bindings.push(endpoint.clone());
+    }
+    if let Some(cert_path) = &update.docker_cert_path {
+        query.push_str(", docker_cert_path = NULLIF(?, '')");
+        bindings.push(cert_path.clone());
+    }
+    // Empty strings clear the update hooks
+    for (column, hook) in [
+        ("pre_update_hook", &update.pre_update_hook),
+        ("post_update_hook", &update.post_update_hook),
+        ("rollback_hook", &update.rollback_hook),
+    ] {
+        if let Some(hook) = hook {
+            query.push_str(&format!(", {} = NULLIF(?, '')", column));
+            bindings.push(hook.clone());
+        }
+    }
 
     query.push_str(" WHERE id = ?");
     bindings.push(id.to_string());
@@ -205,7 +231,8 @@ pub async fn list_enabled_servers(pool: &Pool<Sqlite>) -> Result<Vec<Server>> {
         r#"
         SELECT id, name, host, port, username, ssh_key_path, enabled, description, tags,
                created_at, updated_at, last_seen_at, os_type, os_version, docker_installed,
-               connection_timeout, retry_attempts
+               connection_timeout, retry_attempts, docker_endpoint, docker_cert_path,
+               pre_update_hook, post_update_hook, rollback_hook
         FROM servers
         WHERE enabled = 1
         ORDER BY name
@@ -215,4 +242,3 @@ pub async fn list_enabled_servers(pool: &Pool<Sqlite>) -> Result<Vec<Server>> {
     .await
     .map_err(|e| Error::DatabaseError(format!("Failed to list enabled servers: {}", e)))
 }
-
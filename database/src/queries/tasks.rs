@@ -8,7 +8,9 @@ pub async fn list_tasks(pool: &Pool<Sqlite>) -> Result<Vec<Task>> {
     sqlx::query_as::<_, Task>(
         r#"
         SELECT id, name, description, plugin_id, server_id, schedule, enabled, command, args,
-               timeout, created_at, updated_at, last_run_at, next_run_at, run_count
+               timeout, created_at, updated_at, last_run_at, next_run_at, run_count,
+               catch_up, missed_runs, last_missed_at, overlap_policy, retry_attempts,
+               target, target_tags, last_status
         FROM tasks
         ORDER BY name
         "#,
@@ -23,7 +25,9 @@ pub async fn get_task(pool: &Pool<Sqlite>, id: i64) -> Result<Task> {
     sqlx::query_as::<_, Task>(
         r#"
         SELECT id, name, description, plugin_id, server_id, schedule, enabled, command, args,
-               timeout, created_at, updated_at, last_run_at, next_run_at, run_count
+               timeout, created_at, updated_at, last_run_at, next_run_at, run_count,
+               catch_up, missed_runs, last_missed_at, overlap_policy, retry_attempts,
+               target, target_tags, last_status
         FROM tasks
         WHERE id = ?
         "#,
@@ -34,6 +38,24 @@ pub async fn get_task(pool: &Pool<Sqlite>, id: i64) -> Result<Task> {
     .map_err(|e| Error::DatabaseError(format!("Failed to get task: {}", e)))
 }
 
+/// Get task by ID, or `None` if there is no such task
+pub async fn find_task(pool: &Pool<Sqlite>, id: i64) -> Result<Option<Task>> {
+    sqlx::query_as::<_, Task>(
+        r#"
+        SELECT id, name, description, plugin_id, server_id, schedule, enabled, command, args,
+               timeout, created_at, updated_at, last_run_at, next_run_at, run_count,
+               catch_up, missed_runs, last_missed_at, overlap_policy, retry_attempts,
+               target, target_tags, last_status
+        FROM tasks
+        WHERE id = ?
+        "#,
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to get task: {}", e)))
+}
+
 /// Create task
 pub async fn create_task(pool: &Pool<Sqlite>, task: &CreateTask) -> Result<i64> {
     let args_json = task
@@ -41,10 +63,16 @@ pub async fn create_task(pool: &Pool<Sqlite>, task: &CreateTask) -> Result<i64>
         .as_ref()
         .map(|a| serde_json::to_string(a).unwrap_or_else(|_| "{}".to_string()));
 
+    let target_tags_json = task
+        .target_tags
+        .as_ref()
+        .map(|t| serde_json::to_string(t).unwrap_or_else(|_| "[]".to_string()));
+
     let result = sqlx::query(
         r#"
-        INSERT INTO tasks (name, description, plugin_id, server_id, schedule, command, args, timeout)
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        INSERT INTO tasks (name, description, plugin_id, server_id, schedule, command, args, timeout, catch_up,
+                           overlap_policy, retry_attempts, target, target_tags)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#,
     )
     .bind(&task.name)
@@ -55,6 +83,11 @@ pub async fn create_task(pool: &Pool<Sqlite>, task: &CreateTask) -> Result<i64>
     .bind(&task.command)
     .bind(args_json)
     .bind(task.timeout)
+    .bind(&task.catch_up)
+    .bind(&task.overlap_policy)
+    .bind(task.retry_attempts)
+    .bind(task.target_name())
+    .bind(target_tags_json)
     .execute(pool)
     .await
     .map_err(|e| Error::DatabaseError(format!("Failed to create task: {}", e)))?;
@@ -95,6 +128,38 @@ pub async fn update_task(pool: &Pool<Sqlite>, id: i64, update: &UpdateTask) -> R
         query.push_str(", timeout = ?");
         bindings.push(timeout.to_string());
     }
+    if let Some(catch_up) = &update.catch_up {
+        query.push_str(", catch_up = ?");
+        bindings.push(catch_up.clone());
+    }
+    if let Some(overlap_policy) = &update.overlap_policy {
+        query.push_str(", overlap_policy = ?");
+        bindings.push(overlap_policy.clone());
+    }
+    if let Some(retry_attempts) = update.retry_attempts {
+        if retry_attempts < 0 {
+            query.push_str(", retry_attempts = NULL");
+        } else {
+            query.push_str(", retry_attempts = ?");
+            bindings.push(retry_attempts.to_string());
+        }
+    }
+    if let Some(server_id) = update.server_id {
+        if server_id == 0 {
+            query.push_str(", server_id = NULL");
+        } else {
+            query.push_str(", server_id = ?");
+            bindings.push(server_id.to_string());
+        }
+    }
+    if let Some(target) = &update.target {
+        query.push_str(", target = ?");
+        bindings.push(target.clone());
+    }
+    if let Some(target_tags) = &update.target_tags {
+        query.push_str(", target_tags = ?");
+        bindings.push(serde_json::to_string(target_tags).unwrap_or_else(|_| "[]".to_string()));
+    }
 
     query.push_str(" WHERE id = ?");
     bindings.push(id.to_string());
@@ -146,12 +211,49 @@ pub async fn update_task_run_info(
     Ok(())
 }
 
+/// Set the next scheduled run time of a task
+pub async fn set_task_next_run(
+    pool: &Pool<Sqlite>,
+    id: i64,
+    next_run_at: Option<chrono::DateTime<chrono::Utc>>,
+) -> Result<()> {
+    sqlx::query("UPDATE tasks SET next_run_at = ? WHERE id = ?")
+        .bind(next_run_at.as_ref().map(super::metrics::sql_timestamp))
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to set task next run: {}", e)))?;
+
+    Ok(())
+}
+
+/// Add to a task's count of missed scheduled runs
+pub async fn record_missed_runs(pool: &Pool<Sqlite>, id: i64, missed: i64) -> Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE tasks
+        SET missed_runs = missed_runs + ?,
+            last_missed_at = CURRENT_TIMESTAMP
+        WHERE id = ?
+        "#,
+    )
+    .bind(missed)
+    .bind(id)
+    .execute(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to record missed runs: {}", e)))?;
+
+    Ok(())
+}
+
 /// List enabled tasks
 pub async fn list_enabled_tasks(pool: &Pool<Sqlite>) -> Result<Vec<Task>> {
     sqlx::query_as::<_, Task>(
         r#"
         SELECT id, name, description, plugin_id, server_id, schedule, enabled, command, args,
-               timeout, created_at, updated_at, last_run_at, next_run_at, run_count
+               timeout, created_at, updated_at, last_run_at, next_run_at, run_count,
+               catch_up, missed_runs, last_missed_at, overlap_policy, retry_attempts,
+               target, target_tags, last_status
         FROM tasks
         WHERE enabled = 1
         ORDER BY next_run_at
@@ -167,7 +269,9 @@ pub async fn list_tasks_by_plugin(pool: &Pool<Sqlite>, plugin_id: &str) -> Resul
     sqlx::query_as::<_, Task>(
         r#"
         SELECT id, name, description, plugin_id, server_id, schedule, enabled, command, args,
-               timeout, created_at, updated_at, last_run_at, next_run_at, run_count
+               timeout, created_at, updated_at, last_run_at, next_run_at, run_count,
+               catch_up, missed_runs, last_missed_at, overlap_policy, retry_attempts,
+               target, target_tags, last_status
         FROM tasks
         WHERE plugin_id = ?
         ORDER BY name
@@ -192,7 +296,8 @@ pub async fn get_task_history(
     sqlx::query_as::<_, TaskHistory>(
         r#"
         SELECT id, task_id, plugin_id, server_id, started_at, finished_at, duration_ms,
-               status, exit_code, stdout, stderr, error_message, triggered_by, success, message, timestamp
+               status, exit_code, stdout, stderr, error_message, triggered_by, success, message, timestamp,
+               attempt
         FROM task_history
         WHERE task_id = ?
         ORDER BY started_at DESC
@@ -211,7 +316,8 @@ pub async fn get_recent_task_history(pool: &Pool<Sqlite>, limit: i64) -> Result<
     sqlx::query_as::<_, TaskHistory>(
         r#"
         SELECT id, task_id, plugin_id, server_id, started_at, finished_at, duration_ms,
-               status, exit_code, stdout, stderr, error_message, triggered_by, success, message, timestamp
+               status, exit_code, stdout, stderr, error_message, triggered_by, success, message, timestamp,
+               attempt
         FROM task_history
         ORDER BY started_at DESC
         LIMIT ?
@@ -246,8 +352,9 @@ pub async fn record_task_execution(
 ) -> Result<i64> {
     let result = sqlx::query(
         r#"
-        INSERT INTO task_history (task_id, plugin_id, server_id, success, message, duration_ms, timestamp)
-        VALUES (?, ?, ?, ?, ?, ?, ?)
+        INSERT INTO task_history (task_id, plugin_id, server_id, success, message, error_message, duration_ms,
+                                  timestamp, attempt)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#,
     )
     .bind(entry.task_id.to_string()) // Convert i64 to TEXT
@@ -255,8 +362,10 @@ pub async fn record_task_execution(
     .bind(entry.server_id)
     .bind(entry.success)
     .bind(&entry.output)
+    .bind(&entry.error)
     .bind(entry.duration_ms as i64)
     .bind(entry.executed_at)
+    .bind(entry.attempt)
     .execute(pool)
     .await
     .map_err(|e| Error::DatabaseError(format!("Failed to record task execution: {}", e)))?;
@@ -264,16 +373,94 @@ pub async fn record_task_execution(
     Ok(result.last_insert_rowid())
 }
 
+/// Record the start of an execution attempt with status `running`
+pub async fn start_task_execution(
+    pool: &Pool<Sqlite>,
+    execution: &crate::models::task::NewTaskExecution,
+) -> Result<i64> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO task_history (task_id, plugin_id, server_id, success, status, attempt, triggered_by,
+                                  started_at, timestamp)
+        VALUES (?, ?, ?, 0, 'running', ?, ?, ?, ?)
+        "#,
+    )
+    .bind(execution.task_id.to_string()) // Convert i64 to TEXT
+    .bind(&execution.plugin_id)
+    .bind(execution.server_id)
+    .bind(execution.attempt)
+    .bind(&execution.triggered_by)
+    .bind(execution.started_at)
+    .bind(execution.started_at)
+    .execute(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to record task execution start: {}", e)))?;
+
+    Ok(result.last_insert_rowid())
+}
+
+/// Record the outcome of an execution attempt started with [`start_task_execution`]
+pub async fn finish_task_execution(
+    pool: &Pool<Sqlite>,
+    history_id: i64,
+    outcome: &crate::models::task::TaskExecutionOutcome,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE task_history
+        SET status = ?, success = ?, message = ?, error_message = ?, exit_code = ?, stdout = ?, stderr = ?,
+            duration_ms = ?, finished_at = ?
+        WHERE id = ?
+        "#,
+    )
+    .bind(&outcome.status)
+    .bind(outcome.status == "success")
+    .bind(&outcome.output)
+    .bind(&outcome.error)
+    .bind(outcome.exit_code)
+    .bind(&outcome.stdout)
+    .bind(&outcome.stderr)
+    .bind(outcome.duration_ms as i64)
+    .bind(outcome.finished_at)
+    .bind(history_id)
+    .execute(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to record task execution outcome: {}", e)))?;
+
+    Ok(())
+}
+
+/// Mark executions left `running` by a previous process as failed
+pub async fn fail_interrupted_executions(pool: &Pool<Sqlite>) -> Result<u64> {
+    let result = sqlx::query(
+        r#"
+        UPDATE task_history
+        SET status = 'failed', success = 0, error_message = 'Interrupted by server restart',
+            finished_at = CURRENT_TIMESTAMP
+        WHERE status = 'running'
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        Error::DatabaseError(format!("Failed to clean up interrupted executions: {}", e))
+    })?;
+
+    Ok(result.rows_affected())
+}
+
 /// Update task statistics after execution
-pub async fn update_task_stats(pool: &Pool<Sqlite>, task_id: i64) -> Result<()> {
+pub async fn update_task_stats(pool: &Pool<Sqlite>, task_id: i64, status: &str) -> Result<()> {
     sqlx::query(
         r#"
         UPDATE tasks 
         SET last_run_at = CURRENT_TIMESTAMP,
-            run_count = run_count + 1
+            run_count = run_count + 1,
+            last_status = ?
         WHERE id = ?
         "#,
     )
+    .bind(status)
     .bind(task_id)
     .execute(pool)
     .await
@@ -282,3 +469,261 @@ pub async fn update_task_stats(pool: &Pool<Sqlite>, task_id: i64) -> Result<()>
     Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{NewTaskExecution, TaskExecutionOutcome};
+    use crate::Database;
+    use chrono::Utc;
+
+    async fn test_db(name: &str) -> Database {
+        let path =
+            std::env::temp_dir().join(format!("svrctlrs-{}-{}.db", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let db = Database::new(&format!("sqlite:{}", path.display()))
+            .await
+            .unwrap();
+        db.migrate().await.unwrap();
+        db
+    }
+
+    #[tokio::test]
+    async fn test_execution_lifecycle() {
+        let db = test_db("task-executions").await;
+        let pool = db.pool();
+
+        let task_id = create_task(
+            pool,
+            &CreateTask {
+                name: "Health".to_string(),
+                description: None,
+                plugin_id: "health".to_string(),
+                server_id: None,
+                schedule: "0 */5 * * * *".to_string(),
+                command: "system_metrics".to_string(),
+                args: None,
+                timeout: 300,
+                catch_up: "skip".to_string(),
+                overlap_policy: "skip".to_string(),
+                retry_attempts: None,
+                target: None,
+                target_tags: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        let start = |attempt| NewTaskExecution {
+            task_id,
+            plugin_id: "health".to_string(),
+            server_id: None,
+            attempt,
+            triggered_by: "manual".to_string(),
+            started_at: Utc::now(),
+        };
+
+        let first = start_task_execution(pool, &start(1)).await.unwrap();
+        let history = get_task_history(pool, task_id, 10).await.unwrap();
+        assert_eq!(history[0].status.as_deref(), Some("running"));
+        assert_eq!(history[0].triggered_by.as_deref(), Some("manual"));
+
+        finish_task_execution(
+            pool,
+            first,
+            &TaskExecutionOutcome {
+                status: "cancelled".to_string(),
+                output: String::new(),
+                error: Some("Cancelled".to_string()),
+                exit_code: None,
+                stdout: Some("partial\n".to_string()),
+                stderr: None,
+                duration_ms: 12,
+                finished_at: Utc::now(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let second = start_task_execution(pool, &start(2)).await.unwrap();
+        assert_eq!(fail_interrupted_executions(pool).await.unwrap(), 1);
+
+        let history = get_task_history(pool, task_id, 10).await.unwrap();
+        let status = |id| {
+            history
+                .iter()
+                .find(|h| h.id == id)
+                .and_then(|h| h.status.clone())
+                .unwrap()
+        };
+        assert_eq!(status(first), "cancelled");
+        let cancelled = history.iter().find(|h| h.id == first).unwrap();
+        assert_eq!(cancelled.stdout.as_deref(), Some("partial\n"));
+        assert_eq!(cancelled.exit_code, None);
+        assert_eq!(status(second), "failed");
+        assert!(history.iter().all(|h| !h.success));
+    }
+
+    #[tokio::test]
+    async fn test_execution_output_recorded() {
+        let db = test_db("task-execution-output").await;
+        let pool = db.pool();
+
+        let task_id = create_task(
+            pool,
+            &CreateTask {
+                name: "Uptime".to_string(),
+                description: None,
+                plugin_id: "health".to_string(),
+                server_id: None,
+                schedule: "0 */5 * * * *".to_string(),
+                command: "uptime".to_string(),
+                args: None,
+                timeout: 300,
+                catch_up: "skip".to_string(),
+                overlap_policy: "skip".to_string(),
+                retry_attempts: None,
+                target: None,
+                target_tags: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        let history_id = start_task_execution(
+            pool,
+            &NewTaskExecution {
+                task_id,
+                plugin_id: "health".to_string(),
+                server_id: None,
+                attempt: 1,
+                triggered_by: "schedule".to_string(),
+                started_at: Utc::now(),
+            },
+        )
+        .await
+        .unwrap();
+
+        finish_task_execution(
+            pool,
+            history_id,
+            &TaskExecutionOutcome {
+                status: "failed".to_string(),
+                output: String::new(),
+                error: Some("Command failed with exit code 2".to_string()),
+                exit_code: Some(2),
+                stdout: Some("line 1\nline 2\n".to_string()),
+                stderr: Some("boom\n".to_string()),
+                duration_ms: 40,
+                finished_at: Utc::now(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let history = get_task_history(pool, task_id, 10).await.unwrap();
+        assert_eq!(history[0].status.as_deref(), Some("failed"));
+        assert_eq!(history[0].exit_code, Some(2));
+        assert_eq!(history[0].stdout.as_deref(), Some("line 1\nline 2\n"));
+        assert_eq!(history[0].stderr.as_deref(), Some("boom\n"));
+        assert_eq!(history[0].duration_ms, Some(40));
+    }
+
+    #[tokio::test]
+    async fn test_task_targets() {
+        use crate::models::TaskTarget;
+
+        let db = test_db("task-targets").await;
+        let pool = db.pool();
+
+        let task_id = create_task(
+            pool,
+            &CreateTask {
+                name: "Disk usage".to_string(),
+                description: None,
+                plugin_id: "health".to_string(),
+                server_id: None,
+                schedule: "0 */5 * * * *".to_string(),
+                command: "df -h".to_string(),
+                args: None,
+                timeout: 300,
+                catch_up: "skip".to_string(),
+                overlap_policy: "skip".to_string(),
+                retry_attempts: None,
+                target: Some("tags".to_string()),
+                target_tags: Some(vec!["web".to_string()]),
+            },
+        )
+        .await
+        .unwrap();
+
+        let task = get_task(pool, task_id).await.unwrap();
+        assert_eq!(task.target(), Ok(TaskTarget::Tags(vec!["web".to_string()])));
+        assert_eq!(task.last_status, None);
+
+        let server_id = crate::queries::servers::create_server(
+            pool,
+            &crate::models::CreateServer {
+                name: "web-1".to_string(),
+                host: "10.0.0.1".to_string(),
+                port: 22,
+                username: "root".to_string(),
+                ssh_key_path: None,
+                description: None,
+                tags: Some(vec!["web".to_string()]),
+                docker_endpoint: None,
+                docker_cert_path: None,
+                pre_update_hook: None,
+                post_update_hook: None,
+                rollback_hook: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        update_task(
+            pool,
+            task_id,
+            &UpdateTask {
+                server_id: Some(server_id),
+                target: Some("server".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            get_task(pool, task_id).await.unwrap().target(),
+            Ok(TaskTarget::Server(server_id))
+        );
+
+        update_task_stats(pool, task_id, "partial").await.unwrap();
+        let task = get_task(pool, task_id).await.unwrap();
+        assert_eq!(task.last_status.as_deref(), Some("partial"));
+        assert_eq!(task.run_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_find_task() {
+        let db = test_db("find-task").await;
+        let pool = db.pool();
+
+        let task: CreateTask = serde_json::from_value(serde_json::json!({
+            "name": "Health",
+            "description": null,
+            "plugin_id": "health",
+            "server_id": null,
+            "schedule": "0 */5 * * * *",
+            "command": "system_metrics",
+            "args": null,
+        }))
+        .unwrap();
+        let task_id = create_task(pool, &task).await.unwrap();
+
+        let task = find_task(pool, task_id).await.unwrap();
+        assert_eq!(task.map(|t| t.name).as_deref(), Some("Health"));
+
+        // A deleted task is missing, not an error
+        delete_task(pool, task_id).await.unwrap();
+        assert!(find_task(pool, task_id).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,702 @@
+use sqlx::{Pool, QueryBuilder, Sqlite};
+use svrctlrs_core::{Error, Result};
+
+use crate::models::{
+    NewPendingUpdate, NewUpdateTransaction, PendingUpdate, UpdateBatch, UpdateBatchDecision,
+    UpdateTransaction,
+};
+
+/// Replace a server's pending updates with the result of a new check
+pub async fn replace_pending_updates(
+    pool: &Pool<Sqlite>,
+    server_id: Option<i64>,
+    server_name: &str,
+    updates: &[NewPendingUpdate],
+) -> Result<u64> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to begin transaction: {}", e)))?;
+
+    sqlx::query("DELETE FROM pending_updates WHERE server_name = ?")
+        .bind(server_name)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to clear pending updates: {}", e)))?;
+
+    for update in updates {
+        sqlx::query(
+            r#"
+            INSERT INTO pending_updates (
+                server_id, server_name, name, arch, current_version, new_version,
+                repository, is_security, severity
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(server_id)
+        .bind(server_name)
+        .bind(&update.name)
+        .bind(&update.arch)
+        .bind(&update.current_version)
+        .bind(&update.new_version)
+        .bind(&update.repository)
+        .bind(update.is_security)
+        .bind(&update.severity)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to record pending update: {}", e)))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to commit pending updates: {}", e)))?;
+
+    Ok(updates.len() as u64)
+}
+
+/// List pending updates, security updates first within each server
+pub async fn list_pending_updates(
+    pool: &Pool<Sqlite>,
+    server_name: Option<&str>,
+    security_only: bool,
+) -> Result<Vec<PendingUpdate>> {
+    let mut qb: QueryBuilder<Sqlite> = QueryBuilder::new(
+        "SELECT id, server_id, server_name, name, arch, current_version, new_version, \
+         repository, is_security, severity, checked_at FROM pending_updates WHERE 1 = 1",
+    );
+    if let Some(server_name) = server_name {
+        qb.push(" AND server_name = ").push_bind(server_name);
+    }
+    if security_only {
+        qb.push(" AND is_security = 1");
+    }
+    qb.push(" ORDER BY server_name, is_security DESC, name");
+
+    qb.build_query_as::<PendingUpdate>()
+        .fetch_all(pool)
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to list pending updates: {}", e)))
+}
+
+/// Columns selected for an [`UpdateBatch`]
+const UPDATE_BATCH_COLUMNS: &str = "id, server_id, server_name, status, packages, package_count, \
+     security_count, token, created_at, decided_at, decided_by, decided_via, applied_at, result";
+
+/// Packages of a batch in a comparable form
+fn package_set(updates: &[NewPendingUpdate]) -> Vec<(&str, Option<&str>, Option<&str>)> {
+    let mut set: Vec<_> = updates
+        .iter()
+        .map(|u| (u.name.as_str(), u.arch.as_deref(), u.new_version.as_deref()))
+        .collect();
+    set.sort();
+    set
+}
+
+/// Matches a server's update batches: by `server_id` for registered servers,
+/// so renaming a server keeps its batches, and by name for the local fallback
+const BATCH_SERVER_FILTER: &str =
+    "(server_id = ? OR (? IS NULL AND server_id IS NULL AND server_name = ?))";
+
+/// Record the updates a check found on a server as a batch awaiting approval
+///
+/// An open batch (pending, approved or deferred) with the same packages is
+/// kept as it is, so a decision stands until the updates change. Otherwise
+/// open batches are superseded and a new pending batch is created, unless
+/// there are no updates. Returns the ID of the new batch, if any.
+pub async fn record_update_batch(
+    pool: &Pool<Sqlite>,
+    server_id: Option<i64>,
+    server_name: &str,
+    updates: &[NewPendingUpdate],
+    token: &str,
+) -> Result<Option<i64>> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to begin transaction: {}", e)))?;
+
+    let latest = sqlx::query_as::<_, UpdateBatch>(&format!(
+        "SELECT {} FROM update_batches WHERE {} \
+         AND status IN ('pending', 'approved', 'deferred') ORDER BY id DESC LIMIT 1",
+        UPDATE_BATCH_COLUMNS, BATCH_SERVER_FILTER
+    ))
+    .bind(server_id)
+    .bind(server_id)
+    .bind(server_name)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to get open update batch: {}", e)))?;
+
+    if let Some(latest) = latest {
+        if !updates.is_empty() && package_set(&latest.get_packages()) == package_set(updates) {
+            return Ok(None);
+        }
+    }
+
+    sqlx::query(&format!(
+        "UPDATE update_batches SET status = 'superseded' \
+         WHERE {} AND status IN ('pending', 'approved', 'deferred')",
+        BATCH_SERVER_FILTER
+    ))
+    .bind(server_id)
+    .bind(server_id)
+    .bind(server_name)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to supersede update batches: {}", e)))?;
+
+    let batch_id = if updates.is_empty() {
+        None
+    } else {
+        let packages = serde_json::to_string(updates).map_err(|e| {
+            Error::DatabaseError(format!("Failed to serialize package updates: {}", e))
+        })?;
+        let security_count = updates.iter().filter(|u| u.is_security).count() as i64;
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO update_batches (
+                server_id, server_name, status, packages, package_count, security_count, token
+            )
+            VALUES (?, ?, 'pending', ?, ?, ?, ?)
+            "#,
+        )
+        .bind(server_id)
+        .bind(server_name)
+        .bind(packages)
+        .bind(updates.len() as i64)
+        .bind(security_count)
+        .bind(token)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to create update batch: {}", e)))?;
+        Some(result.last_insert_rowid())
+    };
+
+    tx.commit()
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to commit update batch: {}", e)))?;
+
+    Ok(batch_id)
+}
+
+/// Get an update batch by ID
+pub async fn get_update_batch(pool: &Pool<Sqlite>, id: i64) -> Result<UpdateBatch> {
+    sqlx::query_as::<_, UpdateBatch>(&format!(
+        "SELECT {} FROM update_batches WHERE id = ?",
+        UPDATE_BATCH_COLUMNS
+    ))
+    .bind(id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to get update batch: {}", e)))
+}
+
+/// List update batches, newest first
+pub async fn list_update_batches(
+    pool: &Pool<Sqlite>,
+    server_name: Option<&str>,
+    status: Option<&str>,
+    limit: i64,
+) -> Result<Vec<UpdateBatch>> {
+    let mut qb: QueryBuilder<Sqlite> = QueryBuilder::new(format!(
+        "SELECT {} FROM update_batches WHERE 1 = 1",
+        UPDATE_BATCH_COLUMNS
+    ));
+    if let Some(server_name) = server_name {
+        qb.push(" AND server_name = ").push_bind(server_name);
+    }
+    if let Some(status) = status {
+        qb.push(" AND status = ").push_bind(status);
+    }
+    qb.push(" ORDER BY id DESC LIMIT ").push_bind(limit);
+
+    qb.build_query_as::<UpdateBatch>()
+        .fetch_all(pool)
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to list update batches: {}", e)))
+}
+
+/// Approve or defer an open update batch
+///
+/// Returns `false` if the batch is no longer open (superseded or applied).
+pub async fn decide_update_batch(
+    pool: &Pool<Sqlite>,
+    id: i64,
+    decision: UpdateBatchDecision,
+    decided_by: &str,
+    decided_via: &str,
+) -> Result<bool> {
+    let result = sqlx::query(
+        r#"
+        UPDATE update_batches
+        SET status = ?, decided_at = CURRENT_TIMESTAMP, decided_by = ?, decided_via = ?
+        WHERE id = ? AND status IN ('pending', 'approved', 'deferred')
+        "#,
+    )
+    .bind(decision.status())
+    .bind(decided_by)
+    .bind(decided_via)
+    .bind(id)
+    .execute(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to update update batch: {}", e)))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Record the outcome of applying a server's approved update batch
+pub async fn finish_update_batches(
+    pool: &Pool<Sqlite>,
+    server_id: Option<i64>,
+    server_name: &str,
+    success: bool,
+    result: &str,
+) -> Result<u64> {
+    let result = sqlx::query(&format!(
+        "UPDATE update_batches SET status = ?, applied_at = CURRENT_TIMESTAMP, result = ? \
+         WHERE {} AND status = 'approved'",
+        BATCH_SERVER_FILTER
+    ))
+    .bind(if success { "applied" } else { "failed" })
+    .bind(result)
+    .bind(server_id)
+    .bind(server_id)
+    .bind(server_name)
+    .execute(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to finish update batches: {}", e)))?;
+
+    Ok(result.rows_affected())
+}
+
+const UPDATE_TRANSACTION_COLUMNS: &str = "id, server_id, server_name, package_manager, \
+     transaction_id, packages, status, post_hook_failed, rollback_hook_ran, created_at, \
+     rolled_back_at, rolled_back_by, rollback_output";
+
+/// Record a package transaction made by applying updates
+pub async fn record_update_transaction(
+    pool: &Pool<Sqlite>,
+    server_id: Option<i64>,
+    server_name: &str,
+    transaction: &NewUpdateTransaction,
+) -> Result<i64> {
+    let packages = serde_json::to_string(&transaction.packages).map_err(|e| {
+        Error::DatabaseError(format!("Failed to serialize transaction packages: {}", e))
+    })?;
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO update_transactions (server_id, server_name, package_manager, transaction_id,
+                                         packages, post_hook_failed, rollback_hook_ran)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(server_id)
+    .bind(server_name)
+    .bind(&transaction.package_manager)
+    .bind(&transaction.transaction_id)
+    .bind(packages)
+    .bind(transaction.post_hook_failed)
+    .bind(transaction.rollback_hook_ran)
+    .execute(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to record update transaction: {}", e)))?;
+
+    Ok(result.last_insert_rowid())
+}
+
+/// Get an update transaction by ID
+pub async fn get_update_transaction(pool: &Pool<Sqlite>, id: i64) -> Result<UpdateTransaction> {
+    sqlx::query_as::<_, UpdateTransaction>(&format!(
+        "SELECT {} FROM update_transactions WHERE id = ?",
+        UPDATE_TRANSACTION_COLUMNS
+    ))
+    .bind(id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to get update transaction: {}", e)))
+}
+
+/// List update transactions, newest first
+pub async fn list_update_transactions(
+    pool: &Pool<Sqlite>,
+    server_name: Option<&str>,
+    limit: i64,
+) -> Result<Vec<UpdateTransaction>> {
+    let mut qb: QueryBuilder<Sqlite> = QueryBuilder::new(format!(
+        "SELECT {} FROM update_transactions WHERE 1 = 1",
+        UPDATE_TRANSACTION_COLUMNS
+    ));
+    if let Some(server_name) = server_name {
+        qb.push(" AND server_name = ").push_bind(server_name);
+    }
+    qb.push(" ORDER BY id DESC LIMIT ").push_bind(limit);
+
+    qb.build_query_as::<UpdateTransaction>()
+        .fetch_all(pool)
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to list update transactions: {}", e)))
+}
+
+/// Mark a transaction as being rolled back
+///
+/// Returns `false` if it is already rolled back or being rolled back.
+pub async fn start_transaction_rollback(
+    pool: &Pool<Sqlite>,
+    id: i64,
+    rolled_back_by: &str,
+) -> Result<bool> {
+    let result = sqlx::query(
+        r#"
+        UPDATE update_transactions
+        SET status = 'rolling_back', rolled_back_by = ?, rollback_output = NULL
+        WHERE id = ? AND status IN ('applied', 'rollback_failed')
+        "#,
+    )
+    .bind(rolled_back_by)
+    .bind(id)
+    .execute(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to start transaction rollback: {}", e)))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Record the outcome of rolling back a transaction
+pub async fn finish_transaction_rollback(
+    pool: &Pool<Sqlite>,
+    id: i64,
+    success: bool,
+    output: &str,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE update_transactions
+        SET status = ?, rolled_back_at = CURRENT_TIMESTAMP, rollback_output = ?
+        WHERE id = ?
+        "#,
+    )
+    .bind(if success {
+        "rolled_back"
+    } else {
+        "rollback_failed"
+    })
+    .bind(output)
+    .bind(id)
+    .execute(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to finish transaction rollback: {}", e)))?;
+
+    Ok(())
+}
+
+/// Mark rollbacks left `rolling_back` by a previous process as failed
+pub async fn fail_interrupted_rollbacks(pool: &Pool<Sqlite>) -> Result<u64> {
+    let result = sqlx::query(
+        r#"
+        UPDATE update_transactions
+        SET status = 'rollback_failed', rolled_back_at = CURRENT_TIMESTAMP,
+            rollback_output = 'Interrupted by a restart'
+        WHERE status = 'rolling_back'
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        Error::DatabaseError(format!("Failed to clean up interrupted rollbacks: {}", e))
+    })?;
+
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Database;
+
+    async fn test_db(name: &str) -> Database {
+        let path =
+            std::env::temp_dir().join(format!("svrctlrs-{}-{}.db", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let db = Database::new(&format!("sqlite:{}", path.display()))
+            .await
+            .unwrap();
+        db.migrate().await.unwrap();
+        db
+    }
+
+    fn update(name: &str, is_security: bool) -> NewPendingUpdate {
+        NewPendingUpdate {
+            name: name.to_string(),
+            arch: Some("x86_64".to_string()),
+            current_version: Some("1.0-1".to_string()),
+            new_version: Some("1.0-2".to_string()),
+            repository: Some("updates".to_string()),
+            is_security,
+            severity: is_security.then(|| "Important".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replace_pending_updates() {
+        let db = test_db("pending-updates").await;
+        let pool = db.pool();
+
+        replace_pending_updates(
+            pool,
+            None,
+            "web1",
+            &[update("vim", false), update("openssl", true)],
+        )
+        .await
+        .unwrap();
+        replace_pending_updates(pool, None, "web2", &[update("curl", false)])
+            .await
+            .unwrap();
+
+        // Security updates come first within a server
+        let all = list_pending_updates(pool, None, false).await.unwrap();
+        let names: Vec<_> = all
+            .iter()
+            .map(|u| (u.server_name.as_str(), u.name.as_str()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![("web1", "openssl"), ("web1", "vim"), ("web2", "curl")]
+        );
+        assert_eq!(all[0].severity.as_deref(), Some("Important"));
+
+        // A new check replaces only that server's updates
+        replace_pending_updates(pool, None, "web1", &[])
+            .await
+            .unwrap();
+        assert!(list_pending_updates(pool, Some("web1"), false)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            list_pending_updates(pool, Some("web2"), false)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(list_pending_updates(pool, None, true)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_update_batch_lifecycle() {
+        let db = test_db("update-batches").await;
+        let pool = db.pool();
+        let updates = [update("vim", false), update("openssl", true)];
+
+        let first = record_update_batch(pool, None, "web1", &updates, "t1")
+            .await
+            .unwrap()
+            .unwrap();
+        let batch = get_update_batch(pool, first).await.unwrap();
+        assert_eq!(batch.status, "pending");
+        assert_eq!((batch.package_count, batch.security_count), (2, 1));
+        assert_eq!(batch.get_packages().len(), 2);
+
+        // The same packages (in any order) keep the open batch and its decision
+        assert!(
+            decide_update_batch(pool, first, UpdateBatchDecision::Approve, "alice", "api")
+                .await
+                .unwrap()
+        );
+        let reversed = [update("openssl", true), update("vim", false)];
+        assert_eq!(
+            record_update_batch(pool, None, "web1", &reversed, "t2")
+                .await
+                .unwrap(),
+            None
+        );
+        let batch = get_update_batch(pool, first).await.unwrap();
+        assert_eq!(batch.status, "approved");
+        assert_eq!(batch.decided_by.as_deref(), Some("alice"));
+
+        // Different packages supersede it with a new pending batch
+        let second = record_update_batch(pool, None, "web1", &[update("curl", false)], "t3")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            get_update_batch(pool, first).await.unwrap().status,
+            "superseded"
+        );
+        assert!(
+            !decide_update_batch(pool, first, UpdateBatchDecision::Defer, "bob", "api")
+                .await
+                .unwrap()
+        );
+
+        // Only approved batches are marked applied
+        assert_eq!(
+            finish_update_batches(pool, None, "web1", true, "ok")
+                .await
+                .unwrap(),
+            0
+        );
+        decide_update_batch(
+            pool,
+            second,
+            UpdateBatchDecision::Approve,
+            "bob",
+            "notification",
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            finish_update_batches(pool, None, "web1", true, "ok")
+                .await
+                .unwrap(),
+            1
+        );
+        let batch = get_update_batch(pool, second).await.unwrap();
+        assert_eq!(batch.status, "applied");
+        assert!(batch.applied_at.is_some());
+
+        // No updates closes open batches without opening a new one
+        let third = record_update_batch(pool, None, "web1", &updates, "t4")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            record_update_batch(pool, None, "web1", &[], "t5")
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            get_update_batch(pool, third).await.unwrap().status,
+            "superseded"
+        );
+        assert_eq!(
+            list_update_batches(pool, Some("web1"), Some("superseded"), 10)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_batches_follow_renamed_servers() {
+        let db = test_db("update-batches-rename").await;
+        let pool = db.pool();
+        let server_id = sqlx::query("INSERT INTO servers (name, host) VALUES ('web1', '10.0.0.1')")
+            .execute(pool)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+
+        let first =
+            record_update_batch(pool, Some(server_id), "web1", &[update("vim", false)], "t1")
+                .await
+                .unwrap()
+                .unwrap();
+        decide_update_batch(pool, first, UpdateBatchDecision::Approve, "alice", "api")
+            .await
+            .unwrap();
+
+        // A local batch with the server's new name is a different server
+        record_update_batch(pool, None, "web-1", &[update("curl", false)], "t2")
+            .await
+            .unwrap();
+        assert_eq!(
+            get_update_batch(pool, first).await.unwrap().status,
+            "approved"
+        );
+
+        // After renaming the server, its batches are still found by ID
+        assert_eq!(
+            finish_update_batches(pool, Some(server_id), "web-1", true, "ok")
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            get_update_batch(pool, first).await.unwrap().status,
+            "applied"
+        );
+
+        let second =
+            record_update_batch(pool, Some(server_id), "web1", &[update("vim", false)], "t3")
+                .await
+                .unwrap()
+                .unwrap();
+        record_update_batch(
+            pool,
+            Some(server_id),
+            "web-1",
+            &[update("openssl", true)],
+            "t4",
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            get_update_batch(pool, second).await.unwrap().status,
+            "superseded"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_transaction_rollback() {
+        let db = test_db("update-transactions").await;
+        let pool = db.pool();
+
+        let transaction = NewUpdateTransaction {
+            package_manager: "apt".to_string(),
+            transaction_id: "2025-01-05  03:00:12".to_string(),
+            packages: vec![crate::models::TransactionPackage {
+                name: "openssl:amd64".to_string(),
+                from_version: Some("3.0.2-0ubuntu1.17".to_string()),
+                to_version: Some("3.0.2-0ubuntu1.18".to_string()),
+            }],
+            post_hook_failed: true,
+            rollback_hook_ran: false,
+        };
+        let id = record_update_transaction(pool, None, "web1", &transaction)
+            .await
+            .unwrap();
+        record_update_transaction(pool, None, "web2", &transaction)
+            .await
+            .unwrap();
+
+        let recorded = get_update_transaction(pool, id).await.unwrap();
+        assert_eq!(recorded.status, "applied");
+        assert!(recorded.post_hook_failed);
+        assert_eq!(recorded.get_packages(), transaction.packages);
+        assert_eq!(
+            list_update_transactions(pool, Some("web1"), 10)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        // Only one rollback at a time, and a failed one can be retried
+        assert!(start_transaction_rollback(pool, id, "alice").await.unwrap());
+        assert!(!start_transaction_rollback(pool, id, "bob").await.unwrap());
+        assert_eq!(fail_interrupted_rollbacks(pool).await.unwrap(), 1);
+        assert!(get_update_transaction(pool, id)
+            .await
+            .unwrap()
+            .can_roll_back());
+
+        assert!(start_transaction_rollback(pool, id, "bob").await.unwrap());
+        finish_transaction_rollback(pool, id, true, "downgraded")
+            .await
+            .unwrap();
+        let rolled_back = get_update_transaction(pool, id).await.unwrap();
+        assert_eq!(rolled_back.status, "rolled_back");
+        assert_eq!(rolled_back.rolled_back_by.as_deref(), Some("bob"));
+        assert!(!rolled_back.can_roll_back());
+    }
+}
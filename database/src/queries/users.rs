@@ -0,0 +1,283 @@
+use sqlx::{Pool, Sqlite};
+use svrctlrs_core::{Error, Result};
+
+use crate::models::{CreateUser, UpdateUser, User};
+
+const USER_COLUMNS: &str = r#"
+    id, username, password_hash, enabled, role, server_tags, created_at, updated_at,
+    last_login_at
+"#;
+
+/// List all users
+pub async fn list_users(pool: &Pool<Sqlite>) -> Result<Vec<User>> {
+    sqlx::query_as::<_, User>(&format!(
+        "SELECT {} FROM users ORDER BY username",
+        USER_COLUMNS
+    ))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to list users: {}", e)))
+}
+
+/// Number of user accounts
+pub async fn count_users(pool: &Pool<Sqlite>) -> Result<i64> {
+    sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM users")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to count users: {}", e)))
+}
+
+/// Get user by ID
+pub async fn get_user(pool: &Pool<Sqlite>, id: i64) -> Result<User> {
+    sqlx::query_as::<_, User>(&format!("SELECT {} FROM users WHERE id = ?", USER_COLUMNS))
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to get user: {}", e)))
+}
+
+/// Get user by username (case-insensitive)
+pub async fn get_user_by_username(pool: &Pool<Sqlite>, username: &str) -> Result<Option<User>> {
+    sqlx::query_as::<_, User>(&format!(
+        "SELECT {} FROM users WHERE username = ?",
+        USER_COLUMNS
+    ))
+    .bind(username)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to get user: {}", e)))
+}
+
+/// Number of enabled admin accounts
+pub async fn count_enabled_admins(pool: &Pool<Sqlite>) -> Result<i64> {
+    sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM users WHERE role = 'admin' AND enabled = 1")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to count admins: {}", e)))
+}
+
+/// Create a new user
+pub async fn create_user(pool: &Pool<Sqlite>, user: &CreateUser) -> Result<i64> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO users (username, password_hash, role, server_tags)
+        VALUES (?, ?, ?, ?)
+        "#,
+    )
+    .bind(&user.username)
+    .bind(&user.password_hash)
+    .bind(user.role.as_str())
+    .bind(server_tags_json(&user.server_tags))
+    .execute(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to create user: {}", e)))?;
+
+    Ok(result.last_insert_rowid())
+}
+
+/// Update a user
+pub async fn update_user(pool: &Pool<Sqlite>, id: i64, update: &UpdateUser) -> Result<()> {
+    let mut query = String::from("UPDATE users SET updated_at = CURRENT_TIMESTAMP");
+    let mut bindings: Vec<Option<String>> = Vec::new();
+
+    if let Some(password_hash) = &update.password_hash {
+        query.push_str(", password_hash = ?");
+        bindings.push(Some(password_hash.clone()));
+    }
+    if let Some(role) = update.role {
+        query.push_str(", role = ?");
+        bindings.push(Some(role.as_str().to_string()));
+    }
+    if let Some(tags) = &update.server_tags {
+        query.push_str(", server_tags = ?");
+        bindings.push(server_tags_json(tags));
+    }
+    if let Some(enabled) = update.enabled {
+        query.push_str(", enabled = ?");
+        bindings.push(Some(if enabled { "1" } else { "0" }.to_string()));
+    }
+
+    query.push_str(" WHERE id = ?");
+    bindings.push(Some(id.to_string()));
+
+    let mut q = sqlx::query(&query);
+    for binding in bindings {
+        q = q.bind(binding);
+    }
+
+    q.execute(pool)
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to update user: {}", e)))?;
+
+    Ok(())
+}
+
+/// Delete a user
+pub async fn delete_user(pool: &Pool<Sqlite>, id: i64) -> Result<()> {
+    sqlx::query("DELETE FROM users WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to delete user: {}", e)))?;
+
+    Ok(())
+}
+
+/// Stored form of a server scope; no tags = all servers
+fn server_tags_json(tags: &[String]) -> Option<String> {
+    let tags: Vec<&str> = tags
+        .iter()
+        .map(|t| t.trim())
+        .filter(|t| !t.is_empty())
+        .collect();
+    if tags.is_empty() {
+        return None;
+    }
+    Some(serde_json::to_string(&tags).unwrap_or_else(|_| "[]".to_string()))
+}
+
+/// Replace a user's password hash
+pub async fn update_user_password(pool: &Pool<Sqlite>, id: i64, password_hash: &str) -> Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE users
+        SET password_hash = ?, updated_at = CURRENT_TIMESTAMP
+        WHERE id = ?
+        "#,
+    )
+    .bind(password_hash)
+    .bind(id)
+    .execute(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to update user password: {}", e)))?;
+
+    Ok(())
+}
+
+/// Record a successful login
+pub async fn record_user_login(pool: &Pool<Sqlite>, id: i64) -> Result<()> {
+    sqlx::query("UPDATE users SET last_login_at = CURRENT_TIMESTAMP WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to record user login: {}", e)))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::UserRole;
+    use crate::Database;
+
+    async fn test_db(name: &str) -> Database {
+        let path =
+            std::env::temp_dir().join(format!("svrctlrs-{}-{}.db", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let db = Database::new(&format!("sqlite:{}", path.display()))
+            .await
+            .unwrap();
+        db.migrate().await.unwrap();
+        db
+    }
+
+    #[tokio::test]
+    async fn test_user_roundtrip() {
+        let db = test_db("users").await;
+        let pool = db.pool();
+        assert_eq!(count_users(pool).await.unwrap(), 0);
+
+        let id = create_user(
+            pool,
+            &CreateUser {
+                username: "admin".to_string(),
+                password_hash: "hash".to_string(),
+                role: UserRole::Admin,
+                server_tags: Vec::new(),
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(count_users(pool).await.unwrap(), 1);
+
+        // Usernames are unique regardless of case
+        assert!(create_user(
+            pool,
+            &CreateUser {
+                username: "Admin".to_string(),
+                password_hash: "other".to_string(),
+                role: UserRole::Viewer,
+                server_tags: Vec::new(),
+            },
+        )
+        .await
+        .is_err());
+
+        let user = get_user_by_username(pool, "ADMIN").await.unwrap().unwrap();
+        assert_eq!(user.id, id);
+        assert!(user.enabled);
+        assert!(user.last_login_at.is_none());
+        assert!(get_user_by_username(pool, "nobody")
+            .await
+            .unwrap()
+            .is_none());
+
+        update_user_password(pool, id, "new-hash").await.unwrap();
+        record_user_login(pool, id).await.unwrap();
+        let user = get_user(pool, id).await.unwrap();
+        assert_eq!(user.password_hash, "new-hash");
+        assert!(user.last_login_at.is_some());
+
+        // The hash never leaves the server
+        let json = serde_json::to_value(&user).unwrap();
+        assert!(json.get("password_hash").is_none());
+
+        let operator = create_user(
+            pool,
+            &CreateUser {
+                username: "ops".to_string(),
+                password_hash: "hash".to_string(),
+                role: UserRole::Operator,
+                server_tags: vec!["staging".to_string(), " ".to_string()],
+            },
+        )
+        .await
+        .unwrap();
+        let user = get_user(pool, operator).await.unwrap();
+        assert_eq!(user.role(), UserRole::Operator);
+        assert_eq!(user.get_server_tags(), vec!["staging".to_string()]);
+        assert_eq!(count_enabled_admins(pool).await.unwrap(), 1);
+
+        update_user(
+            pool,
+            operator,
+            &UpdateUser {
+                role: Some(UserRole::Admin),
+                server_tags: Some(Vec::new()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let user = get_user(pool, operator).await.unwrap();
+        assert_eq!(user.role(), UserRole::Admin);
+        assert!(user.server_tags.is_none());
+        assert_eq!(count_enabled_admins(pool).await.unwrap(), 2);
+
+        update_user(
+            pool,
+            operator,
+            &UpdateUser {
+                enabled: Some(false),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(count_enabled_admins(pool).await.unwrap(), 1);
+
+        delete_user(pool, operator).await.unwrap();
+        assert_eq!(list_users(pool).await.unwrap().len(), 1);
+    }
+}
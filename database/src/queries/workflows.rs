@@ -0,0 +1,486 @@
+use sqlx::{Pool, Sqlite};
+use svrctlrs_core::{Error, Result};
+
+use super::metrics::sql_timestamp;
+use crate::models::{
+    CreateWorkflow, StepStatus, UpdateWorkflow, Workflow, WorkflowRun, WorkflowStepOutcome,
+    WorkflowStepRun,
+};
+
+const WORKFLOW_COLUMNS: &str = r#"
+    id, name, description, enabled, schedule, steps, created_at, updated_at, last_run_at, next_run_at
+"#;
+
+const RUN_SELECT: &str = r#"
+    SELECT r.id, r.workflow_id, w.name AS workflow_name, r.status, r.triggered_by, r.started_at,
+           r.finished_at, r.error_message
+    FROM workflow_runs r
+    JOIN workflows w ON w.id = r.workflow_id
+"#;
+
+const STEP_RUN_COLUMNS: &str = r#"
+    id, run_id, step_id, status, started_at, finished_at, duration_ms, message, result
+"#;
+
+/// List all workflows
+pub async fn list_workflows(pool: &Pool<Sqlite>) -> Result<Vec<Workflow>> {
+    sqlx::query_as::<_, Workflow>(&format!(
+        "SELECT {} FROM workflows ORDER BY name",
+        WORKFLOW_COLUMNS
+    ))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to list workflows: {}", e)))
+}
+
+/// Get workflow by ID
+pub async fn get_workflow(pool: &Pool<Sqlite>, id: i64) -> Result<Workflow> {
+    sqlx::query_as::<_, Workflow>(&format!(
+        "SELECT {} FROM workflows WHERE id = ?",
+        WORKFLOW_COLUMNS
+    ))
+    .bind(id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to get workflow: {}", e)))
+}
+
+/// Get workflow by ID, or `None` if there is no such workflow
+pub async fn find_workflow(pool: &Pool<Sqlite>, id: i64) -> Result<Option<Workflow>> {
+    sqlx::query_as::<_, Workflow>(&format!(
+        "SELECT {} FROM workflows WHERE id = ?",
+        WORKFLOW_COLUMNS
+    ))
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to get workflow: {}", e)))
+}
+
+/// Create a new workflow
+pub async fn create_workflow(pool: &Pool<Sqlite>, workflow: &CreateWorkflow) -> Result<i64> {
+    let steps = serde_json::to_string(&workflow.steps)
+        .map_err(|e| Error::DatabaseError(format!("Failed to serialize workflow steps: {}", e)))?;
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO workflows (name, description, enabled, schedule, steps)
+        VALUES (?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&workflow.name)
+    .bind(&workflow.description)
+    .bind(workflow.enabled)
+    .bind(workflow.schedule.as_ref().filter(|s| !s.is_empty()))
+    .bind(steps)
+    .execute(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to create workflow: {}", e)))?;
+
+    Ok(result.last_insert_rowid())
+}
+
+/// Update a workflow
+///
+/// Empty strings clear `description` and `schedule`. Changing the schedule
+/// resets `next_run_at`, which the scheduler fills in again.
+pub async fn update_workflow(pool: &Pool<Sqlite>, id: i64, update: &UpdateWorkflow) -> Result<()> {
+    let mut qb =
+        sqlx::QueryBuilder::<Sqlite>::new("UPDATE workflows SET updated_at = CURRENT_TIMESTAMP");
+
+    if let Some(name) = &update.name {
+        qb.push(", name = ").push_bind(name.clone());
+    }
+    if let Some(description) = &update.description {
+        qb.push(", description = ")
+            .push_bind(Some(description.clone()).filter(|d| !d.is_empty()));
+    }
+    if let Some(enabled) = update.enabled {
+        qb.push(", enabled = ").push_bind(enabled);
+    }
+    if let Some(schedule) = &update.schedule {
+        qb.push(", next_run_at = NULL, schedule = ")
+            .push_bind(Some(schedule.clone()).filter(|s| !s.is_empty()));
+    }
+    if let Some(steps) = &update.steps {
+        let steps = serde_json::to_string(steps).map_err(|e| {
+            Error::DatabaseError(format!("Failed to serialize workflow steps: {}", e))
+        })?;
+        qb.push(", steps = ").push_bind(steps);
+    }
+
+    qb.push(" WHERE id = ").push_bind(id);
+
+    qb.build()
+        .execute(pool)
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to update workflow: {}", e)))?;
+
+    Ok(())
+}
+
+/// Delete a workflow (its runs are removed with it)
+pub async fn delete_workflow(pool: &Pool<Sqlite>, id: i64) -> Result<()> {
+    sqlx::query("DELETE FROM workflows WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to delete workflow: {}", e)))?;
+
+    Ok(())
+}
+
+/// Record when a scheduled workflow runs next
+pub async fn set_workflow_next_run(
+    pool: &Pool<Sqlite>,
+    id: i64,
+    next_run_at: Option<chrono::DateTime<chrono::Utc>>,
+) -> Result<()> {
+    sqlx::query("UPDATE workflows SET next_run_at = ? WHERE id = ?")
+        .bind(next_run_at.as_ref().map(sql_timestamp))
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to set workflow next run: {}", e)))?;
+
+    Ok(())
+}
+
+/// Start a workflow run, with every step pending
+pub async fn create_workflow_run(
+    pool: &Pool<Sqlite>,
+    workflow_id: i64,
+    triggered_by: &str,
+    step_ids: &[String],
+) -> Result<i64> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to begin transaction: {}", e)))?;
+
+    let run_id = sqlx::query(
+        "INSERT INTO workflow_runs (workflow_id, status, triggered_by) VALUES (?, 'running', ?)",
+    )
+    .bind(workflow_id)
+    .bind(triggered_by)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to create workflow run: {}", e)))?
+    .last_insert_rowid();
+
+    for step_id in step_ids {
+        sqlx::query(
+            "INSERT INTO workflow_step_runs (run_id, step_id, status) VALUES (?, ?, 'pending')",
+        )
+        .bind(run_id)
+        .bind(step_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to create workflow step run: {}", e)))?;
+    }
+
+    sqlx::query("UPDATE workflows SET last_run_at = CURRENT_TIMESTAMP WHERE id = ?")
+        .bind(workflow_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to update workflow: {}", e)))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to commit transaction: {}", e)))?;
+
+    Ok(run_id)
+}
+
+/// Mark a step of a run as started
+pub async fn start_workflow_step(pool: &Pool<Sqlite>, run_id: i64, step_id: &str) -> Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE workflow_step_runs SET status = 'running', started_at = CURRENT_TIMESTAMP
+        WHERE run_id = ? AND step_id = ?
+        "#,
+    )
+    .bind(run_id)
+    .bind(step_id)
+    .execute(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to start workflow step: {}", e)))?;
+
+    Ok(())
+}
+
+/// Record how a step of a run finished (or why it was skipped)
+pub async fn finish_workflow_step(
+    pool: &Pool<Sqlite>,
+    run_id: i64,
+    step_id: &str,
+    outcome: &WorkflowStepOutcome,
+) -> Result<()> {
+    let result = outcome
+        .result
+        .as_ref()
+        .map(serde_json::to_string)
+        .transpose()
+        .map_err(|e| Error::DatabaseError(format!("Failed to serialize step result: {}", e)))?;
+
+    sqlx::query(
+        r#"
+        UPDATE workflow_step_runs
+        SET status = ?, message = ?, duration_ms = ?, result = ?, finished_at = CURRENT_TIMESTAMP
+        WHERE run_id = ? AND step_id = ?
+        "#,
+    )
+    .bind(outcome.status.as_str())
+    .bind(&outcome.message)
+    .bind(outcome.duration_ms)
+    .bind(result)
+    .bind(run_id)
+    .bind(step_id)
+    .execute(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to record workflow step outcome: {}", e)))?;
+
+    Ok(())
+}
+
+/// Record the outcome of a run
+pub async fn finish_workflow_run(
+    pool: &Pool<Sqlite>,
+    run_id: i64,
+    status: StepStatus,
+    error_message: Option<&str>,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE workflow_runs SET status = ?, error_message = ?, finished_at = CURRENT_TIMESTAMP
+        WHERE id = ?
+        "#,
+    )
+    .bind(status.as_str())
+    .bind(error_message)
+    .bind(run_id)
+    .execute(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to record workflow run outcome: {}", e)))?;
+
+    Ok(())
+}
+
+/// Get a workflow run by ID
+pub async fn get_workflow_run(pool: &Pool<Sqlite>, run_id: i64) -> Result<WorkflowRun> {
+    sqlx::query_as::<_, WorkflowRun>(&format!("{} WHERE r.id = ?", RUN_SELECT))
+        .bind(run_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to get workflow run: {}", e)))
+}
+
+/// Most recent runs, optionally of a single workflow
+pub async fn list_workflow_runs(
+    pool: &Pool<Sqlite>,
+    workflow_id: Option<i64>,
+    limit: i64,
+) -> Result<Vec<WorkflowRun>> {
+    sqlx::query_as::<_, WorkflowRun>(&format!(
+        "{} WHERE (? IS NULL OR r.workflow_id = ?) ORDER BY r.started_at DESC, r.id DESC LIMIT ?",
+        RUN_SELECT
+    ))
+    .bind(workflow_id)
+    .bind(workflow_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to list workflow runs: {}", e)))
+}
+
+/// Steps of a run, in the order they were defined
+pub async fn list_workflow_step_runs(
+    pool: &Pool<Sqlite>,
+    run_id: i64,
+) -> Result<Vec<WorkflowStepRun>> {
+    sqlx::query_as::<_, WorkflowStepRun>(&format!(
+        "SELECT {} FROM workflow_step_runs WHERE run_id = ? ORDER BY id",
+        STEP_RUN_COLUMNS
+    ))
+    .bind(run_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| Error::DatabaseError(format!("Failed to list workflow step runs: {}", e)))
+}
+
+/// Mark runs left `running` by a previous process as failed
+pub async fn fail_interrupted_workflow_runs(pool: &Pool<Sqlite>) -> Result<u64> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to begin transaction: {}", e)))?;
+
+    sqlx::query(
+        r#"
+        UPDATE workflow_step_runs
+        SET status = CASE status WHEN 'running' THEN 'failed' ELSE 'skipped' END,
+            message = 'Interrupted by server restart', finished_at = CURRENT_TIMESTAMP
+        WHERE status IN ('pending', 'running')
+          AND run_id IN (SELECT id FROM workflow_runs WHERE status = 'running')
+        "#,
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| {
+        Error::DatabaseError(format!(
+            "Failed to clean up interrupted workflow steps: {}",
+            e
+        ))
+    })?;
+
+    let result = sqlx::query(
+        r#"
+        UPDATE workflow_runs
+        SET status = 'failed', error_message = 'Interrupted by server restart',
+            finished_at = CURRENT_TIMESTAMP
+        WHERE status = 'running'
+        "#,
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| {
+        Error::DatabaseError(format!(
+            "Failed to clean up interrupted workflow runs: {}",
+            e
+        ))
+    })?;
+
+    tx.commit()
+        .await
+        .map_err(|e| Error::DatabaseError(format!("Failed to commit transaction: {}", e)))?;
+
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::WorkflowStep;
+    use crate::Database;
+    use svrctlrs_core::PluginResult;
+
+    async fn test_db(name: &str) -> Database {
+        let path =
+            std::env::temp_dir().join(format!("svrctlrs-{}-{}.db", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let db = Database::new(&format!("sqlite:{}", path.display()))
+            .await
+            .unwrap();
+        db.migrate().await.unwrap();
+        db
+    }
+
+    fn step(id: &str, depends_on: &[&str]) -> WorkflowStep {
+        WorkflowStep {
+            id: id.to_string(),
+            task_id: None,
+            server_id: Some(1),
+            command: Some("uptime".to_string()),
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+            condition: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_workflow_run_lifecycle() {
+        let db = test_db("workflow-runs").await;
+        let pool = db.pool();
+
+        let id = create_workflow(
+            pool,
+            &CreateWorkflow {
+                name: "Patch".to_string(),
+                description: None,
+                schedule: Some("0 0 3 * * *".to_string()),
+                steps: vec![step("check", &[]), step("apply", &["check"])],
+                enabled: true,
+            },
+        )
+        .await
+        .unwrap();
+
+        let workflow = get_workflow(pool, id).await.unwrap();
+        let steps = workflow.get_steps().unwrap();
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[1].depends_on, vec!["check"]);
+
+        // Clearing the schedule leaves a manual-only workflow
+        update_workflow(
+            pool,
+            id,
+            &UpdateWorkflow {
+                schedule: Some(String::new()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(get_workflow(pool, id).await.unwrap().schedule, None);
+
+        let step_ids: Vec<String> = steps.iter().map(|s| s.id.clone()).collect();
+        let run_id = create_workflow_run(pool, id, "manual", &step_ids)
+            .await
+            .unwrap();
+        assert!(get_workflow(pool, id).await.unwrap().last_run_at.is_some());
+
+        start_workflow_step(pool, run_id, "check").await.unwrap();
+        finish_workflow_step(
+            pool,
+            run_id,
+            "check",
+            &WorkflowStepOutcome {
+                status: StepStatus::Success,
+                message: Some("3 updates".to_string()),
+                duration_ms: Some(20),
+                result: Some(PluginResult {
+                    success: true,
+                    message: "3 updates".to_string(),
+                    data: Some(serde_json::json!({ "total_updates": 3 })),
+                    metrics: None,
+                }),
+            },
+        )
+        .await
+        .unwrap();
+
+        let step_runs = list_workflow_step_runs(pool, run_id).await.unwrap();
+        assert_eq!(step_runs[0].status, "success");
+        assert!(step_runs[0]
+            .result
+            .as_deref()
+            .unwrap()
+            .contains("total_updates"));
+        assert_eq!(step_runs[1].status, "pending");
+
+        // A restart interrupts the run; its pending step is skipped
+        assert_eq!(fail_interrupted_workflow_runs(pool).await.unwrap(), 1);
+        let run = get_workflow_run(pool, run_id).await.unwrap();
+        assert_eq!(run.status, "failed");
+        assert_eq!(run.workflow_name, "Patch");
+        let step_runs = list_workflow_step_runs(pool, run_id).await.unwrap();
+        assert_eq!(step_runs[1].status, "skipped");
+
+        let second = create_workflow_run(pool, id, "webhook", &step_ids)
+            .await
+            .unwrap();
+        finish_workflow_run(pool, second, StepStatus::Success, None)
+            .await
+            .unwrap();
+        let runs = list_workflow_runs(pool, Some(id), 10).await.unwrap();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].id, second);
+        assert_eq!(runs[0].status, "success");
+        assert!(list_workflow_runs(pool, Some(id + 1), 10)
+            .await
+            .unwrap()
+            .is_empty());
+
+        delete_workflow(pool, id).await.unwrap();
+        assert!(list_workflow_runs(pool, None, 10).await.unwrap().is_empty());
+    }
+}
@@ -11,7 +11,7 @@ use bollard::Docker;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
-use svrctlrs_core::{Error, Result};
+use svrctlrs_core::{Error, RemoteExecutor, Result, Server};
 use tracing::{debug, info, instrument};
 
 /// Unused images analysis result
@@ -71,22 +71,24 @@ pub struct SharedLayerInfo {
 /// Docker analysis manager
 pub struct AnalysisManager {
     docker: Docker,
+    /// Reads container log sizes on remote servers; `None` = read them locally
+    remote: Option<RemoteExecutor>,
 }
 
 impl AnalysisManager {
-    /// Create a new analysis manager
+    /// Create an analysis manager for a server's Docker daemon
     ///
-    /// # Errors
+    /// Container log files live on the server, so on remote servers their
+    /// sizes are read over SSH.
     ///
-    /// Returns error if Docker connection fails
-    #[instrument]
-    pub async fn new() -> Result<Self> {
-        info!("Connecting to Docker daemon for analysis");
-
-        let docker = Docker::connect_with_unix_defaults()
-            .map_err(|e| Error::PluginError(format!("Failed to connect to Docker: {}", e)))?;
+    /// # Arguments
+    ///
+    /// * `ssh_key_path` - Optional SSH private key for remote servers
+    pub fn new(docker: Docker, server: &Server, ssh_key_path: Option<&str>) -> Self {
+        let remote = (!server.is_local())
+            .then(|| RemoteExecutor::for_server(server.clone(), ssh_key_path.map(String::from)));
 
-        Ok(Self { docker })
+        Self { docker, remote }
     }
 
     /// Analyze unused images
@@ -188,7 +190,7 @@ impl AnalysisManager {
         }
 
         // Sort by size descending
-        unused_images.sort_by(|a, b| b.size_bytes.cmp(&a.size_bytes));
+        unused_images.sort_by_key(|img| std::cmp::Reverse(img.size_bytes));
 
         debug!(
             count = unused_images.len(),
@@ -229,9 +231,7 @@ impl AnalysisManager {
         )
         .unwrap_or(100 * 1024 * 1024);
 
-        let mut container_logs = Vec::new();
-        let mut total_size_bytes = 0u64;
-        let mut containers_over_threshold = 0usize;
+        let mut candidates = Vec::new();
 
         for container in containers {
             let id = container.id.clone().unwrap_or_default();
@@ -263,10 +263,22 @@ impl AnalysisManager {
                 continue;
             }
 
-            // Get log file size
-            let log_size = match get_file_size(&log_path) {
-                Ok(size) => size,
-                Err(_) => continue,
+            candidates.push((name, id, has_rotation, log_path));
+        }
+
+        let paths: Vec<&str> = candidates
+            .iter()
+            .map(|(_, _, _, path)| path.as_str())
+            .collect();
+        let log_sizes = self.log_file_sizes(&paths).await;
+
+        let mut container_logs = Vec::new();
+        let mut total_size_bytes = 0u64;
+        let mut containers_over_threshold = 0usize;
+
+        for (name, id, has_rotation, log_path) in candidates {
+            let Some(&log_size) = log_sizes.get(&log_path) else {
+                continue;
             };
 
             total_size_bytes += log_size;
@@ -284,7 +296,7 @@ impl AnalysisManager {
         }
 
         // Sort by size descending
-        container_logs.sort_by(|a, b| b.log_size_bytes.cmp(&a.log_size_bytes));
+        container_logs.sort_by_key(|log| std::cmp::Reverse(log.log_size_bytes));
 
         debug!(
             total_containers = container_logs.len(),
@@ -412,6 +424,44 @@ impl AnalysisManager {
             efficiency_percent,
         })
     }
+
+    /// Sizes of the given log files; files that can't be read are left out
+    async fn log_file_sizes(&self, paths: &[&str]) -> HashMap<String, u64> {
+        let Some(remote) = &self.remote else {
+            return paths
+                .iter()
+                .filter_map(|path| {
+                    get_file_size(path)
+                        .ok()
+                        .map(|size| (path.to_string(), size))
+                })
+                .collect();
+        };
+        if paths.is_empty() {
+            return HashMap::new();
+        }
+
+        let mut args = vec!["-c", "%s %n"];
+        args.extend_from_slice(paths);
+        match remote.execute_command("stat", &args).await {
+            Ok(output) => parse_stat_sizes(&output),
+            Err(e) => {
+                debug!(error = %e, "Failed to read container log sizes");
+                HashMap::new()
+            }
+        }
+    }
+}
+
+/// Parse `stat -c '%s %n'` output into path -> size
+fn parse_stat_sizes(output: &str) -> HashMap<String, u64> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (size, path) = line.trim().split_once(' ')?;
+            Some((path.to_string(), size.parse().ok()?))
+        })
+        .collect()
 }
 
 /// Get file size in bytes
@@ -461,6 +511,15 @@ mod tests {
         assert_eq!(parse_size_threshold("100m").unwrap(), 100 * 1024 * 1024);
     }
 
+    #[test]
+    fn test_parse_stat_sizes() {
+        let sizes =
+            parse_stat_sizes("1024 /var/lib/docker/containers/a/a-json.log\nbad line\n0 /b.log\n");
+        assert_eq!(sizes.len(), 2);
+        assert_eq!(sizes["/var/lib/docker/containers/a/a-json.log"], 1024);
+        assert_eq!(sizes["/b.log"], 0);
+    }
+
     #[test]
     fn test_parse_size_threshold_gb() {
         assert_eq!(parse_size_threshold("1G").unwrap(), 1024 * 1024 * 1024);
@@ -15,7 +15,7 @@ use bollard::Docker;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use svrctlrs_core::{Error, NotificationManager, NotificationMessage, Result};
-use tracing::{debug, info, instrument, warn};
+use tracing::{debug, info, instrument};
 
 /// Cleanup analysis result
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -66,22 +66,14 @@ impl CleanupAnalysis {
 /// Docker cleanup manager
 pub struct CleanupManager {
     docker: Docker,
+    /// Server the daemon runs on, for notifications
+    server: String,
     dry_run: bool,
 }
 
 impl CleanupManager {
-    /// Create a new cleanup manager
-    ///
-    /// # Errors
-    ///
-    /// Returns error if Docker connection fails
-    #[instrument]
-    pub async fn new() -> Result<Self> {
-        info!("Connecting to Docker daemon for cleanup");
-
-        let docker = Docker::connect_with_unix_defaults()
-            .map_err(|e| Error::PluginError(format!("Failed to connect to Docker: {}", e)))?;
-
+    /// Create a cleanup manager for a server's Docker daemon
+    pub fn new(docker: Docker, server: &str) -> Self {
         // Check if dry-run mode is enabled
         let dry_run = std::env::var("DOCKER_CLEANUP_DRY_RUN")
             .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
@@ -89,7 +81,11 @@ impl CleanupManager {
 
         info!(dry_run = %dry_run, "Cleanup manager configured");
 
-        Ok(Self { docker, dry_run })
+        Self {
+            docker,
+            server: server.to_string(),
+            dry_run,
+        }
     }
 
     /// Analyze cleanup opportunities without actually cleaning
@@ -301,7 +297,8 @@ impl CleanupManager {
         analysis: &CleanupAnalysis,
     ) -> Result<()> {
         let title = format!(
-            "Docker Cleanup Report: {} reclaimable",
+            "Docker Cleanup Report for {}: {} reclaimable",
+            self.server,
             analysis.total_space_formatted()
         );
 
@@ -372,135 +369,6 @@ impl CleanupManager {
         info!("Cleanup report sent");
         Ok(())
     }
-
-    /// Execute cleanup operations (respects dry_run setting)
-    ///
-    /// # Arguments
-    ///
-    /// * `notify_mgr` - Notification manager for sending reports
-    ///
-    /// # Returns
-    ///
-    /// Cleanup analysis with actual results
-    #[instrument(skip(self, notify_mgr))]
-    pub async fn execute_cleanup(
-        &self,
-        notify_mgr: &NotificationManager,
-    ) -> Result<CleanupAnalysis> {
-        if self.dry_run {
-            warn!("Dry-run mode: analyzing only, no cleanup performed");
-            return self.analyze(notify_mgr).await;
-        }
-
-        info!("Executing Docker cleanup operations");
-
-        let mut analysis = CleanupAnalysis {
-            images_reclaimable: 0,
-            images_space_bytes: 0,
-            containers_reclaimable: 0,
-            containers_space_bytes: 0,
-            volumes_reclaimable: 0,
-            volumes_space_bytes: 0,
-            networks_reclaimable: 0,
-            build_cache_space_bytes: 0,
-            total_space_bytes: 0,
-        };
-
-        // Prune images
-        if let Ok(result) = self.prune_images().await {
-            analysis.images_reclaimable = result.0;
-            analysis.images_space_bytes = result.1;
-            info!(
-                images = result.0,
-                space = %CleanupAnalysis::format_space(result.1),
-                "Images pruned"
-            );
-        }
-
-        // Prune containers
-        if let Ok(result) = self.prune_containers().await {
-            analysis.containers_reclaimable = result.0;
-            analysis.containers_space_bytes = result.1;
-            info!(
-                containers = result.0,
-                space = %CleanupAnalysis::format_space(result.1),
-                "Containers pruned"
-            );
-        }
-
-        // Prune volumes
-        if let Ok(result) = self.prune_volumes().await {
-            analysis.volumes_reclaimable = result.0;
-            analysis.volumes_space_bytes = result.1;
-            info!(
-                volumes = result.0,
-                space = %CleanupAnalysis::format_space(result.1),
-                "Volumes pruned"
-            );
-        }
-
-        // Prune networks
-        if let Ok(result) = self.prune_networks().await {
-            analysis.networks_reclaimable = result.0;
-            info!(networks = result.0, "Networks pruned");
-        }
-
-        // Prune build cache
-        if let Ok(space) = self.prune_build_cache().await {
-            analysis.build_cache_space_bytes = space;
-            info!(
-                space = %CleanupAnalysis::format_space(space),
-                "Build cache pruned"
-            );
-        }
-
-        // Calculate total
-        analysis.total_space_bytes = analysis.images_space_bytes
-            + analysis.containers_space_bytes
-            + analysis.volumes_space_bytes
-            + analysis.build_cache_space_bytes;
-
-        // Send notification with results
-        self.send_cleanup_report(notify_mgr, &analysis).await?;
-
-        info!(
-            total_space = %analysis.total_space_formatted(),
-            "Cleanup completed"
-        );
-
-        Ok(analysis)
-    }
-
-    /// Prune dangling images
-    async fn prune_images(&self) -> Result<(u64, u64)> {
-        self.analyze_images().await // Same as analyze in dry-run
-    }
-
-    /// Prune stopped containers
-    async fn prune_containers(&self) -> Result<(u64, u64)> {
-        self.analyze_containers().await
-    }
-
-    /// Prune unused volumes
-    async fn prune_volumes(&self) -> Result<(u64, u64)> {
-        self.analyze_volumes().await
-    }
-
-    /// Prune unused networks
-    #[allow(dead_code)]
-    async fn prune_networks(&self) -> Result<(u64, u64)> {
-        self.analyze_networks().await
-    }
-
-    /// Prune build cache
-    /// Note: Build cache pruning is not directly supported by Bollard 0.18
-    /// This would need to be done via CLI: `docker builder prune`
-    #[allow(dead_code)]
-    async fn prune_build_cache(&self) -> Result<u64> {
-        // TODO: Implement via system exec or wait for Bollard API support
-        // For now, return zero space reclaimed
-        Ok(0)
-    }
 }
 
 #[cfg(test)]
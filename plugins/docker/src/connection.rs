@@ -0,0 +1,384 @@
+//! Connections to Docker daemons
+//!
+//! Each server's daemon is reached through its configured endpoint. Without
+//! one, local servers use the local socket and remote servers have their
+//! Docker socket tunnelled over SSH (using the `ssh` command, like
+//! [`svrctlrs_core::RemoteExecutor`]).
+
+use bollard::{Docker, API_DEFAULT_VERSION};
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use svrctlrs_core::{remote::split_ssh_port, Error, Result, Server};
+use tokio::io::AsyncReadExt;
+use tokio::process::{Child, Command};
+use tracing::{debug, info, instrument};
+
+/// Docker API request timeout in seconds
+const REQUEST_TIMEOUT_SECS: u64 = 120;
+
+/// How long to wait for an SSH tunnel to come up
+const TUNNEL_TIMEOUT_SECS: u64 = 15;
+
+/// Docker socket on remote hosts, unless the endpoint names another
+const DEFAULT_REMOTE_SOCKET: &str = "/var/run/docker.sock";
+
+/// Makes local tunnel socket names unique within the process
+static TUNNEL_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Where a server's Docker daemon listens
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DockerEndpoint {
+    /// The local daemon's default socket
+    LocalDefault,
+    /// A unix socket on this machine
+    Unix(String),
+    /// Plain HTTP (`tcp://host:2375`)
+    Http(String),
+    /// HTTPS with the client certificates in `cert_path`
+    Tls { address: String, cert_path: PathBuf },
+    /// A socket on a remote host, tunnelled over SSH
+    Ssh { ssh_host: String, socket: String },
+}
+
+impl DockerEndpoint {
+    /// Work out how to reach a server's daemon
+    ///
+    /// Endpoints are `unix:///path`, `tcp://host:port` (TLS when a
+    /// certificate directory is set), `https://host:port`, or
+    /// `ssh://[user@host[:port]][/socket]`, where the host defaults to the
+    /// server's SSH host.
+    pub fn for_server(server: &Server) -> Result<Self> {
+        let cert_path = server
+            .docker_cert_path
+            .as_deref()
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(PathBuf::from);
+
+        let Some(endpoint) = server
+            .docker_endpoint
+            .as_deref()
+            .map(str::trim)
+            .filter(|e| !e.is_empty())
+        else {
+            return Ok(match &server.ssh_host {
+                Some(ssh_host) => Self::Ssh {
+                    ssh_host: ssh_host.clone(),
+                    socket: DEFAULT_REMOTE_SOCKET.to_string(),
+                },
+                None => Self::LocalDefault,
+            });
+        };
+
+        if let Some(path) = endpoint.strip_prefix("unix://") {
+            Ok(Self::Unix(path.to_string()))
+        } else if endpoint.starts_with("https://")
+            || (endpoint.starts_with("tcp://") && cert_path.is_some())
+        {
+            let cert_path = cert_path.ok_or_else(|| {
+                Error::PluginError(format!(
+                    "Docker endpoint {} needs a TLS certificate directory",
+                    endpoint
+                ))
+            })?;
+            Ok(Self::Tls {
+                address: endpoint.to_string(),
+                cert_path,
+            })
+        } else if endpoint.starts_with("tcp://") || endpoint.starts_with("http://") {
+            Ok(Self::Http(endpoint.to_string()))
+        } else if let Some(rest) = endpoint.strip_prefix("ssh://") {
+            let (host, socket) = match rest.find('/') {
+                Some(i) => rest.split_at(i),
+                None => (rest, ""),
+            };
+            let ssh_host = match host {
+                "" => server.ssh_host.clone().ok_or_else(|| {
+                    Error::PluginError(format!(
+                        "Docker endpoint {} needs a host: server {} has no SSH host",
+                        endpoint, server.name
+                    ))
+                })?,
+                host => host.to_string(),
+            };
+            let socket = match socket {
+                "" => DEFAULT_REMOTE_SOCKET,
+                socket => socket,
+            };
+            Ok(Self::Ssh {
+                ssh_host,
+                socket: socket.to_string(),
+            })
+        } else {
+            Err(Error::PluginError(format!(
+                "Unsupported Docker endpoint '{}' (expected unix://, tcp://, https:// or ssh://)",
+                endpoint
+            )))
+        }
+    }
+}
+
+/// A connection to one server's Docker daemon
+///
+/// Keeps the SSH tunnel (if any) open for as long as the connection lives.
+pub struct DockerConnection {
+    docker: Docker,
+    _tunnel: Option<SshTunnel>,
+}
+
+impl DockerConnection {
+    /// Connect to a server's daemon and check that it answers
+    ///
+    /// # Arguments
+    ///
+    /// * `ssh_key_path` - Optional SSH private key for tunnelled connections
+    #[instrument(skip(server, ssh_key_path), fields(server = %server.name))]
+    pub async fn connect(server: &Server, ssh_key_path: Option<&str>) -> Result<Self> {
+        let endpoint = DockerEndpoint::for_server(server)?;
+        info!(endpoint = ?endpoint, "Connecting to Docker daemon");
+
+        let connect_error = |e: bollard::errors::Error| {
+            Error::PluginError(format!("Failed to connect to Docker: {}", e))
+        };
+
+        let (docker, tunnel) = match endpoint {
+            DockerEndpoint::LocalDefault => (
+                Docker::connect_with_unix_defaults().map_err(connect_error)?,
+                None,
+            ),
+            DockerEndpoint::Unix(path) => (
+                Docker::connect_with_unix(&path, REQUEST_TIMEOUT_SECS, API_DEFAULT_VERSION)
+                    .map_err(connect_error)?,
+                None,
+            ),
+            DockerEndpoint::Http(address) => (
+                Docker::connect_with_http(&address, REQUEST_TIMEOUT_SECS, API_DEFAULT_VERSION)
+                    .map_err(connect_error)?,
+                None,
+            ),
+            DockerEndpoint::Tls { address, cert_path } => (
+                Docker::connect_with_ssl(
+                    &address,
+                    &cert_path.join("key.pem"),
+                    &cert_path.join("cert.pem"),
+                    &cert_path.join("ca.pem"),
+                    REQUEST_TIMEOUT_SECS,
+                    API_DEFAULT_VERSION,
+                )
+                .map_err(connect_error)?,
+                None,
+            ),
+            DockerEndpoint::Ssh { ssh_host, socket } => {
+                let tunnel = SshTunnel::open(&ssh_host, &socket, ssh_key_path).await?;
+                let docker = Docker::connect_with_unix(
+                    &tunnel.socket.to_string_lossy(),
+                    REQUEST_TIMEOUT_SECS,
+                    API_DEFAULT_VERSION,
+                )
+                .map_err(connect_error)?;
+                (docker, Some(tunnel))
+            }
+        };
+
+        // bollard connects lazily; make sure the daemon is really there
+        docker.ping().await.map_err(|e| {
+            Error::PluginError(format!(
+                "Docker daemon on {} is not reachable: {}",
+                server.name, e
+            ))
+        })?;
+
+        Ok(Self {
+            docker,
+            _tunnel: tunnel,
+        })
+    }
+
+    /// Docker client for this connection
+    pub fn docker(&self) -> Docker {
+        self.docker.clone()
+    }
+}
+
+/// An `ssh -L` process forwarding a local socket to a remote Docker socket
+///
+/// The process is killed and the local socket removed on drop.
+struct SshTunnel {
+    child: Child,
+    socket: PathBuf,
+}
+
+impl SshTunnel {
+    /// Start the tunnel and wait until the local socket is listening
+    async fn open(ssh_host: &str, remote_socket: &str, ssh_key_path: Option<&str>) -> Result<Self> {
+        let socket = std::env::temp_dir().join(format!(
+            "svrctlrs-docker-{}-{}.sock",
+            std::process::id(),
+            TUNNEL_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_file(&socket);
+
+        debug!(ssh_host = %ssh_host, remote_socket = %remote_socket, local_socket = %socket.display(), "Opening SSH tunnel");
+
+        let mut cmd = Command::new("ssh");
+        cmd.arg("-o")
+            .arg("BatchMode=yes") // No interactive prompts
+            .arg("-o")
+            .arg("StrictHostKeyChecking=accept-new")
+            .arg("-o")
+            .arg("UserKnownHostsFile=/dev/null")
+            .arg("-o")
+            .arg("LogLevel=ERROR") // Keep host key warnings out of error messages
+            .arg("-o")
+            .arg("ExitOnForwardFailure=yes") // Fail fast if the socket can't be forwarded
+            .arg("-N")
+            .arg("-L")
+            .arg(format!("{}:{}", socket.display(), remote_socket));
+
+        if let Some(key_path) = ssh_key_path {
+            cmd.arg("-i").arg(key_path);
+        }
+
+        let (destination, port) = split_ssh_port(ssh_host);
+        if let Some(port) = port {
+            cmd.arg("-p").arg(port);
+        }
+
+        cmd.arg(destination)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        let child = cmd.spawn().map_err(|e| {
+            Error::RemoteExecutionError(format!(
+                "Failed to start SSH tunnel to {}: {}",
+                ssh_host, e
+            ))
+        })?;
+        let mut tunnel = Self { child, socket };
+
+        let deadline = Instant::now() + Duration::from_secs(TUNNEL_TIMEOUT_SECS);
+        loop {
+            if tunnel.socket.exists() {
+                return Ok(tunnel);
+            }
+
+            let exited = tunnel.child.try_wait().map_err(|e| {
+                Error::RemoteExecutionError(format!("SSH tunnel to {} failed: {}", ssh_host, e))
+            })?;
+            if let Some(status) = exited {
+                let mut stderr = String::new();
+                if let Some(mut pipe) = tunnel.child.stderr.take() {
+                    let _ = pipe.read_to_string(&mut stderr).await;
+                }
+                return Err(Error::RemoteExecutionError(format!(
+                    "SSH tunnel to {} exited ({}): {}",
+                    ssh_host,
+                    status,
+                    stderr.trim()
+                )));
+            }
+
+            if Instant::now() >= deadline {
+                return Err(Error::RemoteExecutionError(format!(
+                    "SSH tunnel to {} did not come up within {}s",
+                    ssh_host, TUNNEL_TIMEOUT_SECS
+                )));
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+}
+
+impl Drop for SshTunnel {
+    fn drop(&mut self) {
+        let _ = self.child.start_kill();
+        let _ = std::fs::remove_file(&self.socket);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(ssh_host: Option<&str>, endpoint: Option<&str>, cert_path: Option<&str>) -> Server {
+        Server {
+            name: "web1".to_string(),
+            ssh_host: ssh_host.map(String::from),
+            docker_endpoint: endpoint.map(String::from),
+            docker_cert_path: cert_path.map(String::from),
+            ..Server::local("web1")
+        }
+    }
+
+    #[test]
+    fn test_default_endpoints() {
+        assert_eq!(
+            DockerEndpoint::for_server(&server(None, None, None)).unwrap(),
+            DockerEndpoint::LocalDefault
+        );
+        assert_eq!(
+            DockerEndpoint::for_server(&server(Some("root@web1:2222"), Some(" "), None)).unwrap(),
+            DockerEndpoint::Ssh {
+                ssh_host: "root@web1:2222".to_string(),
+                socket: "/var/run/docker.sock".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_configured_endpoints() {
+        assert_eq!(
+            DockerEndpoint::for_server(&server(None, Some("unix:///run/docker.sock"), None))
+                .unwrap(),
+            DockerEndpoint::Unix("/run/docker.sock".to_string())
+        );
+        assert_eq!(
+            DockerEndpoint::for_server(&server(None, Some("tcp://web1:2375"), None)).unwrap(),
+            DockerEndpoint::Http("tcp://web1:2375".to_string())
+        );
+        assert_eq!(
+            DockerEndpoint::for_server(&server(None, Some("tcp://web1:2376"), Some("/certs")))
+                .unwrap(),
+            DockerEndpoint::Tls {
+                address: "tcp://web1:2376".to_string(),
+                cert_path: PathBuf::from("/certs"),
+            }
+        );
+        assert!(
+            DockerEndpoint::for_server(&server(None, Some("https://web1:2376"), None)).is_err()
+        );
+        assert!(
+            DockerEndpoint::for_server(&server(None, Some("npipe:////./pipe/docker"), None))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_ssh_endpoints() {
+        assert_eq!(
+            DockerEndpoint::for_server(&server(
+                Some("root@web1"),
+                Some("ssh:///run/user/1000/docker.sock"),
+                None
+            ))
+            .unwrap(),
+            DockerEndpoint::Ssh {
+                ssh_host: "root@web1".to_string(),
+                socket: "/run/user/1000/docker.sock".to_string(),
+            }
+        );
+        assert_eq!(
+            DockerEndpoint::for_server(&server(None, Some("ssh://deploy@docker-host:2222"), None))
+                .unwrap(),
+            DockerEndpoint::Ssh {
+                ssh_host: "deploy@docker-host:2222".to_string(),
+                socket: "/var/run/docker.sock".to_string(),
+            }
+        );
+        assert!(DockerEndpoint::for_server(&server(None, Some("ssh://"), None)).is_err());
+    }
+}
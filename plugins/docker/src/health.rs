@@ -36,24 +36,16 @@ pub struct ContainerHealth {
 /// Docker health monitor
 pub struct HealthMonitor {
     docker: Docker,
+    /// Server the daemon runs on, for notifications
+    server: String,
     cpu_warn_pct: f64,
     mem_warn_pct: f64,
     ignore_list: Vec<String>,
 }
 
 impl HealthMonitor {
-    /// Create a new health monitor
-    ///
-    /// # Errors
-    ///
-    /// Returns error if Docker connection fails
-    #[instrument]
-    pub async fn new() -> Result<Self> {
-        info!("Connecting to Docker daemon");
-
-        let docker = Docker::connect_with_unix_defaults()
-            .map_err(|e| Error::PluginError(format!("Failed to connect to Docker: {}", e)))?;
-
+    /// Create a health monitor for a server's Docker daemon
+    pub fn new(docker: Docker, server: &str) -> Self {
         // Load configuration from environment
         let cpu_warn_pct = std::env::var("DOCKER_CPU_WARN_PCT")
             .ok()
@@ -77,12 +69,13 @@ impl HealthMonitor {
             "Health monitor configured"
         );
 
-        Ok(Self {
+        Self {
             docker,
+            server: server.to_string(),
             cpu_warn_pct,
             mem_warn_pct,
             ignore_list,
-        })
+        }
     }
 
     /// Check health of all containers
@@ -99,7 +92,7 @@ impl HealthMonitor {
         &self,
         notify_mgr: &NotificationManager,
     ) -> Result<Vec<ContainerHealth>> {
-        info!("Starting Docker health check");
+        info!(server = %self.server, "Starting Docker health check");
 
         // List all containers (including stopped)
         let options = Some(ListContainersOptions::<String> {
@@ -278,7 +271,11 @@ impl HealthMonitor {
         notify_mgr: &NotificationManager,
         containers: &[ContainerHealth],
     ) -> Result<()> {
-        let title = format!("Docker Health Alert: {} issue(s)", containers.len());
+        let title = format!(
+            "Docker Health Alert on {}: {} issue(s)",
+            self.server,
+            containers.len()
+        );
 
         let mut body = String::new();
         for container in containers {
@@ -346,7 +343,7 @@ fn calculate_mem_percent(stats: &Stats) -> Option<f64> {
 mod tests {
     #[test]
     fn test_is_ignored_exact_match() {
-        let ignore_list = vec!["test-container".to_string()];
+        let ignore_list = ["test-container".to_string()];
 
         // Simulate is_ignored logic
         let is_ignored = |name: &str| {
@@ -366,7 +363,7 @@ mod tests {
 
     #[test]
     fn test_is_ignored_wildcard() {
-        let ignore_list = vec!["test-*".to_string()];
+        let ignore_list = ["test-*".to_string()];
 
         // Simulate is_ignored logic
         let is_ignored = |name: &str| {
@@ -1,19 +1,30 @@
 //! Docker monitoring plugin
+//!
+//! Every task runs against the Docker daemon of each server in the plugin
+//! context (reached over an SSH tunnel or a configured endpoint) and reports
+//! results per server.
 
 mod analysis;
 mod cleanup;
+mod connection;
 mod health;
 
 use analysis::AnalysisManager;
 use async_trait::async_trait;
-use cleanup::CleanupManager;
+use bollard::Docker;
+use cleanup::{CleanupAnalysis, CleanupManager};
+use connection::DockerConnection;
+use futures_util::future::join_all;
 use health::HealthMonitor;
 use serde_json::json;
 use std::collections::HashMap;
+use std::future::Future;
+use std::sync::LazyLock;
 use svrctlrs_core::{
-    Error, Plugin, PluginContext, PluginMetadata, PluginResult, Result, ScheduledTask,
+    server_metric_key, Error, Plugin, PluginContext, PluginMetadata, PluginResult, Result,
+    ScheduledTask, Server,
 };
-use tracing::{info, instrument};
+use tracing::{info, instrument, warn};
 
 /// Docker monitoring and management plugin
 pub struct DockerPlugin {}
@@ -87,23 +98,52 @@ impl DockerPlugin {
     async fn check_health(&self, context: &PluginContext) -> Result<PluginResult> {
         info!("Running Docker health check");
 
-        // Create health monitor
-        let monitor = HealthMonitor::new().await?;
-
-        // Check health of all containers
-        let health_statuses = monitor.check_health(&context.notification_manager).await?;
-
-        // Count containers by status
-        let total = health_statuses.len();
-        let running = health_statuses.iter().filter(|c| c.running).count();
-        let with_issues = health_statuses
-            .iter()
-            .filter(|c| !c.issues.is_empty())
-            .count();
+        let runs = for_each_server(context, |server, docker| async move {
+            HealthMonitor::new(docker, &server.name)
+                .check_health(&context.notification_manager)
+                .await
+        })
+        .await;
+
+        let (mut total, mut running, mut with_issues) = (0, 0, 0);
+        let report = runs.report(|server, health_statuses, metrics| {
+            let server_total = health_statuses.len();
+            let server_running = health_statuses.iter().filter(|c| c.running).count();
+            let server_with_issues = health_statuses
+                .iter()
+                .filter(|c| !c.issues.is_empty())
+                .count();
+            total += server_total;
+            running += server_running;
+            with_issues += server_with_issues;
+
+            metrics.insert(
+                server_metric_key(&server.name, "total_containers"),
+                server_total as f64,
+            );
+            metrics.insert(
+                server_metric_key(&server.name, "running_containers"),
+                server_running as f64,
+            );
+            metrics.insert(
+                server_metric_key(&server.name, "containers_with_issues"),
+                server_with_issues as f64,
+            );
+
+            json!({
+                "total_containers": server_total,
+                "running_containers": server_running,
+                "containers_with_issues": server_with_issues,
+                "health_statuses": health_statuses,
+            })
+        });
 
         let message = format!(
-            "Docker health check complete: {} containers ({} running, {} with issues)",
-            total, running, with_issues
+            "Docker health check complete: {} containers ({} running, {} with issues){}",
+            total,
+            running,
+            with_issues,
+            report.failure_note()
         );
 
         // Prepare structured data
@@ -111,20 +151,14 @@ impl DockerPlugin {
             "total_containers": total,
             "running_containers": running,
             "containers_with_issues": with_issues,
-            "health_statuses": health_statuses,
+            "servers": report.servers,
         });
 
-        // Prepare metrics
-        let mut metrics = HashMap::new();
-        metrics.insert("total_containers".to_string(), total as f64);
-        metrics.insert("running_containers".to_string(), running as f64);
-        metrics.insert("containers_with_issues".to_string(), with_issues as f64);
-
         Ok(PluginResult {
-            success: with_issues == 0,
+            success: report.failed == 0 && with_issues == 0,
             message,
             data: Some(data),
-            metrics: Some(metrics),
+            metrics: Some(report.metrics),
         })
     }
 
@@ -132,63 +166,69 @@ impl DockerPlugin {
     async fn analyze_cleanup(&self, context: &PluginContext) -> Result<PluginResult> {
         info!("Running Docker cleanup analysis");
 
-        // Create cleanup manager
-        let manager = CleanupManager::new().await?;
-
-        // Analyze cleanup opportunities
-        let analysis = manager.analyze(&context.notification_manager).await?;
+        let runs = for_each_server(context, |server, docker| async move {
+            CleanupManager::new(docker, &server.name)
+                .analyze(&context.notification_manager)
+                .await
+        })
+        .await;
+
+        let (mut total_items, mut total_space_bytes) = (0, 0);
+        let report = runs.report(|server, analysis, metrics| {
+            total_items += analysis.total_items();
+            total_space_bytes += analysis.total_space_bytes;
+
+            let mut add = |name: &str, value: f64| {
+                metrics.insert(server_metric_key(&server.name, name), value);
+            };
+            add("images_reclaimable", analysis.images_reclaimable as f64);
+            add(
+                "images_space_mb",
+                analysis.images_space_bytes as f64 / 1024.0 / 1024.0,
+            );
+            add(
+                "containers_reclaimable",
+                analysis.containers_reclaimable as f64,
+            );
+            add("volumes_reclaimable", analysis.volumes_reclaimable as f64);
+            add("networks_reclaimable", analysis.networks_reclaimable as f64);
+            add(
+                "total_space_mb",
+                analysis.total_space_bytes as f64 / 1024.0 / 1024.0,
+            );
+
+            json!({
+                "images_reclaimable": analysis.images_reclaimable,
+                "images_space_bytes": analysis.images_space_bytes,
+                "containers_reclaimable": analysis.containers_reclaimable,
+                "containers_space_bytes": analysis.containers_space_bytes,
+                "volumes_reclaimable": analysis.volumes_reclaimable,
+                "volumes_space_bytes": analysis.volumes_space_bytes,
+                "networks_reclaimable": analysis.networks_reclaimable,
+                "build_cache_space_bytes": analysis.build_cache_space_bytes,
+                "total_space_bytes": analysis.total_space_bytes,
+            })
+        });
 
         let message = format!(
-            "Docker cleanup analysis: {} reclaimable ({})",
-            analysis.total_items(),
-            analysis.total_space_formatted()
+            "Docker cleanup analysis: {} reclaimable ({}){}",
+            total_items,
+            CleanupAnalysis::format_space(total_space_bytes),
+            report.failure_note()
         );
 
         // Prepare structured data
         let data = json!({
-            "images_reclaimable": analysis.images_reclaimable,
-            "images_space_bytes": analysis.images_space_bytes,
-            "containers_reclaimable": analysis.containers_reclaimable,
-            "containers_space_bytes": analysis.containers_space_bytes,
-            "volumes_reclaimable": analysis.volumes_reclaimable,
-            "volumes_space_bytes": analysis.volumes_space_bytes,
-            "networks_reclaimable": analysis.networks_reclaimable,
-            "build_cache_space_bytes": analysis.build_cache_space_bytes,
-            "total_space_bytes": analysis.total_space_bytes,
+            "total_items": total_items,
+            "total_space_bytes": total_space_bytes,
+            "servers": report.servers,
         });
 
-        // Prepare metrics
-        let mut metrics = HashMap::new();
-        metrics.insert(
-            "images_reclaimable".to_string(),
-            analysis.images_reclaimable as f64,
-        );
-        metrics.insert(
-            "images_space_mb".to_string(),
-            analysis.images_space_bytes as f64 / 1024.0 / 1024.0,
-        );
-        metrics.insert(
-            "containers_reclaimable".to_string(),
-            analysis.containers_reclaimable as f64,
-        );
-        metrics.insert(
-            "volumes_reclaimable".to_string(),
-            analysis.volumes_reclaimable as f64,
-        );
-        metrics.insert(
-            "networks_reclaimable".to_string(),
-            analysis.networks_reclaimable as f64,
-        );
-        metrics.insert(
-            "total_space_mb".to_string(),
-            analysis.total_space_bytes as f64 / 1024.0 / 1024.0,
-        );
-
         Ok(PluginResult {
-            success: true,
+            success: report.failed == 0,
             message,
             data: Some(data),
-            metrics: Some(metrics),
+            metrics: Some(report.metrics),
         })
     }
 
@@ -196,95 +236,111 @@ impl DockerPlugin {
     async fn advanced_analysis(&self, context: &PluginContext) -> Result<PluginResult> {
         info!("Running advanced Docker analysis");
 
-        // Create analysis manager
-        let manager = AnalysisManager::new().await?;
-
-        // Perform all analyses
-        let unused_images = manager.analyze_unused_images().await?;
-        let container_logs = manager.analyze_container_logs().await?;
-        let image_layers = manager.analyze_image_layers().await?;
+        let runs = for_each_server(context, |server, docker| async move {
+            let manager = AnalysisManager::new(docker, server, server.ssh_key_path.as_deref());
+
+            // Perform all analyses
+            let unused_images = manager.analyze_unused_images().await?;
+            let container_logs = manager.analyze_container_logs().await?;
+            let image_layers = manager.analyze_image_layers().await?;
+
+            // Send notification with summary
+            self.send_analysis_notification(
+                &context.notification_manager,
+                &server.name,
+                &unused_images,
+                &container_logs,
+                &image_layers,
+            )
+            .await?;
+
+            Ok((unused_images, container_logs, image_layers))
+        })
+        .await;
+
+        let (mut unused_count, mut unused_bytes, mut large_logs) = (0, 0, 0);
+        let report = runs.report(
+            |server, (unused_images, container_logs, image_layers), metrics| {
+                unused_count += unused_images.total_count;
+                unused_bytes += unused_images.total_size_bytes;
+                large_logs += container_logs.containers_over_threshold;
+
+                let mut add = |name: &str, value: f64| {
+                    metrics.insert(server_metric_key(&server.name, name), value);
+                };
+                add("unused_images_count", unused_images.total_count as f64);
+                add(
+                    "unused_images_mb",
+                    unused_images.total_size_bytes as f64 / 1024.0 / 1024.0,
+                );
+                add(
+                    "large_logs_count",
+                    container_logs.containers_over_threshold as f64,
+                );
+                add(
+                    "total_logs_mb",
+                    container_logs.total_size_bytes as f64 / 1024.0 / 1024.0,
+                );
+                add("layer_efficiency_percent", image_layers.efficiency_percent);
+                add(
+                    "shared_layers_count",
+                    image_layers.shared_layers.len() as f64,
+                );
+
+                json!({
+                    "unused_images": {
+                        "count": unused_images.total_count,
+                        "total_size_bytes": unused_images.total_size_bytes,
+                        "images": unused_images.images,
+                    },
+                    "container_logs": {
+                        "total_size_bytes": container_logs.total_size_bytes,
+                        "containers_over_threshold": container_logs.containers_over_threshold,
+                        "containers": container_logs.containers,
+                    },
+                    "image_layers": {
+                        "total_shared_bytes": image_layers.total_shared_bytes,
+                        "total_unique_bytes": image_layers.total_unique_bytes,
+                        "efficiency_percent": image_layers.efficiency_percent,
+                        "shared_layers_count": image_layers.shared_layers.len(),
+                    },
+                })
+            },
+        );
 
         let message = format!(
-            "Docker analysis: {} unused images ({:.1} MB), {} large logs ({:.1} MB), {:.1}% layer efficiency",
-            unused_images.total_count,
-            unused_images.total_size_bytes as f64 / 1024.0 / 1024.0,
-            container_logs.containers_over_threshold,
-            container_logs.total_size_bytes as f64 / 1024.0 / 1024.0,
-            image_layers.efficiency_percent
+            "Docker analysis: {} unused images ({:.1} MB), {} large logs{}",
+            unused_count,
+            unused_bytes as f64 / 1024.0 / 1024.0,
+            large_logs,
+            report.failure_note()
         );
 
         // Prepare structured data
         let data = json!({
-            "unused_images": {
-                "count": unused_images.total_count,
-                "total_size_bytes": unused_images.total_size_bytes,
-                "images": unused_images.images,
-            },
-            "container_logs": {
-                "total_size_bytes": container_logs.total_size_bytes,
-                "containers_over_threshold": container_logs.containers_over_threshold,
-                "containers": container_logs.containers,
-            },
-            "image_layers": {
-                "total_shared_bytes": image_layers.total_shared_bytes,
-                "total_unique_bytes": image_layers.total_unique_bytes,
-                "efficiency_percent": image_layers.efficiency_percent,
-                "shared_layers_count": image_layers.shared_layers.len(),
-            },
+            "unused_images_count": unused_count,
+            "unused_images_bytes": unused_bytes,
+            "large_logs_count": large_logs,
+            "servers": report.servers,
         });
 
-        // Prepare metrics
-        let mut metrics = HashMap::new();
-        metrics.insert(
-            "unused_images_count".to_string(),
-            unused_images.total_count as f64,
-        );
-        metrics.insert(
-            "unused_images_mb".to_string(),
-            unused_images.total_size_bytes as f64 / 1024.0 / 1024.0,
-        );
-        metrics.insert(
-            "large_logs_count".to_string(),
-            container_logs.containers_over_threshold as f64,
-        );
-        metrics.insert(
-            "total_logs_mb".to_string(),
-            container_logs.total_size_bytes as f64 / 1024.0 / 1024.0,
-        );
-        metrics.insert(
-            "layer_efficiency_percent".to_string(),
-            image_layers.efficiency_percent,
-        );
-        metrics.insert(
-            "shared_layers_count".to_string(),
-            image_layers.shared_layers.len() as f64,
-        );
-
-        // Send notification with summary
-        self.send_analysis_notification(
-            &context.notification_manager,
-            &unused_images,
-            &container_logs,
-            &image_layers,
-        )
-        .await?;
-
         Ok(PluginResult {
-            success: true,
+            success: report.failed == 0,
             message,
             data: Some(data),
-            metrics: Some(metrics),
+            metrics: Some(report.metrics),
         })
     }
 
     async fn send_analysis_notification(
         &self,
         notify_mgr: &svrctlrs_core::NotificationManager,
+        server: &str,
         unused_images: &analysis::UnusedImagesAnalysis,
         container_logs: &analysis::ContainerLogsAnalysis,
         image_layers: &analysis::LayersAnalysis,
     ) -> Result<()> {
-        let title = "Docker Advanced Analysis Report".to_string();
+        let title = format!("Docker Advanced Analysis Report for {}", server);
 
         let mut body = String::new();
         body.push_str("## Docker Resource Analysis\n\n");
@@ -352,3 +408,105 @@ impl DockerPlugin {
         Ok(())
     }
 }
+
+/// Run `f` against the Docker daemon of every server in the context, in parallel
+///
+/// Without configured servers the local daemon is used. A server whose
+/// daemon can't be reached gets an error instead of a result.
+async fn for_each_server<'a, T, F, Fut>(context: &'a PluginContext, f: F) -> ServerRuns<'a, T>
+where
+    F: Fn(&'a Server, Docker) -> Fut,
+    Fut: Future<Output = Result<T>> + 'a,
+{
+    let servers = if context.servers.is_empty() {
+        std::slice::from_ref(&*LOCAL_SERVER)
+    } else {
+        context.servers.as_slice()
+    };
+    info!(servers = servers.len(), "Connecting to Docker daemons");
+
+    let results = join_all(servers.iter().map(|server| {
+        let run = &f;
+        async move {
+            let connection =
+                DockerConnection::connect(server, server.ssh_key_path.as_deref()).await?;
+            run(server, connection.docker()).await
+        }
+    }))
+    .await;
+
+    ServerRuns {
+        results: servers.iter().zip(results).collect(),
+    }
+}
+
+/// The local machine, used when no servers are configured
+static LOCAL_SERVER: LazyLock<Server> = LazyLock::new(|| Server::local("localhost"));
+
+/// Per-server results of a Docker task
+struct ServerRuns<'a, T> {
+    results: Vec<(&'a Server, Result<T>)>,
+}
+
+impl<T> ServerRuns<'_, T> {
+    /// Build the per-server report; `describe` adds a server's metrics and
+    /// returns its data
+    fn report(
+        self,
+        mut describe: impl FnMut(&Server, T, &mut HashMap<String, f64>) -> serde_json::Value,
+    ) -> ServerReport {
+        let mut report = ServerReport {
+            servers: Vec::new(),
+            metrics: HashMap::new(),
+            failed: 0,
+        };
+
+        for (server, result) in self.results {
+            match result {
+                Ok(value) => {
+                    report
+                        .metrics
+                        .insert(server_metric_key(&server.name, "reachable"), 1.0);
+                    let mut data = describe(server, value, &mut report.metrics);
+                    if let Some(object) = data.as_object_mut() {
+                        object.insert("server".to_string(), json!(server.name));
+                        object.insert("success".to_string(), json!(true));
+                    }
+                    report.servers.push(data);
+                }
+                Err(e) => {
+                    warn!(server = %server.name, error = %e, "Docker task failed");
+                    report.failed += 1;
+                    report
+                        .metrics
+                        .insert(server_metric_key(&server.name, "reachable"), 0.0);
+                    report.servers.push(json!({
+                        "server": server.name,
+                        "success": false,
+                        "error": e.to_string(),
+                    }));
+                }
+            }
+        }
+
+        report
+    }
+}
+
+/// Per-server data and metrics of a Docker task
+struct ServerReport {
+    servers: Vec<serde_json::Value>,
+    metrics: HashMap<String, f64>,
+    /// Servers whose daemon couldn't be reached or whose check failed
+    failed: usize,
+}
+
+impl ServerReport {
+    /// Suffix for the task message when some servers failed
+    fn failure_note(&self) -> String {
+        match self.failed {
+            0 => String::new(),
+            failed => format!(" ({} of {} servers failed)", failed, self.servers.len()),
+        }
+    }
+}
@@ -0,0 +1,204 @@
+//! Metrics collection for local and remote servers
+//!
+//! Localhost is read straight from /proc. Remote servers are sampled with a
+//! single SSH round trip through `RemoteExecutor` that dumps the same files.
+
+use std::collections::HashMap;
+use svrctlrs_core::{Error, RemoteExecutor, Result, Server};
+use tokio::time::{sleep, Duration};
+use tracing::{debug, instrument};
+
+use crate::metrics::{self, CpuStats, SystemMetrics};
+
+/// Seconds between the two /proc/stat samples used to compute CPU usage
+const CPU_SAMPLE_INTERVAL_SECS: u64 = 1;
+
+/// Timeout for a single collection run on one server
+const COLLECT_TIMEOUT_SECS: u64 = 30;
+
+/// Arguments for `df`: POSIX format, filesystem type, sizes in bytes
+const DF_ARGS: &[&str] = &["-P", "-T", "-B1"];
+
+/// Raw command/file output gathered from a server
+#[derive(Debug, Default)]
+struct RawSnapshot {
+    stat_before: String,
+    stat_after: String,
+    loadavg: String,
+    meminfo: String,
+    net_dev: String,
+    df: String,
+}
+
+/// Collects system metrics from a single server
+pub struct MetricsCollector {
+    executor: RemoteExecutor,
+}
+
+impl MetricsCollector {
+    /// Create a collector for a server
+    pub fn new(server: Server, ssh_key_path: Option<String>) -> Self {
+        Self {
+            executor: RemoteExecutor::for_server(server, ssh_key_path)
+                .with_timeout(COLLECT_TIMEOUT_SECS),
+        }
+    }
+
+    /// Collect all metrics from the server
+    #[instrument(skip(self), fields(server = %self.executor.server().name))]
+    pub async fn collect(&self) -> Result<SystemMetrics> {
+        let snapshot = if self.executor.server().is_local() {
+            self.snapshot_local().await?
+        } else {
+            self.snapshot_remote().await?
+        };
+
+        build_metrics(&snapshot)
+    }
+
+    /// Read /proc files directly on the local machine
+    async fn snapshot_local(&self) -> Result<RawSnapshot> {
+        let stat_before = read_proc("/proc/stat").await?;
+        sleep(Duration::from_secs(CPU_SAMPLE_INTERVAL_SECS)).await;
+        let stat_after = read_proc("/proc/stat").await?;
+
+        // df is the only portable way to get filesystem usage without extra dependencies
+        let df = self
+            .executor
+            .execute_command("df", DF_ARGS)
+            .await
+            .unwrap_or_default();
+
+        Ok(RawSnapshot {
+            stat_before,
+            stat_after,
+            loadavg: read_proc("/proc/loadavg").await?,
+            meminfo: read_proc("/proc/meminfo").await?,
+            net_dev: read_proc("/proc/net/dev").await?,
+            df,
+        })
+    }
+
+    /// Dump all needed /proc files in one SSH session
+    async fn snapshot_remote(&self) -> Result<RawSnapshot> {
+        let script = format!(
+            "echo '@@stat_before'; cat /proc/stat; sleep {interval}; \
+             echo '@@stat_after'; cat /proc/stat; \
+             echo '@@loadavg'; cat /proc/loadavg; \
+             echo '@@meminfo'; cat /proc/meminfo; \
+             echo '@@net_dev'; cat /proc/net/dev; \
+             echo '@@df'; df {df_args} 2>/dev/null",
+            interval = CPU_SAMPLE_INTERVAL_SECS,
+            df_args = DF_ARGS.join(" "),
+        );
+
+        let output = self
+            .executor
+            .execute_command("sh", &["-c", &script])
+            .await?;
+        let mut sections = split_sections(&output);
+        debug!(
+            sections = sections.len(),
+            "Received remote metrics snapshot"
+        );
+
+        if !sections.contains_key("stat_before") {
+            return Err(Error::PluginError(format!(
+                "No metrics returned from {}",
+                self.executor.server().name
+            )));
+        }
+
+        let mut take = |name: &str| sections.remove(name).unwrap_or_default();
+        Ok(RawSnapshot {
+            stat_before: take("stat_before"),
+            stat_after: take("stat_after"),
+            loadavg: take("loadavg"),
+            meminfo: take("meminfo"),
+            net_dev: take("net_dev"),
+            df: take("df"),
+        })
+    }
+}
+
+async fn read_proc(path: &str) -> Result<String> {
+    tokio::fs::read_to_string(path)
+        .await
+        .map_err(|e| Error::PluginError(format!("Failed to read {}: {}", path, e)))
+}
+
+/// Split `@@name` delimited output into named sections
+fn split_sections(output: &str) -> HashMap<String, String> {
+    let mut sections = HashMap::new();
+    let mut current: Option<(String, String)> = None;
+
+    for line in output.lines() {
+        if let Some(name) = line.strip_prefix("@@") {
+            if let Some((name, body)) = current.take() {
+                sections.insert(name, body);
+            }
+            current = Some((name.trim().to_string(), String::new()));
+        } else if let Some((_, body)) = current.as_mut() {
+            body.push_str(line);
+            body.push('\n');
+        }
+    }
+
+    if let Some((name, body)) = current {
+        sections.insert(name, body);
+    }
+
+    sections
+}
+
+fn build_metrics(snapshot: &RawSnapshot) -> Result<SystemMetrics> {
+    let before = metrics::parse_cpu_times(&snapshot.stat_before)
+        .ok_or_else(|| Error::PluginError("Failed to parse /proc/stat".to_string()))?;
+    let usage_percent = metrics::parse_cpu_times(&snapshot.stat_after)
+        .map(|after| after.usage_since(&before))
+        .unwrap_or(0.0);
+
+    Ok(SystemMetrics {
+        cpu: CpuStats {
+            cores: metrics::parse_cpu_cores(&snapshot.stat_before),
+            usage_percent,
+        },
+        load: metrics::parse_loadavg(&snapshot.loadavg).unwrap_or_default(),
+        memory: metrics::parse_meminfo(&snapshot.meminfo),
+        disks: metrics::parse_df(&snapshot.df),
+        network: metrics::parse_net_dev(&snapshot.net_dev),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_sections() {
+        let output = "@@stat_before\ncpu  1 2 3 4\n@@loadavg\n0.1 0.2 0.3 1/2 3\n@@df\n";
+        let sections = split_sections(output);
+
+        assert_eq!(sections["stat_before"], "cpu  1 2 3 4\n");
+        assert_eq!(sections["loadavg"], "0.1 0.2 0.3 1/2 3\n");
+        assert_eq!(sections["df"], "");
+    }
+
+    #[test]
+    fn test_build_metrics_requires_stat() {
+        assert!(build_metrics(&RawSnapshot::default()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_collect_local() {
+        if !std::path::Path::new("/proc/stat").exists() {
+            return;
+        }
+
+        let collector = MetricsCollector::new(Server::local("localhost"), None);
+        let metrics = collector.collect().await.unwrap();
+
+        assert!(metrics.cpu.cores > 0);
+        assert!(metrics.memory.total_bytes > 0);
+    }
+}
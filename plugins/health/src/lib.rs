@@ -1,8 +1,20 @@
 //! System health monitoring plugin
+//!
+//! Collects CPU, load, memory, disk, and network metrics from every server
+
+mod collector;
+mod metrics;
 
 use async_trait::async_trait;
-use svrctlrs_core::{Plugin, PluginContext, PluginMetadata, PluginResult, Result, ScheduledTask};
-use tracing::info;
+use collector::MetricsCollector;
+use serde_json::json;
+use std::collections::HashMap;
+use svrctlrs_core::{
+    server_metric_key, Plugin, PluginContext, PluginMetadata, PluginResult, Result, ScheduledTask,
+    Server,
+};
+use tokio::task::JoinSet;
+use tracing::{info, instrument, warn};
 
 /// System health monitoring plugin (CPU, memory, disk, network)
 pub struct HealthPlugin {}
@@ -56,13 +68,79 @@ impl Plugin for HealthPlugin {
 }
 
 impl HealthPlugin {
-    async fn collect_metrics(&self, _context: &PluginContext) -> Result<PluginResult> {
-        // TODO: Implement actual metrics collection
+    #[instrument(skip(self, context))]
+    async fn collect_metrics(&self, context: &PluginContext) -> Result<PluginResult> {
+        // Fall back to the local machine when no servers are configured
+        let servers = if context.servers.is_empty() {
+            vec![Server::local("localhost")]
+        } else {
+            context.servers.clone()
+        };
+        info!(servers = servers.len(), "Collecting system metrics");
+
+        // Collect from all servers in parallel
+        let mut set = JoinSet::new();
+        for (index, server) in servers.into_iter().enumerate() {
+            let collector = MetricsCollector::new(server.clone(), server.ssh_key_path.clone());
+            set.spawn(async move { (index, server, collector.collect().await) });
+        }
+
+        let mut results = Vec::new();
+        while let Some(joined) = set.join_next().await {
+            match joined {
+                Ok(result) => results.push(result),
+                Err(e) => warn!(error = %e, "Metrics collection task panicked"),
+            }
+        }
+        results.sort_by_key(|(index, _, _)| *index);
+
+        let total = results.len();
+        let mut failed = 0;
+        let mut metrics = HashMap::new();
+        let mut server_data = Vec::new();
+
+        for (_, server, result) in results {
+            match result {
+                Ok(system_metrics) => {
+                    metrics.insert(server_metric_key(&server.name, "reachable"), 1.0);
+                    for (name, value) in system_metrics.to_metric_pairs() {
+                        metrics.insert(server_metric_key(&server.name, &name), value);
+                    }
+                    server_data.push(json!({
+                        "server": server.name,
+                        "success": true,
+                        "metrics": system_metrics,
+                    }));
+                }
+                Err(e) => {
+                    warn!(server = %server.name, error = %e, "Failed to collect metrics");
+                    failed += 1;
+                    metrics.insert(server_metric_key(&server.name, "reachable"), 0.0);
+                    server_data.push(json!({
+                        "server": server.name,
+                        "success": false,
+                        "error": e.to_string(),
+                    }));
+                }
+            }
+        }
+
+        let message = if failed == 0 {
+            format!("Collected system metrics from {} servers", total)
+        } else {
+            format!(
+                "Collected system metrics from {}/{} servers ({} failed)",
+                total - failed,
+                total,
+                failed
+            )
+        };
+
         Ok(PluginResult {
-            success: true,
-            message: "Metrics collection placeholder".to_string(),
-            data: None,
-            metrics: None,
+            success: failed == 0,
+            message,
+            data: Some(json!({ "servers": server_data })),
+            metrics: Some(metrics),
         })
     }
 }
@@ -0,0 +1,454 @@
+//! System metrics types and parsers
+//!
+//! Parses the raw output of /proc files and `df` into structured metrics.
+//! Parsers are pure functions so they work the same for local and remote hosts.
+
+use serde::{Deserialize, Serialize};
+
+/// Filesystem types that are never reported as disks
+const IGNORED_FS_TYPES: &[&str] = &[
+    "tmpfs", "devtmpfs", "overlay", "squashfs", "proc", "sysfs", "cgroup", "cgroup2", "devpts",
+    "efivarfs",
+];
+
+/// Aggregate CPU time counters from the first line of /proc/stat (in jiffies)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CpuTimes {
+    pub idle: u64,
+    pub total: u64,
+}
+
+impl CpuTimes {
+    /// CPU usage percentage between two samples
+    pub fn usage_since(&self, earlier: &CpuTimes) -> f64 {
+        let total = self.total.saturating_sub(earlier.total);
+        if total == 0 {
+            return 0.0;
+        }
+        let idle = self.idle.saturating_sub(earlier.idle);
+        (total.saturating_sub(idle)) as f64 / total as f64 * 100.0
+    }
+}
+
+/// CPU statistics
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CpuStats {
+    pub cores: usize,
+    pub usage_percent: f64,
+}
+
+/// Load averages from /proc/loadavg
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct LoadAverage {
+    pub one: f64,
+    pub five: f64,
+    pub fifteen: f64,
+}
+
+/// Memory and swap statistics from /proc/meminfo (in bytes)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct MemoryStats {
+    pub total_bytes: u64,
+    pub available_bytes: u64,
+    pub used_bytes: u64,
+    pub used_percent: f64,
+    pub swap_total_bytes: u64,
+    pub swap_used_bytes: u64,
+    pub swap_used_percent: f64,
+}
+
+/// Disk usage for a single mount point
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct DiskUsage {
+    pub filesystem: String,
+    pub fs_type: String,
+    pub mount_point: String,
+    pub total_bytes: u64,
+    pub used_bytes: u64,
+    pub available_bytes: u64,
+    pub used_percent: f64,
+}
+
+/// Network counters for a single interface from /proc/net/dev
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct NetworkInterface {
+    pub name: String,
+    pub rx_bytes: u64,
+    pub rx_packets: u64,
+    pub rx_errors: u64,
+    pub rx_dropped: u64,
+    pub tx_bytes: u64,
+    pub tx_packets: u64,
+    pub tx_errors: u64,
+    pub tx_dropped: u64,
+}
+
+/// All metrics collected from one server
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SystemMetrics {
+    pub cpu: CpuStats,
+    pub load: LoadAverage,
+    pub memory: MemoryStats,
+    pub disks: Vec<DiskUsage>,
+    pub network: Vec<NetworkInterface>,
+}
+
+impl SystemMetrics {
+    /// Flatten into `(metric_name, value)` pairs
+    ///
+    /// Per-mount and per-interface metrics carry the mount point or interface
+    /// name after a colon (e.g. `disk_used_percent:/var`, `net_rx_bytes:eth0`).
+    pub fn to_metric_pairs(&self) -> Vec<(String, f64)> {
+        let mut pairs = vec![
+            ("cpu_cores".to_string(), self.cpu.cores as f64),
+            ("cpu_usage_percent".to_string(), self.cpu.usage_percent),
+            ("load_1m".to_string(), self.load.one),
+            ("load_5m".to_string(), self.load.five),
+            ("load_15m".to_string(), self.load.fifteen),
+            (
+                "mem_total_bytes".to_string(),
+                self.memory.total_bytes as f64,
+            ),
+            ("mem_used_bytes".to_string(), self.memory.used_bytes as f64),
+            ("mem_used_percent".to_string(), self.memory.used_percent),
+            (
+                "swap_total_bytes".to_string(),
+                self.memory.swap_total_bytes as f64,
+            ),
+            (
+                "swap_used_bytes".to_string(),
+                self.memory.swap_used_bytes as f64,
+            ),
+            (
+                "swap_used_percent".to_string(),
+                self.memory.swap_used_percent,
+            ),
+        ];
+
+        for disk in &self.disks {
+            let mount = &disk.mount_point;
+            pairs.push((
+                format!("disk_total_bytes:{}", mount),
+                disk.total_bytes as f64,
+            ));
+            pairs.push((format!("disk_used_bytes:{}", mount), disk.used_bytes as f64));
+            pairs.push((format!("disk_used_percent:{}", mount), disk.used_percent));
+        }
+
+        for iface in &self.network {
+            let name = &iface.name;
+            pairs.push((format!("net_rx_bytes:{}", name), iface.rx_bytes as f64));
+            pairs.push((format!("net_tx_bytes:{}", name), iface.tx_bytes as f64));
+            pairs.push((format!("net_rx_errors:{}", name), iface.rx_errors as f64));
+            pairs.push((format!("net_tx_errors:{}", name), iface.tx_errors as f64));
+        }
+
+        pairs
+    }
+}
+
+/// Parse the aggregate `cpu` line of /proc/stat
+pub fn parse_cpu_times(stat: &str) -> Option<CpuTimes> {
+    let line = stat.lines().find(|l| l.starts_with("cpu "))?;
+    let values: Vec<u64> = line
+        .split_whitespace()
+        .skip(1)
+        .filter_map(|v| v.parse().ok())
+        .collect();
+
+    if values.len() < 4 {
+        return None;
+    }
+
+    // user nice system idle iowait irq softirq steal [guest guest_nice]
+    // guest time is already included in user/nice, so only sum the first 8
+    let total = values.iter().take(8).sum();
+    let idle = values[3] + values.get(4).copied().unwrap_or(0);
+
+    Some(CpuTimes { idle, total })
+}
+
+/// Count per-core `cpuN` lines in /proc/stat
+pub fn parse_cpu_cores(stat: &str) -> usize {
+    stat.lines()
+        .filter(|l| {
+            l.starts_with("cpu") && l[3..].chars().next().is_some_and(|c| c.is_ascii_digit())
+        })
+        .count()
+}
+
+/// Parse /proc/loadavg
+pub fn parse_loadavg(loadavg: &str) -> Option<LoadAverage> {
+    let mut parts = loadavg.split_whitespace();
+    Some(LoadAverage {
+        one: parts.next()?.parse().ok()?,
+        five: parts.next()?.parse().ok()?,
+        fifteen: parts.next()?.parse().ok()?,
+    })
+}
+
+/// Parse /proc/meminfo
+pub fn parse_meminfo(meminfo: &str) -> MemoryStats {
+    let field = |name: &str| -> Option<u64> {
+        meminfo
+            .lines()
+            .find(|l| l.split(':').next() == Some(name))
+            .and_then(|l| l.split_whitespace().nth(1))
+            .and_then(|v| v.parse::<u64>().ok())
+            .map(|kb| kb * 1024)
+    };
+
+    let total_bytes = field("MemTotal").unwrap_or(0);
+    // MemAvailable is missing on very old kernels, approximate it
+    let available_bytes = field("MemAvailable").unwrap_or_else(|| {
+        field("MemFree").unwrap_or(0) + field("Buffers").unwrap_or(0) + field("Cached").unwrap_or(0)
+    });
+    let used_bytes = total_bytes.saturating_sub(available_bytes);
+
+    let swap_total_bytes = field("SwapTotal").unwrap_or(0);
+    let swap_used_bytes = swap_total_bytes.saturating_sub(field("SwapFree").unwrap_or(0));
+
+    MemoryStats {
+        total_bytes,
+        available_bytes,
+        used_bytes,
+        used_percent: percent(used_bytes, total_bytes),
+        swap_total_bytes,
+        swap_used_bytes,
+        swap_used_percent: percent(swap_used_bytes, swap_total_bytes),
+    }
+}
+
+/// Parse `df -P -T -B1` output
+///
+/// Pseudo filesystems and duplicate mounts of the same device are skipped.
+pub fn parse_df(df: &str) -> Vec<DiskUsage> {
+    let mut disks: Vec<DiskUsage> = Vec::new();
+
+    for line in df.lines().skip(1) {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 7 {
+            continue;
+        }
+
+        let fs_type = parts[1];
+        if IGNORED_FS_TYPES.contains(&fs_type) {
+            continue;
+        }
+
+        let (Ok(total_bytes), Ok(used_bytes), Ok(available_bytes)) = (
+            parts[2].parse::<u64>(),
+            parts[3].parse::<u64>(),
+            parts[4].parse::<u64>(),
+        ) else {
+            continue;
+        };
+
+        if total_bytes == 0 || disks.iter().any(|d| d.filesystem == parts[0]) {
+            continue;
+        }
+
+        disks.push(DiskUsage {
+            filesystem: parts[0].to_string(),
+            fs_type: fs_type.to_string(),
+            // Mount points may contain spaces
+            mount_point: parts[6..].join(" "),
+            total_bytes,
+            used_bytes,
+            available_bytes,
+            // Match df's Use% (used / (used + available)), which excludes reserved blocks
+            used_percent: percent(used_bytes, used_bytes + available_bytes),
+        });
+    }
+
+    disks
+}
+
+/// Parse /proc/net/dev, skipping the loopback interface
+pub fn parse_net_dev(net_dev: &str) -> Vec<NetworkInterface> {
+    net_dev
+        .lines()
+        .skip(2) // Two header lines
+        .filter_map(|line| {
+            let (name, counters) = line.split_once(':')?;
+            let name = name.trim();
+            if name == "lo" {
+                return None;
+            }
+
+            let values: Vec<u64> = counters
+                .split_whitespace()
+                .filter_map(|v| v.parse().ok())
+                .collect();
+            if values.len() < 16 {
+                return None;
+            }
+
+            Some(NetworkInterface {
+                name: name.to_string(),
+                rx_bytes: values[0],
+                rx_packets: values[1],
+                rx_errors: values[2],
+                rx_dropped: values[3],
+                tx_bytes: values[8],
+                tx_packets: values[9],
+                tx_errors: values[10],
+                tx_dropped: values[11],
+            })
+        })
+        .collect()
+}
+
+fn percent(part: u64, whole: u64) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f64 / whole as f64 * 100.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STAT_BEFORE: &str = "cpu  4705 356 584 3699176 23060 0 277 0 0 0
+cpu0 1393 280 283 924823 8370 0 269 0 0 0
+cpu1 1079 23 116 924966 4831 0 3 0 0 0
+cpu2 1114 28 99 925020 5035 0 2 0 0 0
+cpu3 1119 25 86 924367 4824 0 3 0 0 0
+intr 4286563 30 10 0 0 0 0 0 0 1 0 0 0 156 0 0
+ctxt 7421389
+btime 1700000000
+";
+
+    const STAT_AFTER: &str = "cpu  4805 356 684 3699476 23160 0 277 0 0 0
+cpu0 1418 280 308 924898 8395 0 269 0 0 0
+";
+
+    #[test]
+    fn test_parse_cpu_times_and_usage() {
+        let before = parse_cpu_times(STAT_BEFORE).unwrap();
+        let after = parse_cpu_times(STAT_AFTER).unwrap();
+
+        assert_eq!(before.idle, 3699176 + 23060);
+        assert_eq!(before.total, 4705 + 356 + 584 + 3699176 + 23060 + 277);
+
+        // 200 busy jiffies out of 600 total
+        let usage = after.usage_since(&before);
+        assert!((usage - 33.333).abs() < 0.01, "usage was {}", usage);
+    }
+
+    #[test]
+    fn test_parse_cpu_cores() {
+        assert_eq!(parse_cpu_cores(STAT_BEFORE), 4);
+    }
+
+    #[test]
+    fn test_parse_loadavg() {
+        let load = parse_loadavg("0.52 0.58 0.59 2/1002 12345\n").unwrap();
+        assert_eq!(
+            load,
+            LoadAverage {
+                one: 0.52,
+                five: 0.58,
+                fifteen: 0.59
+            }
+        );
+        assert!(parse_loadavg("").is_none());
+    }
+
+    #[test]
+    fn test_parse_meminfo() {
+        let output = "MemTotal:        8000000 kB
+MemFree:          500000 kB
+MemAvailable:    2000000 kB
+Buffers:          100000 kB
+Cached:          1500000 kB
+SwapCached:            0 kB
+SwapTotal:       2000000 kB
+SwapFree:        1500000 kB
+";
+        let mem = parse_meminfo(output);
+
+        assert_eq!(mem.total_bytes, 8000000 * 1024);
+        assert_eq!(mem.available_bytes, 2000000 * 1024);
+        assert_eq!(mem.used_bytes, 6000000 * 1024);
+        assert!((mem.used_percent - 75.0).abs() < f64::EPSILON);
+        assert_eq!(mem.swap_used_bytes, 500000 * 1024);
+        assert!((mem.swap_used_percent - 25.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_parse_meminfo_without_mem_available() {
+        let output = "MemTotal:        1000 kB
+MemFree:          200 kB
+Buffers:          100 kB
+Cached:           200 kB
+SwapTotal:          0 kB
+SwapFree:           0 kB
+";
+        let mem = parse_meminfo(output);
+
+        assert_eq!(mem.available_bytes, 500 * 1024);
+        assert!((mem.used_percent - 50.0).abs() < f64::EPSILON);
+        assert_eq!(mem.swap_used_percent, 0.0);
+    }
+
+    #[test]
+    fn test_parse_df() {
+        let output = "Filesystem     Type      1-blocks        Used   Available Capacity Mounted on
+/dev/sda1      ext4   105089261568 42035704627 57666015232      43% /
+tmpfs          tmpfs    4096000000           0  4096000000       0% /dev/shm
+/dev/sdb1      xfs    2000000000000 1500000000000 500000000000      75% /mnt/backup disk
+/dev/sda1      ext4   105089261568 42035704627 57666015232      43% /var/lib/docker
+overlay        overlay 105089261568 42035704627 57666015232      43% /var/lib/docker/overlay2/abc/merged
+";
+        let disks = parse_df(output);
+
+        assert_eq!(disks.len(), 2);
+        assert_eq!(disks[0].mount_point, "/");
+        assert_eq!(disks[0].fs_type, "ext4");
+        assert_eq!(disks[0].total_bytes, 105089261568);
+        assert_eq!(disks[1].mount_point, "/mnt/backup disk");
+        assert!((disks[1].used_percent - 75.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_parse_net_dev() {
+        let output = "Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+    lo: 1234567    8901    0    0    0     0          0         0  1234567    8901    0    0    0     0       0          0
+  eth0: 98765432  123456    1    2    0     0          0       100 12345678   65432    3    4    0     0       0          0
+";
+        let ifaces = parse_net_dev(output);
+
+        assert_eq!(ifaces.len(), 1);
+        assert_eq!(ifaces[0].name, "eth0");
+        assert_eq!(ifaces[0].rx_bytes, 98765432);
+        assert_eq!(ifaces[0].rx_errors, 1);
+        assert_eq!(ifaces[0].tx_bytes, 12345678);
+        assert_eq!(ifaces[0].tx_dropped, 4);
+    }
+
+    #[test]
+    fn test_to_metric_pairs() {
+        let metrics = SystemMetrics {
+            disks: vec![DiskUsage {
+                mount_point: "/".to_string(),
+                used_percent: 43.0,
+                ..Default::default()
+            }],
+            network: vec![NetworkInterface {
+                name: "eth0".to_string(),
+                rx_bytes: 10,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let pairs = metrics.to_metric_pairs();
+
+        assert!(pairs.contains(&("disk_used_percent:/".to_string(), 43.0)));
+        assert!(pairs.contains(&("net_rx_bytes:eth0".to_string(), 10.0)));
+        assert!(pairs.iter().any(|(name, _)| name == "cpu_usage_percent"));
+    }
+}
@@ -1,23 +1,45 @@
 //! OS update detection module
 //!
-//! Detects available OS updates using package managers (apt, dnf, pacman)
+//! Detects available OS updates using package managers (apt, dnf, yum,
+//! zypper, apk, pacman), plus flatpak and snap when they are installed
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use svrctlrs_core::{Error, RemoteExecutor, Result, Server};
-use tracing::{debug, info, instrument};
+use tracing::{debug, info, instrument, warn};
 
 /// Package manager types
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PackageManager {
     Apt,
     Dnf,
+    Yum,
+    Zypper,
+    Apk,
     Pacman,
+    Flatpak,
+    Snap,
 }
 
 impl PackageManager {
-    /// Get all supported package managers
+    /// Get all supported system package managers, in detection order
+    ///
+    /// DNF comes before YUM because `yum` is an alias for `dnf` on newer
+    /// RHEL-family systems.
     pub fn all() -> Vec<Self> {
-        vec![Self::Apt, Self::Dnf, Self::Pacman]
+        vec![
+            Self::Apt,
+            Self::Dnf,
+            Self::Yum,
+            Self::Zypper,
+            Self::Apk,
+            Self::Pacman,
+        ]
+    }
+
+    /// Get the application package managers checked alongside the system one
+    pub fn secondary() -> Vec<Self> {
+        vec![Self::Flatpak, Self::Snap]
     }
 
     /// Get the binary name for this package manager
@@ -25,7 +47,26 @@ impl PackageManager {
         match self {
             Self::Apt => "apt",
             Self::Dnf => "dnf",
+            Self::Yum => "yum",
+            Self::Zypper => "zypper",
+            Self::Apk => "apk",
             Self::Pacman => "pacman",
+            Self::Flatpak => "flatpak",
+            Self::Snap => "snap",
+        }
+    }
+
+    /// Get the full path of the binary (SSH sessions may have a minimal PATH)
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Apt => "/usr/bin/apt",
+            Self::Dnf => "/usr/bin/dnf",
+            Self::Yum => "/usr/bin/yum",
+            Self::Zypper => "/usr/bin/zypper",
+            Self::Apk => "/sbin/apk",
+            Self::Pacman => "/usr/bin/pacman",
+            Self::Flatpak => "/usr/bin/flatpak",
+            Self::Snap => "/usr/bin/snap",
         }
     }
 
@@ -34,19 +75,90 @@ impl PackageManager {
         match self {
             Self::Apt => "APT",
             Self::Dnf => "DNF",
+            Self::Yum => "YUM",
+            Self::Zypper => "Zypper",
+            Self::Apk => "APK",
             Self::Pacman => "Pacman",
+            Self::Flatpak => "Flatpak",
+            Self::Snap => "Snap",
         }
     }
 }
 
+/// A package with a pending update
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackageUpdate {
+    pub name: String,
+    pub arch: Option<String>,
+    /// Installed version, when the package manager reports it
+    pub current_version: Option<String>,
+    pub new_version: Option<String>,
+    pub repository: Option<String>,
+    pub is_security: bool,
+    /// Advisory severity ("Critical", "Important", "Moderate", "Low")
+    pub severity: Option<String>,
+}
+
+impl PackageUpdate {
+    fn named(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+impl std::fmt::Display for PackageUpdate {
+    /// `name current -> new (security, Important)`, omitting what is unknown
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)?;
+        match (&self.current_version, &self.new_version) {
+            (Some(current), Some(new)) => write!(f, " {} -> {}", current, new)?,
+            (None, Some(new)) => write!(f, " -> {}", new)?,
+            _ => {}
+        }
+        match (&self.severity, self.is_security) {
+            (Some(severity), true) => write!(f, " (security, {})", severity),
+            (None, true) => write!(f, " (security)"),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Rank of an advisory severity, higher is more severe
+fn severity_rank(severity: Option<&str>) -> u8 {
+    match severity.map(|s| s.to_ascii_lowercase()).as_deref() {
+        Some("critical") => 4,
+        Some("important") | Some("high") => 3,
+        Some("moderate") | Some("medium") => 2,
+        Some("low") => 1,
+        _ => 0,
+    }
+}
+
 /// Update checker trait for different package managers
 pub trait UpdateChecker: Send + Sync {
     /// Get the command to check for available updates
     /// Returns: (command, args)
     fn check_command(&self) -> (&str, Vec<&str>);
 
-    /// Parse the output from the check command into a list of package names
-    fn parse_updates(&self, output: &str) -> Vec<String>;
+    /// Get the command that refreshes the metadata cache the check reads from,
+    /// for checkers that only consult the local cache
+    fn refresh_command(&self) -> Option<(&str, Vec<&str>)> {
+        None
+    }
+
+    /// Parse the output from the check command into the pending updates
+    fn parse_updates(&self, output: &str) -> Vec<PackageUpdate>;
+
+    /// Get extra commands whose output fills in details the check command
+    /// lacks (installed versions, security advisories)
+    fn detail_commands(&self) -> Vec<(&str, Vec<&str>)> {
+        Vec::new()
+    }
+
+    /// Fill in update details from the output of each detail command, in order
+    fn add_details(&self, _outputs: &[String], _updates: &mut [PackageUpdate]) {}
 }
 
 /// APT package manager checker (Debian, Ubuntu, etc.)
@@ -59,28 +171,141 @@ impl UpdateChecker for AptChecker {
         ("/usr/bin/apt", vec!["list", "--upgradable"])
     }
 
-    fn parse_updates(&self, output: &str) -> Vec<String> {
+    fn parse_updates(&self, output: &str) -> Vec<PackageUpdate> {
+        // name/suite[,suite] new-version arch [upgradable from: current-version]
         output
             .lines()
             .skip(1) // Skip "Listing..." header
             .filter(|line| line.contains("[upgradable from:"))
-            .map(|line| {
-                // Extract package name (everything before the first '/')
-                let package_name = line.split('/').next().unwrap_or(line);
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let (name, suites) = fields.next()?.split_once('/')?;
+                let new_version = fields.next();
+                let arch = fields.next();
+                let current_version = line
+                    .split("[upgradable from:")
+                    .nth(1)
+                    .map(|rest| rest.trim().trim_end_matches(']').trim());
 
-                // Check if this is a security update
-                let is_security = line.contains("-security");
-
-                if is_security {
-                    format!("{} (security)", package_name)
-                } else {
-                    package_name.to_string()
-                }
+                Some(PackageUpdate {
+                    name: name.to_string(),
+                    arch: arch.map(String::from),
+                    current_version: current_version.map(String::from),
+                    new_version: new_version.map(String::from),
+                    repository: Some(suites.to_string()),
+                    // Security fixes are published to the "<release>-security" suite
+                    is_security: suites.split(',').any(|s| s.ends_with("-security")),
+                    severity: None,
+                })
             })
             .collect()
     }
 }
 
+/// Split a `name-version-release` package string (RPM, APK) into the name
+/// and `version-release`
+fn split_name_version(package: &str) -> Option<(&str, &str)> {
+    let mut parts = package.rsplitn(3, '-');
+    let _release = parts.next()?;
+    let _version = parts.next()?;
+    let name = parts.next().filter(|name| !name.is_empty())?;
+    Some((name, &package[name.len() + 1..]))
+}
+
+/// Parse `dnf check-update` / `yum check-update` output
+///
+/// Update lines are `name.arch  version  repository`. Continuation lines are
+/// indented, and the "Obsoleting Packages" section that may follow lists
+/// packages that are already counted above.
+fn parse_check_update(output: &str) -> Vec<PackageUpdate> {
+    output
+        .lines()
+        .take_while(|line| !line.starts_with("Obsoleting Packages"))
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter(|line| !line.starts_with(char::is_whitespace))
+        .filter_map(|line| {
+            // Lines with updates have at least 3 parts (package, version, repo)
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let [name_arch, version, repository, ..] = parts.as_slice() else {
+                return None;
+            };
+
+            // Split the arch suffix (e.g., "docker-ce.x86_64" -> "docker-ce", "x86_64")
+            let (name, arch) = match name_arch.rsplit_once('.') {
+                Some((name, arch)) => (name, Some(arch)),
+                None => (*name_arch, None),
+            };
+
+            Some(PackageUpdate {
+                name: name.to_string(),
+                arch: arch.map(String::from),
+                new_version: Some(version.to_string()),
+                repository: Some(repository.to_string()),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// Command listing the installed version of every RPM package
+const RPM_INSTALLED_COMMAND: (&str, &[&str]) = (
+    "/usr/bin/rpm",
+    &["-qa", "--qf", "%{NAME} %{ARCH} %{VERSION}-%{RELEASE}\\n"],
+);
+
+/// Fill in installed versions from `rpm -qa` output (`name arch version-release`)
+fn add_rpm_installed_versions(output: &str, updates: &mut [PackageUpdate]) {
+    let installed: HashMap<(&str, &str), &str> = output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            Some(((fields.next()?, fields.next()?), fields.next()?))
+        })
+        .collect();
+
+    for update in updates {
+        let arch = update.arch.as_deref().unwrap_or_default();
+        if let Some(version) = installed.get(&(update.name.as_str(), arch)) {
+            update.current_version = Some(version.to_string());
+        }
+    }
+}
+
+/// Mark updates covered by security advisories, from `updateinfo list` output
+///
+/// Handles the dnf 4 / yum layout (`ADVISORY  Severity/Sec.  name-evr.arch`)
+/// and the dnf 5 layout (`ADVISORY  security  Severity  name-evr.arch  issued`).
+/// A package covered by several advisories gets the highest severity.
+fn add_security_advisories(output: &str, updates: &mut [PackageUpdate]) {
+    for line in output.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let (severity, nevra) = match fields.as_slice() {
+            [_, kind, nevra] if kind.ends_with("/Sec.") => (kind.trim_end_matches("/Sec."), *nevra),
+            [_, "Sec.", nevra] => ("", *nevra),
+            [_, "security", severity, nevra, ..] => (*severity, *nevra),
+            _ => continue,
+        };
+
+        let Some((nevr, arch)) = nevra.rsplit_once('.') else {
+            continue;
+        };
+        let Some((name, _)) = split_name_version(nevr) else {
+            continue;
+        };
+        let severity = Some(severity).filter(|s| severity_rank(Some(s)) > 0);
+
+        for update in updates
+            .iter_mut()
+            .filter(|u| u.name == name && u.arch.as_deref().is_none_or(|a| a == arch))
+        {
+            update.is_security = true;
+            if severity_rank(severity) > severity_rank(update.severity.as_deref()) {
+                update.severity = severity.map(String::from);
+            }
+        }
+    }
+}
+
 /// DNF package manager checker (Fedora, RHEL 8+, CentOS Stream, etc.)
 pub struct DnfChecker;
 
@@ -94,23 +319,157 @@ impl UpdateChecker for DnfChecker {
         )
     }
 
-    fn parse_updates(&self, output: &str) -> Vec<String> {
+    fn refresh_command(&self) -> Option<(&str, Vec<&str>)> {
+        Some(("/usr/bin/dnf", vec!["makecache", "--quiet"]))
+    }
+
+    fn parse_updates(&self, output: &str) -> Vec<PackageUpdate> {
+        parse_check_update(output)
+    }
+
+    fn detail_commands(&self) -> Vec<(&str, Vec<&str>)> {
+        vec![
+            (RPM_INSTALLED_COMMAND.0, RPM_INSTALLED_COMMAND.1.to_vec()),
+            (
+                "/usr/bin/dnf",
+                vec!["updateinfo", "list", "--updates", "--quiet", "--cacheonly"],
+            ),
+        ]
+    }
+
+    fn add_details(&self, outputs: &[String], updates: &mut [PackageUpdate]) {
+        if let [installed, advisories] = outputs {
+            add_rpm_installed_versions(installed, updates);
+            add_security_advisories(advisories, updates);
+        }
+    }
+}
+
+/// YUM package manager checker (CentOS 7, RHEL 7, etc.)
+pub struct YumChecker;
+
+impl UpdateChecker for YumChecker {
+    fn check_command(&self) -> (&str, Vec<&str>) {
+        // Same output and exit codes as dnf; -C reads the cache only
+        ("/usr/bin/yum", vec!["check-update", "--quiet", "-C"])
+    }
+
+    fn refresh_command(&self) -> Option<(&str, Vec<&str>)> {
+        Some(("/usr/bin/yum", vec!["makecache", "fast", "--quiet"]))
+    }
+
+    fn parse_updates(&self, output: &str) -> Vec<PackageUpdate> {
+        parse_check_update(output)
+    }
+
+    fn detail_commands(&self) -> Vec<(&str, Vec<&str>)> {
+        vec![
+            (RPM_INSTALLED_COMMAND.0, RPM_INSTALLED_COMMAND.1.to_vec()),
+            (
+                "/usr/bin/yum",
+                vec!["updateinfo", "list", "updates", "--quiet", "-C"],
+            ),
+        ]
+    }
+
+    fn add_details(&self, outputs: &[String], updates: &mut [PackageUpdate]) {
+        if let [installed, advisories] = outputs {
+            add_rpm_installed_versions(installed, updates);
+            add_security_advisories(advisories, updates);
+        }
+    }
+}
+
+/// Zypper package manager checker (openSUSE, SLES)
+pub struct ZypperChecker;
+
+impl UpdateChecker for ZypperChecker {
+    fn check_command(&self) -> (&str, Vec<&str>) {
+        (
+            "/usr/bin/zypper",
+            vec!["--non-interactive", "--quiet", "list-updates"],
+        )
+    }
+
+    fn parse_updates(&self, output: &str) -> Vec<PackageUpdate> {
+        // S | Repository | Name | Current Version | Available Version | Arch
+        let mut header: Vec<String> = Vec::new();
+        let mut updates = Vec::new();
+
+        for line in output.lines().filter(|line| line.contains('|')) {
+            let columns: Vec<&str> = line.split('|').map(str::trim).collect();
+            if columns.first() == Some(&"S") {
+                header = columns.iter().map(|c| c.to_string()).collect();
+                continue;
+            }
+            if columns.len() < 5 {
+                continue;
+            }
+
+            // Look columns up by header, falling back to the usual layout
+            let column = |title: &str, fallback: usize| {
+                let index = header.iter().position(|h| h == title).unwrap_or(fallback);
+                columns
+                    .get(index)
+                    .filter(|value| !value.is_empty())
+                    .map(|value| value.to_string())
+            };
+
+            let Some(name) = column("Name", 2) else {
+                continue;
+            };
+            updates.push(PackageUpdate {
+                name,
+                arch: column("Arch", 5),
+                current_version: column("Current Version", 3),
+                new_version: column("Available Version", 4),
+                repository: column("Repository", 1),
+                ..Default::default()
+            });
+        }
+
+        updates
+    }
+}
+
+/// APK package manager checker (Alpine Linux)
+pub struct ApkChecker;
+
+impl UpdateChecker for ApkChecker {
+    fn check_command(&self) -> (&str, Vec<&str>) {
+        ("/sbin/apk", vec!["list", "--upgradable"])
+    }
+
+    fn parse_updates(&self, output: &str) -> Vec<PackageUpdate> {
+        // busybox-1.36.1-r16 x86_64 {busybox} (GPL-2.0-only) [upgradable from: busybox-1.36.1-r15]
         output
             .lines()
-            .filter(|line| !line.is_empty() && !line.starts_with('#'))
-            .filter(|line| {
-                // Lines with updates have at least 3 parts (package, version, repo)
-                line.split_whitespace().count() >= 3
-            })
-            .map(|line| {
-                // Extract package name (first column, before the dot and arch)
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if let Some(first) = parts.first() {
-                    // Split on '.' to remove arch suffix (e.g., "docker-ce.x86_64" -> "docker-ce")
-                    first.split('.').next().unwrap_or(first).to_string()
-                } else {
-                    line.to_string()
-                }
+            .filter(|line| line.contains("[upgradable from:"))
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let name_version = fields.next()?;
+                let arch = fields.next();
+
+                // The last two '-' separated parts are the version and release
+                let (name, new_version) = split_name_version(name_version)?;
+                let current_version = line
+                    .split("[upgradable from:")
+                    .nth(1)
+                    .map(|rest| rest.trim().trim_end_matches(']').trim())
+                    .map(|current| {
+                        current
+                            .strip_prefix(name)
+                            .and_then(|v| v.strip_prefix('-'))
+                            .unwrap_or(current)
+                    });
+
+                Some(PackageUpdate {
+                    name: name.to_string(),
+                    arch: arch.map(String::from),
+                    current_version: current_version.map(String::from),
+                    new_version: Some(new_version.to_string()),
+                    ..Default::default()
+                })
             })
             .collect()
     }
@@ -124,16 +483,122 @@ impl UpdateChecker for PacmanChecker {
         ("/usr/bin/pacman", vec!["-Qu"])
     }
 
-    fn parse_updates(&self, output: &str) -> Vec<String> {
+    fn parse_updates(&self, output: &str) -> Vec<PackageUpdate> {
+        // name current -> new [ignored]
         output
             .lines()
             .filter(|line| !line.is_empty())
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                match fields.as_slice() {
+                    [name, current, "->", new, ..] => Some(PackageUpdate {
+                        name: name.to_string(),
+                        current_version: Some(current.to_string()),
+                        new_version: Some(new.to_string()),
+                        ..Default::default()
+                    }),
+                    [name, ..] => Some(PackageUpdate::named(name)),
+                    [] => None,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Flatpak checker (application updates from configured remotes)
+pub struct FlatpakChecker;
+
+impl UpdateChecker for FlatpakChecker {
+    fn check_command(&self) -> (&str, Vec<&str>) {
+        (
+            "/usr/bin/flatpak",
+            vec![
+                "remote-ls",
+                "--updates",
+                "--columns=application,version,arch,origin",
+            ],
+        )
+    }
+
+    fn parse_updates(&self, output: &str) -> Vec<PackageUpdate> {
+        // Columns are tab-separated when not writing to a terminal; the
+        // version is often empty
+        output
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            // A header is only printed on a terminal, but be safe
+            .filter(|line| !line.starts_with("Application ID"))
             .map(|line| {
-                // Extract package name (first column)
-                line.split_whitespace().next().unwrap_or(line).to_string()
+                let columns: Vec<&str> = line.split('\t').map(str::trim).collect();
+                let column = |index: usize| {
+                    columns
+                        .get(index)
+                        .filter(|value| !value.is_empty())
+                        .map(|value| value.to_string())
+                };
+
+                PackageUpdate {
+                    name: columns[0].to_string(),
+                    arch: column(2),
+                    new_version: column(1),
+                    repository: column(3),
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+}
+
+/// Snap checker
+pub struct SnapChecker;
+
+impl UpdateChecker for SnapChecker {
+    fn check_command(&self) -> (&str, Vec<&str>) {
+        // "All snaps up to date." goes to stderr
+        ("/usr/bin/snap", vec!["refresh", "--list"])
+    }
+
+    fn parse_updates(&self, output: &str) -> Vec<PackageUpdate> {
+        // Name  Version  Rev  Size  Publisher  Notes
+        output
+            .lines()
+            .filter(|line| !line.is_empty() && !line.starts_with("Name "))
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let name = fields.next()?;
+                Some(PackageUpdate {
+                    name: name.to_string(),
+                    new_version: fields.next().map(String::from),
+                    ..Default::default()
+                })
             })
             .collect()
     }
+
+    fn detail_commands(&self) -> Vec<(&str, Vec<&str>)> {
+        vec![("/usr/bin/snap", vec!["list"])]
+    }
+
+    fn add_details(&self, outputs: &[String], updates: &mut [PackageUpdate]) {
+        // snap list: Name  Version  Rev  Tracking  Publisher  Notes
+        let Some(installed) = outputs.first() else {
+            return;
+        };
+        let versions: HashMap<&str, &str> = installed
+            .lines()
+            .skip(1)
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                Some((fields.next()?, fields.next()?))
+            })
+            .collect();
+
+        for update in updates {
+            if let Some(version) = versions.get(update.name.as_str()) {
+                update.current_version = Some(version.to_string());
+            }
+        }
+    }
 }
 
 /// Get the appropriate checker for a package manager
@@ -141,7 +606,12 @@ pub fn get_checker(pm: &PackageManager) -> Box<dyn UpdateChecker> {
     match pm {
         PackageManager::Apt => Box::new(AptChecker),
         PackageManager::Dnf => Box::new(DnfChecker),
+        PackageManager::Yum => Box::new(YumChecker),
+        PackageManager::Zypper => Box::new(ZypperChecker),
+        PackageManager::Apk => Box::new(ApkChecker),
         PackageManager::Pacman => Box::new(PacmanChecker),
+        PackageManager::Flatpak => Box::new(FlatpakChecker),
+        PackageManager::Snap => Box::new(SnapChecker),
     }
 }
 
@@ -149,101 +619,163 @@ pub fn get_checker(pm: &PackageManager) -> Box<dyn UpdateChecker> {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateInfo {
     pub package_manager: String,
+    /// Flatpak / Snap, when installed
+    pub secondary_managers: Vec<String>,
     pub total_updates: usize,
     pub security_updates: usize,
-    pub packages: Vec<String>,
+    pub packages: Vec<PackageUpdate>,
 }
 
-/// Update detector
-pub struct UpdateDetector {}
+/// Check whether a package manager's binary is present
+async fn is_installed(executor: &RemoteExecutor, pm: PackageManager) -> Result<bool> {
+    let check_cmd = format!("test -x {} && echo found", pm.path());
+    let output = executor.execute_command("sh", &["-c", &check_cmd]).await?;
+    Ok(output.trim() == "found")
+}
 
-impl UpdateDetector {
-    pub fn new() -> Self {
-        Self {}
+/// Detect the package manager on a server (local or remote)
+#[instrument(skip(executor), fields(server = %executor.server().name))]
+pub async fn detect_package_manager(executor: &RemoteExecutor) -> Result<PackageManager> {
+    for pm in PackageManager::all() {
+        // The server is unreachable if this fails; probing further won't help
+        if is_installed(executor, pm).await? {
+            info!("Detected package manager: {:?}", pm);
+            return Ok(pm);
+        }
     }
 
-    /// Detect package manager on a remote server
-    #[instrument(skip(executor))]
-    async fn detect_package_manager(executor: &RemoteExecutor) -> Result<PackageManager> {
-        for pm in PackageManager::all() {
-            let binary = pm.binary();
+    Err(Error::PluginError(format!(
+        "No supported package manager found on {}",
+        executor.server().name
+    )))
+}
 
-            // Check if the binary exists using full path
-            let check_cmd = format!("test -x /usr/bin/{} && echo found", binary);
-            match executor.execute_command("sh", &["-c", &check_cmd]).await {
-                Ok(output) if output.trim() == "found" => {
-                    info!("Detected package manager: {:?}", pm);
-                    return Ok(pm);
-                }
-                _ => continue,
+/// Detect the secondary package managers (flatpak, snap) installed on a server
+#[instrument(skip(executor), fields(server = %executor.server().name))]
+pub async fn detect_secondary_managers(executor: &RemoteExecutor) -> Vec<PackageManager> {
+    let mut found = Vec::new();
+    for pm in PackageManager::secondary() {
+        if is_installed(executor, pm).await.unwrap_or(false) {
+            debug!("Detected secondary package manager: {:?}", pm);
+            found.push(pm);
+        }
+    }
+    found
+}
+
+/// List the pending updates of one package manager
+///
+/// Updates from secondary managers have the manager's name prefixed to
+/// their repository, e.g. `flatpak/flathub`.
+pub async fn list_updates(
+    executor: &RemoteExecutor,
+    pm: PackageManager,
+) -> Result<Vec<PackageUpdate>> {
+    let checker = get_checker(&pm);
+    let (cmd, args) = checker.check_command();
+
+    let output = executor
+        .execute_command(cmd, &args)
+        .await
+        .map_err(|e| Error::PluginError(format!("Failed to check updates: {}", e)))?;
+
+    let mut updates = checker.parse_updates(&output);
+    if PackageManager::secondary().contains(&pm) {
+        for update in &mut updates {
+            update.repository = Some(match &update.repository {
+                Some(repository) => format!("{}/{}", pm.binary(), repository),
+                None => pm.binary().to_string(),
+            });
+        }
+    }
+    Ok(updates)
+}
+
+/// Fill in installed versions and security classification
+///
+/// Details are best effort: a failed detail command leaves the updates as
+/// the check command reported them.
+async fn add_details(executor: &RemoteExecutor, pm: PackageManager, updates: &mut [PackageUpdate]) {
+    if updates.is_empty() {
+        return;
+    }
+
+    let checker = get_checker(&pm);
+    let mut outputs = Vec::new();
+    for (cmd, args) in checker.detail_commands() {
+        match executor.execute_command(cmd, &args).await {
+            Ok(output) => outputs.push(output),
+            Err(e) => {
+                warn!(
+                    package_manager = %pm.display_name(),
+                    error = %e,
+                    "Failed to fetch update details"
+                );
+                return;
             }
         }
+    }
+    checker.add_details(&outputs, updates);
+}
 
-        Err(Error::PluginError(format!(
-            "No supported package manager found on {}",
-            executor.server().name
-        )))
+/// Update detector
+pub struct UpdateDetector {}
+
+impl UpdateDetector {
+    pub fn new() -> Self {
+        Self {}
     }
 
-    /// Check for updates on a remote server via SSH
-    #[instrument(skip(self, ssh_key))]
-    pub async fn check_remote_updates(
+    /// Check for updates on a server, locally or via SSH
+    #[instrument(skip(self, server, ssh_key), fields(server = %server.name))]
+    pub async fn check_updates(
         &self,
-        server_name: &str,
-        ssh_host: &str,
-        ssh_user: Option<&str>,
+        server: &Server,
         ssh_key: Option<&str>,
     ) -> Result<UpdateInfo> {
-        info!(
-            server = %server_name,
-            host = %ssh_host,
-            "Checking remote updates via SSH"
-        );
-
-        // Build SSH connection string (user@host or just host)
-        let ssh_connection = if let Some(user) = ssh_user {
-            format!("{}@{}", user, ssh_host)
-        } else {
-            ssh_host.to_string()
-        };
+        info!(server = %server.display(), "Checking updates");
 
-        // Build server config
-        let server = Server::remote(server_name, &ssh_connection);
-
-        // Create remote executor
         let executor = RemoteExecutor::for_server(server.clone(), ssh_key.map(|s| s.to_string()));
 
         // Detect package manager
-        let pm = Self::detect_package_manager(&executor).await?;
+        let pm = detect_package_manager(&executor).await?;
         debug!(package_manager = %pm.display_name(), "Package manager detected");
 
-        // Get appropriate checker
-        let checker = get_checker(&pm);
-        let (cmd, args) = checker.check_command();
-
-        // If this is DNF with --cacheonly, refresh the cache in the background
-        if matches!(pm, PackageManager::Dnf) && args.contains(&"--cacheonly") {
+        // Checks that read a local cache (dnf/yum) refresh it in the background
+        if let Some((cmd, args)) = get_checker(&pm).refresh_command() {
+            let cmd = cmd.to_string();
+            let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
             let server_clone = server.clone();
             let ssh_key_clone = ssh_key.map(|s| s.to_string());
             tokio::spawn(async move {
-                debug!("Refreshing DNF cache in background");
+                debug!("Refreshing package cache in background");
                 let bg_executor = RemoteExecutor::for_server(server_clone, ssh_key_clone);
-                let _ = bg_executor
-                    .execute_command("/usr/bin/dnf", &["makecache", "--quiet"])
-                    .await;
+                let args: Vec<&str> = args.iter().map(String::as_str).collect();
+                let _ = bg_executor.execute_command(&cmd, &args).await;
             });
         }
 
-        // Execute check command
-        let output = executor
-            .execute_command(cmd, &args)
-            .await
-            .map_err(|e| Error::PluginError(format!("Failed to check updates: {}", e)))?;
+        let mut packages = list_updates(&executor, pm).await?;
+        add_details(&executor, pm, &mut packages).await;
+
+        // Flatpak / snap are optional: a failed check is logged, not fatal
+        let secondary = detect_secondary_managers(&executor).await;
+        for secondary_pm in &secondary {
+            match list_updates(&executor, *secondary_pm).await {
+                Ok(mut secondary_packages) => {
+                    add_details(&executor, *secondary_pm, &mut secondary_packages).await;
+                    packages.extend(secondary_packages);
+                }
+                Err(e) => warn!(
+                    package_manager = %secondary_pm.display_name(),
+                    error = %e,
+                    "Secondary update check failed"
+                ),
+            }
+        }
 
-        // Parse updates
-        let packages = checker.parse_updates(&output);
         let total_updates = packages.len();
-        let security_updates = packages.iter().filter(|p| p.contains("(security)")).count();
+        let security_updates = packages.iter().filter(|p| p.is_security).count();
 
         info!(
             total = total_updates,
@@ -253,114 +785,245 @@ impl UpdateDetector {
 
         Ok(UpdateInfo {
             package_manager: pm.display_name().to_string(),
+            secondary_managers: secondary
+                .iter()
+                .map(|pm| pm.display_name().to_string())
+                .collect(),
             total_updates,
             security_updates,
             packages,
         })
     }
+}
 
-    /// Check for updates on the local system
-    #[instrument(skip(self))]
-    pub async fn check_local_updates(&self) -> Result<UpdateInfo> {
-        info!("Checking local system updates");
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        // Detect package manager locally
-        let pm = self.detect_local_package_manager().await?;
-        debug!(package_manager = %pm.display_name(), "Package manager detected");
+    fn names(updates: &[PackageUpdate]) -> Vec<&str> {
+        updates.iter().map(|u| u.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_apt_parse_updates() {
+        let checker = AptChecker;
+        let output = r#"Listing...
+docker-ce/jammy 5:25.0.0-1~ubuntu.22.04~jammy amd64 [upgradable from: 5:24.0.7-1~ubuntu.22.04~jammy]
+linux-image-generic/jammy-updates,jammy-security 5.15.0.91.89 amd64 [upgradable from: 5.15.0.89.87]
+vim/jammy 2:8.2.3995-1ubuntu2.15 amd64 [upgradable from: 2:8.2.3995-1ubuntu2.14]
+"#;
 
-        // Get appropriate checker
-        let checker = get_checker(&pm);
-        let (cmd, args) = checker.check_command();
+        let updates = checker.parse_updates(output);
+
+        assert_eq!(
+            names(&updates),
+            vec!["docker-ce", "linux-image-generic", "vim"]
+        );
+        assert_eq!(
+            updates[0],
+            PackageUpdate {
+                name: "docker-ce".to_string(),
+                arch: Some("amd64".to_string()),
+                current_version: Some("5:24.0.7-1~ubuntu.22.04~jammy".to_string()),
+                new_version: Some("5:25.0.0-1~ubuntu.22.04~jammy".to_string()),
+                repository: Some("jammy".to_string()),
+                is_security: false,
+                severity: None,
+            }
+        );
+        assert!(updates[1].is_security);
+        assert_eq!(
+            updates[1].repository.as_deref(),
+            Some("jammy-updates,jammy-security")
+        );
+        assert!(!updates[2].is_security);
+    }
 
-        // Execute command locally using tokio Command
-        use tokio::process::Command;
+    #[test]
+    fn test_dnf_parse_updates() {
+        let checker = DnfChecker;
+        let output = r#"docker-ce.x86_64                    3:25.0.0-1.fc39                    docker-ce-stable
+kernel.x86_64                       6.6.8-200.fc39                     updates
+vim-enhanced.x86_64                 2:9.0.2120-1.fc39                  updates
+"#;
 
-        let output = Command::new(cmd)
-            .args(&args)
-            .output()
-            .await
-            .map_err(|e| Error::PluginError(format!("Failed to execute command: {}", e)))?;
+        let updates = checker.parse_updates(output);
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert_eq!(names(&updates), vec!["docker-ce", "kernel", "vim-enhanced"]);
+        assert_eq!(updates[0].arch.as_deref(), Some("x86_64"));
+        assert_eq!(updates[0].new_version.as_deref(), Some("3:25.0.0-1.fc39"));
+        assert_eq!(updates[0].repository.as_deref(), Some("docker-ce-stable"));
+        assert_eq!(updates[0].current_version, None);
+    }
 
-        // Parse updates
-        let packages = checker.parse_updates(&stdout);
-        let total_updates = packages.len();
-        let security_updates = packages.iter().filter(|p| p.contains("(security)")).count();
+    #[test]
+    fn test_dnf_parse_skips_obsoleting_section() {
+        let checker = DnfChecker;
+        let output = r#"
+kernel.x86_64                       6.6.8-200.fc39                     updates
+Obsoleting Packages
+grub2-tools.x86_64                  1:2.06-100.fc39                    updates
+    grub2-tools.x86_64              1:2.06-95.fc39                     @updates
+"#;
 
-        info!(
-            total = total_updates,
-            security = security_updates,
-            "Updates found"
+        let updates = checker.parse_updates(output);
+
+        assert_eq!(names(&updates), vec!["kernel"]);
+    }
+
+    #[test]
+    fn test_dnf_details_add_installed_versions_and_advisories() {
+        let checker = DnfChecker;
+        let mut updates = checker.parse_updates(
+            r#"kernel.x86_64                       6.6.9-200.fc39                     updates
+openssl-libs.x86_64                 1:3.1.1-4.fc39                     updates
+vim-enhanced.x86_64                 2:9.0.2120-1.fc39                  updates
+"#,
         );
+        // rpm -qa --qf '%{NAME} %{ARCH} %{VERSION}-%{RELEASE}\n'
+        let installed = r#"kernel x86_64 6.6.8-200.fc39
+openssl-libs x86_64 3.1.1-1.fc39
+openssl-libs i686 3.1.1-1.fc39
+vim-enhanced x86_64 9.0.2110-1.fc39
+"#;
+        // dnf updateinfo list --updates (dnf 4)
+        let advisories = r#"FEDORA-2024-0a1b2c3d4e bugfix         kernel-6.6.9-200.fc39.x86_64
+FEDORA-2024-1f2e3d4c5b Moderate/Sec.  openssl-libs-1:3.1.1-4.fc39.x86_64
+FEDORA-2024-9a8b7c6d5e Important/Sec. openssl-libs-1:3.1.1-4.fc39.x86_64
+"#;
 
-        Ok(UpdateInfo {
-            package_manager: pm.display_name().to_string(),
-            total_updates,
-            security_updates,
-            packages,
-        })
+        checker.add_details(
+            &[installed.to_string(), advisories.to_string()],
+            &mut updates,
+        );
+
+        assert_eq!(
+            updates[0].current_version.as_deref(),
+            Some("6.6.8-200.fc39")
+        );
+        assert!(!updates[0].is_security);
+        assert_eq!(updates[1].current_version.as_deref(), Some("3.1.1-1.fc39"));
+        assert!(updates[1].is_security);
+        assert_eq!(updates[1].severity.as_deref(), Some("Important"));
+        assert!(!updates[2].is_security);
     }
 
-    /// Detect package manager on local system
-    async fn detect_local_package_manager(&self) -> Result<PackageManager> {
-        use tokio::process::Command;
+    #[test]
+    fn test_dnf5_advisories() {
+        let mut updates = vec![PackageUpdate {
+            name: "openssl".to_string(),
+            arch: Some("x86_64".to_string()),
+            ..Default::default()
+        }];
+        // dnf5 updateinfo list --updates
+        let advisories = r#"Name                  Type        Severity                 Package                 Issued
+FEDORA-2024-5f6a7b8c9d enhancement None      curl-8.6.0-7.fc40.x86_64       2024-05-02 01:12:45
+FEDORA-2024-0e1d2c3b4a security    Critical  openssl-1:3.2.1-2.fc40.x86_64  2024-05-14 02:31:07
+"#;
 
-        for pm in PackageManager::all() {
-            let binary = format!("/usr/bin/{}", pm.binary());
+        add_security_advisories(advisories, &mut updates);
 
-            // Check if binary exists and is executable
-            let check = Command::new("test").arg("-x").arg(&binary).status().await;
+        assert!(updates[0].is_security);
+        assert_eq!(updates[0].severity.as_deref(), Some("Critical"));
+    }
 
-            if let Ok(status) = check {
-                if status.success() {
-                    info!("Detected package manager: {:?}", pm);
-                    return Ok(pm);
-                }
-            }
-        }
+    #[test]
+    fn test_yum_parse_updates() {
+        let checker = YumChecker;
+        // yum check-update -q on CentOS 7
+        let output = r#"
+bind-libs.x86_64                  32:9.11.4-26.P2.el7_9.16           updates
+kernel.x86_64                     3.10.0-1160.119.1.el7              updates
+python-perf.x86_64                3.10.0-1160.119.1.el7              updates
+yum-plugin-fastestmirror.noarch   1.1.31-54.el7_8                    base
+"#;
+
+        let updates = checker.parse_updates(output);
 
-        Err(Error::PluginError(
-            "No supported package manager found on local system".to_string(),
-        ))
+        assert_eq!(
+            names(&updates),
+            vec![
+                "bind-libs",
+                "kernel",
+                "python-perf",
+                "yum-plugin-fastestmirror"
+            ]
+        );
+        assert_eq!(updates[3].arch.as_deref(), Some("noarch"));
+        assert_eq!(updates[3].repository.as_deref(), Some("base"));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_yum_advisories() {
+        let mut updates = vec![PackageUpdate {
+            name: "bind-libs".to_string(),
+            arch: Some("x86_64".to_string()),
+            ..Default::default()
+        }];
+        // yum updateinfo list updates -q on RHEL 7
+        let advisories = r#"RHSA-2024:1234 Moderate/Sec.  bind-libs-32:9.11.4-26.P2.el7_9.16.x86_64
+RHBA-2024:0999 bugfix         tzdata-2024a-1.el7.noarch
+"#;
+
+        add_security_advisories(advisories, &mut updates);
+
+        assert!(updates[0].is_security);
+        assert_eq!(updates[0].severity.as_deref(), Some("Moderate"));
+    }
 
     #[test]
-    fn test_apt_parse_updates() {
-        let checker = AptChecker;
-        let output = r#"Listing...
-docker-ce/jammy 5:25.0.0-1~ubuntu.22.04~jammy amd64 [upgradable from: 5:24.0.7-1~ubuntu.22.04~jammy]
-linux-image-generic/jammy-security 5.15.0.91.89 amd64 [upgradable from: 5.15.0.89.87]
-vim/jammy 2:8.2.3995-1ubuntu2.15 amd64 [upgradable from: 2:8.2.3995-1ubuntu2.14]
+    fn test_zypper_parse_updates() {
+        let checker = ZypperChecker;
+        // zypper --non-interactive --quiet list-updates on openSUSE Leap 15.5
+        let output = r#"S | Repository             | Name              | Current Version     | Available Version   | Arch
+--+------------------------+-------------------+---------------------+---------------------+-------
+v | Main Update Repository | libzypp           | 17.31.15-150400.3.4 | 17.31.22-150400.3.5 | x86_64
+v | Main Update Repository | openssh           | 8.4p1-150300.3.22.1 | 8.4p1-150300.3.30.1 | x86_64
+v | repo-sle-update        | tzdata            | 2023c-150000.3.200  | 2024a-150000.3.203  | noarch
 "#;
 
         let updates = checker.parse_updates(output);
 
-        assert_eq!(updates.len(), 3);
-        assert_eq!(updates[0], "docker-ce");
-        assert_eq!(updates[1], "linux-image-generic (security)");
-        assert_eq!(updates[2], "vim");
+        assert_eq!(names(&updates), vec!["libzypp", "openssh", "tzdata"]);
+        assert_eq!(
+            updates[1],
+            PackageUpdate {
+                name: "openssh".to_string(),
+                arch: Some("x86_64".to_string()),
+                current_version: Some("8.4p1-150300.3.22.1".to_string()),
+                new_version: Some("8.4p1-150300.3.30.1".to_string()),
+                repository: Some("Main Update Repository".to_string()),
+                is_security: false,
+                severity: None,
+            }
+        );
     }
 
     #[test]
-    fn test_dnf_parse_updates() {
-        let checker = DnfChecker;
-        let output = r#"docker-ce.x86_64                    3:25.0.0-1.fc39                    docker-ce-stable
-kernel.x86_64                       6.6.8-200.fc39                     updates
-vim-enhanced.x86_64                 2:9.0.2120-1.fc39                  updates
+    fn test_zypper_parse_no_updates() {
+        let checker = ZypperChecker;
+
+        assert!(checker.parse_updates("No updates found.\n").is_empty());
+    }
+
+    #[test]
+    fn test_apk_parse_updates() {
+        let checker = ApkChecker;
+        // apk list --upgradable on Alpine 3.19
+        let output = r#"busybox-1.36.1-r16 x86_64 {busybox} (GPL-2.0-only) [upgradable from: busybox-1.36.1-r15]
+libcrypto3-3.1.4-r5 x86_64 {openssl} (Apache-2.0) [upgradable from: libcrypto3-3.1.4-r4]
+py3-setuptools-pyc-70.3.0-r0 noarch {py3-setuptools} (MIT) [upgradable from: py3-setuptools-pyc-68.2.2-r0]
 "#;
 
         let updates = checker.parse_updates(output);
 
-        assert_eq!(updates.len(), 3);
-        assert_eq!(updates[0], "docker-ce");
-        assert_eq!(updates[1], "kernel");
-        assert_eq!(updates[2], "vim-enhanced");
+        assert_eq!(
+            names(&updates),
+            vec!["busybox", "libcrypto3", "py3-setuptools-pyc"]
+        );
+        assert_eq!(updates[2].arch.as_deref(), Some("noarch"));
+        assert_eq!(updates[2].current_version.as_deref(), Some("68.2.2-r0"));
+        assert_eq!(updates[2].new_version.as_deref(), Some("70.3.0-r0"));
     }
 
     #[test]
@@ -372,8 +1035,57 @@ vim 9.0.2120-1 -> 9.0.2121-1
 
         let updates = checker.parse_updates(output);
 
-        assert_eq!(updates.len(), 2);
-        assert_eq!(updates[0], "linux");
-        assert_eq!(updates[1], "vim");
+        assert_eq!(names(&updates), vec!["linux", "vim"]);
+        assert_eq!(updates[0].current_version.as_deref(), Some("6.6.8.arch1-1"));
+        assert_eq!(updates[0].new_version.as_deref(), Some("6.6.9.arch1-1"));
+    }
+
+    #[test]
+    fn test_flatpak_parse_updates() {
+        let checker = FlatpakChecker;
+        // flatpak remote-ls --updates --columns=application,version,arch,origin
+        let output = "org.gimp.GIMP\t2.10.36\tx86_64\tflathub\n\
+                      org.freedesktop.Platform.GL.default\t\tx86_64\tflathub\n";
+
+        let updates = checker.parse_updates(output);
+
+        assert_eq!(
+            names(&updates),
+            vec!["org.gimp.GIMP", "org.freedesktop.Platform.GL.default"]
+        );
+        assert_eq!(updates[0].new_version.as_deref(), Some("2.10.36"));
+        assert_eq!(updates[0].repository.as_deref(), Some("flathub"));
+        assert_eq!(updates[1].new_version, None);
+        assert_eq!(updates[1].arch.as_deref(), Some("x86_64"));
+    }
+
+    #[test]
+    fn test_snap_parse_updates() {
+        let checker = SnapChecker;
+        // snap refresh --list
+        let output = r#"Name     Version         Rev   Size   Publisher   Notes
+core22   20240111        1122  77MB   canonical✓  base
+firefox  122.0-2         3728  247MB  mozilla✓    -
+"#;
+        // snap list
+        let installed = r#"Name     Version         Rev    Tracking       Publisher   Notes
+core22   20231123        1033   latest/stable  canonical✓  base
+firefox  121.0.1-1       3600   latest/stable  mozilla✓    -
+"#;
+
+        let mut updates = checker.parse_updates(output);
+        checker.add_details(&[installed.to_string()], &mut updates);
+
+        assert_eq!(names(&updates), vec!["core22", "firefox"]);
+        assert_eq!(updates[1].current_version.as_deref(), Some("121.0.1-1"));
+        assert_eq!(updates[1].new_version.as_deref(), Some("122.0-2"));
+    }
+
+    #[test]
+    fn test_snap_parse_up_to_date() {
+        let checker = SnapChecker;
+
+        // "All snaps up to date." is written to stderr
+        assert!(checker.parse_updates("").is_empty());
     }
 }
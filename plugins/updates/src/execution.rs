@@ -2,18 +2,234 @@
 //!
 //! Applies OS updates using package managers
 
-use crate::detection::{get_checker, PackageManager, UpdateChecker};
+use crate::detection::{
+    detect_package_manager, detect_secondary_managers, list_updates, PackageManager, PackageUpdate,
+};
+use crate::hooks::{run_hook, Hook};
+use crate::reboot::{detect_reboot, RebootStatus};
+use crate::transaction::{latest_transaction_id, shell_quote, transaction_since, Transaction};
 use serde::{Deserialize, Serialize};
-use svrctlrs_core::{Error, RemoteExecutor, Result, Server};
+use std::collections::HashMap;
+use svrctlrs_core::{RemoteExecutor, Result, Server};
 use tracing::{debug, info, instrument, warn};
 
+/// Time allowed for a single package manager command while applying updates
+pub(crate) const APPLY_TIMEOUT_SECS: u64 = 1800;
+
+/// Shell prelude setting `$SUDO` to `sudo` unless already running as root
+pub(crate) const SUDO_PREFIX: &str = r#"SUDO=; [ "$(id -u)" = "0" ] || SUDO=sudo; "#;
+
+/// A package manager command that needs root
+pub(crate) struct Step {
+    /// What the step does, for error messages ("update package lists")
+    pub action: &'static str,
+    /// What the step did, for summaries ("updated package lists")
+    pub done: &'static str,
+    /// Shell script; `$SUDO` is `sudo` unless already running as root
+    pub script: &'static str,
+}
+
+/// Run a step's script as root, through sudo when not already root
+pub(crate) async fn run_as_root(executor: &RemoteExecutor, step: &Step) -> Result<String> {
+    run_script_as_root(executor, step.script).await
+}
+
+/// Run a shell script as root, through sudo when not already root
+async fn run_script_as_root(executor: &RemoteExecutor, script: &str) -> Result<String> {
+    let script = format!("{}{}", SUDO_PREFIX, script);
+    executor.execute_command("sh", &["-c", &script]).await
+}
+
+/// Commands that apply every pending update, in order
+fn upgrade_steps(pm: PackageManager) -> &'static [Step] {
+    const APPLY: &str = "apply updates";
+    const APPLIED: &str = "applied updates";
+
+    match pm {
+        PackageManager::Apt => &[
+            Step {
+                action: "update package lists",
+                done: "updated package lists",
+                script: "$SUDO apt-get update -qq",
+            },
+            Step {
+                action: APPLY,
+                done: APPLIED,
+                script: "$SUDO env DEBIAN_FRONTEND=noninteractive apt-get full-upgrade -y",
+            },
+        ],
+        PackageManager::Dnf => &[Step {
+            action: APPLY,
+            done: APPLIED,
+            script: "$SUDO dnf upgrade -y",
+        }],
+        PackageManager::Yum => &[Step {
+            action: APPLY,
+            done: APPLIED,
+            script: "$SUDO yum update -y",
+        }],
+        PackageManager::Zypper => &[
+            Step {
+                action: "refresh repositories",
+                done: "refreshed repositories",
+                script: "$SUDO zypper --non-interactive refresh",
+            },
+            Step {
+                action: APPLY,
+                done: APPLIED,
+                script: "$SUDO zypper --non-interactive update",
+            },
+        ],
+        PackageManager::Apk => &[
+            Step {
+                action: "update package index",
+                done: "updated package index",
+                script: "$SUDO apk update -q",
+            },
+            Step {
+                action: APPLY,
+                done: APPLIED,
+                script: "$SUDO apk upgrade",
+            },
+        ],
+        PackageManager::Pacman => &[Step {
+            action: APPLY,
+            done: APPLIED,
+            script: "$SUDO pacman -Syu --noconfirm",
+        }],
+        PackageManager::Flatpak => &[Step {
+            action: "apply flatpak updates",
+            done: "applied flatpak updates",
+            script: "$SUDO flatpak update -y --noninteractive",
+        }],
+        PackageManager::Snap => &[Step {
+            action: "refresh snaps",
+            done: "refreshed snaps",
+            script: "$SUDO snap refresh",
+        }],
+    }
+}
+
+/// Package name to the version approved for it, when the check reported one
+pub type ApprovedPackages = HashMap<String, Option<String>>;
+
+/// Pending updates split by whether they may be applied
+#[derive(Debug, Default, PartialEq)]
+struct Selection {
+    /// Packages to upgrade, with the version to pin them to
+    selected: Vec<(String, Option<String>)>,
+    /// Updates that were never approved
+    not_approved: usize,
+    /// Approved packages whose candidate version is no longer the approved one
+    changed: Vec<String>,
+}
+
+/// Pick the pending updates to apply
+///
+/// Without approval everything is applied. Otherwise only approved packages
+/// are, pinned to the approved version; a package whose candidate version
+/// changed since the approval is left pending.
+fn select_updates(updates: &[PackageUpdate], approved: Option<&ApprovedPackages>) -> Selection {
+    let mut selection = Selection::default();
+    for update in updates {
+        let Some(approved) = approved else {
+            selection.selected.push((update.name.clone(), None));
+            continue;
+        };
+        match approved.get(&update.name) {
+            None => selection.not_approved += 1,
+            Some(Some(version))
+                if update
+                    .new_version
+                    .as_ref()
+                    .is_some_and(|new| new != version) =>
+            {
+                selection.changed.push(format!(
+                    "{} {} (approved {})",
+                    update.name,
+                    update.new_version.as_deref().unwrap_or_default(),
+                    version
+                ));
+            }
+            Some(version) => selection
+                .selected
+                .push((update.name.clone(), version.clone())),
+        }
+    }
+    selection
+}
+
+/// A package argument, pinned to `version` where the package manager allows it
+fn package_spec(pm: PackageManager, name: &str, version: Option<&str>) -> String {
+    let spec = match (pm, version) {
+        (PackageManager::Apt, Some(version)) => format!("{}={}", name, version),
+        (PackageManager::Dnf | PackageManager::Yum | PackageManager::Zypper, Some(version)) => {
+            format!("{}-{}", name, version)
+        }
+        _ => name.to_string(),
+    };
+    shell_quote(&spec)
+}
+
+/// Command upgrading only the given packages, run in place of the last of
+/// [`upgrade_steps`]
+///
+/// Packages are pinned to their version where the package manager supports
+/// it. Returns `None` for pacman, which only supports upgrading everything.
+fn upgrade_packages_script(
+    pm: PackageManager,
+    packages: &[(String, Option<String>)],
+) -> Option<String> {
+    let command = match pm {
+        PackageManager::Apt => {
+            "$SUDO env DEBIAN_FRONTEND=noninteractive apt-get install --only-upgrade -y"
+        }
+        PackageManager::Dnf => "$SUDO dnf upgrade -y",
+        PackageManager::Yum => "$SUDO yum update -y",
+        PackageManager::Zypper => "$SUDO zypper --non-interactive update",
+        PackageManager::Apk => "$SUDO apk upgrade",
+        PackageManager::Flatpak => "$SUDO flatpak update -y --noninteractive",
+        PackageManager::Snap => "$SUDO snap refresh",
+        PackageManager::Pacman => return None,
+    };
+    let packages: Vec<String> = packages
+        .iter()
+        .map(|(name, version)| package_spec(pm, name, version.as_deref()))
+        .collect();
+    Some(format!("{} {}", command, packages.join(" ")))
+}
+
 /// Result of update execution
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ExecutionResult {
     pub success: bool,
     pub summary: String,
     pub packages_updated: usize,
     pub errors: Vec<String>,
+    /// Whether the server needs a reboot to run the updated kernel / libraries
+    #[serde(default)]
+    pub reboot: RebootStatus,
+    /// Package manager transaction the updates were installed in
+    #[serde(default)]
+    pub transaction: Option<Transaction>,
+    /// The post-update hook (health probe) failed
+    #[serde(default)]
+    pub post_hook_failed: bool,
+    /// The rollback hook ran after the post-update hook failed
+    #[serde(default)]
+    pub rollback_hook_ran: bool,
+}
+
+impl ExecutionResult {
+    /// Result of a run that failed before applying anything
+    pub(crate) fn failed(summary: &str, error: impl ToString) -> Self {
+        Self {
+            success: false,
+            summary: summary.to_string(),
+            errors: vec![error.to_string()],
+            ..Default::default()
+        }
+    }
 }
 
 /// Update executor
@@ -24,370 +240,311 @@ impl UpdateExecutor {
         Self {}
     }
 
-    /// Detect package manager on a remote server
-    #[instrument(skip(executor))]
-    async fn detect_package_manager(executor: &RemoteExecutor) -> Result<PackageManager> {
-        for pm in PackageManager::all() {
-            let binary = pm.binary();
-
-            let check_cmd = format!("test -x /usr/bin/{} && echo found", binary);
-            match executor.execute_command("sh", &["-c", &check_cmd]).await {
-                Ok(output) if output.trim() == "found" => {
-                    info!("Detected package manager: {:?}", pm);
-                    return Ok(pm);
-                }
-                _ => continue,
-            }
-        }
-
-        Err(Error::PluginError(format!(
-            "No supported package manager found on {}",
-            executor.server().name
-        )))
-    }
-
-    /// Apply updates on a remote server via SSH
-    #[instrument(skip(self, ssh_key))]
-    pub async fn apply_remote_updates(
+    /// Apply updates on a server, locally or via SSH
+    ///
+    /// With `approved` set, only those packages are upgraded, to the approved
+    /// versions, and any other pending update is left for a later approval. The server's pre-update
+    /// hook runs first and must succeed. After updating, the package manager
+    /// transaction is recorded and the post-update hook runs; if it fails,
+    /// the rollback hook runs.
+    #[instrument(skip(self, server, ssh_key, approved), fields(server = %server.name))]
+    pub async fn apply_updates(
         &self,
-        server_name: &str,
-        ssh_host: &str,
-        ssh_user: Option<&str>,
+        server: &Server,
         ssh_key: Option<&str>,
+        approved: Option<&ApprovedPackages>,
     ) -> Result<ExecutionResult> {
-        info!(
-            server = %server_name,
-            host = %ssh_host,
-            "Applying remote updates via SSH"
-        );
+        info!(server = %server.display(), "Applying updates");
+
+        // Upgrades routinely outlast the default command timeout
+        let executor = RemoteExecutor::for_server(server.clone(), ssh_key.map(|s| s.to_string()))
+            .with_timeout(APPLY_TIMEOUT_SECS);
+
+        if let Err(e) = run_hook(&executor, Hook::PreUpdate, None).await {
+            warn!(error = %e, "Not applying updates");
+            return Ok(ExecutionResult::failed(
+                "Pre-update hook failed, no updates applied",
+                e,
+            ));
+        }
 
-        // Build SSH connection string (user@host or just host)
-        let ssh_connection = if let Some(user) = ssh_user {
-            format!("{}@{}", user, ssh_host)
-        } else {
-            ssh_host.to_string()
+        let mut result = match detect_package_manager(&executor).await {
+            Ok(pm) => {
+                debug!(package_manager = %pm.display_name(), "Package manager detected");
+                let before = latest_transaction_id(&executor, pm).await;
+                let mut result = self
+                    .apply_packages(&executor, pm, approved)
+                    .await
+                    .unwrap_or_else(|e| ExecutionResult::failed("Failed to apply updates", e));
+                result.transaction = transaction_since(&executor, pm, before.as_deref()).await;
+                result
+            }
+            Err(e) => ExecutionResult::failed("Failed to apply updates", e),
         };
 
-        // Build server config
-        let server = Server::remote(server_name, &ssh_connection);
-
-        // Create remote executor
-        let executor = RemoteExecutor::for_server(server.clone(), ssh_key.map(|s| s.to_string()));
-
-        // Detect package manager
-        let pm = Self::detect_package_manager(&executor).await?;
-        debug!(package_manager = %pm.display_name(), "Package manager detected");
-
-        // Get checker to verify updates before applying
-        let checker = get_checker(&pm);
-        let updates_before = self.check_updates(&executor, &checker).await?;
-
-        if updates_before.is_empty() {
-            return Ok(ExecutionResult {
-                success: true,
-                summary: "No updates available".to_string(),
-                packages_updated: 0,
-                errors: vec![],
-            });
+        if let Err(e) = run_hook(&executor, Hook::PostUpdate, result.transaction.as_ref()).await {
+            warn!(error = %e, "Post-update hook failed, running rollback hook");
+            result.success = false;
+            result.post_hook_failed = true;
+            result.errors.push(e.to_string());
+            match run_hook(&executor, Hook::Rollback, result.transaction.as_ref()).await {
+                Ok(output) => result.rollback_hook_ran = output.is_some(),
+                Err(e) => result.errors.push(e.to_string()),
+            }
+            result.summary = if result.rollback_hook_ran {
+                "Post-update hook failed, rollback hook ran".to_string()
+            } else {
+                "Post-update hook failed".to_string()
+            };
         }
 
-        info!(count = updates_before.len(), "Applying updates");
+        result.reboot = detect_reboot(&executor).await;
+        Ok(result)
+    }
 
-        // Apply updates based on package manager
-        let mut errors = Vec::new();
+    /// Apply the updates of the system package manager, then those of
+    /// flatpak and snap if they are installed
+    async fn apply_packages(
+        &self,
+        executor: &RemoteExecutor,
+        pm: PackageManager,
+        approved: Option<&ApprovedPackages>,
+    ) -> Result<ExecutionResult> {
+        let mut managers = vec![pm];
+        managers.extend(detect_secondary_managers(executor).await);
 
-        match pm {
-            PackageManager::Apt => {
-                // Update package lists
-                match executor
-                    .execute_command(
-                        "sh",
-                        &["-c", r#"if [ "$(id -u)" = "0" ]; then apt-get update -qq; else sudo apt-get update -qq; fi"#],
-                    )
-                    .await
-                {
-                    Ok(_) => debug!("Package lists updated"),
-                    Err(e) => {
-                        errors.push(format!("Failed to update package lists: {}", e));
-                        return Ok(ExecutionResult {
-                            success: false,
-                            summary: "Failed to update package lists".to_string(),
-                            packages_updated: 0,
-                            errors,
-                        });
-                    }
+        let mut errors = Vec::new();
+        let mut pending = 0;
+        let mut packages_updated = 0;
+        let mut remaining = 0;
+        let mut not_approved = 0;
+
+        for manager in managers {
+            let is_primary = manager == pm;
+
+            // Check updates before applying
+            let updates_before = match list_updates(executor, manager).await {
+                Ok(updates) => updates,
+                Err(e) if is_primary => return Err(e),
+                Err(e) => {
+                    errors.push(format!("{}: {}", manager.display_name(), e));
+                    continue;
                 }
+            };
+            let Selection {
+                selected,
+                not_approved: unapproved,
+                changed,
+            } = select_updates(&updates_before, approved);
+            not_approved += unapproved + changed.len();
+            if !changed.is_empty() {
+                errors.push(format!(
+                    "{}: versions changed since approval, left pending (run a new check and approve again): {}",
+                    manager.display_name(),
+                    changed.join(", ")
+                ));
+            }
+            if selected.is_empty() {
+                continue;
+            }
+            pending += selected.len();
 
-                // Full upgrade
-                match executor
-                    .execute_command(
-                        "sh",
-                        &["-c", r#"if [ "$(id -u)" = "0" ]; then DEBIAN_FRONTEND=noninteractive apt-get full-upgrade -y; else DEBIAN_FRONTEND=noninteractive sudo apt-get full-upgrade -y; fi"#],
-                    )
-                    .await
-                {
-                    Ok(output) => debug!(output = %output, "Updates applied"),
-                    Err(e) => {
-                        errors.push(format!("Failed to apply updates: {}", e));
-                        return Ok(ExecutionResult {
-                            success: false,
-                            summary: "Failed to apply updates".to_string(),
-                            packages_updated: 0,
-                            errors,
-                        });
+            info!(
+                package_manager = %manager.display_name(),
+                count = selected.len(),
+                "Applying updates"
+            );
+
+            // Without approval everything is upgraded; approved packages are
+            // upgraded by name and version, so updates that showed up since
+            // are left alone
+            let steps = upgrade_steps(manager);
+            let targeted = match approved {
+                None => None,
+                Some(_) => match upgrade_packages_script(manager, &selected) {
+                    Some(script) => Some(script),
+                    None if selected.len() == updates_before.len() => None,
+                    None => {
+                        errors.push(format!(
+                            "{}: pending updates changed since approval; it can only upgrade everything, so run a new check and approve again",
+                            manager.display_name()
+                        ));
+                        if is_primary {
+                            return Ok(ExecutionResult {
+                                success: false,
+                                summary: "Pending updates changed since approval".to_string(),
+                                errors,
+                                ..Default::default()
+                            });
+                        }
+                        continue;
                     }
-                }
-            }
-            PackageManager::Dnf => {
-                match executor
-                    .execute_command(
-                        "sh",
-                        &["-c", r#"if [ "$(id -u)" = "0" ]; then dnf upgrade -y; else sudo dnf upgrade -y; fi"#],
-                    )
-                    .await
-                {
-                    Ok(output) => debug!(output = %output, "Updates applied"),
+                },
+            };
+            let steps = match targeted {
+                Some(_) => &steps[..steps.len() - 1],
+                None => steps,
+            };
+
+            let mut failed = None;
+            for step in steps {
+                match run_as_root(executor, step).await {
+                    Ok(output) => debug!(output = %output, "{}", step.done),
                     Err(e) => {
-                        errors.push(format!("Failed to apply updates: {}", e));
-                        return Ok(ExecutionResult {
-                            success: false,
-                            summary: "Failed to apply updates".to_string(),
-                            packages_updated: 0,
-                            errors,
-                        });
+                        errors.push(format!("Failed to {}: {}", step.action, e));
+                        failed = Some(step.action);
+                        break;
                     }
                 }
             }
-            PackageManager::Pacman => {
-                match executor
-                    .execute_command(
-                        "sh",
-                        &["-c", r#"if [ "$(id -u)" = "0" ]; then pacman -Syu --noconfirm; else sudo pacman -Syu --noconfirm; fi"#],
-                    )
-                    .await
-                {
-                    Ok(output) => debug!(output = %output, "Updates applied"),
+            if let (None, Some(script)) = (failed, &targeted) {
+                match run_script_as_root(executor, script).await {
+                    Ok(output) => debug!(output = %output, "applied approved updates"),
                     Err(e) => {
                         errors.push(format!("Failed to apply updates: {}", e));
-                        return Ok(ExecutionResult {
-                            success: false,
-                            summary: "Failed to apply updates".to_string(),
-                            packages_updated: 0,
-                            errors,
-                        });
+                        failed = Some("apply updates");
                     }
                 }
             }
-        }
-
-        // Verify by checking for remaining updates
-        let updates_after = self.check_updates(&executor, &checker).await?;
-
-        let packages_updated = updates_before.len().saturating_sub(updates_after.len());
-
-        if updates_after.is_empty() {
-            Ok(ExecutionResult {
-                success: true,
-                summary: "Up to date".to_string(),
-                packages_updated,
-                errors,
-            })
-        } else {
-            warn!(
-                remaining = updates_after.len(),
-                "Some updates still available"
-            );
-            Ok(ExecutionResult {
-                success: true,
-                summary: format!(
-                    "{} updates still available (may require reboot or manual intervention)",
-                    updates_after.len()
-                ),
-                packages_updated,
-                errors,
-            })
-        }
-    }
 
-    /// Apply updates on the local system
-    #[instrument(skip(self))]
-    pub async fn apply_local_updates(&self) -> Result<ExecutionResult> {
-        info!("Applying local system updates");
-
-        // Detect package manager locally
-        let pm = self.detect_local_package_manager().await?;
-        debug!(package_manager = %pm.display_name(), "Package manager detected");
-
-        // Get checker to verify updates before applying
-        let checker = get_checker(&pm);
-
-        use tokio::process::Command;
-
-        // Check updates before
-        let (cmd, args) = checker.check_command();
-        let output_before = Command::new(cmd)
-            .args(&args)
-            .output()
-            .await
-            .map_err(|e| Error::PluginError(format!("Failed to check updates: {}", e)))?;
-
-        let stdout_before = String::from_utf8_lossy(&output_before.stdout);
-        let updates_before = checker.parse_updates(&stdout_before);
-
-        if updates_before.is_empty() {
-            return Ok(ExecutionResult {
-                success: true,
-                summary: "No updates available".to_string(),
-                packages_updated: 0,
-                errors: vec![],
-            });
-        }
-
-        info!(count = updates_before.len(), "Applying updates");
-
-        let mut errors = Vec::new();
-
-        // Apply updates based on package manager
-        match pm {
-            PackageManager::Apt => {
-                // Update package lists
-                let update_result = Command::new("sudo")
-                    .args(&["apt-get", "update", "-qq"])
-                    .status()
-                    .await;
-
-                if let Err(e) = update_result {
-                    errors.push(format!("Failed to update package lists: {}", e));
+            if let Some(action) = failed {
+                if is_primary {
                     return Ok(ExecutionResult {
                         success: false,
-                        summary: "Failed to update package lists".to_string(),
-                        packages_updated: 0,
-                        errors,
-                    });
-                }
-
-                // Full upgrade
-                let upgrade_result = Command::new("sudo")
-                    .env("DEBIAN_FRONTEND", "noninteractive")
-                    .args(&["apt-get", "full-upgrade", "-y"])
-                    .status()
-                    .await;
-
-                if let Err(e) = upgrade_result {
-                    errors.push(format!("Failed to apply updates: {}", e));
-                    return Ok(ExecutionResult {
-                        success: false,
-                        summary: "Failed to apply updates".to_string(),
-                        packages_updated: 0,
-                        errors,
-                    });
-                }
-            }
-            PackageManager::Dnf => {
-                let result = Command::new("sudo")
-                    .args(&["dnf", "upgrade", "-y"])
-                    .status()
-                    .await;
-
-                if let Err(e) = result {
-                    errors.push(format!("Failed to apply updates: {}", e));
-                    return Ok(ExecutionResult {
-                        success: false,
-                        summary: "Failed to apply updates".to_string(),
-                        packages_updated: 0,
-                        errors,
-                    });
-                }
-            }
-            PackageManager::Pacman => {
-                let result = Command::new("sudo")
-                    .args(&["pacman", "-Syu", "--noconfirm"])
-                    .status()
-                    .await;
-
-                if let Err(e) = result {
-                    errors.push(format!("Failed to apply updates: {}", e));
-                    return Ok(ExecutionResult {
-                        success: false,
-                        summary: "Failed to apply updates".to_string(),
-                        packages_updated: 0,
+                        summary: format!("Failed to {}", action),
                         errors,
+                        ..Default::default()
                     });
                 }
+                continue;
             }
+
+            // Verify by checking which of the selected updates remain
+            let updates_after = list_updates(executor, manager).await?;
+            let still_pending = updates_after
+                .iter()
+                .filter(|u| selected.iter().any(|(name, _)| *name == u.name))
+                .count();
+            packages_updated += selected.len().saturating_sub(still_pending);
+            remaining += still_pending;
         }
 
-        // Verify by checking for remaining updates
-        let output_after = Command::new(cmd)
-            .args(&args)
-            .output()
-            .await
-            .map_err(|e| Error::PluginError(format!("Failed to check updates: {}", e)))?;
-
-        let stdout_after = String::from_utf8_lossy(&output_after.stdout);
-        let updates_after = checker.parse_updates(&stdout_after);
-
-        let packages_updated = updates_before.len().saturating_sub(updates_after.len());
-
-        if updates_after.is_empty() {
-            Ok(ExecutionResult {
-                success: true,
-                summary: "Up to date".to_string(),
-                packages_updated,
-                errors,
-            })
+        let mut summary = if pending == 0 && not_approved > 0 {
+            "No approved updates pending".to_string()
+        } else if pending == 0 {
+            "No updates available".to_string()
+        } else if remaining == 0 {
+            "Up to date".to_string()
         } else {
-            warn!(
-                remaining = updates_after.len(),
-                "Some updates still available"
-            );
-            Ok(ExecutionResult {
-                success: true,
-                summary: format!(
-                    "{} updates still available (may require reboot or manual intervention)",
-                    updates_after.len()
-                ),
-                packages_updated,
-                errors,
-            })
+            warn!(remaining = remaining, "Some updates still available");
+            format!(
+                "{} updates still available (may require reboot or manual intervention)",
+                remaining
+            )
+        };
+        if pending > 0 && not_approved > 0 {
+            summary.push_str(&format!(
+                " ({} updates not approved, left pending)",
+                not_approved
+            ));
         }
-    }
 
-    /// Check for updates using the given checker
-    async fn check_updates(
-        &self,
-        executor: &RemoteExecutor,
-        checker: &Box<dyn UpdateChecker>,
-    ) -> Result<Vec<String>> {
-        let (cmd, args) = checker.check_command();
+        Ok(ExecutionResult {
+            success: errors.is_empty(),
+            summary,
+            packages_updated,
+            errors,
+            ..Default::default()
+        })
+    }
+}
 
-        let output = executor
-            .execute_command(cmd, &args)
-            .await
-            .map_err(|e| Error::PluginError(format!("Failed to check updates: {}", e)))?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        Ok(checker.parse_updates(&output))
+    fn update(name: &str, new_version: Option<&str>) -> PackageUpdate {
+        PackageUpdate {
+            name: name.to_string(),
+            new_version: new_version.map(String::from),
+            ..Default::default()
+        }
     }
 
-    /// Detect package manager on local system
-    async fn detect_local_package_manager(&self) -> Result<PackageManager> {
-        use tokio::process::Command;
+    #[test]
+    fn test_upgrade_packages_script() {
+        let packages = vec![
+            ("openssl".to_string(), Some("3.0.13-1".to_string())),
+            ("libssl3".to_string(), None),
+        ];
+        assert_eq!(
+            upgrade_packages_script(PackageManager::Apt, &packages).as_deref(),
+            Some("$SUDO env DEBIAN_FRONTEND=noninteractive apt-get install --only-upgrade -y 'openssl=3.0.13-1' 'libssl3'")
+        );
+        assert_eq!(
+            upgrade_packages_script(PackageManager::Dnf, &packages).as_deref(),
+            Some("$SUDO dnf upgrade -y 'openssl-3.0.13-1' 'libssl3'")
+        );
+        assert_eq!(
+            upgrade_packages_script(PackageManager::Apk, &packages).as_deref(),
+            Some("$SUDO apk upgrade 'openssl' 'libssl3'")
+        );
 
-        for pm in PackageManager::all() {
-            let binary = format!("/usr/bin/{}", pm.binary());
+        let injected = vec![("vim; reboot".to_string(), Some("9.1'; reboot".to_string()))];
+        assert_eq!(
+            upgrade_packages_script(PackageManager::Zypper, &injected).as_deref(),
+            Some("$SUDO zypper --non-interactive update 'vim; reboot-9.1'\\''; reboot'")
+        );
 
-            let check = Command::new("test").arg("-x").arg(&binary).status().await;
+        assert_eq!(
+            upgrade_packages_script(PackageManager::Pacman, &packages),
+            None
+        );
+    }
 
-            if let Ok(status) = check {
-                if status.success() {
-                    info!("Detected package manager: {:?}", pm);
-                    return Ok(pm);
-                }
+    #[test]
+    fn test_select_updates() {
+        let updates = [
+            update("openssl", Some("3.0.13")),
+            update("vim", Some("9.1")),
+            update("curl", Some("8.6")),
+            update("firefox", None),
+        ];
+
+        let all = select_updates(&updates, None);
+        assert_eq!(all.selected.len(), 4);
+        assert!(all.selected.iter().all(|(_, version)| version.is_none()));
+
+        let approved = ApprovedPackages::from([
+            ("openssl".to_string(), Some("3.0.13".to_string())),
+            ("vim".to_string(), Some("9.0".to_string())),
+            ("firefox".to_string(), None),
+        ]);
+        assert_eq!(
+            select_updates(&updates, Some(&approved)),
+            Selection {
+                selected: vec![
+                    ("openssl".to_string(), Some("3.0.13".to_string())),
+                    ("firefox".to_string(), None),
+                ],
+                not_approved: 1,
+                changed: vec!["vim 9.1 (approved 9.0)".to_string()],
             }
-        }
+        );
+    }
 
-        Err(Error::PluginError(
-            "No supported package manager found on local system".to_string(),
-        ))
+    #[test]
+    fn test_targeted_upgrades_keep_refresh_steps() {
+        // Upgrading selected packages replaces only the final step
+        for pm in [
+            PackageManager::Apt,
+            PackageManager::Zypper,
+            PackageManager::Apk,
+        ] {
+            let steps = upgrade_steps(pm);
+            assert!(steps.len() > 1);
+            assert_ne!(steps[0].action, "apply updates");
+            assert_eq!(steps[steps.len() - 1].action, "apply updates");
+        }
     }
 }
@@ -0,0 +1,128 @@
+//! Update hooks
+//!
+//! Servers can run their own shell scripts around applying updates: a
+//! pre-update hook (take an LVM/btrfs/ZFS snapshot, stop compose stacks)
+//! that must succeed before anything is installed, a post-update hook
+//! (start services again, probe health) and a rollback hook that runs when
+//! the post-update hook fails.
+//!
+//! Hooks run with `sh -c` as the SSH user, so they use sudo themselves where
+//! needed, and fail on a non-zero exit code. They get these variables:
+//! - `SVRCTLRS_SERVER`: the server's name
+//! - `SVRCTLRS_PACKAGE_MANAGER`: the package manager transaction's manager
+//!   (`apt`, `dnf`, `yum`), empty when none was recorded
+//! - `SVRCTLRS_TRANSACTION_ID`: the transaction applying updates made, empty
+//!   before updates are applied or when none was recorded
+
+use crate::transaction::{shell_quote, Transaction};
+use svrctlrs_core::{Error, RemoteExecutor, Result, Server};
+use tracing::info;
+
+/// Point in the update run a hook belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Hook {
+    PreUpdate,
+    PostUpdate,
+    Rollback,
+}
+
+impl Hook {
+    fn name(self) -> &'static str {
+        match self {
+            Self::PreUpdate => "Pre-update",
+            Self::PostUpdate => "Post-update",
+            Self::Rollback => "Rollback",
+        }
+    }
+
+    /// The server's script for this hook, if it has one
+    pub(crate) fn script(self, server: &Server) -> Option<&str> {
+        let script = match self {
+            Self::PreUpdate => &server.pre_update_hook,
+            Self::PostUpdate => &server.post_update_hook,
+            Self::Rollback => &server.rollback_hook,
+        };
+        script.as_deref().filter(|s| !s.trim().is_empty())
+    }
+}
+
+/// Run a server's hook, if it has one
+///
+/// Returns the hook's output, or `None` if the server has no such hook.
+pub(crate) async fn run_hook(
+    executor: &RemoteExecutor,
+    hook: Hook,
+    transaction: Option<&Transaction>,
+) -> Result<Option<String>> {
+    let server = executor.server();
+    let Some(script) = hook.script(server) else {
+        return Ok(None);
+    };
+
+    info!(server = %server.name, hook = hook.name(), "Running update hook");
+    executor
+        .execute_command_checked("sh", &["-c", &hook_script(server, script, transaction)])
+        .await
+        .map(Some)
+        .map_err(|e| Error::PluginError(format!("{} hook failed: {}", hook.name(), e)))
+}
+
+/// The hook's script, preceded by its environment
+fn hook_script(server: &Server, script: &str, transaction: Option<&Transaction>) -> String {
+    let (package_manager, transaction_id) = transaction
+        .map(|t| (t.package_manager.as_str(), t.transaction_id.as_str()))
+        .unwrap_or_default();
+
+    format!(
+        "export SVRCTLRS_SERVER={} SVRCTLRS_PACKAGE_MANAGER={} SVRCTLRS_TRANSACTION_ID={}\n{}",
+        shell_quote(&server.name),
+        shell_quote(package_manager),
+        shell_quote(transaction_id),
+        script
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(post_update_hook: &str) -> Server {
+        Server {
+            post_update_hook: Some(post_update_hook.to_string()),
+            ..Server::local("web1")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hook_environment() {
+        let executor = RemoteExecutor::for_server(
+            server(r#"echo "$SVRCTLRS_SERVER $SVRCTLRS_PACKAGE_MANAGER $SVRCTLRS_TRANSACTION_ID""#),
+            None,
+        );
+        let transaction = Transaction {
+            package_manager: "apt".to_string(),
+            transaction_id: "2025-01-05  03:00:12".to_string(),
+            packages: Vec::new(),
+        };
+
+        let output = run_hook(&executor, Hook::PostUpdate, Some(&transaction))
+            .await
+            .unwrap();
+        assert_eq!(output.as_deref(), Some("web1 apt 2025-01-05  03:00:12\n"));
+
+        // Hooks the server does not have are skipped
+        assert_eq!(
+            run_hook(&executor, Hook::PreUpdate, None).await.unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failing_hook() {
+        let executor = RemoteExecutor::for_server(server("exit 7"), None);
+        let err = run_hook(&executor, Hook::PostUpdate, None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Post-update hook failed"));
+    }
+}
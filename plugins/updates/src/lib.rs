@@ -1,21 +1,37 @@
 //! System updates monitoring and management plugin
 //!
-//! Provides OS update detection, execution, and cleanup operations
+//! Provides OS update detection, execution, and cleanup operations on every
+//! server in the plugin context, in parallel
 
 mod cleanup;
 mod detection;
 mod execution;
+mod hooks;
+mod reboot;
+mod transaction;
 
 use async_trait::async_trait;
 use cleanup::CleanupExecutor;
 use detection::UpdateDetector;
-use execution::UpdateExecutor;
+use execution::{ApprovedPackages, UpdateExecutor};
 use serde_json::json;
 use std::collections::HashMap;
+use std::future::Future;
 use svrctlrs_core::{
-    Error, Plugin, PluginContext, PluginMetadata, PluginResult, Result, ScheduledTask,
+    server_metric_key, Error, Plugin, PluginContext, PluginMetadata, PluginResult, RemoteExecutor,
+    Result, ScheduledTask, Server,
 };
-use tracing::{info, instrument};
+use tokio::task::JoinSet;
+use tracing::{info, instrument, warn};
+
+pub use reboot::{RebootStatus, REBOOT_CHECK_SCRIPT};
+pub use transaction::{rollback_transaction, Transaction, TransactionPackage};
+
+/// Task rebooting the servers that need it one at a time
+///
+/// It waits for every server to come back before moving on, so the server
+/// runs it rather than the plugin.
+pub const REBOOT_ROLLOUT_TASK: &str = "reboot_rollout";
 
 /// System and package updates monitoring plugin
 pub struct UpdatesPlugin {}
@@ -58,6 +74,12 @@ impl Plugin for UpdatesPlugin {
                 description: "Apply OS updates (if enabled)".to_string(),
                 enabled: false, // Disabled by default for safety
             },
+            ScheduledTask {
+                id: REBOOT_ROLLOUT_TASK.to_string(),
+                schedule: "0 30 3 * * Sun".to_string(), // Sundays at 3:30 AM
+                description: "Reboot servers that need it, one at a time".to_string(),
+                enabled: false, // Disabled by default for safety
+            },
             ScheduledTask {
                 id: "os_cleanup".to_string(),
                 schedule: "0 0 4 * * 0".to_string(), // Sundays at 4 AM
@@ -74,6 +96,15 @@ impl Plugin for UpdatesPlugin {
             "updates_check" => self.check_updates(context).await,
             "updates_apply" => self.apply_updates(context).await,
             "os_cleanup" => self.cleanup_os(context).await,
+            REBOOT_ROLLOUT_TASK => Ok(PluginResult {
+                success: false,
+                message: format!(
+                    "{} is orchestrated by the server; run it as a task",
+                    REBOOT_ROLLOUT_TASK
+                ),
+                data: None,
+                metrics: None,
+            }),
             _ => Ok(PluginResult {
                 success: false,
                 message: format!("Unknown task: {}", task_id),
@@ -84,233 +115,450 @@ impl Plugin for UpdatesPlugin {
     }
 }
 
-impl UpdatesPlugin {
-    #[instrument(skip(self, context))]
-    async fn check_updates(&self, context: &PluginContext) -> Result<PluginResult> {
-        info!("Checking for OS updates");
+/// Outcome of an operation on one server
+type ServerOutcome<T> = (Server, Result<T>);
+
+/// Run an operation on every server in the context in parallel
+///
+/// Falls back to the local machine when no servers are configured. Results
+/// come back in the order the servers were given.
+async fn for_each_server<T, F, Fut>(context: &PluginContext, op: F) -> Vec<ServerOutcome<T>>
+where
+    T: Send + 'static,
+    F: Fn(Server, Option<String>) -> Fut,
+    Fut: Future<Output = Result<T>> + Send + 'static,
+{
+    let servers = if context.servers.is_empty() {
+        vec![Server::local("localhost")]
+    } else {
+        context.servers.clone()
+    };
+    let mut set = JoinSet::new();
+    for (index, server) in servers.into_iter().enumerate() {
+        let ssh_key_path = server.ssh_key_path.clone();
+        let run = op(server.clone(), ssh_key_path);
+        set.spawn(async move { (index, server, run.await) });
+    }
 
-        // Get server configuration from context
-        let server_name =
-            std::env::var("UPDATES_SERVER_NAME").unwrap_or_else(|_| "localhost".to_string());
-        let ssh_enabled = std::env::var("UPDATES_SSH_ENABLED")
-            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
-            .unwrap_or(false);
-
-        let detector = UpdateDetector::new();
-
-        let update_info = if ssh_enabled {
-            // Remote update check via SSH
-            let ssh_host = std::env::var("UPDATES_SSH_HOST")
-                .map_err(|_| Error::PluginError("UPDATES_SSH_HOST not set".to_string()))?;
-            let ssh_user = std::env::var("UPDATES_SSH_USER").ok();
-            let ssh_key = std::env::var("UPDATES_SSH_KEY").ok();
-
-            detector
-                .check_remote_updates(
-                    &server_name,
-                    &ssh_host,
-                    ssh_user.as_deref(),
-                    ssh_key.as_deref(),
-                )
-                .await?
-        } else {
-            // Local update check
-            detector.check_local_updates().await?
-        };
+    let mut results = Vec::new();
+    while let Some(joined) = set.join_next().await {
+        match joined {
+            Ok(result) => results.push(result),
+            Err(e) => warn!(error = %e, "Updates task panicked"),
+        }
+    }
+    results.sort_by_key(|(index, _, _)| *index);
 
-        let message = format!(
-            "Updates check: {} packages available on {}",
-            update_info.total_updates, server_name
-        );
+    results
+        .into_iter()
+        .map(|(_, server, result)| (server, result))
+        .collect()
+}
 
-        // Prepare structured data
-        let data = json!({
-            "server_name": server_name,
-            "package_manager": update_info.package_manager,
-            "total_updates": update_info.total_updates,
-            "security_updates": update_info.security_updates,
-            "packages": update_info.packages,
+/// Outcome of an updates check on every server
+struct CheckSummary {
+    /// Servers checked, with their updates
+    found: Vec<(String, detection::UpdateInfo)>,
+    /// Servers that couldn't be checked, with the error
+    failed: Vec<(String, String)>,
+    total_updates: usize,
+    result: PluginResult,
+}
+
+/// Per-server metrics and data plus totals of an updates check
+fn summarize_checks(
+    results: Vec<ServerOutcome<(detection::UpdateInfo, RebootStatus)>>,
+) -> CheckSummary {
+    let mut metrics = HashMap::new();
+    let mut server_data = Vec::new();
+    let mut found = Vec::new();
+    let mut failed = Vec::new();
+
+    for (server, result) in results {
+        match result {
+            Ok((update_info, reboot)) => {
+                metrics.insert(
+                    server_metric_key(&server.name, "total_updates"),
+                    update_info.total_updates as f64,
+                );
+                metrics.insert(
+                    server_metric_key(&server.name, "security_updates"),
+                    update_info.security_updates as f64,
+                );
+                metrics.insert(
+                    server_metric_key(&server.name, "reboot_required"),
+                    if reboot.required { 1.0 } else { 0.0 },
+                );
+                server_data.push(json!({
+                    "server": server.name,
+                    "success": true,
+                    "package_manager": update_info.package_manager,
+                    "secondary_managers": update_info.secondary_managers,
+                    "total_updates": update_info.total_updates,
+                    "security_updates": update_info.security_updates,
+                    "packages": update_info.packages,
+                    "reboot_required": reboot.required,
+                    "reboot_reasons": reboot.reasons,
+                }));
+                found.push((server.name, update_info));
+            }
+            Err(e) => {
+                warn!(server = %server.name, error = %e, "Failed to check updates");
+                server_data.push(json!({
+                    "server": server.name,
+                    "success": false,
+                    "error": e.to_string(),
+                }));
+                failed.push((server.name, e.to_string()));
+            }
+        }
+    }
+
+    let total_updates: usize = found.iter().map(|(_, info)| info.total_updates).sum();
+    let security_updates: usize = found.iter().map(|(_, info)| info.security_updates).sum();
+    let total_servers = found.len() + failed.len();
+
+    metrics.insert("total_updates".to_string(), total_updates as f64);
+    metrics.insert("security_updates".to_string(), security_updates as f64);
+
+    let mut message = format!(
+        "Updates check: {} packages available ({} security) across {} servers",
+        total_updates, security_updates, total_servers
+    );
+    if !failed.is_empty() {
+        message.push_str(&format!(" ({} failed)", failed.len()));
+    }
+
+    let data = json!({
+        "servers": server_data,
+        "total_updates": total_updates,
+        "security_updates": security_updates,
+    });
+
+    CheckSummary {
+        result: PluginResult {
+            success: failed.is_empty(),
+            message,
+            data: Some(data),
+            metrics: Some(metrics),
+        },
+        found,
+        failed,
+        total_updates,
+    }
+}
+
+/// Per-server metrics and data plus totals of applying updates
+fn summarize_apply(
+    results: Vec<ServerOutcome<execution::ExecutionResult>>,
+) -> (Vec<(String, execution::ExecutionResult)>, PluginResult) {
+    let mut metrics = HashMap::new();
+    let mut server_data = Vec::new();
+    let mut outcomes = Vec::new();
+
+    for (server, result) in results {
+        let result = result.unwrap_or_else(|e| {
+            warn!(server = %server.name, error = %e, "Failed to apply updates");
+            execution::ExecutionResult::failed("Failed to apply updates", e)
         });
 
-        // Prepare metrics
-        let mut metrics = HashMap::new();
         metrics.insert(
-            "total_updates".to_string(),
-            update_info.total_updates as f64,
+            server_metric_key(&server.name, "packages_updated"),
+            result.packages_updated as f64,
         );
         metrics.insert(
-            "security_updates".to_string(),
-            update_info.security_updates as f64,
+            server_metric_key(&server.name, "success"),
+            if result.success { 1.0 } else { 0.0 },
         );
+        metrics.insert(
+            server_metric_key(&server.name, "reboot_required"),
+            if result.reboot.required { 1.0 } else { 0.0 },
+        );
+        metrics.insert(
+            server_metric_key(&server.name, "post_hook_failed"),
+            if result.post_hook_failed { 1.0 } else { 0.0 },
+        );
+        server_data.push(json!({
+            "server": server.name,
+            "success": result.success,
+            "summary": result.summary,
+            "packages_updated": result.packages_updated,
+            "errors": result.errors,
+            "reboot_required": result.reboot.required,
+            "reboot_reasons": result.reboot.reasons,
+            "transaction": result.transaction,
+            "post_hook_failed": result.post_hook_failed,
+            "rollback_hook_ran": result.rollback_hook_ran,
+        }));
+        outcomes.push((server.name, result));
+    }
+
+    let packages_updated: usize = outcomes.iter().map(|(_, r)| r.packages_updated).sum();
+    let failed = outcomes.iter().filter(|(_, r)| !r.success).count();
+    let reboot_required = outcomes.iter().filter(|(_, r)| r.reboot.required).count();
+    let post_hook_failed = outcomes.iter().filter(|(_, r)| r.post_hook_failed).count();
+    metrics.insert("packages_updated".to_string(), packages_updated as f64);
+    metrics.insert("reboot_required".to_string(), reboot_required as f64);
+    metrics.insert("post_hook_failed".to_string(), post_hook_failed as f64);
+
+    let mut message = format!(
+        "Updates applied on {} servers: {} packages updated",
+        outcomes.len(),
+        packages_updated
+    );
+    if failed > 0 {
+        message.push_str(&format!(" ({} failed)", failed));
+    }
+    if reboot_required > 0 {
+        message.push_str(&format!(", {} need a reboot", reboot_required));
+    }
+    if post_hook_failed > 0 {
+        message.push_str(&format!(
+            ", post-update hook failed on {}",
+            post_hook_failed
+        ));
+    }
+
+    let result = PluginResult {
+        success: failed == 0,
+        message,
+        data: Some(json!({
+            "servers": server_data,
+            "packages_updated": packages_updated,
+            "reboot_required": reboot_required,
+            "post_hook_failed": post_hook_failed,
+        })),
+        metrics: Some(metrics),
+    };
+    (outcomes, result)
+}
+
+/// Per-server metrics and data plus totals of an OS cleanup
+fn summarize_cleanup(
+    results: Vec<ServerOutcome<cleanup::CleanupResult>>,
+) -> (Vec<(String, cleanup::CleanupResult)>, PluginResult) {
+    let mut metrics = HashMap::new();
+    let mut server_data = Vec::new();
+    let mut outcomes = Vec::new();
+
+    for (server, result) in results {
+        let result = result.unwrap_or_else(|e| {
+            warn!(server = %server.name, error = %e, "Failed to clean up");
+            cleanup::CleanupResult {
+                success: false,
+                summary: "No cleanup performed".to_string(),
+                space_freed_bytes: 0,
+                errors: vec![e.to_string()],
+            }
+        });
+
+        metrics.insert(
+            server_metric_key(&server.name, "space_freed_mb"),
+            result.space_freed_bytes as f64 / 1024.0 / 1024.0,
+        );
+        server_data.push(json!({
+            "server": server.name,
+            "success": result.success,
+            "summary": result.summary,
+            "space_freed_bytes": result.space_freed_bytes,
+            "errors": result.errors,
+        }));
+        outcomes.push((server.name, result));
+    }
+
+    let space_freed_bytes: u64 = outcomes.iter().map(|(_, r)| r.space_freed_bytes).sum();
+    let failed = outcomes.iter().filter(|(_, r)| !r.success).count();
+    metrics.insert(
+        "space_freed_mb".to_string(),
+        space_freed_bytes as f64 / 1024.0 / 1024.0,
+    );
 
-        // Send notification if updates available
-        if update_info.total_updates > 0 {
+    let mut message = format!("OS cleanup on {} servers", outcomes.len());
+    if failed > 0 {
+        message.push_str(&format!(" ({} failed)", failed));
+    }
+
+    let result = PluginResult {
+        success: failed == 0,
+        message,
+        data: Some(json!({
+            "servers": server_data,
+            "space_freed_bytes": space_freed_bytes,
+        })),
+        metrics: Some(metrics),
+    };
+    (outcomes, result)
+}
+
+impl UpdatesPlugin {
+    #[instrument(skip(self, context))]
+    async fn check_updates(&self, context: &PluginContext) -> Result<PluginResult> {
+        info!("Checking for OS updates");
+
+        let results = for_each_server(context, |server, ssh_key| async move {
+            let update_info = UpdateDetector::new()
+                .check_updates(&server, ssh_key.as_deref())
+                .await?;
+            let executor = RemoteExecutor::for_server(server, ssh_key);
+            let reboot = reboot::detect_reboot(&executor).await;
+            Ok((update_info, reboot))
+        })
+        .await;
+
+        let summary = summarize_checks(results);
+
+        // One digest for the whole run, only when there is something to report.
+        // When updates need approval, the server asks for it per server
+        // instead, so only failed checks are reported here.
+        let approval_required = context
+            .config
+            .get("approval_required")
+            .is_some_and(|v| v == "true");
+        if approval_required {
+            if !summary.failed.is_empty() {
+                self.send_update_notification(&context.notification_manager, &[], &summary.failed)
+                    .await?;
+            }
+        } else if summary.total_updates > 0 || !summary.failed.is_empty() {
             self.send_update_notification(
                 &context.notification_manager,
-                &update_info,
-                &server_name,
+                &summary.found,
+                &summary.failed,
             )
             .await?;
         }
 
-        Ok(PluginResult {
-            success: true,
-            message,
-            data: Some(data),
-            metrics: Some(metrics),
-        })
+        Ok(summary.result)
     }
 
+    /// Apply updates on every server
+    ///
+    /// The `approved_packages` setting (JSON object of server name to an
+    /// object of package name to approved version), set when updates need
+    /// approval, limits each server to the packages and versions approved
+    /// for it.
     #[instrument(skip(self, context))]
     async fn apply_updates(&self, context: &PluginContext) -> Result<PluginResult> {
         info!("Applying OS updates");
 
-        let server_name =
-            std::env::var("UPDATES_SERVER_NAME").unwrap_or_else(|_| "localhost".to_string());
-        let ssh_enabled = std::env::var("UPDATES_SSH_ENABLED")
-            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
-            .unwrap_or(false);
-
-        let executor = UpdateExecutor::new();
-
-        let result = if ssh_enabled {
-            let ssh_host = std::env::var("UPDATES_SSH_HOST")
-                .map_err(|_| Error::PluginError("UPDATES_SSH_HOST not set".to_string()))?;
-            let ssh_user = std::env::var("UPDATES_SSH_USER").ok();
-            let ssh_key = std::env::var("UPDATES_SSH_KEY").ok();
-
-            executor
-                .apply_remote_updates(
-                    &server_name,
-                    &ssh_host,
-                    ssh_user.as_deref(),
-                    ssh_key.as_deref(),
-                )
-                .await?
-        } else {
-            executor.apply_local_updates().await?
-        };
-
-        let message = format!("Updates applied on {}: {}", server_name, result.summary);
+        let approved: Option<HashMap<String, ApprovedPackages>> = context
+            .config
+            .get("approved_packages")
+            .map(|packages| serde_json::from_str(packages))
+            .transpose()
+            .map_err(|e| Error::PluginError(format!("Invalid approved_packages: {}", e)))?;
+
+        let results = for_each_server(context, |server, ssh_key| {
+            let approved = approved
+                .as_ref()
+                .map(|approved| approved.get(&server.name).cloned().unwrap_or_default());
+            async move {
+                UpdateExecutor::new()
+                    .apply_updates(&server, ssh_key.as_deref(), approved.as_ref())
+                    .await
+            }
+        })
+        .await;
 
-        // Prepare metrics
-        let mut metrics = HashMap::new();
-        metrics.insert(
-            "packages_updated".to_string(),
-            result.packages_updated as f64,
-        );
-        metrics.insert(
-            "success".to_string(),
-            if result.success { 1.0 } else { 0.0 },
-        );
+        let (outcomes, result) = summarize_apply(results);
 
-        // Send notification with results
-        self.send_execution_notification(&context.notification_manager, &result, &server_name)
+        self.send_execution_notification(&context.notification_manager, &outcomes)
             .await?;
 
-        Ok(PluginResult {
-            success: result.success,
-            message,
-            data: None,
-            metrics: Some(metrics),
-        })
+        Ok(result)
     }
 
     #[instrument(skip(self, context))]
     async fn cleanup_os(&self, context: &PluginContext) -> Result<PluginResult> {
         info!("Running OS cleanup");
 
-        let server_name =
-            std::env::var("UPDATES_SERVER_NAME").unwrap_or_else(|_| "localhost".to_string());
-        let ssh_enabled = std::env::var("UPDATES_SSH_ENABLED")
-            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
-            .unwrap_or(false);
-
-        let cleanup_executor = CleanupExecutor::new();
-
-        let result = if ssh_enabled {
-            let ssh_host = std::env::var("UPDATES_SSH_HOST")
-                .map_err(|_| Error::PluginError("UPDATES_SSH_HOST not set".to_string()))?;
-            let ssh_user = std::env::var("UPDATES_SSH_USER").ok();
-            let ssh_key = std::env::var("UPDATES_SSH_KEY").ok();
-
-            cleanup_executor
-                .cleanup_remote(
-                    &server_name,
-                    &ssh_host,
-                    ssh_user.as_deref(),
-                    ssh_key.as_deref(),
-                )
-                .await?
-        } else {
-            cleanup_executor.cleanup_local().await?
-        };
-
-        let message = format!("OS cleanup on {}: {}", server_name, result.summary);
+        let results = for_each_server(context, |server, ssh_key| async move {
+            CleanupExecutor::new()
+                .cleanup(&server, ssh_key.as_deref())
+                .await
+        })
+        .await;
 
-        // Prepare metrics
-        let mut metrics = HashMap::new();
-        metrics.insert(
-            "space_freed_mb".to_string(),
-            result.space_freed_bytes as f64 / 1024.0 / 1024.0,
-        );
+        let (outcomes, result) = summarize_cleanup(results);
 
-        // Send notification with results
-        self.send_cleanup_notification(&context.notification_manager, &result, &server_name)
+        self.send_cleanup_notification(&context.notification_manager, &outcomes)
             .await?;
 
-        Ok(PluginResult {
-            success: result.success,
-            message,
-            data: None,
-            metrics: Some(metrics),
-        })
+        Ok(result)
     }
 
     async fn send_update_notification(
         &self,
         notify_mgr: &svrctlrs_core::NotificationManager,
-        update_info: &detection::UpdateInfo,
-        server_name: &str,
+        found: &[(String, detection::UpdateInfo)],
+        failed: &[(String, String)],
     ) -> Result<()> {
-        let title = format!("Updates Available: {}", server_name);
+        let total_updates: usize = found.iter().map(|(_, info)| info.total_updates).sum();
+        let security_updates: usize = found.iter().map(|(_, info)| info.security_updates).sum();
+        let servers_with_updates = found
+            .iter()
+            .filter(|(_, info)| info.total_updates > 0)
+            .count();
+
+        let title = if total_updates == 0 {
+            format!("Updates Check Failed on {} servers", failed.len())
+        } else {
+            format!(
+                "Updates Available: {} packages on {} servers",
+                total_updates, servers_with_updates
+            )
+        };
 
         let mut body = String::new();
-        body.push_str(&format!(
-            "## {} Updates Available\n\n",
-            update_info.total_updates
-        ));
-        body.push_str(&format!("**Server**: {}\n", server_name));
-        body.push_str(&format!(
-            "**Package Manager**: {}\n\n",
-            update_info.package_manager
-        ));
+        if total_updates > 0 {
+            body.push_str(&format!("## {} Updates Available\n\n", total_updates));
+        }
 
-        if update_info.security_updates > 0 {
+        if security_updates > 0 {
             body.push_str(&format!(
                 "🔒 **Security Updates**: {}\n\n",
-                update_info.security_updates
+                security_updates
             ));
         }
 
-        // List first 10 packages
-        if !update_info.packages.is_empty() {
-            body.push_str("**Available Packages**:\n");
+        for (server_name, update_info) in found.iter().filter(|(_, info)| info.total_updates > 0) {
+            let mut managers = vec![update_info.package_manager.clone()];
+            managers.extend(update_info.secondary_managers.iter().cloned());
+            body.push_str(&format!(
+                "### {} ({}): {} updates",
+                server_name,
+                managers.join(" + "),
+                update_info.total_updates
+            ));
+            if update_info.security_updates > 0 {
+                body.push_str(&format!(", {} security", update_info.security_updates));
+            }
+            body.push('\n');
+
+            // List first 10 packages
             for (i, pkg) in update_info.packages.iter().take(10).enumerate() {
                 body.push_str(&format!("{}. {}\n", i + 1, pkg));
             }
             if update_info.packages.len() > 10 {
                 body.push_str(&format!(
-                    "\n...and {} more\n",
+                    "...and {} more\n",
                     update_info.packages.len() - 10
                 ));
             }
+            body.push('\n');
+        }
+
+        if !failed.is_empty() {
+            body.push_str("**Check Failed**:\n");
+            for (server_name, error) in failed {
+                body.push_str(&format!("  - {}: {}\n", server_name, error));
+            }
         }
 
         let message = svrctlrs_core::NotificationMessage {
             title,
             body,
-            priority: if update_info.security_updates > 0 {
+            priority: if security_updates > 0 || !failed.is_empty() {
                 4
             } else {
                 3
@@ -329,37 +577,69 @@ impl UpdatesPlugin {
     async fn send_execution_notification(
         &self,
         notify_mgr: &svrctlrs_core::NotificationManager,
-        result: &execution::ExecutionResult,
-        server_name: &str,
+        outcomes: &[(String, execution::ExecutionResult)],
     ) -> Result<()> {
-        let title = if result.success {
-            format!("✅ Updates Applied: {}", server_name)
+        let failed = outcomes.iter().filter(|(_, r)| !r.success).count();
+        let packages_updated: usize = outcomes.iter().map(|(_, r)| r.packages_updated).sum();
+        let post_hook_failed = outcomes.iter().filter(|(_, r)| r.post_hook_failed).count();
+
+        // A failed health probe after updating is the one outcome that needs attention now
+        let title = if post_hook_failed > 0 {
+            format!(
+                "🚨 Post-Update Hook Failed: {}/{} servers",
+                post_hook_failed,
+                outcomes.len()
+            )
+        } else if failed == 0 {
+            format!("✅ Updates Applied: {} servers", outcomes.len())
         } else {
-            format!("❌ Update Failed: {}", server_name)
+            format!("❌ Update Failed: {}/{} servers", failed, outcomes.len())
         };
 
         let mut body = String::new();
-        body.push_str(&format!("**Server**: {}\n", server_name));
-        body.push_str(&format!("**Summary**: {}\n\n", result.summary));
-
-        if result.packages_updated > 0 {
-            body.push_str(&format!(
-                "📦 **Packages Updated**: {}\n",
-                result.packages_updated
-            ));
-        }
+        body.push_str(&format!(
+            "📦 **Packages Updated**: {}\n\n",
+            packages_updated
+        ));
 
-        if !result.errors.is_empty() {
-            body.push_str("\n**Errors**:\n");
+        for (server_name, result) in outcomes {
+            let icon = if result.success { "✅" } else { "❌" };
+            body.push_str(&format!("{} **{}**: {}", icon, server_name, result.summary));
+            if result.packages_updated > 0 {
+                body.push_str(&format!(" ({} packages)", result.packages_updated));
+            }
+            body.push('\n');
             for error in &result.errors {
                 body.push_str(&format!("  - {}\n", error));
             }
+            if result.post_hook_failed {
+                body.push_str(if result.rollback_hook_ran {
+                    "  - 🚨 Rollback hook ran\n"
+                } else {
+                    "  - 🚨 No rollback hook ran\n"
+                });
+            }
+            if let Some(transaction) = &result.transaction {
+                body.push_str(&format!(
+                    "  - {} transaction {}\n",
+                    transaction.package_manager, transaction.transaction_id
+                ));
+            }
+            for reason in &result.reboot.reasons {
+                body.push_str(&format!("  - 🔄 Reboot required: {}\n", reason));
+            }
         }
 
         let message = svrctlrs_core::NotificationMessage {
             title,
             body,
-            priority: if result.success { 3 } else { 4 },
+            priority: if post_hook_failed > 0 {
+                5
+            } else if failed == 0 {
+                3
+            } else {
+                4
+            },
             actions: vec![],
         };
 
@@ -374,24 +654,34 @@ impl UpdatesPlugin {
     async fn send_cleanup_notification(
         &self,
         notify_mgr: &svrctlrs_core::NotificationManager,
-        result: &cleanup::CleanupResult,
-        server_name: &str,
+        outcomes: &[(String, cleanup::CleanupResult)],
     ) -> Result<()> {
-        let title = format!("OS Cleanup: {}", server_name);
+        let space_freed_bytes: u64 = outcomes.iter().map(|(_, r)| r.space_freed_bytes).sum();
+        let title = format!("OS Cleanup: {} servers", outcomes.len());
 
         let mut body = String::new();
         body.push_str("## Cleanup Complete\n\n");
-        body.push_str(&format!("**Server**: {}\n", server_name));
         body.push_str(&format!(
             "**Space Freed**: {:.2} MB\n\n",
-            result.space_freed_bytes as f64 / 1024.0 / 1024.0
+            space_freed_bytes as f64 / 1024.0 / 1024.0
         ));
-        body.push_str(&format!("**Summary**: {}\n", result.summary));
 
+        for (server_name, result) in outcomes {
+            let icon = if result.success { "✅" } else { "❌" };
+            body.push_str(&format!(
+                "{} **{}**: {}\n",
+                icon, server_name, result.summary
+            ));
+            for error in &result.errors {
+                body.push_str(&format!("  - {}\n", error));
+            }
+        }
+
+        let failed = outcomes.iter().any(|(_, r)| !r.success);
         let message = svrctlrs_core::NotificationMessage {
             title,
             body,
-            priority: 3,
+            priority: if failed { 4 } else { 3 },
             actions: vec![],
         };
 
@@ -403,3 +693,143 @@ impl UpdatesPlugin {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metric(result: &PluginResult, key: &str) -> f64 {
+        result.metrics.as_ref().unwrap()[key]
+    }
+
+    fn update_info(total_updates: usize, security_updates: usize) -> detection::UpdateInfo {
+        detection::UpdateInfo {
+            package_manager: "apt".to_string(),
+            secondary_managers: Vec::new(),
+            total_updates,
+            security_updates,
+            packages: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_summarize_checks() {
+        let reboot = RebootStatus {
+            required: true,
+            reasons: vec!["kernel".to_string()],
+        };
+        let summary = summarize_checks(vec![
+            (
+                Server::remote("web1", "web1"),
+                Ok((update_info(3, 1), reboot)),
+            ),
+            (
+                Server::remote("web2", "web2"),
+                Ok((update_info(2, 2), RebootStatus::default())),
+            ),
+            (
+                Server::remote("db1", "db1"),
+                Err(Error::RemoteExecutionError(
+                    "Connection refused".to_string(),
+                )),
+            ),
+        ]);
+
+        assert_eq!(summary.total_updates, 5);
+        assert_eq!(summary.found.len(), 2);
+        assert_eq!(summary.failed.len(), 1);
+        assert_eq!(summary.failed[0].0, "db1");
+
+        let result = &summary.result;
+        assert!(!result.success);
+        assert_eq!(
+            result.message,
+            "Updates check: 5 packages available (3 security) across 3 servers (1 failed)"
+        );
+        assert_eq!(metric(result, "web1::total_updates"), 3.0);
+        assert_eq!(metric(result, "web1::reboot_required"), 1.0);
+        assert_eq!(metric(result, "web2::security_updates"), 2.0);
+        assert_eq!(metric(result, "web2::reboot_required"), 0.0);
+        assert_eq!(metric(result, "total_updates"), 5.0);
+        assert_eq!(metric(result, "security_updates"), 3.0);
+        assert!(!result
+            .metrics
+            .as_ref()
+            .unwrap()
+            .contains_key("db1::total_updates"));
+
+        let data = result.data.as_ref().unwrap();
+        let servers: Vec<_> = data["servers"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|s| {
+                (
+                    s["server"].as_str().unwrap(),
+                    s["success"].as_bool().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(servers, [("web1", true), ("web2", true), ("db1", false)]);
+        assert_eq!(data["servers"][2]["error"], json!(summary.failed[0].1));
+    }
+
+    #[test]
+    fn test_summarize_apply() {
+        let updated = execution::ExecutionResult {
+            success: true,
+            summary: "Updated 4 packages".to_string(),
+            packages_updated: 4,
+            reboot: RebootStatus {
+                required: true,
+                reasons: Vec::new(),
+            },
+            ..Default::default()
+        };
+        let (outcomes, result) = summarize_apply(vec![
+            (Server::remote("web1", "web1"), Ok(updated)),
+            (
+                Server::remote("web2", "web2"),
+                Err(Error::RemoteExecutionError(
+                    "Connection refused".to_string(),
+                )),
+            ),
+        ]);
+
+        // A server that couldn't be reached counts as a failed run
+        assert_eq!(outcomes.len(), 2);
+        assert!(!outcomes[1].1.success);
+        assert!(!result.success);
+        assert_eq!(
+            result.message,
+            "Updates applied on 2 servers: 4 packages updated (1 failed), 1 need a reboot"
+        );
+        assert_eq!(metric(&result, "web1::packages_updated"), 4.0);
+        assert_eq!(metric(&result, "web1::success"), 1.0);
+        assert_eq!(metric(&result, "web2::success"), 0.0);
+        assert_eq!(metric(&result, "packages_updated"), 4.0);
+        assert_eq!(metric(&result, "reboot_required"), 1.0);
+    }
+
+    #[test]
+    fn test_summarize_cleanup() {
+        let cleaned = |bytes| cleanup::CleanupResult {
+            success: true,
+            summary: "Cleaned".to_string(),
+            space_freed_bytes: bytes,
+            errors: Vec::new(),
+        };
+        let (outcomes, result) = summarize_cleanup(vec![
+            (Server::remote("web1", "web1"), Ok(cleaned(1024 * 1024))),
+            (Server::remote("web2", "web2"), Ok(cleaned(3 * 1024 * 1024))),
+        ]);
+
+        assert_eq!(outcomes.len(), 2);
+        assert!(result.success);
+        assert_eq!(result.message, "OS cleanup on 2 servers");
+        assert_eq!(metric(&result, "web1::space_freed_mb"), 1.0);
+        assert_eq!(metric(&result, "web2::space_freed_mb"), 3.0);
+        assert_eq!(metric(&result, "space_freed_mb"), 4.0);
+        assert_eq!(result.data.unwrap()["space_freed_bytes"], 4 * 1024 * 1024);
+    }
+}
@@ -0,0 +1,118 @@
+//! Reboot-required detection
+//!
+//! A server needs a reboot when its package manager says so
+//! (`/var/run/reboot-required` on Debian/Ubuntu, `needs-restarting -r` on
+//! RHEL/Fedora) or when the running kernel is no longer the newest one
+//! installed, which covers distributions without a marker (Arch, Alpine,
+//! openSUSE).
+
+use serde::{Deserialize, Serialize};
+use svrctlrs_core::{RemoteExecutor, Result};
+use tracing::{info, warn};
+
+/// Prefix of the reason lines printed by [`REBOOT_CHECK_SCRIPT`]
+const REASON_PREFIX: &str = "REBOOT: ";
+
+/// Shell script printing one `REBOOT: <reason>` line per reason a reboot is needed
+///
+/// Needs no root and prints nothing when no reboot is needed.
+pub const REBOOT_CHECK_SCRIPT: &str = r#"if [ -f /var/run/reboot-required ]; then
+  pkgs=$(tr '\n' ' ' < /var/run/reboot-required.pkgs 2>/dev/null)
+  echo "REBOOT: /var/run/reboot-required is set${pkgs:+ by $pkgs}"
+fi
+if command -v needs-restarting >/dev/null 2>&1; then
+  needs-restarting -r >/dev/null 2>&1
+  [ $? -eq 1 ] && echo "REBOOT: needs-restarting reports updated core libraries"
+fi
+running=$(uname -r)
+latest=$(ls -1 /lib/modules 2>/dev/null | sort -V | tail -n 1)
+if [ -n "$latest" ] && [ ! -d "/lib/modules/$running" ]; then
+  echo "REBOOT: running kernel $running is no longer installed (newest is $latest)"
+elif [ -n "$latest" ] && [ "$latest" != "$running" ]; then
+  echo "REBOOT: kernel $latest is installed but $running is running"
+fi
+true"#;
+
+/// Whether a server needs a reboot, and why
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RebootStatus {
+    pub required: bool,
+    pub reasons: Vec<String>,
+}
+
+impl RebootStatus {
+    /// Run [`REBOOT_CHECK_SCRIPT`] on the executor's server
+    pub async fn detect(executor: &RemoteExecutor) -> Result<Self> {
+        let output = executor
+            .execute_command("sh", &["-c", REBOOT_CHECK_SCRIPT])
+            .await?;
+        Ok(Self::parse(&output))
+    }
+
+    /// Parse the output of [`REBOOT_CHECK_SCRIPT`]
+    pub fn parse(output: &str) -> Self {
+        let reasons: Vec<String> = output
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix(REASON_PREFIX))
+            .map(|reason| reason.trim().to_string())
+            .filter(|reason| !reason.is_empty())
+            .collect();
+
+        Self {
+            required: !reasons.is_empty(),
+            reasons,
+        }
+    }
+}
+
+/// Check whether a server needs a reboot; failing to tell is not an error
+pub(crate) async fn detect_reboot(executor: &RemoteExecutor) -> RebootStatus {
+    match RebootStatus::detect(executor).await {
+        Ok(status) => {
+            if status.required {
+                info!(reasons = ?status.reasons, "Reboot required");
+            }
+            status
+        }
+        Err(e) => {
+            warn!(error = %e, "Failed to check whether a reboot is required");
+            RebootStatus::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_no_reboot() {
+        assert_eq!(RebootStatus::parse(""), RebootStatus::default());
+        // Unrelated output (login banners, sudo warnings) is ignored
+        assert!(!RebootStatus::parse("Welcome to Ubuntu\n").required);
+    }
+
+    #[test]
+    fn test_parse_reasons() {
+        let output = "REBOOT: /var/run/reboot-required is set by linux-image-6.8.0-45-generic libc6 \n\
+                      REBOOT: kernel 6.8.0-45-generic is installed but 6.8.0-40-generic is running\n";
+        let status = RebootStatus::parse(output);
+        assert!(status.required);
+        assert_eq!(
+            status.reasons,
+            vec![
+                "/var/run/reboot-required is set by linux-image-6.8.0-45-generic libc6",
+                "kernel 6.8.0-45-generic is installed but 6.8.0-40-generic is running",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_detect_runs_locally() {
+        // Whatever the host's state, the script must run and parse cleanly
+        let status = RebootStatus::detect(&RemoteExecutor::new(None))
+            .await
+            .unwrap();
+        assert_eq!(status.required, !status.reasons.is_empty());
+    }
+}
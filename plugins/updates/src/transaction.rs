@@ -0,0 +1,290 @@
+//! Package manager transactions
+//!
+//! Applying updates records the transaction the package manager made so it
+//! can be rolled back later: the `dnf history` / `yum history` ID, or the
+//! `Start-Date` of the entry in apt's history log together with the
+//! versions it upgraded from. Other package managers keep no history that
+//! can be undone, so nothing is recorded for them.
+
+use crate::detection::PackageManager;
+use crate::execution::{APPLY_TIMEOUT_SECS, SUDO_PREFIX};
+use serde::{Deserialize, Serialize};
+use svrctlrs_core::{Error, RemoteExecutor, Result, Server};
+use tracing::{info, instrument, warn};
+
+/// Log apt appends an entry to for every transaction
+const APT_HISTORY_LOG: &str = "/var/log/apt/history.log";
+
+/// A package manager transaction made by applying updates
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transaction {
+    /// `apt`, `dnf` or `yum`
+    pub package_manager: String,
+    /// dnf/yum history ID, or the apt history entry's start date
+    pub transaction_id: String,
+    /// Upgraded packages; only known for apt, dnf and yum undo by ID
+    #[serde(default)]
+    pub packages: Vec<TransactionPackage>,
+}
+
+/// Package changed by a transaction
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransactionPackage {
+    pub name: String,
+    pub from_version: Option<String>,
+    pub to_version: Option<String>,
+}
+
+impl Transaction {
+    /// Shell script rolling the transaction back, run as root
+    ///
+    /// apt installs the previous version of every upgraded package, which
+    /// needs those versions to still be available from a repository or the
+    /// package cache; packages the transaction newly installed stay. Returns
+    /// `None` if there is nothing that can be rolled back.
+    pub fn rollback_script(&self) -> Option<String> {
+        match self.package_manager.as_str() {
+            pm @ ("dnf" | "yum") => {
+                if self.transaction_id.is_empty()
+                    || !self.transaction_id.chars().all(|c| c.is_ascii_digit())
+                {
+                    return None;
+                }
+                Some(format!(
+                    "$SUDO {} history undo -y {}",
+                    pm, self.transaction_id
+                ))
+            }
+            "apt" => {
+                let packages: Vec<String> = self
+                    .packages
+                    .iter()
+                    .filter_map(|p| {
+                        let version = p.from_version.as_ref()?;
+                        Some(shell_quote(&format!("{}={}", p.name, version)))
+                    })
+                    .collect();
+                if packages.is_empty() {
+                    return None;
+                }
+                Some(format!(
+                    "$SUDO env DEBIAN_FRONTEND=noninteractive apt-get install -y --allow-downgrades {}",
+                    packages.join(" ")
+                ))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Roll back a transaction on a server, locally or via SSH
+#[instrument(skip(server, ssh_key, transaction), fields(server = %server.name))]
+pub async fn rollback_transaction(
+    server: &Server,
+    ssh_key: Option<&str>,
+    transaction: &Transaction,
+) -> Result<String> {
+    let script = transaction.rollback_script().ok_or_else(|| {
+        Error::PluginError(format!(
+            "{} transaction {} cannot be rolled back",
+            transaction.package_manager, transaction.transaction_id
+        ))
+    })?;
+
+    info!(
+        package_manager = %transaction.package_manager,
+        transaction_id = %transaction.transaction_id,
+        "Rolling back transaction"
+    );
+    let executor = RemoteExecutor::for_server(server.clone(), ssh_key.map(|s| s.to_string()))
+        .with_timeout(APPLY_TIMEOUT_SECS);
+    executor
+        .execute_command_checked("sh", &["-c", &format!("{}{}", SUDO_PREFIX, script)])
+        .await
+}
+
+/// Name transactions of a package manager are recorded under, if it has any
+fn history_name(pm: PackageManager) -> Option<&'static str> {
+    match pm {
+        PackageManager::Apt => Some("apt"),
+        PackageManager::Dnf => Some("dnf"),
+        PackageManager::Yum => Some("yum"),
+        _ => None,
+    }
+}
+
+/// ID of the package manager's most recent transaction
+pub(crate) async fn latest_transaction_id(
+    executor: &RemoteExecutor,
+    pm: PackageManager,
+) -> Option<String> {
+    let name = history_name(pm)?;
+    let script = match pm {
+        PackageManager::Apt => format!("grep '^Start-Date:' {} | tail -n 1", APT_HISTORY_LOG),
+        _ => format!("$SUDO {} history list", name),
+    };
+
+    match executor
+        .execute_command("sh", &["-c", &format!("{}{}", SUDO_PREFIX, script)])
+        .await
+    {
+        Ok(output) if pm == PackageManager::Apt => output
+            .trim()
+            .strip_prefix("Start-Date:")
+            .map(|date| date.trim().to_string()),
+        Ok(output) => parse_history_list(&output),
+        Err(e) => {
+            warn!(error = %e, "Failed to read package manager history");
+            None
+        }
+    }
+}
+
+/// The transaction made since `before` was the latest one, if any
+pub(crate) async fn transaction_since(
+    executor: &RemoteExecutor,
+    pm: PackageManager,
+    before: Option<&str>,
+) -> Option<Transaction> {
+    let name = history_name(pm)?;
+    let transaction_id = latest_transaction_id(executor, pm).await?;
+    if before == Some(transaction_id.as_str()) {
+        return None;
+    }
+
+    let packages = if pm == PackageManager::Apt {
+        let script = format!(
+            "awk -v start={} '$0 == start {{ found = 1 }} found && /^$/ {{ exit }} found' {}",
+            shell_quote(&format!("Start-Date: {}", transaction_id)),
+            APT_HISTORY_LOG
+        );
+        match executor.execute_command("sh", &["-c", &script]).await {
+            Ok(entry) => parse_apt_entry(&entry),
+            Err(e) => {
+                warn!(error = %e, "Failed to read apt history entry");
+                Vec::new()
+            }
+        }
+    } else {
+        Vec::new()
+    };
+
+    Some(Transaction {
+        package_manager: name.to_string(),
+        transaction_id,
+        packages,
+    })
+}
+
+/// First transaction ID in `dnf history list` / `yum history list` output
+fn parse_history_list(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let id = line.split(['|', ' ']).find(|field| !field.is_empty())?;
+        id.chars()
+            .all(|c| c.is_ascii_digit())
+            .then(|| id.to_string())
+    })
+}
+
+/// Upgraded packages of an apt history entry
+///
+/// `Upgrade: libssl3:amd64 (3.0.2-0ubuntu1.17, 3.0.2-0ubuntu1.18), ...`
+fn parse_apt_entry(entry: &str) -> Vec<TransactionPackage> {
+    entry
+        .lines()
+        .filter_map(|line| line.strip_prefix("Upgrade: "))
+        .flat_map(|list| list.split("), "))
+        .filter_map(|item| {
+            let (name, versions) = item.split_once(" (")?;
+            let (from, to) = versions.trim_end_matches(')').split_once(", ")?;
+            Some(TransactionPackage {
+                name: name.trim().to_string(),
+                from_version: Some(from.trim().to_string()),
+                to_version: Some(to.trim().to_string()),
+            })
+        })
+        .collect()
+}
+
+/// Quote a value for use as a single shell word
+pub(crate) fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_history_list() {
+        let dnf4 = "ID     | Command line             | Date and time    | Action(s)      | Altered\n\
+                    -------------------------------------------------------------------------------\n\
+                    \x20   14 | upgrade -y               | 2025-01-05 03:00 | Upgrade        |   12\n\
+                    \x20   13 | install vim              | 2024-12-20 10:11 | Install        |    1\n";
+        assert_eq!(parse_history_list(dnf4), Some("14".to_string()));
+
+        let dnf5 = "ID Command line      Date and time       Action(s) Altered\n\
+                    \x207 dnf upgrade -y    2025-01-05 03:00:12                  9\n";
+        assert_eq!(parse_history_list(dnf5), Some("7".to_string()));
+
+        assert_eq!(parse_history_list("No transactions\n"), None);
+    }
+
+    #[test]
+    fn test_parse_apt_entry() {
+        let entry = "Start-Date: 2025-01-05  03:00:12\n\
+                     Commandline: apt-get full-upgrade -y\n\
+                     Install: linux-image-6.8.0-51-generic:amd64 (6.8.0-51.52, automatic)\n\
+                     Upgrade: libssl3:amd64 (3.0.2-0ubuntu1.17, 3.0.2-0ubuntu1.18), openssl:amd64 (3.0.2-0ubuntu1.17, 3.0.2-0ubuntu1.18)\n\
+                     End-Date: 2025-01-05  03:00:40\n";
+        let packages = parse_apt_entry(entry);
+        assert_eq!(packages.len(), 2);
+        assert_eq!(packages[1].name, "openssl:amd64");
+        assert_eq!(
+            packages[1].from_version.as_deref(),
+            Some("3.0.2-0ubuntu1.17")
+        );
+        assert_eq!(packages[1].to_version.as_deref(), Some("3.0.2-0ubuntu1.18"));
+    }
+
+    #[test]
+    fn test_rollback_script() {
+        let dnf = Transaction {
+            package_manager: "dnf".to_string(),
+            transaction_id: "14".to_string(),
+            packages: Vec::new(),
+        };
+        assert_eq!(
+            dnf.rollback_script().as_deref(),
+            Some("$SUDO dnf history undo -y 14")
+        );
+
+        // IDs end up in a shell command
+        let injected = Transaction {
+            transaction_id: "14; reboot".to_string(),
+            ..dnf
+        };
+        assert_eq!(injected.rollback_script(), None);
+
+        let apt = Transaction {
+            package_manager: "apt".to_string(),
+            transaction_id: "2025-01-05  03:00:12".to_string(),
+            packages: parse_apt_entry(
+                "Upgrade: openssl:amd64 (3.0.2-0ubuntu1.17, 3.0.2-0ubuntu1.18)\n",
+            ),
+        };
+        assert_eq!(
+            apt.rollback_script().as_deref(),
+            Some(
+                "$SUDO env DEBIAN_FRONTEND=noninteractive apt-get install -y --allow-downgrades \
+                 'openssl:amd64=3.0.2-0ubuntu1.17'"
+            )
+        );
+
+        let empty_apt = Transaction {
+            packages: Vec::new(),
+            ..apt
+        };
+        assert_eq!(empty_apt.rollback_script(), None);
+    }
+}
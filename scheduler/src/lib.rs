@@ -1,27 +1,195 @@
 //! Task scheduler
+//!
+//! The scheduler sleeps until the earliest upcoming fire time rather than
+//! polling, so every cron occurrence (down to the second) fires exactly once.
+//! Occurrences that are reached too late - after downtime, or when the
+//! runtime stalled - are treated as missed and handled by the task's
+//! [`CatchUpPolicy`].
+//!
+//! At most one run of a task is active at a time; an occurrence that comes
+//! due while the previous run is still going is handled by the task's
+//! [`OverlapPolicy`]. A global limit caps concurrent runs across all tasks.
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use cron::Schedule;
+use std::future::Future;
+use std::pin::Pin;
 use std::str::FromStr;
-use std::sync::Arc;
-use tokio::sync::RwLock;
-use tracing::{debug, error, info};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{Mutex as AsyncMutex, Notify, RwLock, Semaphore};
+use tokio::task::AbortHandle;
+use tracing::{debug, error, info, warn};
 
 use svrctlrs_core::{Error, Result};
 
 /// Async task handler type
-pub type AsyncTaskHandler = Arc<dyn Fn() -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<()>> + Send>> + Send + Sync>;
+pub type AsyncTaskHandler =
+    Arc<dyn Fn() -> Pin<Box<dyn Future<Output = Result<()>> + Send>> + Send + Sync>;
+
+/// Async handler notified of scheduler events (e.g. to persist `next_run_at`)
+pub type SchedulerEventHandler =
+    Arc<dyn Fn(SchedulerEvent) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
+/// How late an occurrence may be picked up and still count as on time
+const MISFIRE_GRACE_SECS: i64 = 60;
+
+/// Maximum number of catch-up runs fired for one task at once
+const MAX_CATCH_UP_RUNS: usize = 100;
+
+/// Maximum number of past occurrences enumerated when counting missed runs
+const MAX_MISSED_SCAN: usize = 10_000;
+
+/// Longest single sleep, so wall-clock jumps are noticed within a minute
+const MAX_SLEEP_SECS: u64 = 60;
+
+/// Maximum number of runs queued behind an active run of the same task
+const MAX_QUEUED_RUNS: usize = 10;
+
+/// What to do with occurrences that were missed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CatchUpPolicy {
+    /// Report missed runs but do not execute them
+    #[default]
+    Skip,
+    /// Run once to cover all missed occurrences
+    Once,
+    /// Run once per missed occurrence (capped)
+    All,
+}
+
+impl CatchUpPolicy {
+    /// Parse a policy name (`skip`, `once`, `all`)
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "skip" => Some(Self::Skip),
+            "once" => Some(Self::Once),
+            "all" => Some(Self::All),
+            _ => None,
+        }
+    }
+
+    /// Policy name as stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Skip => "skip",
+            Self::Once => "once",
+            Self::All => "all",
+        }
+    }
+}
+
+/// What to do when a task comes due while its previous run is still active
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverlapPolicy {
+    /// Drop the new run
+    #[default]
+    Skip,
+    /// Start the new run once the previous one finishes
+    Queue,
+    /// Abort the previous run and start the new one
+    KillPrevious,
+}
+
+impl OverlapPolicy {
+    /// Parse a policy name (`skip`, `queue`, `kill`)
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "skip" => Some(Self::Skip),
+            "queue" => Some(Self::Queue),
+            "kill" | "kill_previous" => Some(Self::KillPrevious),
+            _ => None,
+        }
+    }
+
+    /// Policy name as stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Skip => "skip",
+            Self::Queue => "queue",
+            Self::KillPrevious => "kill",
+        }
+    }
+}
+
+/// Per-task scheduling options
+#[derive(Debug, Clone, Default)]
+pub struct TaskOptions {
+    /// Handling of missed occurrences
+    pub catch_up: CatchUpPolicy,
+    /// Occurrence the task was last known to be waiting for (e.g. a persisted
+    /// `next_run_at`). If it is in the past, runs since then count as missed.
+    pub resume_from: Option<DateTime<Utc>>,
+    /// Register the task without scheduling it until resumed
+    pub paused: bool,
+    /// Handling of runs that come due while the previous run is active
+    pub overlap: OverlapPolicy,
+}
+
+/// Events reported by the running scheduler
+#[derive(Debug, Clone)]
+pub enum SchedulerEvent {
+    /// The next fire time of a task changed (`None` = no further occurrences)
+    NextRun {
+        task_id: String,
+        next_run: Option<DateTime<Utc>>,
+    },
+    /// Occurrences were reached too late to count as on time
+    Missed {
+        task_id: String,
+        /// Number of missed occurrences (capped at an internal scan limit)
+        missed: usize,
+        first: DateTime<Utc>,
+        last: DateTime<Utc>,
+        policy: CatchUpPolicy,
+        /// Catch-up runs fired for them
+        catch_up_runs: usize,
+    },
+}
 
 /// Scheduled task
 pub struct Task {
     pub id: String,
     pub schedule: Schedule,
     pub handler: AsyncTaskHandler,
+    pub catch_up: CatchUpPolicy,
+    /// Next fire time (`None` while paused or when the schedule is exhausted)
+    pub next_run: Option<DateTime<Utc>>,
+    pub paused: bool,
+    pub overlap: OverlapPolicy,
+    runs: Arc<TaskRuns>,
+}
+
+/// Run bookkeeping shared by all runs of one task
+///
+/// Kept across [`Scheduler::replace_task`] so overlap handling still applies
+/// to a run started before the task was edited.
+#[derive(Default)]
+struct TaskRuns {
+    /// Held by the active run
+    active: Arc<AsyncMutex<()>>,
+    /// Abort handle of the most recently started run
+    current: Mutex<Option<AbortHandle>>,
+    /// Runs waiting for the active one to finish
+    queued: AtomicUsize,
+}
+
+/// Releases a queue slot when a queued run starts (or is aborted)
+struct QueueSlot(Arc<TaskRuns>);
+
+impl Drop for QueueSlot {
+    fn drop(&mut self) {
+        self.0.queued.fetch_sub(1, Ordering::SeqCst);
+    }
 }
 
 /// Task scheduler
 pub struct Scheduler {
     tasks: Arc<RwLock<Vec<Task>>>,
+    wake: Arc<Notify>,
+    event_handler: Option<SchedulerEventHandler>,
+    /// Caps concurrent runs across all tasks (`None` = unlimited)
+    limit: Option<Arc<Semaphore>>,
 }
 
 impl Scheduler {
@@ -29,76 +197,275 @@ impl Scheduler {
     pub fn new() -> Self {
         Self {
             tasks: Arc::new(RwLock::new(Vec::new())),
+            wake: Arc::new(Notify::new()),
+            event_handler: None,
+            limit: None,
         }
     }
 
+    /// Limit the number of task runs executing at once (0 = unlimited)
+    ///
+    /// Runs beyond the limit wait for a free slot rather than being dropped.
+    pub fn with_max_concurrent(mut self, max: usize) -> Self {
+        self.limit = (max > 0).then(|| Arc::new(Semaphore::new(max)));
+        self
+    }
+
+    /// Set the handler notified of scheduler events
+    pub fn with_event_handler(mut self, handler: SchedulerEventHandler) -> Self {
+        self.event_handler = Some(handler);
+        self
+    }
+
     /// Add a task to the scheduler
     pub async fn add_task(
         &self,
         id: impl Into<String>,
         cron_expr: &str,
         handler: AsyncTaskHandler,
-    ) -> Result<()>
-    {
-        let schedule = Schedule::from_str(cron_expr)
-            .map_err(|e| Error::SchedulerError(format!("Invalid cron expression: {}", e)))?;
-
-        let task_id = id.into();
-        let task = Task {
-            id: task_id.clone(),
-            schedule,
-            handler,
-        };
+    ) -> Result<()> {
+        self.add_task_with_options(id, cron_expr, handler, TaskOptions::default())
+            .await
+    }
+
+    /// Add a task to the scheduler with explicit options
+    pub async fn add_task_with_options(
+        &self,
+        id: impl Into<String>,
+        cron_expr: &str,
+        handler: AsyncTaskHandler,
+        options: TaskOptions,
+    ) -> Result<()> {
+        let task = build_task(id.into(), cron_expr, handler, options)?;
+        let (task_id, next_run) = (task.id.clone(), task.next_run);
 
         let mut tasks = self.tasks.write().await;
+        if tasks.iter().any(|t| t.id == task_id) {
+            return Err(Error::SchedulerError(format!(
+                "Task '{}' is already scheduled",
+                task_id
+            )));
+        }
         tasks.push(task);
+        drop(tasks);
+
+        info!(id = %task_id, schedule = %cron_expr, next_run = ?next_run, "Scheduled task added");
+
+        self.emit(SchedulerEvent::NextRun { task_id, next_run })
+            .await;
+        self.wake.notify_one();
 
-        info!(id = %task_id, schedule = %cron_expr, "Scheduled task added");
+        Ok(())
+    }
+
+    /// Replace a task's schedule, handler and options (adds it if missing)
+    ///
+    /// The next run is recomputed from the new schedule.
+    pub async fn replace_task(
+        &self,
+        id: impl Into<String>,
+        cron_expr: &str,
+        handler: AsyncTaskHandler,
+        options: TaskOptions,
+    ) -> Result<()> {
+        let task = build_task(id.into(), cron_expr, handler, options)?;
+        let (task_id, next_run) = (task.id.clone(), task.next_run);
+
+        let mut tasks = self.tasks.write().await;
+        match tasks.iter_mut().find(|t| t.id == task_id) {
+            Some(existing) => {
+                let runs = existing.runs.clone();
+                *existing = Task { runs, ..task };
+            }
+            None => tasks.push(task),
+        }
+        drop(tasks);
+
+        info!(id = %task_id, schedule = %cron_expr, next_run = ?next_run, "Scheduled task replaced");
+
+        self.emit(SchedulerEvent::NextRun { task_id, next_run })
+            .await;
+        self.wake.notify_one();
+
+        Ok(())
+    }
+
+    /// Remove a task from the scheduler
+    ///
+    /// Returns `false` if no task with that ID was scheduled. Runs already in
+    /// progress are not affected.
+    pub async fn remove_task(&self, id: &str) -> bool {
+        let mut tasks = self.tasks.write().await;
+        let before = tasks.len();
+        tasks.retain(|t| t.id != id);
+        let removed = tasks.len() != before;
+        drop(tasks);
+
+        if removed {
+            info!(id = %id, "Scheduled task removed");
+            self.emit(SchedulerEvent::NextRun {
+                task_id: id.to_string(),
+                next_run: None,
+            })
+            .await;
+            self.wake.notify_one();
+        }
+
+        removed
+    }
+
+    /// Stop firing a task until it is resumed
+    pub async fn pause_task(&self, id: &str) -> Result<()> {
+        self.set_paused(id, true).await
+    }
+
+    /// Resume a paused task from its next upcoming occurrence
+    ///
+    /// Occurrences that fell inside the pause are not treated as missed.
+    pub async fn resume_task(&self, id: &str) -> Result<()> {
+        self.set_paused(id, false).await
+    }
+
+    async fn set_paused(&self, id: &str, paused: bool) -> Result<()> {
+        let mut tasks = self.tasks.write().await;
+        let task = tasks
+            .iter_mut()
+            .find(|t| t.id == id)
+            .ok_or_else(|| Error::SchedulerError(format!("Task '{}' is not scheduled", id)))?;
+
+        if task.paused == paused {
+            return Ok(());
+        }
+
+        task.paused = paused;
+        task.next_run = if paused {
+            None
+        } else {
+            task.schedule.after(&Utc::now()).next()
+        };
+        let next_run = task.next_run;
+        drop(tasks);
+
+        info!(id = %id, paused, next_run = ?next_run, "Scheduled task {}", if paused { "paused" } else { "resumed" });
+
+        self.emit(SchedulerEvent::NextRun {
+            task_id: id.to_string(),
+            next_run,
+        })
+        .await;
+        self.wake.notify_one();
 
         Ok(())
     }
 
+    /// Whether a task is scheduled (paused or not)
+    pub async fn contains(&self, id: &str) -> bool {
+        self.tasks.read().await.iter().any(|t| t.id == id)
+    }
+
+    /// Whether a task is paused (`None` if it is not scheduled)
+    pub async fn is_paused(&self, id: &str) -> Option<bool> {
+        let tasks = self.tasks.read().await;
+        tasks.iter().find(|t| t.id == id).map(|t| t.paused)
+    }
+
+    /// Next fire time of a task
+    pub async fn next_run(&self, id: &str) -> Option<DateTime<Utc>> {
+        let tasks = self.tasks.read().await;
+        tasks.iter().find(|t| t.id == id).and_then(|t| t.next_run)
+    }
+
+    async fn emit(&self, event: SchedulerEvent) {
+        if let Some(handler) = &self.event_handler {
+            handler(event).await;
+        }
+    }
+
     /// Start the scheduler
     pub async fn start(&self) -> Result<()> {
         info!("Starting scheduler");
 
         let tasks = self.tasks.clone();
+        let wake = self.wake.clone();
+        let event_handler = self.event_handler.clone();
+        let limit = self.limit.clone();
 
         tokio::spawn(async move {
             loop {
                 let now = Utc::now();
+                let mut due = Vec::new();
+                let mut events = Vec::new();
+
+                let mut tasks_write = tasks.write().await;
+                for task in tasks_write.iter_mut() {
+                    let Some(next) = task.next_run else { continue };
+                    if next > now {
+                        continue;
+                    }
+
+                    let plan = plan_due(&task.schedule, next, now, task.catch_up);
+
+                    if let (Some(first), Some(last)) = (plan.missed.first(), plan.missed.last()) {
+                        warn!(
+                            task_id = %task.id,
+                            missed = plan.missed.len(),
+                            first = %first,
+                            policy = task.catch_up.as_str(),
+                            catch_up_runs = plan.catch_up_runs,
+                            "Missed scheduled runs"
+                        );
+                        events.push(SchedulerEvent::Missed {
+                            task_id: task.id.clone(),
+                            missed: plan.missed.len(),
+                            first: *first,
+                            last: *last,
+                            policy: task.catch_up,
+                            catch_up_runs: plan.catch_up_runs,
+                        });
+                    }
 
-                let tasks_read = tasks.read().await;
-                for task in tasks_read.iter() {
-                    // Check if task should run now
-                    if let Some(next) = task.schedule.upcoming(Utc).next() {
-                        let time_until = (next - now).num_seconds();
-
-                        // Run if within 1 minute window
-                        if time_until <= 60 && time_until >= 0 {
-                            debug!(task_id = %task.id, "Executing scheduled task");
-
-                            let handler = task.handler.clone();
-                            let task_id = task.id.clone();
-                            
-                            // Spawn task execution in background
-                            tokio::spawn(async move {
-                                match handler().await {
-                                    Ok(()) => {
-                                        info!(task_id = %task_id, "Task completed successfully");
-                                    }
-                                    Err(e) => {
-                                        error!(task_id = %task_id, error = %e, "Task execution failed");
-                                    }
-                                }
-                            });
-                        }
+                    for _ in 0..plan.on_time + plan.catch_up_runs {
+                        due.push((
+                            task.id.clone(),
+                            task.handler.clone(),
+                            task.overlap,
+                            task.runs.clone(),
+                        ));
+                    }
+
+                    task.next_run = plan.next_run;
+                    events.push(SchedulerEvent::NextRun {
+                        task_id: task.id.clone(),
+                        next_run: plan.next_run,
+                    });
+                }
+                let earliest = tasks_write.iter().filter_map(|t| t.next_run).min();
+                drop(tasks_write);
+
+                for (task_id, handler, overlap, runs) in due {
+                    dispatch(task_id, handler, overlap, runs, limit.clone());
+                }
+
+                if let Some(handler) = &event_handler {
+                    for event in events {
+                        handler(event).await;
                     }
                 }
-                drop(tasks_read);
 
-                // Check every minute
-                tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
+                // Sleep until the earliest occurrence, or until tasks change
+                let max_sleep = std::time::Duration::from_secs(MAX_SLEEP_SECS);
+                let wait = match earliest {
+                    Some(t) => (t - Utc::now())
+                        .to_std()
+                        .unwrap_or(std::time::Duration::ZERO)
+                        .min(max_sleep),
+                    None => max_sleep,
+                };
+
+                tokio::select! {
+                    _ = tokio::time::sleep(wait) => {}
+                    _ = wake.notified() => {}
+                }
             }
         });
 
@@ -111,3 +478,475 @@ impl Default for Scheduler {
         Self::new()
     }
 }
+
+/// Start one run of a task, applying its overlap policy and the global limit
+fn dispatch(
+    task_id: String,
+    handler: AsyncTaskHandler,
+    overlap: OverlapPolicy,
+    runs: Arc<TaskRuns>,
+    limit: Option<Arc<Semaphore>>,
+) {
+    let mut active = None;
+    let mut queue_slot = None;
+
+    match overlap {
+        OverlapPolicy::Skip => match runs.active.clone().try_lock_owned() {
+            Ok(guard) => active = Some(guard),
+            Err(_) => {
+                warn!(task_id = %task_id, "Previous run still active, skipping this run");
+                return;
+            }
+        },
+        OverlapPolicy::Queue => {
+            if runs.queued.fetch_add(1, Ordering::SeqCst) >= MAX_QUEUED_RUNS {
+                runs.queued.fetch_sub(1, Ordering::SeqCst);
+                warn!(task_id = %task_id, "Run queue is full, skipping this run");
+                return;
+            }
+            queue_slot = Some(QueueSlot(runs.clone()));
+        }
+        OverlapPolicy::KillPrevious => {
+            let previous = runs
+                .current
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .take();
+            if let Some(previous) = previous.filter(|h| !h.is_finished()) {
+                warn!(task_id = %task_id, "Previous run still active, aborting it");
+                previous.abort();
+            }
+        }
+    }
+
+    let task_runs = runs.clone();
+    let run = tokio::spawn(async move {
+        // Wait for the previous run to finish (or to be dropped after an abort)
+        let _active = match active {
+            Some(guard) => guard,
+            None => task_runs.active.clone().lock_owned().await,
+        };
+        drop(queue_slot);
+
+        let _permit = match &limit {
+            Some(limit) => limit.clone().acquire_owned().await.ok(),
+            None => None,
+        };
+
+        debug!(task_id = %task_id, "Executing scheduled task");
+        match handler().await {
+            Ok(()) => {
+                info!(task_id = %task_id, "Task completed successfully");
+            }
+            Err(e) => {
+                error!(task_id = %task_id, error = %e, "Task execution failed");
+            }
+        }
+    });
+
+    *runs.current.lock().unwrap_or_else(|e| e.into_inner()) = Some(run.abort_handle());
+}
+
+fn build_task(
+    id: String,
+    cron_expr: &str,
+    handler: AsyncTaskHandler,
+    options: TaskOptions,
+) -> Result<Task> {
+    let schedule = Schedule::from_str(cron_expr)
+        .map_err(|e| Error::SchedulerError(format!("Invalid cron expression: {}", e)))?;
+
+    let now = Utc::now();
+    let next_run = match options.resume_from {
+        _ if options.paused => None,
+        // Resume from a past occurrence so missed runs are detected
+        Some(resume) if resume <= now => Some(resume),
+        _ => schedule.after(&now).next(),
+    };
+
+    Ok(Task {
+        id,
+        schedule,
+        handler,
+        catch_up: options.catch_up,
+        next_run,
+        paused: options.paused,
+        overlap: options.overlap,
+        runs: Arc::new(TaskRuns::default()),
+    })
+}
+
+/// What to do for a task whose next occurrence has passed
+#[derive(Debug, PartialEq)]
+struct DuePlan {
+    /// 1 if the latest due occurrence was picked up within the grace period
+    on_time: usize,
+    /// Occurrences picked up too late
+    missed: Vec<DateTime<Utc>>,
+    /// Extra runs fired for the missed occurrences
+    catch_up_runs: usize,
+    /// First occurrence after `now`
+    next_run: Option<DateTime<Utc>>,
+}
+
+fn plan_due(
+    schedule: &Schedule,
+    next: DateTime<Utc>,
+    now: DateTime<Utc>,
+    policy: CatchUpPolicy,
+) -> DuePlan {
+    let mut missed: Vec<DateTime<Utc>> = std::iter::once(next)
+        .chain(schedule.after(&next))
+        .take_while(|t| *t <= now)
+        .take(MAX_MISSED_SCAN)
+        .collect();
+
+    // Only the latest due occurrence can be on time; anything before it was
+    // skipped over and is handled by the catch-up policy
+    let on_time = match missed.last() {
+        Some(latest) if (now - *latest).num_seconds() <= MISFIRE_GRACE_SECS => {
+            missed.pop();
+            1
+        }
+        _ => 0,
+    };
+
+    let catch_up_runs = match policy {
+        _ if missed.is_empty() => 0,
+        CatchUpPolicy::Skip => 0,
+        // An on-time run already covers the missed ones
+        CatchUpPolicy::Once if on_time > 0 => 0,
+        CatchUpPolicy::Once => 1,
+        CatchUpPolicy::All => missed.len().min(MAX_CATCH_UP_RUNS),
+    };
+
+    DuePlan {
+        on_time,
+        missed,
+        catch_up_runs,
+        next_run: schedule.after(&now).next(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn every_minute() -> Schedule {
+        Schedule::from_str("0 * * * * *").unwrap()
+    }
+
+    #[test]
+    fn test_plan_on_time() {
+        let next = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let now = next + Duration::milliseconds(5);
+
+        let plan = plan_due(&every_minute(), next, now, CatchUpPolicy::Skip);
+        assert_eq!(plan.on_time, 1);
+        assert!(plan.missed.is_empty());
+        assert_eq!(plan.next_run, Some(next + Duration::minutes(1)));
+    }
+
+    #[test]
+    fn test_plan_late_loop_runs_latest_once() {
+        let schedule = Schedule::from_str("* * * * * *").unwrap();
+        let next = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let now = next + Duration::milliseconds(2500);
+
+        let plan = plan_due(&schedule, next, now, CatchUpPolicy::Skip);
+        assert_eq!(plan.on_time, 1);
+        assert_eq!(plan.missed.len(), 2);
+        assert_eq!(plan.next_run, Some(next + Duration::seconds(3)));
+    }
+
+    #[test]
+    fn test_plan_missed_policies() {
+        let next = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        // Down for 10 minutes, back 30s after the 12:10 occurrence
+        let now = next + Duration::minutes(10) + Duration::seconds(30);
+
+        let skip = plan_due(&every_minute(), next, now, CatchUpPolicy::Skip);
+        assert_eq!(skip.missed.len(), 10);
+        assert_eq!(skip.on_time, 1);
+        assert_eq!(skip.catch_up_runs, 0);
+
+        let once = plan_due(&every_minute(), next, now, CatchUpPolicy::Once);
+        assert_eq!(once.catch_up_runs, 0);
+
+        let all = plan_due(&every_minute(), next, now, CatchUpPolicy::All);
+        assert_eq!(all.catch_up_runs, 10);
+
+        // Nothing on time: "once" has to run by itself
+        let hourly = Schedule::from_str("0 0 * * * *").unwrap();
+        let later = next + Duration::hours(3) + Duration::minutes(30);
+        let once = plan_due(&hourly, next, later, CatchUpPolicy::Once);
+        assert_eq!(once.on_time, 0);
+        assert_eq!(once.missed.len(), 4);
+        assert_eq!(once.catch_up_runs, 1);
+    }
+
+    #[test]
+    fn test_catch_up_policy_parse() {
+        assert_eq!(CatchUpPolicy::parse("ALL"), Some(CatchUpPolicy::All));
+        assert_eq!(CatchUpPolicy::parse("once").unwrap().as_str(), "once");
+        assert!(CatchUpPolicy::parse("never").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_fires_once_per_occurrence() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let counter = count.clone();
+        let handler: AsyncTaskHandler = Arc::new(move || {
+            let counter = counter.clone();
+            Box::pin(async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            })
+        });
+
+        let scheduler = Scheduler::new();
+        scheduler
+            .add_task("every_second", "* * * * * *", handler)
+            .await
+            .unwrap();
+        scheduler.start().await.unwrap();
+
+        tokio::time::sleep(std::time::Duration::from_millis(3500)).await;
+        let fired = count.load(Ordering::SeqCst);
+        assert!((3..=4).contains(&fired), "fired {} times", fired);
+    }
+
+    /// Handler that sleeps, tracking started / finished / peak concurrent runs
+    fn slow_handler(
+        millis: u64,
+        started: Arc<AtomicUsize>,
+        finished: Arc<AtomicUsize>,
+        running: Arc<AtomicUsize>,
+        peak: Arc<AtomicUsize>,
+    ) -> AsyncTaskHandler {
+        Arc::new(move || {
+            let (started, finished) = (started.clone(), finished.clone());
+            let (running, peak) = (running.clone(), peak.clone());
+            Box::pin(async move {
+                started.fetch_add(1, Ordering::SeqCst);
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(std::time::Duration::from_millis(millis)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                finished.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            })
+        })
+    }
+
+    fn counters() -> [Arc<AtomicUsize>; 4] {
+        std::array::from_fn(|_| Arc::new(AtomicUsize::new(0)))
+    }
+
+    #[tokio::test]
+    async fn test_overlap_policies() {
+        let wait = || tokio::time::sleep(std::time::Duration::from_millis(20));
+
+        // Skip: the second run is dropped while the first is active
+        let [started, finished, running, peak] = counters();
+        let handler = slow_handler(200, started.clone(), finished.clone(), running, peak);
+        let runs = Arc::new(TaskRuns::default());
+        dispatch(
+            "t".into(),
+            handler.clone(),
+            OverlapPolicy::Skip,
+            runs.clone(),
+            None,
+        );
+        wait().await;
+        dispatch("t".into(), handler, OverlapPolicy::Skip, runs, None);
+        tokio::time::sleep(std::time::Duration::from_millis(400)).await;
+        assert_eq!(started.load(Ordering::SeqCst), 1);
+        assert_eq!(finished.load(Ordering::SeqCst), 1);
+
+        // Queue: runs execute one after another
+        let [started, finished, running, peak] = counters();
+        let handler = slow_handler(
+            100,
+            started.clone(),
+            finished.clone(),
+            running,
+            peak.clone(),
+        );
+        let runs = Arc::new(TaskRuns::default());
+        for _ in 0..3 {
+            dispatch(
+                "t".into(),
+                handler.clone(),
+                OverlapPolicy::Queue,
+                runs.clone(),
+                None,
+            );
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        assert_eq!(finished.load(Ordering::SeqCst), 3);
+        assert_eq!(peak.load(Ordering::SeqCst), 1);
+        assert_eq!(runs.queued.load(Ordering::SeqCst), 0);
+
+        // Kill previous: the first run is aborted and never finishes
+        let [started, finished, running, peak] = counters();
+        let handler = slow_handler(200, started.clone(), finished.clone(), running, peak);
+        let runs = Arc::new(TaskRuns::default());
+        dispatch(
+            "t".into(),
+            handler.clone(),
+            OverlapPolicy::KillPrevious,
+            runs.clone(),
+            None,
+        );
+        wait().await;
+        dispatch("t".into(), handler, OverlapPolicy::KillPrevious, runs, None);
+        tokio::time::sleep(std::time::Duration::from_millis(400)).await;
+        assert_eq!(started.load(Ordering::SeqCst), 2);
+        assert_eq!(finished.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_global_concurrency_limit() {
+        let [started, finished, running, peak] = counters();
+        let handler = slow_handler(100, started, finished.clone(), running, peak.clone());
+        let limit = Some(Arc::new(Semaphore::new(2)));
+
+        for i in 0..5 {
+            let runs = Arc::new(TaskRuns::default());
+            dispatch(
+                format!("t{}", i),
+                handler.clone(),
+                OverlapPolicy::Skip,
+                runs,
+                limit.clone(),
+            );
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(450)).await;
+        assert_eq!(finished.load(Ordering::SeqCst), 5);
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_overlap_policy_parse() {
+        for policy in [
+            OverlapPolicy::Skip,
+            OverlapPolicy::Queue,
+            OverlapPolicy::KillPrevious,
+        ] {
+            assert_eq!(OverlapPolicy::parse(policy.as_str()), Some(policy));
+        }
+        assert_eq!(
+            OverlapPolicy::parse("kill_previous"),
+            Some(OverlapPolicy::KillPrevious)
+        );
+        assert_eq!(OverlapPolicy::parse("parallel"), None);
+    }
+
+    #[tokio::test]
+    async fn test_replace_pause_resume_remove() {
+        let handler: AsyncTaskHandler = Arc::new(|| Box::pin(async { Ok(()) }));
+        let scheduler = Scheduler::new();
+
+        scheduler
+            .add_task("job", "0 0 * * * *", handler.clone())
+            .await
+            .unwrap();
+        assert!(scheduler
+            .add_task("job", "0 0 * * * *", handler.clone())
+            .await
+            .is_err());
+        let hourly = scheduler.next_run("job").await.unwrap();
+
+        scheduler
+            .replace_task(
+                "job",
+                "* * * * * *",
+                handler.clone(),
+                TaskOptions::default(),
+            )
+            .await
+            .unwrap();
+        let every_second = scheduler.next_run("job").await.unwrap();
+        assert!(every_second <= hourly);
+        assert!(every_second <= Utc::now() + Duration::seconds(1));
+
+        scheduler.pause_task("job").await.unwrap();
+        assert_eq!(scheduler.is_paused("job").await, Some(true));
+        assert!(scheduler.next_run("job").await.is_none());
+
+        scheduler.resume_task("job").await.unwrap();
+        assert_eq!(scheduler.is_paused("job").await, Some(false));
+        assert!(scheduler.next_run("job").await.is_some());
+
+        assert!(scheduler.remove_task("job").await);
+        assert!(!scheduler.remove_task("job").await);
+        assert!(scheduler.pause_task("job").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_paused_task_does_not_fire() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let counter = count.clone();
+        let handler: AsyncTaskHandler = Arc::new(move || {
+            let counter = counter.clone();
+            Box::pin(async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            })
+        });
+
+        let scheduler = Scheduler::new();
+        scheduler
+            .add_task_with_options(
+                "paused",
+                "* * * * * *",
+                handler,
+                TaskOptions {
+                    paused: true,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        scheduler.start().await.unwrap();
+
+        tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
+        assert_eq!(count.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_resume_from_past_reports_missed() {
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let event_handler: SchedulerEventHandler = Arc::new(move |event| {
+            sink.lock().unwrap().push(event);
+            Box::pin(async {})
+        });
+        let handler: AsyncTaskHandler = Arc::new(|| Box::pin(async { Ok(()) }));
+
+        let scheduler = Scheduler::new().with_event_handler(event_handler);
+        scheduler
+            .add_task_with_options(
+                "hourly",
+                "0 0 * * * *",
+                handler,
+                TaskOptions {
+                    catch_up: CatchUpPolicy::Skip,
+                    resume_from: Some(Utc::now() - Duration::hours(3)),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        scheduler.start().await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+
+        assert!(events.lock().unwrap().iter().any(|e| matches!(
+            e,
+            SchedulerEvent::Missed { missed, catch_up_runs: 0, .. } if *missed >= 3
+        )));
+        assert!(scheduler.next_run("hourly").await.unwrap() > Utc::now());
+    }
+}
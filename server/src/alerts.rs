@@ -0,0 +1,242 @@
+//! Alert rule evaluation
+//!
+//! Rules are evaluated against every batch of metrics a plugin reports.
+//! Each (rule, server, metric) combination keeps its own state, so a rule
+//! matching several servers or disks fires and resolves independently.
+//! Notifications are only sent on transitions (firing / resolved), which
+//! keeps a condition that persists across runs from re-alerting.
+
+use chrono::Utc;
+use std::collections::HashMap;
+use tracing::{debug, error, info, warn};
+
+use svrctlrs_core::NotificationMessage;
+use svrctlrs_database::{
+    models::{
+        AlertOperator, AlertRule, AlertStateUpdate, AlertTransition, NewAlertEvent, NewMetric,
+        Server as DbServer, ALERT_SEVERITIES,
+    },
+    queries, Database,
+};
+
+use crate::state::AppState;
+
+/// Notification service name used for alert routing (e.g. `ALERTS_GOTIFY_KEY`)
+pub const ALERTS_SERVICE: &str = "alerts";
+
+/// Server name used for samples not tied to any server
+const LOCAL_SERVER_NAME: &str = "local";
+
+/// Validate the user-supplied fields of an alert rule
+pub fn validate_rule(
+    metric_name: &str,
+    operator: &str,
+    threshold: f64,
+    duration_secs: i64,
+    severity: &str,
+) -> Result<(), String> {
+    if metric_name.trim().is_empty() {
+        return Err("Metric name is required".to_string());
+    }
+    if AlertOperator::parse(operator).is_none() {
+        return Err(format!(
+            "Invalid operator '{}' (expected >, >=, <, <=, == or !=)",
+            operator
+        ));
+    }
+    if !threshold.is_finite() {
+        return Err("Threshold must be a finite number".to_string());
+    }
+    if duration_secs < 0 {
+        return Err("Duration cannot be negative".to_string());
+    }
+    if !ALERT_SEVERITIES.contains(&severity) {
+        return Err(format!(
+            "Invalid severity '{}' (expected {})",
+            severity,
+            ALERT_SEVERITIES.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Evaluate enabled alert rules against freshly stored metric samples
+pub async fn evaluate_samples(
+    state: &AppState,
+    db: &Database,
+    samples: &[NewMetric],
+    servers: &[DbServer],
+) {
+    let rules = match queries::alerts::list_enabled_alert_rules(db.pool()).await {
+        Ok(rules) => rules,
+        Err(e) => {
+            error!("Failed to load alert rules: {}", e);
+            return;
+        }
+    };
+
+    if rules.is_empty() {
+        return;
+    }
+
+    let servers_by_id: HashMap<i64, &DbServer> = servers.iter().map(|s| (s.id, s)).collect();
+    let now = Utc::now();
+    let mut notifications = Vec::new();
+
+    for sample in samples {
+        let server = sample.server_id.and_then(|id| servers_by_id.get(&id));
+        let server_name = match server {
+            Some(s) => s.name.clone(),
+            None => sample
+                .metadata
+                .as_ref()
+                .and_then(|m| m.get("server"))
+                .and_then(|s| s.as_str())
+                .unwrap_or(LOCAL_SERVER_NAME)
+                .to_string(),
+        };
+        let tags = server.map(|s| s.get_tags()).unwrap_or_default();
+
+        for rule in rules.iter().filter(|r| {
+            r.matches(
+                &sample.plugin_id,
+                &sample.metric_name,
+                sample.server_id,
+                &tags,
+            )
+        }) {
+            let current = match queries::alerts::get_alert_state(
+                db.pool(),
+                rule.id,
+                &server_name,
+                &sample.metric_name,
+            )
+            .await
+            {
+                Ok(current) => current,
+                Err(e) => {
+                    error!("Failed to load alert state for rule {}: {}", rule.id, e);
+                    continue;
+                }
+            };
+
+            let eval = rule.evaluate(current.as_ref(), sample.metric_value, now);
+
+            let update = AlertStateUpdate {
+                rule_id: rule.id,
+                server_id: sample.server_id,
+                server_name: server_name.clone(),
+                metric_name: sample.metric_name.clone(),
+                status: eval.status,
+                value: sample.metric_value,
+                pending_since: eval.pending_since,
+                firing_since: eval.firing_since,
+                resolved_at: eval.resolved_at,
+            };
+            if let Err(e) = queries::alerts::upsert_alert_state(db.pool(), &update).await {
+                error!("Failed to update alert state for rule {}: {}", rule.id, e);
+                continue;
+            }
+
+            let event = match eval.transition {
+                AlertTransition::Fired => "firing",
+                AlertTransition::Resolved => "resolved",
+                AlertTransition::Pending | AlertTransition::Cleared => {
+                    debug!(
+                        "Alert '{}' on {} ({}) is now {}",
+                        rule.name,
+                        server_name,
+                        sample.metric_name,
+                        eval.status.as_str()
+                    );
+                    continue;
+                }
+                AlertTransition::None => continue,
+            };
+
+            let message = condition_summary(rule, &sample.metric_name, sample.metric_value);
+            info!(
+                "Alert '{}' {} on {}: {}",
+                rule.name, event, server_name, message
+            );
+
+            let history = NewAlertEvent {
+                rule_id: rule.id,
+                server_name: server_name.clone(),
+                metric_name: sample.metric_name.clone(),
+                event: event.to_string(),
+                severity: rule.severity.clone(),
+                value: sample.metric_value,
+                threshold: rule.threshold,
+                message: message.clone(),
+            };
+            if let Err(e) = queries::alerts::record_alert_event(db.pool(), &history).await {
+                error!("Failed to record alert event for rule {}: {}", rule.id, e);
+            }
+
+            if eval.transition == AlertTransition::Fired || rule.notify_resolved {
+                notifications.push(build_notification(
+                    rule,
+                    eval.transition,
+                    &server_name,
+                    &message,
+                ));
+            }
+        }
+    }
+
+    if notifications.is_empty() {
+        return;
+    }
+
+    let manager = state.notification_manager().await;
+    for notification in &notifications {
+        if let Err(e) = manager.send_for_service(ALERTS_SERVICE, notification).await {
+            warn!("Failed to send alert notification: {}", e);
+        }
+    }
+}
+
+/// Human-readable description of a sample against a rule's condition
+fn condition_summary(rule: &AlertRule, metric_name: &str, value: f64) -> String {
+    format!(
+        "{} = {:.2} (threshold {} {})",
+        metric_name, value, rule.operator, rule.threshold
+    )
+}
+
+fn build_notification(
+    rule: &AlertRule,
+    transition: AlertTransition,
+    server_name: &str,
+    summary: &str,
+) -> NotificationMessage {
+    let (title, priority) = if transition == AlertTransition::Resolved {
+        (format!("✅ [RESOLVED] {}", rule.name), 3)
+    } else {
+        let (icon, priority) = match rule.severity.as_str() {
+            "critical" => ("🔥", 5),
+            "warning" => ("⚠️", 4),
+            _ => ("ℹ️", 3),
+        };
+        (
+            format!("{} [{}] {}", icon, rule.severity.to_uppercase(), rule.name),
+            priority,
+        )
+    };
+
+    let mut body = format!("**Server**: {}\n**Metric**: {}", server_name, summary);
+    if transition == AlertTransition::Fired && rule.duration_secs > 0 {
+        body.push_str(&format!("\n**For**: {}s", rule.duration_secs));
+    }
+    if let Some(description) = &rule.description {
+        body.push_str(&format!("\n\n{}", description));
+    }
+
+    NotificationMessage {
+        title,
+        body,
+        priority,
+        actions: vec![],
+    }
+}
@@ -0,0 +1,299 @@
+//! Audit log
+//!
+//! Mutating handlers record who did what to which record, how it ended, and
+//! where the request came from, together with the fields of the record that
+//! changed. Actions refused for lack of a role or scope are recorded by
+//! [`crate::rbac`].
+//!
+//! A handler builds an [`Audit`] before making its change, passes fallible
+//! steps through [`Audit::check`] so failures are recorded, and finishes with
+//! [`Audit::success`].
+
+use std::convert::Infallible;
+use std::fmt::Display;
+use std::net::{IpAddr, SocketAddr};
+
+use axum::{
+    extract::{ConnectInfo, FromRequestParts},
+    http::{request::Parts, Extensions},
+};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use svrctlrs_database::{
+    models::{AuditOutcome, NewAuditEntry},
+    queries,
+};
+use tracing::error;
+
+use crate::{rbac::CurrentUser, state::AppState};
+
+/// Shown instead of passwords, tokens and other secrets
+const REDACTED: &str = "[redacted]";
+
+/// Fields that change on every update and would only clutter diffs
+const IGNORED_FIELDS: &[&str] = &["updated_at"];
+
+/// Address a request came from
+#[derive(Debug, Clone, Copy)]
+pub struct ClientIp(pub Option<IpAddr>);
+
+impl ClientIp {
+    pub fn from_extensions(extensions: &Extensions) -> Self {
+        Self(
+            extensions
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(addr)| addr.ip()),
+        )
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for ClientIp {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_extensions(&parts.extensions))
+    }
+}
+
+/// An action being recorded in the audit log
+pub struct Audit<'a> {
+    state: &'a AppState,
+    actor: &'a CurrentUser,
+    action: String,
+    target: String,
+    before: Option<Value>,
+    after: Option<Value>,
+}
+
+impl<'a> Audit<'a> {
+    /// `action` names what is being done (e.g. `server.delete`) and `target`
+    /// what it is done to (e.g. `server 3 (web1)`)
+    pub fn new(
+        state: &'a AppState,
+        actor: &'a CurrentUser,
+        action: &str,
+        target: impl Into<String>,
+    ) -> Self {
+        Self {
+            state,
+            actor,
+            action: action.to_string(),
+            target: target.into(),
+            before: None,
+            after: None,
+        }
+    }
+
+    /// The record before the change
+    pub fn before(mut self, record: &impl Serialize) -> Self {
+        self.before = serde_json::to_value(record).ok();
+        self
+    }
+
+    /// The record after the change
+    pub fn after(mut self, record: &impl Serialize) -> Self {
+        self.after = serde_json::to_value(record).ok();
+        self
+    }
+
+    /// Record that the action succeeded
+    pub async fn success(self) {
+        self.record(AuditOutcome::Success, None).await;
+    }
+
+    /// Record that the action failed
+    pub async fn failure(&self, error: impl Display) {
+        self.record(AuditOutcome::Failure, Some(error.to_string()))
+            .await;
+    }
+
+    /// Record a failure if `result` is an error, and pass it on
+    pub async fn check<T, E: Display>(&self, result: Result<T, E>) -> Result<T, E> {
+        if let Err(e) = &result {
+            self.failure(e).await;
+        }
+        result
+    }
+
+    async fn record(&self, outcome: AuditOutcome, message: Option<String>) {
+        let entry = NewAuditEntry {
+            actor: self.actor.username.clone(),
+            action: self.action.clone(),
+            target: Some(self.target.clone()),
+            outcome,
+            message,
+            source_ip: self.actor.source_ip.map(|ip| ip.to_string()),
+            changes: changes(self.before.as_ref(), self.after.as_ref()),
+        };
+        let db = self.state.db().await;
+        if let Err(e) = queries::audit_log::record_audit_entry(db.pool(), &entry).await {
+            error!(action = %self.action, error = %e, "Failed to record audit entry");
+        }
+    }
+}
+
+/// Fields that differ between two versions of a record
+///
+/// Returns `{"field": {"before": ..., "after": ...}}`, or `None` if nothing
+/// changed. A missing version (before a create, after a delete) counts as
+/// all fields being null. JSON stored in string fields (plugin and
+/// notification configs) is compared as JSON, and secrets are redacted.
+fn changes(before: Option<&Value>, after: Option<&Value>) -> Option<Value> {
+    let before = fields(before);
+    let after = fields(after);
+
+    let mut changed = Map::new();
+    for key in before.keys().chain(after.keys()) {
+        if changed.contains_key(key) || IGNORED_FIELDS.contains(&key.as_str()) {
+            continue;
+        }
+        let old = before.get(key).unwrap_or(&Value::Null);
+        let new = after.get(key).unwrap_or(&Value::Null);
+        if old != new {
+            let mut change = Map::new();
+            change.insert("before".to_string(), redact(key, old));
+            change.insert("after".to_string(), redact(key, new));
+            changed.insert(key.clone(), Value::Object(change));
+        }
+    }
+
+    (!changed.is_empty()).then_some(Value::Object(changed))
+}
+
+/// A record's fields, with JSON strings parsed
+fn fields(record: Option<&Value>) -> Map<String, Value> {
+    let mut fields = match record {
+        Some(Value::Object(fields)) => fields.clone(),
+        Some(value) => Map::from_iter([("value".to_string(), value.clone())]),
+        None => Map::new(),
+    };
+    for value in fields.values_mut() {
+        let parsed = match value {
+            Value::String(s) if s.starts_with('{') => serde_json::from_str::<Value>(s).ok(),
+            _ => None,
+        };
+        if let Some(parsed @ Value::Object(_)) = parsed {
+            *value = parsed;
+        }
+    }
+    fields
+}
+
+/// A field's value with secrets hidden
+fn redact(key: &str, value: &Value) -> Value {
+    if value.is_null() {
+        return Value::Null;
+    }
+    if is_secret(key) {
+        return Value::String(REDACTED.to_string());
+    }
+    match value {
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(k, v)| (k.clone(), redact(k, v)))
+                .collect(),
+        ),
+        _ => value.clone(),
+    }
+}
+
+/// Parts of field names that hold secrets
+const SECRET_KEY_PARTS: &[&str] = &[
+    "password",
+    "token",
+    "secret",
+    "api_key",
+    "apikey",
+    "authorization",
+    "webhook",
+];
+
+/// Field names that hold secrets without saying so: notification backend
+/// URLs (Slack, Discord and webhook URLs carry their credentials) and
+/// webhook request headers
+const SECRET_KEYS: &[&str] = &["url", "headers"];
+
+/// A JSON object without its secret fields
+pub(crate) fn without_secrets(value: &Value) -> Value {
+    match value {
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .filter(|(k, _)| !is_secret(k))
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+        ),
+        _ => value.clone(),
+    }
+}
+
+fn is_secret(key: &str) -> bool {
+    let key = key.to_lowercase();
+    SECRET_KEYS.contains(&key.as_str()) || SECRET_KEY_PARTS.iter().any(|s| key.contains(s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_notification_backend_secrets_are_redacted() {
+        let before = json!({
+            "name": "pager",
+            "config": "{\"url\":\"https://hooks.slack.com/services/T0/B0/old\"}",
+        });
+        let after = json!({
+            "name": "pager",
+            "config": json!({
+                "url": "https://hooks.slack.com/services/T0/B0/new",
+                "token": "syt_matrix",
+                "access_token": "syt_matrix",
+                "headers": { "Authorization": "Bearer k3y" },
+                "room_id": "!ops:example.org",
+            })
+            .to_string(),
+        });
+
+        let changes = changes(Some(&before), Some(&after)).unwrap();
+        let config = &changes["config"];
+        assert_eq!(config["before"]["url"], REDACTED);
+        assert_eq!(config["after"]["url"], REDACTED);
+        assert_eq!(config["after"]["token"], REDACTED);
+        assert_eq!(config["after"]["access_token"], REDACTED);
+        assert_eq!(config["after"]["headers"], REDACTED);
+        assert_eq!(config["after"]["room_id"], "!ops:example.org");
+        assert!(!changes.to_string().contains("k3y"));
+        assert!(!changes.to_string().contains("hooks.slack.com"));
+    }
+
+    #[test]
+    fn test_without_secrets() {
+        let config = json!({ "schedule": "0 0 * * * *", "api_key": "owm-key", "zip": "10001" });
+        assert_eq!(
+            without_secrets(&config),
+            json!({ "schedule": "0 0 * * * *", "zip": "10001" })
+        );
+    }
+
+    #[test]
+    fn test_is_secret() {
+        for key in [
+            "password",
+            "smtp_password",
+            "token",
+            "access_token",
+            "url",
+            "webhook_url",
+            "headers",
+            "Authorization",
+            "api_key",
+        ] {
+            assert!(is_secret(key), "{} should be redacted", key);
+        }
+        for key in ["name", "room_id", "host", "username", "topic"] {
+            assert!(!is_secret(key), "{} should be kept", key);
+        }
+    }
+}
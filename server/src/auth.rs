@@ -0,0 +1,616 @@
+//! Authentication
+//!
+//! Users log in with a username and password, stored as argon2 hashes in
+//! the `users` table, and get a session cookie backed by the SQLite session
+//! store. [`require_auth`] guards every UI and `/api` route except the login
+//! page, static files, the health check and the webhooks, which check their
+//! own token.
+//!
+//! `/api/v1` requests can instead carry a per-user API token as
+//! `Authorization: Bearer <token>`. Tokens are random strings stored as
+//! SHA-256 hashes (a fast hash is fine for secrets this long) and act as their
+//! user, limited by the token's scopes.
+//!
+//! On first run, when there are no users yet, [`bootstrap_admin`] creates
+//! the admin account from `ADMIN_USERNAME` / `ADMIN_PASSWORD`, generating
+//! (and logging) a password if none is set.
+
+use anyhow::{anyhow, Result};
+use argon2::{
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use axum::{
+    extract::{Request, State},
+    http::{header::AUTHORIZATION, HeaderMap, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Redirect, Response},
+};
+use rand::{distributions::Alphanumeric, Rng};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use svrctlrs_database::{
+    models::{ApiTokenScope, CreateUser, UserRole},
+    queries, Database,
+};
+use tower_sessions::{
+    cookie::{time::Duration, SameSite},
+    session_store::ExpiredDeletion,
+    Expiry, Session, SessionManagerLayer,
+};
+use tower_sessions_sqlx_store::SqliteStore;
+use tracing::{error, info, warn};
+
+use crate::{audit::ClientIp, rbac::CurrentUser, state::AppState};
+
+/// Session key the logged-in user is stored under
+const SESSION_USER_KEY: &str = "user";
+
+/// Sessions expire after this long without a request
+const SESSION_INACTIVITY_DAYS: i64 = 7;
+
+/// How often expired sessions are deleted from the store
+const EXPIRED_SESSION_CLEANUP_SECS: u64 = 3600;
+
+/// Username of the first-run admin account unless `ADMIN_USERNAME` is set
+const DEFAULT_ADMIN_USERNAME: &str = "admin";
+
+/// Length of generated admin passwords
+const GENERATED_PASSWORD_LEN: usize = 20;
+
+/// Start of every API token, so leaked tokens are easy to recognise
+const API_TOKEN_PREFIX: &str = "svrctlrs_";
+
+/// Number of random characters in an API token
+const API_TOKEN_RANDOM_LEN: usize = 40;
+
+/// Characters of an API token kept to tell tokens apart
+const API_TOKEN_SHOWN_LEN: usize = 13;
+
+/// Where unauthenticated UI requests are sent
+pub const LOGIN_PATH: &str = "/auth/login";
+
+/// The logged-in user, as stored in the session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionUser {
+    pub id: i64,
+    pub username: String,
+}
+
+/// Hash a password for storage
+pub fn hash_password(password: &str) -> Result<String> {
+    let salt = SaltString::encode_b64(&rand::random::<[u8; 16]>())
+        .map_err(|e| anyhow!("Failed to encode salt: {}", e))?;
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| anyhow!("Failed to hash password: {}", e))
+}
+
+/// Check a password against a stored hash
+pub fn verify_password(hash: &str, password: &str) -> bool {
+    match PasswordHash::new(hash) {
+        Ok(parsed) => Argon2::default()
+            .verify_password(password.as_bytes(), &parsed)
+            .is_ok(),
+        Err(e) => {
+            warn!(error = %e, "Stored password hash is invalid");
+            false
+        }
+    }
+}
+
+/// Session layer storing sessions in the database
+///
+/// Cookies are only marked `Secure` when `PUBLIC_URL` is an `https://` URL,
+/// so plain-HTTP installs on a LAN can still log in.
+pub async fn session_layer(
+    db: &Database,
+    secure: bool,
+) -> Result<SessionManagerLayer<SqliteStore>> {
+    let store = SqliteStore::new(db.pool().clone());
+    store.migrate().await?;
+
+    tokio::spawn(
+        store
+            .clone()
+            .continuously_delete_expired(tokio::time::Duration::from_secs(
+                EXPIRED_SESSION_CLEANUP_SECS,
+            )),
+    );
+
+    Ok(SessionManagerLayer::new(store)
+        .with_name("svrctlrs_session")
+        .with_secure(secure)
+        .with_same_site(SameSite::Lax)
+        .with_expiry(Expiry::OnInactivity(Duration::days(
+            SESSION_INACTIVITY_DAYS,
+        ))))
+}
+
+/// Create the admin account if there are no users yet
+pub async fn bootstrap_admin(db: &Database) -> Result<()> {
+    if queries::users::count_users(db.pool()).await? > 0 {
+        return Ok(());
+    }
+
+    let username = std::env::var("ADMIN_USERNAME")
+        .ok()
+        .map(|u| u.trim().to_string())
+        .filter(|u| !u.is_empty())
+        .unwrap_or_else(|| DEFAULT_ADMIN_USERNAME.to_string());
+    let (password, generated) = match std::env::var("ADMIN_PASSWORD") {
+        Ok(password) if !password.is_empty() => (password, false),
+        _ => (generate_password(), true),
+    };
+
+    queries::users::create_user(
+        db.pool(),
+        &CreateUser {
+            username: username.clone(),
+            password_hash: hash_password(&password)?,
+            role: UserRole::Admin,
+            server_tags: Vec::new(),
+        },
+    )
+    .await?;
+
+    if generated {
+        warn!(
+            "Created admin account '{}' with generated password: {} (set ADMIN_PASSWORD to choose one)",
+            username, password
+        );
+    } else {
+        info!("Created admin account '{}'", username);
+    }
+    Ok(())
+}
+
+/// Random password for the first-run admin account
+fn generate_password() -> String {
+    random_string(GENERATED_PASSWORD_LEN)
+}
+
+fn random_string(len: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+/// Generate a new API token
+pub fn generate_api_token() -> String {
+    format!(
+        "{}{}",
+        API_TOKEN_PREFIX,
+        random_string(API_TOKEN_RANDOM_LEN)
+    )
+}
+
+/// Hash an API token for storage and lookup
+pub fn hash_api_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+/// Start of an API token, stored to tell tokens apart
+pub fn api_token_prefix(token: &str) -> String {
+    token.chars().take(API_TOKEN_SHOWN_LEN).collect()
+}
+
+/// Check a user's credentials and log them in on this session
+///
+/// Returns `None` if the username or password is wrong or the account is
+/// disabled.
+pub async fn login(
+    db: &Database,
+    session: &Session,
+    username: &str,
+    password: &str,
+) -> Result<Option<SessionUser>> {
+    let user = match queries::users::get_user_by_username(db.pool(), username.trim()).await? {
+        Some(user) if user.enabled && verify_password(&user.password_hash, password) => user,
+        _ => return Ok(None),
+    };
+
+    // New session ID on login so a planted cookie can't be reused
+    session.cycle_id().await?;
+    let session_user = SessionUser {
+        id: user.id,
+        username: user.username,
+    };
+    session.insert(SESSION_USER_KEY, &session_user).await?;
+    queries::users::record_user_login(db.pool(), user.id).await?;
+
+    info!(username = %session_user.username, "User logged in");
+    Ok(Some(session_user))
+}
+
+/// Log the session's user out
+pub async fn logout(session: &Session) -> Result<()> {
+    if let Some(user) = current_user(session).await {
+        info!(username = %user.username, "User logged out");
+    }
+    session.flush().await?;
+    Ok(())
+}
+
+/// The user logged in on this session, if any
+pub async fn current_user(session: &Session) -> Option<SessionUser> {
+    match session.get::<SessionUser>(SESSION_USER_KEY).await {
+        Ok(user) => user,
+        Err(e) => {
+            warn!(error = %e, "Failed to read session");
+            None
+        }
+    }
+}
+
+/// Paths reachable without logging in
+fn is_public(path: &str) -> bool {
+    path == LOGIN_PATH
+        || path == "/api/v1/health"
+        || path.starts_with("/static/")
+        || path.starts_with("/api/webhooks/")
+}
+
+/// Middleware rejecting requests without a logged-in, enabled user
+///
+/// `/api/v1` requests with a bearer token are authenticated by the token
+/// alone. The user is put into the request extensions as a [`CurrentUser`], also
+/// on public paths when someone is logged in. API requests without one get
+/// `401 Unauthorized`; UI requests are redirected to the login page (via
+/// `HX-Redirect` for HTMX requests, whose responses are swapped into the
+/// page).
+pub async fn require_auth(
+    State(state): State<AppState>,
+    session: Session,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    if request.uri().path().starts_with("/api/v1/") {
+        if let Some(token) = bearer_token(request.headers()) {
+            return match authenticate_api_token(&state, &token, request.method()).await {
+                Ok(mut user) => {
+                    user.source_ip = ClientIp::from_extensions(request.extensions()).0;
+                    request.extensions_mut().insert(user);
+                    next.run(request).await
+                }
+                Err(response) => response,
+            };
+        }
+    }
+
+    if let Some(user) = current_user(&session).await {
+        // Accounts disabled or deleted since logging in lose access
+        let db = state.db().await;
+        match queries::users::get_user(db.pool(), user.id).await {
+            Ok(account) if account.enabled => {
+                drop(db);
+                let mut user = CurrentUser::from_user(&account);
+                user.source_ip = ClientIp::from_extensions(request.extensions()).0;
+                request.extensions_mut().insert(user);
+                return next.run(request).await;
+            }
+            _ => {
+                warn!(username = %user.username, "Session user no longer exists or is disabled");
+                if let Err(e) = session.flush().await {
+                    warn!(error = %e, "Failed to clear session");
+                }
+            }
+        }
+    }
+
+    let path = request.uri().path();
+    if is_public(path) {
+        next.run(request).await
+    } else if path.starts_with("/api/") {
+        (StatusCode::UNAUTHORIZED, "Authentication required").into_response()
+    } else if is_htmx(request.headers()) {
+        (StatusCode::UNAUTHORIZED, [("HX-Redirect", LOGIN_PATH)]).into_response()
+    } else {
+        Redirect::to(LOGIN_PATH).into_response()
+    }
+}
+
+/// Whether a request was made by HTMX
+fn is_htmx(headers: &HeaderMap) -> bool {
+    headers.contains_key("HX-Request")
+}
+
+/// The bearer token of a request, if it has one
+fn bearer_token(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(|t| t.trim().to_string())
+}
+
+/// Authenticate an API request by its token
+///
+/// Returns the token's user with the role capped by the token's scopes, or
+/// the response to send for unknown, expired or out-of-scope tokens.
+async fn authenticate_api_token(
+    state: &AppState,
+    token: &str,
+    method: &Method,
+) -> Result<CurrentUser, Response> {
+    let db = state.db().await;
+    let api_token =
+        match queries::api_tokens::get_api_token_by_hash(db.pool(), &hash_api_token(token)).await {
+            Ok(Some(api_token)) if !api_token.is_expired() => api_token,
+            Ok(_) => {
+                return Err(
+                    (StatusCode::UNAUTHORIZED, "Invalid or expired API token").into_response()
+                );
+            }
+            Err(e) => {
+                error!(error = %e, "Failed to look up API token");
+                return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+            }
+        };
+    let user = match queries::users::get_user(db.pool(), api_token.user_id).await {
+        Ok(user) if user.enabled => user,
+        _ => {
+            warn!(token = %api_token.name, "API token of a disabled user");
+            return Err((StatusCode::UNAUTHORIZED, "Invalid or expired API token").into_response());
+        }
+    };
+
+    let scopes = api_token.get_scopes();
+    let reads = method == Method::GET || method == Method::HEAD;
+    if reads && !scopes.contains(&ApiTokenScope::Read) {
+        return Err((StatusCode::FORBIDDEN, "API token lacks the read scope").into_response());
+    }
+
+    if let Err(e) = queries::api_tokens::record_api_token_use(db.pool(), api_token.id).await {
+        warn!(error = %e, "Failed to record API token use");
+    }
+    Ok(CurrentUser::for_api_token(&user, &scopes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use axum::{
+        body::Body,
+        http::{header, Request as HttpRequest},
+        routing::get,
+        Router,
+    };
+    use serde_json::json;
+    use svrctlrs_database::models::{CreateApiToken, UpdateUser};
+    use tower::ServiceExt;
+
+    async fn test_state(name: &str) -> AppState {
+        let path =
+            std::env::temp_dir().join(format!("svrctlrs-{}-{}.db", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let url = format!("sqlite:{}", path.display());
+        let db = Database::new(&url).await.unwrap();
+        db.migrate().await.unwrap();
+
+        let config: Config = serde_json::from_value(json!({
+            "database_url": url,
+            "servers": [],
+            "ssh_key_path": null,
+        }))
+        .unwrap();
+        AppState::new(config, db).await.unwrap()
+    }
+
+    async fn create_user(state: &AppState, username: &str, role: UserRole) -> i64 {
+        let db = state.db().await;
+        let user = CreateUser {
+            username: username.to_string(),
+            password_hash: hash_password("correct horse").unwrap(),
+            role,
+            server_tags: Vec::new(),
+        };
+        queries::users::create_user(db.pool(), &user).await.unwrap()
+    }
+
+    async fn disable_user(state: &AppState, id: i64) {
+        let db = state.db().await;
+        let update = UpdateUser {
+            password_hash: None,
+            role: None,
+            server_tags: None,
+            enabled: Some(false),
+        };
+        queries::users::update_user(db.pool(), id, &update)
+            .await
+            .unwrap();
+    }
+
+    /// App with a page, an API route and a login route that logs `alice` in
+    async fn test_app(state: &AppState) -> Router {
+        let login_state = state.clone();
+        let sessions = session_layer(&*state.db().await, false).await.unwrap();
+        Router::new()
+            .route("/", get(|| async { "page" }))
+            .route(
+                "/api/v1/servers",
+                get(|| async { "servers" }).post(|| async { "created" }),
+            )
+            .route(
+                LOGIN_PATH,
+                get(move |session: Session| async move {
+                    let db = login_state.db().await;
+                    login(&db, &session, "alice", "correct horse")
+                        .await
+                        .unwrap()
+                        .map(|user| user.username)
+                        .unwrap_or_default()
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                require_auth,
+            ))
+            .layer(sessions)
+    }
+
+    fn get_request(uri: &str) -> HttpRequest<Body> {
+        HttpRequest::get(uri).body(Body::empty()).unwrap()
+    }
+
+    async fn log_in(app: &Router) -> String {
+        let response = app.clone().oneshot(get_request(LOGIN_PATH)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let cookie = response.headers()[header::SET_COOKIE].to_str().unwrap();
+        cookie.split(';').next().unwrap().to_string()
+    }
+
+    async fn status_with_cookie(app: &Router, uri: &str, cookie: &str) -> StatusCode {
+        let request = HttpRequest::get(uri)
+            .header(header::COOKIE, cookie)
+            .body(Body::empty())
+            .unwrap();
+        app.clone().oneshot(request).await.unwrap().status()
+    }
+
+    #[test]
+    fn test_verify_password() {
+        let hash = hash_password("correct horse").unwrap();
+        assert!(verify_password(&hash, "correct horse"));
+        assert!(!verify_password(&hash, "battery staple"));
+        assert!(!verify_password("not a hash", "correct horse"));
+        assert!(!verify_password("", ""));
+    }
+
+    #[tokio::test]
+    async fn test_unauthenticated_requests() {
+        let state = test_state("auth-anonymous").await;
+        let app = test_app(&state).await;
+
+        // API requests are rejected outright
+        let response = app
+            .clone()
+            .oneshot(get_request("/api/v1/servers"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // Pages redirect to the login page
+        let response = app.clone().oneshot(get_request("/")).await.unwrap();
+        assert!(response.status().is_redirection());
+        assert_eq!(response.headers()[header::LOCATION], LOGIN_PATH);
+
+        // HTMX requests are told where to go instead of swapping in the login page
+        let request = HttpRequest::get("/")
+            .header("HX-Request", "true")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.headers()["HX-Redirect"], LOGIN_PATH);
+    }
+
+    #[tokio::test]
+    async fn test_disabled_users_lose_their_session() {
+        let state = test_state("auth-session").await;
+        let id = create_user(&state, "alice", UserRole::Viewer).await;
+        let app = test_app(&state).await;
+
+        let cookie = log_in(&app).await;
+        assert_eq!(status_with_cookie(&app, "/", &cookie).await, StatusCode::OK);
+        assert_eq!(
+            status_with_cookie(&app, "/api/v1/servers", &cookie).await,
+            StatusCode::OK
+        );
+
+        disable_user(&state, id).await;
+        assert_eq!(
+            status_with_cookie(&app, "/api/v1/servers", &cookie).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert!(status_with_cookie(&app, "/", &cookie)
+            .await
+            .is_redirection());
+    }
+
+    async fn create_token(
+        state: &AppState,
+        user_id: i64,
+        scopes: Vec<ApiTokenScope>,
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> String {
+        let token = generate_api_token();
+        let db = state.db().await;
+        let create = CreateApiToken {
+            user_id,
+            name: "test".to_string(),
+            token_hash: hash_api_token(&token),
+            token_prefix: api_token_prefix(&token),
+            scopes,
+            expires_at,
+        };
+        queries::api_tokens::create_api_token(db.pool(), &create)
+            .await
+            .unwrap();
+        token
+    }
+
+    async fn status_with_token(app: &Router, method: Method, uri: &str, token: &str) -> StatusCode {
+        let request = HttpRequest::builder()
+            .method(method)
+            .uri(uri)
+            .header(AUTHORIZATION, format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap();
+        app.clone().oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_api_tokens() {
+        let state = test_state("auth-tokens").await;
+        let id = create_user(&state, "alice", UserRole::Operator).await;
+        let app = test_app(&state).await;
+        let reader = create_token(&state, id, vec![ApiTokenScope::Read], None).await;
+        let writer = create_token(&state, id, vec![ApiTokenScope::Operate], None).await;
+        let expired = create_token(
+            &state,
+            id,
+            vec![ApiTokenScope::Read],
+            Some(chrono::Utc::now() - chrono::Duration::hours(1)),
+        )
+        .await;
+
+        assert_eq!(
+            status_with_token(&app, Method::GET, "/api/v1/servers", &reader).await,
+            StatusCode::OK
+        );
+        // Reading needs the read scope, even for tokens that may operate
+        assert_eq!(
+            status_with_token(&app, Method::GET, "/api/v1/servers", &writer).await,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            status_with_token(&app, Method::POST, "/api/v1/servers", &writer).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status_with_token(&app, Method::GET, "/api/v1/servers", &expired).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status_with_token(&app, Method::GET, "/api/v1/servers", &generate_api_token()).await,
+            StatusCode::UNAUTHORIZED
+        );
+        // Tokens only authenticate the API, not the UI
+        assert!(status_with_token(&app, Method::GET, "/", &reader)
+            .await
+            .is_redirection());
+
+        // Tokens of disabled users stop working
+        disable_user(&state, id).await;
+        assert_eq!(
+            status_with_token(&app, Method::GET, "/api/v1/servers", &reader).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+}
@@ -4,7 +4,10 @@
 //! for managing plugins, triggering tasks, and viewing system status.
 
 use clap::{Parser, Subcommand};
-use reqwest::Client;
+use reqwest::{
+    header::{HeaderMap, HeaderValue, AUTHORIZATION},
+    Client,
+};
 use serde_json::Value;
 use std::process;
 use tracing::{error, info};
@@ -73,6 +76,22 @@ enum StatusCommands {
     Metrics {
         /// Get metrics for specific plugin
         plugin_id: Option<String>,
+
+        /// Filter by server name
+        #[arg(long)]
+        server: Option<String>,
+
+        /// Metric name (or prefix ending in `*`)
+        #[arg(long)]
+        metric: Option<String>,
+
+        /// Time range to query (e.g. 1h, 7d)
+        #[arg(long)]
+        range: Option<String>,
+
+        /// Down-sampling bucket (e.g. 5m, raw)
+        #[arg(long)]
+        bucket: Option<String>,
     },
 }
 
@@ -177,7 +196,13 @@ async fn main() {
         .init();
 
     let cli = Cli::parse();
-    let client = Client::new();
+    let client = match build_client(cli.token.as_deref()) {
+        Ok(client) => client,
+        Err(e) => {
+            error!("Failed to create HTTP client: {}", e);
+            process::exit(1);
+        }
+    };
 
     let result = match cli.command {
         Commands::Health => handle_health(&client, &cli.url).await,
@@ -193,10 +218,21 @@ async fn main() {
     }
 }
 
+/// HTTP client sending the API token, if any, with every request
+fn build_client(token: Option<&str>) -> anyhow::Result<Client> {
+    let mut headers = HeaderMap::new();
+    if let Some(token) = token {
+        let mut value = HeaderValue::from_str(&format!("Bearer {}", token))?;
+        value.set_sensitive(true);
+        headers.insert(AUTHORIZATION, value);
+    }
+    Ok(Client::builder().default_headers(headers).build()?)
+}
+
 async fn handle_health(client: &Client, base_url: &str) -> anyhow::Result<()> {
     let url = format!("{}/api/v1/health", base_url);
     let response = client.get(&url).send().await?;
-    
+
     if response.status().is_success() {
         let json: Value = response.json().await?;
         println!("{}", serde_json::to_string_pretty(&json)?);
@@ -222,13 +258,28 @@ async fn handle_status(
             let response: Value = client.get(&url).send().await?.json().await?;
             println!("{}", serde_json::to_string_pretty(&response)?);
         }
-        StatusCommands::Metrics { plugin_id } => {
+        StatusCommands::Metrics {
+            plugin_id,
+            server,
+            metric,
+            range,
+            bucket,
+        } => {
             let url = if let Some(id) = plugin_id {
                 format!("{}/api/v1/metrics/{}", base_url, id)
             } else {
                 format!("{}/api/v1/metrics", base_url)
             };
-            let response: Value = client.get(&url).send().await?.json().await?;
+            let params: Vec<(&str, String)> = [
+                ("server", server),
+                ("metric", metric),
+                ("range", range),
+                ("bucket", bucket),
+            ]
+            .into_iter()
+            .filter_map(|(key, value)| value.map(|v| (key, v)))
+            .collect();
+            let response: Value = client.get(&url).query(&params).send().await?.json().await?;
             println!("{}", serde_json::to_string_pretty(&response)?);
         }
     }
@@ -14,9 +14,28 @@ pub struct Config {
 
     /// SSH key path for remote execution
     pub ssh_key_path: Option<String>,
+
+    /// Maximum number of scheduled task runs executing at once (0 = unlimited)
+    #[serde(default = "default_max_concurrent_tasks")]
+    pub max_concurrent_tasks: usize,
+
+    /// Maximum number of concurrent SSH task executions per server (0 = unlimited)
+    #[serde(default = "default_max_ssh_per_server")]
+    pub max_ssh_per_server: usize,
+
+    /// Externally reachable base URL (e.g. `https://svrctl.example.com`),
+    /// used for links and action buttons in notifications
+    #[serde(default)]
+    pub public_url: Option<String>,
 }
 
+fn default_max_concurrent_tasks() -> usize {
+    8
+}
 
+fn default_max_ssh_per_server() -> usize {
+    2
+}
 
 impl Config {
     /// Load configuration from file or environment
@@ -51,13 +70,37 @@ impl Config {
 
         let ssh_key_path = std::env::var("SSH_KEY_PATH").ok();
 
+        let max_concurrent_tasks =
+            Self::parse_limit("MAX_CONCURRENT_TASKS", default_max_concurrent_tasks())?;
+        let max_ssh_per_server =
+            Self::parse_limit("MAX_SSH_PER_SERVER", default_max_ssh_per_server())?;
+
+        let public_url = std::env::var("PUBLIC_URL")
+            .ok()
+            .map(|url| url.trim().trim_end_matches('/').to_string())
+            .filter(|url| !url.is_empty());
+
         Ok(Config {
             database_url,
             servers,
             ssh_key_path,
+            max_concurrent_tasks,
+            max_ssh_per_server,
+            public_url,
         })
     }
 
+    /// Parse a concurrency limit from an environment variable
+    fn parse_limit(var: &str, default: usize) -> Result<usize> {
+        match std::env::var(var) {
+            Ok(value) => value
+                .trim()
+                .parse()
+                .map_err(|_| Error::ConfigError(format!("Invalid {}: {}", var, value))),
+            Err(_) => Ok(default),
+        }
+    }
+
     /// Parse server list from string
     fn parse_servers(input: &str) -> Result<Vec<Server>> {
         input
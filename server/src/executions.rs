@@ -0,0 +1,248 @@
+//! Registry of in-flight task executions
+//!
+//! Every call to [`crate::executor::execute_task`] registers itself here for
+//! as long as it runs, so executions can be listed with their elapsed time
+//! and cancelled from the UI or API. Remote command output is published
+//! here line by line so it can be followed live.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+use tokio::task::AbortHandle;
+use tokio_util::sync::CancellationToken;
+
+use svrctlrs_database::models::Task;
+
+use crate::ssh::OutputStream;
+
+/// Output lines kept per execution for viewers that connect late
+const MAX_BUFFERED_LINES: usize = 1000;
+
+/// Output lines a slow viewer may fall behind before it starts skipping
+const OUTPUT_CHANNEL_CAPACITY: usize = 256;
+
+/// What started an execution
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskTrigger {
+    Schedule,
+    Manual,
+    /// A step of a workflow run
+    Workflow,
+    /// An authenticated webhook call
+    Webhook,
+}
+
+impl TaskTrigger {
+    /// Trigger name as stored in `task_history.triggered_by`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Schedule => "schedule",
+            Self::Manual => "manual",
+            Self::Workflow => "workflow",
+            Self::Webhook => "webhook",
+        }
+    }
+}
+
+/// Snapshot of a running execution
+#[derive(Debug, Clone, Serialize)]
+pub struct RunningExecution {
+    pub id: u64,
+    pub task_id: i64,
+    pub task_name: String,
+    pub plugin_id: String,
+    pub server_id: Option<i64>,
+    pub triggered_by: &'static str,
+    /// Current attempt (1 = first try)
+    pub attempt: u32,
+    pub started_at: DateTime<Utc>,
+    pub elapsed_ms: i64,
+    /// Cancellation was requested and the execution is stopping
+    pub cancelling: bool,
+}
+
+/// One line of remote command output
+#[derive(Debug, Clone, Serialize)]
+pub struct OutputLine {
+    /// Attempt that produced the line
+    pub attempt: u32,
+    /// Server the command ran on
+    pub server: Option<String>,
+    pub stream: OutputStream,
+    pub line: String,
+}
+
+/// Output of an execution so far, plus a receiver for lines still to come
+///
+/// The receiver closes once the execution finishes.
+pub struct OutputSubscription {
+    pub backlog: Vec<OutputLine>,
+    pub receiver: broadcast::Receiver<OutputLine>,
+}
+
+/// Bookkeeping for an execution that was removed from the registry
+pub(crate) struct FinishedEntry {
+    /// `task_history` rows of the attempts in progress (one per server)
+    pub history_ids: Vec<i64>,
+    pub task_id: i64,
+    pub started_at: DateTime<Utc>,
+    abort: Option<AbortHandle>,
+}
+
+impl FinishedEntry {
+    /// Abort the execution's tokio task (no-op if it already finished)
+    pub fn abort(&self) {
+        if let Some(abort) = &self.abort {
+            abort.abort();
+        }
+    }
+}
+
+struct Entry {
+    info: RunningExecution,
+    /// `task_history` rows of the attempts in progress
+    history_ids: Vec<i64>,
+    cancel: CancellationToken,
+    abort: Option<AbortHandle>,
+    /// Most recent output lines
+    output: VecDeque<OutputLine>,
+    output_tx: broadcast::Sender<OutputLine>,
+}
+
+/// In-flight executions, keyed by execution ID
+#[derive(Default)]
+pub struct RunningExecutions {
+    next_id: AtomicU64,
+    entries: Mutex<HashMap<u64, Entry>>,
+}
+
+impl RunningExecutions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn entries(&self) -> std::sync::MutexGuard<'_, HashMap<u64, Entry>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Register a new execution, returning its ID and cancellation token
+    pub(crate) fn register(&self, task: &Task, trigger: TaskTrigger) -> (u64, CancellationToken) {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+        let cancel = CancellationToken::new();
+
+        let entry = Entry {
+            info: RunningExecution {
+                id,
+                task_id: task.id,
+                task_name: task.name.clone(),
+                plugin_id: task.plugin_id.clone(),
+                server_id: task.server_id,
+                triggered_by: trigger.as_str(),
+                attempt: 1,
+                started_at: Utc::now(),
+                elapsed_ms: 0,
+                cancelling: false,
+            },
+            history_ids: Vec::new(),
+            cancel: cancel.clone(),
+            abort: None,
+            output: VecDeque::new(),
+            output_tx: broadcast::channel(OUTPUT_CHANNEL_CAPACITY).0,
+        };
+        self.entries().insert(id, entry);
+
+        (id, cancel)
+    }
+
+    /// Attach the tokio task running the execution, so it can be aborted
+    pub(crate) fn set_abort_handle(&self, id: u64, abort: AbortHandle) {
+        if let Some(entry) = self.entries().get_mut(&id) {
+            entry.abort = Some(abort);
+        }
+    }
+
+    /// Record the start of an attempt and its `task_history` row
+    ///
+    /// Executions that fan out to several servers have one attempt in
+    /// progress per server; the highest attempt number is reported.
+    pub(crate) fn begin_attempt(&self, id: u64, attempt: u32, history_id: Option<i64>) {
+        if let Some(entry) = self.entries().get_mut(&id) {
+            entry.info.attempt = entry.info.attempt.max(attempt);
+            entry.history_ids.extend(history_id);
+        }
+    }
+
+    /// Record that an attempt's outcome has been written to its `task_history` row
+    pub(crate) fn end_attempt(&self, id: u64, history_id: i64) {
+        if let Some(entry) = self.entries().get_mut(&id) {
+            entry.history_ids.retain(|h| *h != history_id);
+        }
+    }
+
+    /// Publish a line of output to anyone following the execution
+    pub(crate) fn push_output(&self, id: u64, line: OutputLine) {
+        if let Some(entry) = self.entries().get_mut(&id) {
+            if entry.output.len() == MAX_BUFFERED_LINES {
+                entry.output.pop_front();
+            }
+            entry.output.push_back(line.clone());
+            // No receivers is fine: nobody is watching
+            let _ = entry.output_tx.send(line);
+        }
+    }
+
+    /// Follow an execution's output; `None` if it is not running
+    pub fn subscribe_output(&self, id: u64) -> Option<OutputSubscription> {
+        self.entries().get(&id).map(|entry| OutputSubscription {
+            backlog: entry.output.iter().cloned().collect(),
+            receiver: entry.output_tx.subscribe(),
+        })
+    }
+
+    /// Remove an execution; `None` if it was already removed
+    ///
+    /// Whoever removes an execution is responsible for recording its outcome.
+    pub(crate) fn take(&self, id: u64) -> Option<FinishedEntry> {
+        self.entries().remove(&id).map(|entry| FinishedEntry {
+            history_ids: entry.history_ids,
+            task_id: entry.info.task_id,
+            started_at: entry.info.started_at,
+            abort: entry.abort,
+        })
+    }
+
+    /// Ask an execution to stop; returns `false` if it is not running
+    pub(crate) fn request_cancel(&self, id: u64) -> bool {
+        match self.entries().get_mut(&id) {
+            Some(entry) => {
+                entry.info.cancelling = true;
+                entry.cancel.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Whether an execution is still registered
+    pub fn contains(&self, id: u64) -> bool {
+        self.entries().contains_key(&id)
+    }
+
+    /// Running executions, oldest first
+    pub fn list(&self) -> Vec<RunningExecution> {
+        let now = Utc::now();
+        let mut executions: Vec<RunningExecution> = self
+            .entries()
+            .values()
+            .map(|entry| RunningExecution {
+                elapsed_ms: (now - entry.info.started_at).num_milliseconds().max(0),
+                ..entry.info.clone()
+            })
+            .collect();
+        executions.sort_by_key(|e| (e.started_at, e.id));
+        executions
+    }
+}
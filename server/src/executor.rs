@@ -2,133 +2,741 @@
 //!
 //! Handles execution of scheduled tasks, including:
 //! - Plugin task execution
-//! - SSH command execution on remote servers
-//! - Task history tracking
+//! - SSH command execution on remote servers, with output streamed live
+//! - Fan-out of a command to every server a task targets, in parallel
+//! - Task history tracking (including in-flight `running` executions)
+//! - Cancellation of running executions
+//! - Per-server concurrency limits for SSH execution
 //! - Error handling and retries
 
 use anyhow::{Context, Result};
+use serde::Serialize;
 use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::task::JoinSet;
 use tracing::{debug, error, info, warn};
 
+use tokio_util::sync::CancellationToken;
+
+use svrctlrs_core::PluginResult;
 use svrctlrs_database::{
-    models::task::{Task, TaskHistoryEntry},
-    queries,
+    models::task::{NewTaskExecution, Task, TaskExecutionOutcome, TaskTarget},
+    queries, Database,
 };
 
 use crate::{
-    ssh::{self, SshConfig},
+    executions::{OutputLine, TaskTrigger},
+    ssh::{self, OutputStream, SshConfig},
     state::AppState,
 };
 
+/// Delay before the first retry; doubles with each further attempt
+const RETRY_BASE_DELAY_SECS: u64 = 2;
+
+/// Longest delay between retries
+const RETRY_MAX_DELAY_SECS: u64 = 60;
+
+/// Upper bound on configured retries, whatever the task or server says
+pub const MAX_RETRY_ATTEMPTS: i32 = 10;
+
+/// How long a cancelled execution may take to stop cleanly before it is aborted
+const CANCEL_GRACE_SECS: u64 = 5;
+
+/// Time limit for ad-hoc commands run by workflow steps
+const COMMAND_TIMEOUT_SECS: u64 = 300;
+
 /// Execute a task by ID
-pub async fn execute_task(state: &AppState, task_id: i64) -> Result<TaskExecutionResult> {
+///
+/// The execution runs in its own tokio task and is listed in
+/// [`AppState::executions`] until it finishes, so it can be cancelled with
+/// [`cancel_execution`]. Dropping the returned future also cancels it.
+pub async fn execute_task(
+    state: &AppState,
+    task_id: i64,
+    trigger: TaskTrigger,
+) -> Result<TaskExecutionResult> {
     let start_time = Instant::now();
-    
-    info!("Starting execution of task {}", task_id);
-    
+
+    info!(
+        "Starting execution of task {} ({})",
+        task_id,
+        trigger.as_str()
+    );
+
     // Load task from database
-    let db = state.db().await;
-    let task = queries::tasks::get_task(db.pool(), task_id)
-        .await
-        .context("Failed to load task")?;
-    
+    let task = {
+        let db = state.db().await;
+        queries::tasks::get_task(db.pool(), task_id)
+            .await
+            .context("Failed to load task")?
+    };
+
     if !task.enabled {
         warn!("Task {} is disabled, skipping execution", task_id);
         return Ok(TaskExecutionResult {
-            task_id,
             success: false,
             output: "Task is disabled".to_string(),
             error: None,
             duration_ms: start_time.elapsed().as_millis() as u64,
+            attempts: 0,
+            plugin_result: None,
+            status: ExecutionStatus::Failed,
+            servers: Vec::new(),
         });
     }
-    
-    // Execute based on task type
-    let result = match task.server_id {
-        Some(server_id) => {
-            // Task requires SSH execution on a remote server
-            execute_remote_task(state, &task, server_id).await
-        }
-        None => {
-            // Task is a local plugin execution
-            execute_plugin_task(state, &task).await
+
+    let (execution_id, cancel) = state.executions.register(&task, trigger);
+    let run_state = state.clone();
+    let handle =
+        tokio::spawn(
+            async move { run_task(&run_state, &task, execution_id, trigger, &cancel).await },
+        );
+    state
+        .executions
+        .set_abort_handle(execution_id, handle.abort_handle());
+
+    // Cancel the execution if the caller stops waiting for it
+    let mut guard = CancelOnDrop {
+        state: Some(state.clone()),
+        execution_id,
+    };
+    let joined = handle.await;
+    guard.state = None;
+
+    let outcome = match joined {
+        Ok(outcome) => outcome,
+        Err(e) if e.is_cancelled() => RunOutcome::failed(anyhow::anyhow!("Execution cancelled")),
+        Err(e) => {
+            let message = format!("Execution panicked: {}", e);
+            abandon_execution(state, execution_id, "failed", &message).await;
+            RunOutcome::failed(anyhow::anyhow!(message))
         }
     };
-    
+    let RunOutcome {
+        result,
+        attempts,
+        plugin_result,
+        status,
+        servers,
+    } = outcome;
+
     let duration_ms = start_time.elapsed().as_millis() as u64;
-    
-    // Record execution in task history
-    let history_entry = TaskHistoryEntry {
-        task_id,
-        plugin_id: task.plugin_id.clone(),
-        server_id: task.server_id,
-        success: result.is_ok(),
-        output: result.as_ref().map(|s| s.clone()).unwrap_or_default(),
-        error: result.as_ref().err().map(|e| e.to_string()),
-        duration_ms,
-        executed_at: chrono::Utc::now(),
-    };
-    
-    if let Err(e) = queries::tasks::record_task_execution(db.pool(), &history_entry).await {
-        error!("Failed to record task execution in history: {}", e);
-    }
-    
-    // Update task's last_run_at and run_count
-    if let Err(e) = queries::tasks::update_task_stats(db.pool(), task_id).await {
-        error!("Failed to update task stats: {}", e);
-    }
-    
+
     match result {
         Ok(output) => {
-            info!("Task {} completed successfully in {}ms", task_id, duration_ms);
+            info!(
+                "Task {} completed successfully in {}ms",
+                task_id, duration_ms
+            );
             Ok(TaskExecutionResult {
-                task_id,
                 success: true,
                 output,
                 error: None,
                 duration_ms,
+                attempts,
+                plugin_result,
+                status,
+                servers,
             })
         }
         Err(e) => {
             error!("Task {} failed after {}ms: {}", task_id, duration_ms, e);
             Ok(TaskExecutionResult {
-                task_id,
                 success: false,
                 output: String::new(),
                 error: Some(e.to_string()),
                 duration_ms,
+                attempts,
+                plugin_result,
+                status,
+                servers,
             })
         }
     }
 }
 
-/// Execute a task on a remote server via SSH
-async fn execute_remote_task(state: &AppState, task: &Task, server_id: i64) -> Result<String> {
-    debug!("Executing remote task {} on server {}", task.id, server_id);
-    
-    // Load server configuration
+/// Cancel a running execution
+///
+/// The execution is signalled first so it can close its SSH channel; if it
+/// has not stopped after a grace period its tokio task is aborted. Returns
+/// `false` if no such execution is running.
+pub async fn cancel_execution(state: &AppState, execution_id: u64) -> bool {
+    if !state.executions.request_cancel(execution_id) {
+        return false;
+    }
+    info!("Cancelling execution {}", execution_id);
+
+    let deadline = Instant::now() + std::time::Duration::from_secs(CANCEL_GRACE_SECS);
+    while state.executions.contains(execution_id) && Instant::now() < deadline {
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+
+    if state.executions.contains(execution_id) {
+        warn!(
+            "Execution {} did not stop within {}s, aborting it",
+            execution_id, CANCEL_GRACE_SECS
+        );
+        abandon_execution(state, execution_id, "cancelled", "Execution cancelled").await;
+    }
+
+    true
+}
+
+/// Cancels an execution when the future awaiting it is dropped
+struct CancelOnDrop {
+    state: Option<AppState>,
+    execution_id: u64,
+}
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        if let Some(state) = self.state.take() {
+            let execution_id = self.execution_id;
+            tokio::spawn(async move {
+                cancel_execution(&state, execution_id).await;
+            });
+        }
+    }
+}
+
+/// Remove an execution that did not finish on its own, abort it, and record its outcome
+async fn abandon_execution(state: &AppState, execution_id: u64, status: &str, message: &str) {
+    let Some(entry) = state.executions.take(execution_id) else {
+        return;
+    };
+    entry.abort();
+
     let db = state.db().await;
+    for history_id in entry.history_ids {
+        let outcome = TaskExecutionOutcome {
+            status: status.to_string(),
+            output: String::new(),
+            error: Some(message.to_string()),
+            exit_code: None,
+            stdout: None,
+            stderr: None,
+            duration_ms: (chrono::Utc::now() - entry.started_at)
+                .num_milliseconds()
+                .max(0) as u64,
+            finished_at: chrono::Utc::now(),
+        };
+        if let Err(e) = queries::tasks::finish_task_execution(db.pool(), history_id, &outcome).await
+        {
+            error!("Failed to record task execution outcome: {}", e);
+        }
+    }
+    if let Err(e) = queries::tasks::update_task_stats(db.pool(), entry.task_id, status).await {
+        error!("Failed to update task stats: {}", e);
+    }
+}
+
+/// How an execution ended
+struct RunOutcome {
+    result: Result<String>,
+    attempts: u32,
+    /// What the plugin (or remote command) reported, if it got that far
+    plugin_result: Option<PluginResult>,
+    status: ExecutionStatus,
+    /// One result per target server (empty for local plugin tasks)
+    servers: Vec<ServerResult>,
+}
+
+impl RunOutcome {
+    /// An execution that never produced a result
+    fn failed(error: anyhow::Error) -> Self {
+        Self::single(Err(error), 0, None, false)
+    }
+
+    /// Outcome of an execution that ran in one place
+    fn single(
+        result: Result<String>,
+        attempts: u32,
+        plugin_result: Option<PluginResult>,
+        cancelled: bool,
+    ) -> Self {
+        let status = match &result {
+            Ok(_) => ExecutionStatus::Success,
+            Err(_) if cancelled => ExecutionStatus::Cancelled,
+            Err(_) => ExecutionStatus::Failed,
+        };
+        Self {
+            result,
+            attempts,
+            plugin_result,
+            status,
+            servers: Vec::new(),
+        }
+    }
+}
+
+/// Body of an execution, run in its own tokio task
+async fn run_task(
+    state: &AppState,
+    task: &Task,
+    execution_id: u64,
+    trigger: TaskTrigger,
+    cancel: &CancellationToken,
+) -> RunOutcome {
+    let attempt = Attempt {
+        state,
+        task,
+        server_id: task.server_id,
+        execution_id,
+        trigger,
+        cancel,
+    };
+
+    // Execute based on the task's target; each attempt is recorded in task history
+    let outcome = match task.target() {
+        Ok(TaskTarget::Server(server_id)) => {
+            // Task requires SSH execution on a remote server
+            let (result, attempts, plugin_result) = execute_remote_task(&attempt, server_id).await;
+            let mut outcome =
+                RunOutcome::single(result, attempts, plugin_result, cancel.is_cancelled());
+            outcome.servers = vec![ServerResult::new(
+                server_id,
+                None,
+                &outcome.result,
+                outcome.attempts,
+                outcome.plugin_result.as_ref(),
+            )];
+            outcome
+        }
+        Ok(target @ (TaskTarget::AllServers | TaskTarget::Tags(_))) => {
+            // Run the command on every targeted server
+            execute_fan_out(state, task, execution_id, trigger, cancel, &target).await
+        }
+        Err(e) => {
+            let started = attempt.begin(1).await;
+            let result = Err(anyhow::anyhow!("Invalid task target: {}", e));
+            attempt.finish(started, &result, None).await;
+            RunOutcome::single(result, 1, None, false)
+        }
+        Ok(TaskTarget::Local) => {
+            // Task is a local plugin execution
+            let started = attempt.begin(1).await;
+            let executed = tokio::select! {
+                result = execute_plugin_task(state, task) => result,
+                _ = cancel.cancelled() => Err(anyhow::anyhow!("Execution cancelled")),
+            };
+            let (result, plugin_result) = match executed {
+                Ok(plugin_result) => (plugin_outcome(task, &plugin_result), Some(plugin_result)),
+                Err(e) => (Err(e), None),
+            };
+            attempt.finish(started, &result, None).await;
+            RunOutcome::single(result, 1, plugin_result, cancel.is_cancelled())
+        }
+    };
+
+    // Done; unless the execution was abandoned meanwhile, update the task's stats
+    if state.executions.take(execution_id).is_some() {
+        let db = state.db().await;
+        if let Err(e) =
+            queries::tasks::update_task_stats(db.pool(), task.id, outcome.status.as_str()).await
+        {
+            error!("Failed to update task stats: {}", e);
+        }
+    }
+
+    outcome
+}
+
+/// Records the attempts of one execution in task history
+struct Attempt<'a> {
+    state: &'a AppState,
+    task: &'a Task,
+    /// Server the attempts run on
+    server_id: Option<i64>,
+    execution_id: u64,
+    trigger: TaskTrigger,
+    cancel: &'a CancellationToken,
+}
+
+/// An attempt in progress: its history row and start time
+struct StartedAttempt {
+    number: u32,
+    history_id: Option<i64>,
+    started: Instant,
+}
+
+/// Output of a remote attempt, published live and kept for task history
+struct AttemptOutput<'a> {
+    state: &'a AppState,
+    execution_id: u64,
+    attempt: u32,
+    server: Option<String>,
+    stdout: String,
+    stderr: String,
+    exit_code: Option<i32>,
+}
+
+impl AttemptOutput<'_> {
+    /// The attempt as a plugin result, for workflow conditions
+    fn plugin_result(&self, result: &Result<String>) -> PluginResult {
+        command_plugin_result(result, self.exit_code, &self.stdout, &self.stderr)
+    }
+
+    fn push(&mut self, stream: OutputStream, line: &str) {
+        let buffer = match stream {
+            OutputStream::Stdout => &mut self.stdout,
+            OutputStream::Stderr => &mut self.stderr,
+        };
+        buffer.push_str(line);
+        buffer.push('\n');
+
+        self.state.executions.push_output(
+            self.execution_id,
+            OutputLine {
+                attempt: self.attempt,
+                server: self.server.clone(),
+                stream,
+                line: line.to_string(),
+            },
+        );
+    }
+}
+
+impl Attempt<'_> {
+    /// Record the start of an attempt with status `running`
+    async fn begin(&self, attempt: u32) -> StartedAttempt {
+        let execution = NewTaskExecution {
+            task_id: self.task.id,
+            plugin_id: self.task.plugin_id.clone(),
+            server_id: self.server_id,
+            attempt: attempt as i32,
+            triggered_by: self.trigger.as_str().to_string(),
+            started_at: chrono::Utc::now(),
+        };
+
+        let db = self.state.db().await;
+        let history_id = match queries::tasks::start_task_execution(db.pool(), &execution).await {
+            Ok(id) => Some(id),
+            Err(e) => {
+                error!("Failed to record task execution in history: {}", e);
+                None
+            }
+        };
+        self.state
+            .executions
+            .begin_attempt(self.execution_id, attempt, history_id);
+
+        StartedAttempt {
+            number: attempt,
+            history_id,
+            started: Instant::now(),
+        }
+    }
+
+    /// Collector for the remote command output of an attempt on a server
+    fn output(&self, attempt: &StartedAttempt, server: &str) -> AttemptOutput<'_> {
+        AttemptOutput {
+            state: self.state,
+            execution_id: self.execution_id,
+            attempt: attempt.number,
+            server: Some(server.to_string()),
+            stdout: String::new(),
+            stderr: String::new(),
+            exit_code: None,
+        }
+    }
+
+    /// Record the outcome of an attempt, with the remote command's output if it ran one
+    async fn finish(
+        &self,
+        attempt: StartedAttempt,
+        result: &Result<String>,
+        output: Option<AttemptOutput<'_>>,
+    ) {
+        let Some(history_id) = attempt.history_id else {
+            return;
+        };
+
+        let status = match result {
+            Ok(_) => "success",
+            Err(_) if self.cancel.is_cancelled() => "cancelled",
+            Err(_) => "failed",
+        };
+        let outcome = TaskExecutionOutcome {
+            status: status.to_string(),
+            output: result.as_ref().ok().cloned().unwrap_or_default(),
+            // Include the cause chain so flaky hosts can be told apart from broken commands
+            error: result.as_ref().err().map(|e| format!("{:#}", e)),
+            exit_code: output.as_ref().and_then(|o| o.exit_code),
+            stdout: output.as_ref().map(|o| o.stdout.clone()),
+            stderr: output.map(|o| o.stderr),
+            duration_ms: attempt.started.elapsed().as_millis() as u64,
+            finished_at: chrono::Utc::now(),
+        };
+
+        let db = self.state.db().await;
+        if let Err(e) = queries::tasks::finish_task_execution(db.pool(), history_id, &outcome).await
+        {
+            error!("Failed to record task execution outcome: {}", e);
+        }
+        self.state
+            .executions
+            .end_attempt(self.execution_id, history_id);
+    }
+}
+
+/// Backoff before retry number `retry` (1-based)
+fn retry_delay(retry: u32) -> std::time::Duration {
+    let secs = RETRY_BASE_DELAY_SECS.saturating_mul(1 << (retry - 1).min(16));
+    std::time::Duration::from_secs(secs.min(RETRY_MAX_DELAY_SECS))
+}
+
+/// Execute a task on a remote server via SSH, retrying transient failures
+///
+/// The retry limit comes from the task, falling back to the server's
+/// `retry_attempts`. Returns the final result, the number of attempts made,
+/// and the command's result as a plugin result if it ran.
+async fn execute_remote_task(
+    attempt: &Attempt<'_>,
+    server_id: i64,
+) -> (Result<String>, u32, Option<PluginResult>) {
+    let (state, task, cancel) = (attempt.state, attempt.task, attempt.cancel);
+    debug!("Executing remote task {} on server {}", task.id, server_id);
+
+    let server = {
+        let db = state.db().await;
+        load_remote_server(&db, server_id).await
+    };
+    let server = match server {
+        Ok(server) => server,
+        Err(e) => {
+            let started = attempt.begin(1).await;
+            let result = Err(e);
+            attempt.finish(started, &result, None).await;
+            return (result, 1, None);
+        }
+    };
+
+    let retries = task
+        .retry_attempts
+        .unwrap_or(server.retry_attempts)
+        .clamp(0, MAX_RETRY_ATTEMPTS) as u32;
+    let ssh_config = SshConfig {
+        host: server.host.clone().unwrap_or_default(),
+        port: server.port as u16,
+        username: server.username.clone(),
+        key_path: server.ssh_key_path.clone(),
+        timeout: std::time::Duration::from_secs(server.connection_timeout.max(1) as u64),
+    };
+    let command = build_remote_command(task);
+
+    let mut attempt_number = 1;
+    loop {
+        let started = attempt.begin(attempt_number).await;
+        let mut output = attempt.output(&started, &server.name);
+
+        // Wait for a free SSH slot on the target server (released during backoff)
+        let result = tokio::select! {
+            server_slot = state.server_limiter.acquire(server_id) => {
+                let result = run_remote_command(&ssh_config, &server.name, &command, task.timeout, cancel, &mut output).await;
+                drop(server_slot);
+                result
+            }
+            _ = cancel.cancelled() => Err(anyhow::anyhow!("Execution cancelled")),
+        };
+
+        let plugin_result = output.plugin_result(&result);
+        attempt.finish(started, &result, Some(output)).await;
+
+        match result {
+            Err(e)
+                if attempt_number <= retries && ssh::is_transient(&e) && !cancel.is_cancelled() =>
+            {
+                let delay = retry_delay(attempt_number);
+                warn!(
+                    "Task {} attempt {}/{} on {} failed: {:#}. Retrying in {}s",
+                    task.id,
+                    attempt_number,
+                    retries + 1,
+                    server.name,
+                    e,
+                    delay.as_secs()
+                );
+                tokio::select! {
+                    _ = tokio::time::sleep(delay) => {}
+                    _ = cancel.cancelled() => {
+                        // Record the retry that never ran so history ends in `cancelled`
+                        let started = attempt.begin(attempt_number + 1).await;
+                        let result = Err(anyhow::anyhow!("Execution cancelled while waiting to retry"));
+                        attempt.finish(started, &result, None).await;
+                        return (result, attempt_number + 1, None);
+                    }
+                }
+                attempt_number += 1;
+            }
+            result => return (result, attempt_number, Some(plugin_result)),
+        }
+    }
+}
+
+/// Run a task's command on every server it targets, in parallel
+///
+/// Each server gets its own attempts (and retries) in task history. The
+/// execution succeeds if every server succeeded, is `partial` if only some
+/// did, and fails if none did.
+async fn execute_fan_out(
+    state: &AppState,
+    task: &Task,
+    execution_id: u64,
+    trigger: TaskTrigger,
+    cancel: &CancellationToken,
+    target: &TaskTarget,
+) -> RunOutcome {
+    let servers = {
+        let db = state.db().await;
+        queries::servers::list_servers(db.pool()).await
+    };
+    let servers: Vec<_> = match servers {
+        Ok(servers) => servers.into_iter().filter(|s| target.includes(s)).collect(),
+        Err(e) => {
+            return record_fan_out_failure(
+                state,
+                task,
+                execution_id,
+                trigger,
+                cancel,
+                anyhow::anyhow!(e).context("Failed to load servers"),
+            )
+            .await;
+        }
+    };
+    if servers.is_empty() {
+        let error = anyhow::anyhow!("No enabled servers match the task's target");
+        return record_fan_out_failure(state, task, execution_id, trigger, cancel, error).await;
+    }
+    info!("Running task {} on {} servers", task.id, servers.len());
+
+    let mut running = JoinSet::new();
+    for server in servers {
+        let (state, task, cancel) = (state.clone(), task.clone(), cancel.clone());
+        running.spawn(async move {
+            let attempt = Attempt {
+                state: &state,
+                task: &task,
+                server_id: Some(server.id),
+                execution_id,
+                trigger,
+                cancel: &cancel,
+            };
+            let (result, attempts, plugin_result) = execute_remote_task(&attempt, server.id).await;
+            ServerResult::new(
+                server.id,
+                Some(server.name),
+                &result,
+                attempts,
+                plugin_result.as_ref(),
+            )
+        });
+    }
+
+    let mut results = Vec::new();
+    while let Some(joined) = running.join_next().await {
+        match joined {
+            Ok(result) => results.push(result),
+            Err(e) => error!("Task {} server execution panicked: {}", task.id, e),
+        }
+    }
+    results.sort_by(|a, b| a.server_name.cmp(&b.server_name));
+
+    let succeeded = results.iter().filter(|r| r.success).count();
+    let status = if cancel.is_cancelled() {
+        ExecutionStatus::Cancelled
+    } else if succeeded == results.len() {
+        ExecutionStatus::Success
+    } else if succeeded == 0 {
+        ExecutionStatus::Failed
+    } else {
+        ExecutionStatus::Partial
+    };
+
+    let summary = format!("{} of {} servers succeeded", succeeded, results.len());
+    let result = if status == ExecutionStatus::Success {
+        Ok(summary.clone())
+    } else {
+        let failures: Vec<String> = results
+            .iter()
+            .filter(|r| !r.success)
+            .map(|r| {
+                format!(
+                    "{}: {}",
+                    r.server_name.as_deref().unwrap_or_default(),
+                    r.error.as_deref().unwrap_or_default()
+                )
+            })
+            .collect();
+        Err(anyhow::anyhow!("{}; {}", summary, failures.join("; ")))
+    };
+    let plugin_result = PluginResult {
+        success: status == ExecutionStatus::Success,
+        message: summary,
+        data: Some(serde_json::json!({
+            "status": status,
+            "servers": results,
+        })),
+        metrics: None,
+    };
+
+    RunOutcome {
+        result,
+        attempts: results.iter().map(|r| r.attempts).max().unwrap_or(0),
+        plugin_result: Some(plugin_result),
+        status,
+        servers: results,
+    }
+}
+
+/// Record a fan-out that could not start as a single failed attempt
+async fn record_fan_out_failure(
+    state: &AppState,
+    task: &Task,
+    execution_id: u64,
+    trigger: TaskTrigger,
+    cancel: &CancellationToken,
+    error: anyhow::Error,
+) -> RunOutcome {
+    let attempt = Attempt {
+        state,
+        task,
+        server_id: None,
+        execution_id,
+        trigger,
+        cancel,
+    };
+    let started = attempt.begin(1).await;
+    let result = Err(error);
+    attempt.finish(started, &result, None).await;
+    RunOutcome::single(result, 1, None, false)
+}
+
+/// Load a server for remote execution, checking it can be used
+async fn load_remote_server(
+    db: &Database,
+    server_id: i64,
+) -> Result<svrctlrs_database::models::Server> {
     let server = queries::servers::get_server(db.pool(), server_id)
         .await
         .context("Failed to load server")?;
-    
+
     if !server.enabled {
         anyhow::bail!("Server {} is disabled", server_id);
     }
-    
-    // Build SSH configuration
-    let host = server.host.ok_or_else(|| anyhow::anyhow!("Server has no host configured"))?;
-    let ssh_config = SshConfig {
-        host,
-        port: server.port as u16,
-        username: server.username,
-        key_path: server.ssh_key_path,
-        timeout: std::time::Duration::from_secs(task.timeout as u64),
-    };
-    
-    // Build command with args
-    let command = if let Some(args_str) = &task.args {
+    if server.host.is_none() {
+        anyhow::bail!("Server has no host configured");
+    }
+
+    Ok(server)
+}
+
+/// Build the shell command for a remote task, appending its args
+fn build_remote_command(task: &Task) -> String {
+    if let Some(args_str) = &task.args {
         // Parse args as JSON and append to command
         match serde_json::from_str::<JsonValue>(args_str) {
             Ok(JsonValue::Array(args)) => {
@@ -156,79 +764,269 @@ async fn execute_remote_task(state: &AppState, task: &Task, server_id: i64) -> R
         }
     } else {
         task.command.clone()
+    }
+}
+
+/// Run a command once on a remote server, bounded by the task timeout
+///
+/// Output lines go to `output` as they arrive, so it holds whatever the
+/// command printed even if it times out or is cancelled.
+async fn run_remote_command(
+    ssh_config: &SshConfig,
+    server_name: &str,
+    command: &str,
+    timeout_secs: i32,
+    cancel: &CancellationToken,
+    output: &mut AttemptOutput<'_>,
+) -> Result<String> {
+    info!("Executing command on {}: {}", server_name, command);
+
+    let timeout = std::time::Duration::from_secs(timeout_secs.max(1) as u64);
+    let execute = ssh::execute_command(ssh_config, command, cancel, |stream, line| {
+        output.push(stream, line)
+    });
+    let result = tokio::time::timeout(timeout, execute)
+        .await
+        .map_err(|_| anyhow::anyhow!("Command timed out after {} seconds", timeout.as_secs()))?
+        .context("Failed to execute command")?;
+    // The command ran to completion: keep its exact output rather than the joined lines
+    output.exit_code = Some(result.exit_code);
+    output.stdout.clone_from(&result.stdout);
+    output.stderr.clone_from(&result.stderr);
+
+    if !result.success {
+        anyhow::bail!(
+            "Command failed with exit code {}: {}",
+            result.exit_code,
+            result.stderr
+        );
+    }
+
+    Ok(result.stdout)
+}
+
+/// Run an ad-hoc command on a server, as a workflow step does
+///
+/// Unlike tasks, the command is not retried or recorded in task history; its
+/// exit code and output are returned as the result's data. Failing to reach
+/// the server is an error, while a failing command is an unsuccessful result.
+pub async fn run_server_command(
+    state: &AppState,
+    server_id: i64,
+    command: &str,
+    cancel: &CancellationToken,
+) -> Result<PluginResult> {
+    let server = {
+        let db = state.db().await;
+        load_remote_server(&db, server_id).await?
+    };
+    let ssh_config = SshConfig {
+        host: server.host.clone().unwrap_or_default(),
+        port: server.port as u16,
+        username: server.username.clone(),
+        key_path: server.ssh_key_path.clone(),
+        timeout: std::time::Duration::from_secs(server.connection_timeout.max(1) as u64),
+    };
+
+    let _server_slot = tokio::select! {
+        slot = state.server_limiter.acquire(server_id) => slot,
+        _ = cancel.cancelled() => anyhow::bail!("Execution cancelled"),
     };
-    
     info!("Executing command on {}: {}", server.name, command);
-    
-    // Execute command
-    let output = ssh::execute_command(&ssh_config, &command)
+
+    let timeout = std::time::Duration::from_secs(COMMAND_TIMEOUT_SECS);
+    let execute = ssh::execute_command(&ssh_config, command, cancel, |_, _| {});
+    let output = tokio::time::timeout(timeout, execute)
         .await
+        .map_err(|_| anyhow::anyhow!("Command timed out after {} seconds", timeout.as_secs()))?
         .context("Failed to execute command")?;
-    
-    if !output.success {
-        anyhow::bail!(
+
+    let result = if output.success {
+        Ok(output.stdout.clone())
+    } else {
+        Err(anyhow::anyhow!(
             "Command failed with exit code {}: {}",
             output.exit_code,
             output.stderr
-        );
+        ))
+    };
+    Ok(command_plugin_result(
+        &result,
+        Some(output.exit_code),
+        &output.stdout,
+        &output.stderr,
+    ))
+}
+
+/// Describe a remote command run as a plugin result
+///
+/// `data` holds `exit_code`, `stdout` and `stderr`.
+fn command_plugin_result(
+    result: &Result<String>,
+    exit_code: Option<i32>,
+    stdout: &str,
+    stderr: &str,
+) -> PluginResult {
+    PluginResult {
+        success: result.is_ok(),
+        message: match result {
+            Ok(stdout) => stdout.trim().to_string(),
+            Err(e) => format!("{:#}", e),
+        },
+        data: Some(serde_json::json!({
+            "exit_code": exit_code,
+            "stdout": stdout,
+            "stderr": stderr,
+        })),
+        metrics: None,
+    }
+}
+
+/// Turn a plugin's result into the task's outcome
+fn plugin_outcome(task: &Task, result: &PluginResult) -> Result<String> {
+    if result.success {
+        Ok(format!(
+            "Plugin {} executed successfully: {}",
+            task.plugin_id, result.message
+        ))
+    } else {
+        anyhow::bail!(
+            "Plugin {} execution failed: {}",
+            task.plugin_id,
+            result.message
+        )
+    }
+}
+
+/// Plugin view of a database server
+pub(crate) fn core_server(s: &svrctlrs_database::models::Server) -> svrctlrs_core::Server {
+    // Build SSH host string (username@host:port)
+    let ssh_host = s.host.as_ref().map(|host| {
+        if s.port != 22 {
+            format!("{}@{}:{}", s.username, host, s.port)
+        } else {
+            format!("{}@{}", s.username, host)
+        }
+    });
+
+    svrctlrs_core::Server {
+        name: s.name.clone(),
+        ssh_host,
+        ssh_key_path: s.ssh_key_path.clone(),
+        docker_endpoint: s.docker_endpoint.clone(),
+        docker_cert_path: s.docker_cert_path.clone(),
+        pre_update_hook: s.pre_update_hook.clone(),
+        post_update_hook: s.post_update_hook.clone(),
+        rollback_hook: s.rollback_hook.clone(),
+    }
+}
+
+/// Plugin config from a task's args (string values only)
+fn plugin_config(task: &Task) -> HashMap<String, String> {
+    match task.args.as_deref().map(serde_json::from_str::<JsonValue>) {
+        Some(Ok(JsonValue::Object(obj))) => obj
+            .iter()
+            .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
+            .collect(),
+        _ => HashMap::new(),
     }
-    
-    Ok(output.stdout)
 }
 
 /// Execute a plugin task locally
-async fn execute_plugin_task(state: &AppState, task: &Task) -> Result<String> {
+///
+/// Errors mean the plugin could not run; an unsuccessful run is returned as
+/// a result with `success: false`.
+async fn execute_plugin_task(state: &AppState, task: &Task) -> Result<PluginResult> {
+    debug!(
+        "Executing plugin task {} for plugin {}",
+        task.id, task.plugin_id
+    );
+    info!(
+        "Executing plugin {} task '{}' ({})",
+        task.plugin_id, task.command, task.name
+    );
+
+    run_plugin_task(
+        state,
+        &task.plugin_id,
+        &task.command,
+        plugin_config(task),
+        task.server_id,
+    )
+    .await
+}
+
+/// Run a plugin task against the registered servers
+///
+/// Scheduled tasks, manual API runs and webhooks all go through here, so
+/// every run targets the enabled servers from the database, waits for
+/// update approval, and records its metrics, alerts and update history.
+/// Plain metric keys are attributed to `server_id`.
+pub(crate) async fn run_plugin_task(
+    state: &AppState,
+    plugin_id: &str,
+    command: &str,
+    mut config: HashMap<String, String>,
+    server_id: Option<i64>,
+) -> Result<PluginResult> {
     use svrctlrs_core::{PluginContext, Server as CoreServer};
-    use std::collections::HashMap;
 
-    debug!("Executing plugin task {} for plugin {}", task.id, task.plugin_id);
-    
     // Get plugin from registry
     let plugins = state.plugins.read().await;
     let plugin = plugins
-        .get(&task.plugin_id)
-        .ok_or_else(|| anyhow::anyhow!("Plugin '{}' not found in registry", task.plugin_id))?;
+        .get(plugin_id)
+        .ok_or_else(|| anyhow::anyhow!("Plugin '{}' not found in registry", plugin_id))?;
+
+    // Rolling reboots need SSH access the plugin does not have, so the server runs them
+    #[cfg(feature = "plugin-updates")]
+    if plugin_id == "updates" && command == svrctlrs_plugin_updates::REBOOT_ROLLOUT_TASK {
+        drop(plugins);
+        return crate::reboots::run_rollout(state, &config).await;
+    }
 
     // Build plugin context
     let db = state.db().await;
-    
+
     // Load servers from database (all enabled servers)
     let db_servers = queries::servers::list_servers(db.pool())
         .await
         .context("Failed to load servers for plugin execution")?;
-    
+
+    // Keep name -> id so per-server metrics can be attributed after execution
+    let server_ids: HashMap<String, i64> =
+        db_servers.iter().map(|s| (s.name.clone(), s.id)).collect();
+
     let servers: Vec<CoreServer> = db_servers
-        .into_iter()
+        .iter()
         .filter(|s| s.enabled)
-        .map(|s| {
-            // Build SSH host string (username@host:port)
-            let ssh_host = s.host.map(|host| {
-                if s.port != 22 {
-                    format!("{}@{}:{}", s.username, host, s.port)
-                } else {
-                    format!("{}@{}", s.username, host)
-                }
-            });
-            
-            CoreServer {
-                name: s.name,
-                ssh_host,
-            }
-        })
+        .map(core_server)
         .collect();
 
-    // Parse task config from args
-    let config: HashMap<String, String> = if let Some(args_str) = &task.args {
-        match serde_json::from_str::<JsonValue>(args_str) {
-            Ok(JsonValue::Object(obj)) => obj
-                .iter()
-                .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
-                .collect(),
-            _ => HashMap::new(),
+    // Updates wait for approval when the updates plugin requires it
+    let approval_required =
+        plugin_id == "updates" && crate::updates::approval_required(&db).await?;
+    let mut servers = servers;
+    if approval_required {
+        config.insert("approval_required".to_string(), "true".to_string());
+        if command == "updates_apply" {
+            let (approved_servers, approved_packages) =
+                crate::updates::approved_updates(&db, servers, &server_ids).await?;
+            servers = approved_servers;
+            config.insert(
+                "approved_packages".to_string(),
+                serde_json::to_string(&approved_packages)?,
+            );
+            if servers.is_empty() {
+                info!("No approved update batches to apply");
+                return Ok(PluginResult {
+                    success: true,
+                    message: "No approved update batches to apply".to_string(),
+                    data: None,
+                    metrics: None,
+                });
+            }
         }
-    } else {
-        HashMap::new()
-    };
+    }
 
     // Get notification manager
     let notification_manager = state.notification_manager().await;
@@ -239,35 +1037,249 @@ async fn execute_plugin_task(state: &AppState, task: &Task) -> Result<String> {
         notification_manager,
     };
 
-    // Execute plugin with the task's command as the plugin task ID
-    // The command field stores the plugin's task ID (e.g., "docker_health", "system_metrics")
-    info!("Executing plugin {} task '{}' ({})", task.plugin_id, task.command, task.name);
     let result = plugin
-        .execute(&task.command, &context)
+        .execute(command, &context)
         .await
-        .context(format!("Plugin {} execution failed", task.plugin_id))?;
+        .context(format!("Plugin {} execution failed", plugin_id))?;
 
-    if result.success {
-        Ok(format!(
-            "Plugin {} executed successfully: {}",
-            task.plugin_id, result.message
-        ))
+    if let Some(metrics) = &result.metrics {
+        let samples = store_plugin_metrics(&db, plugin_id, server_id, &server_ids, metrics).await;
+        crate::alerts::evaluate_samples(state, &db, &samples, &db_servers).await;
+    }
+
+    // Keep the packages found by update checks for review before applying
+    if plugin_id == "updates" {
+        match command {
+            "updates_check" => {
+                crate::updates::record_check(state, &db, &server_ids, &result, approval_required)
+                    .await;
+            }
+            "updates_apply" => {
+                crate::updates::record_apply(&db, &server_ids, &result, approval_required).await;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(result)
+}
+
+/// Persist the metrics returned by a plugin
+///
+/// Server-scoped keys (`server::metric`) are attributed to the matching
+/// registered server; plain keys belong to `server_id` (if any).
+/// Returns the samples so they can be checked against alert rules.
+async fn store_plugin_metrics(
+    db: &Database,
+    plugin_id: &str,
+    server_id: Option<i64>,
+    server_ids: &HashMap<String, i64>,
+    metrics: &HashMap<String, f64>,
+) -> Vec<svrctlrs_database::models::NewMetric> {
+    use svrctlrs_core::split_server_metric_key;
+    use svrctlrs_database::models::NewMetric;
+
+    let samples: Vec<NewMetric> = metrics
+        .iter()
+        .filter(|(_, value)| value.is_finite())
+        .map(|(key, value)| {
+            let (server_name, metric_name) = split_server_metric_key(key);
+            let (metric_server_id, metadata) = match server_name {
+                Some(name) => match server_ids.get(name) {
+                    Some(id) => (Some(*id), None),
+                    // Not a registered server (e.g. the local fallback), keep its name
+                    None => (None, Some(serde_json::json!({ "server": name }))),
+                },
+                None => (server_id, None),
+            };
+
+            NewMetric {
+                server_id: metric_server_id,
+                plugin_id: plugin_id.to_string(),
+                metric_name: metric_name.to_string(),
+                metric_value: *value,
+                metric_unit: metric_unit(metric_name).map(String::from),
+                metadata,
+                timestamp: None,
+            }
+        })
+        .collect();
+
+    match queries::metrics::record_metrics(db.pool(), &samples).await {
+        Ok(count) => debug!("Stored {} metrics for plugin {}", count, plugin_id),
+        Err(e) => error!("Failed to store metrics for plugin {}: {}", plugin_id, e),
+    }
+
+    samples
+}
+
+/// Infer a metric's unit from its naming convention
+fn metric_unit(metric_name: &str) -> Option<&'static str> {
+    // Strip the ":mount" / ":iface" qualifier
+    let base = metric_name.split(':').next().unwrap_or(metric_name);
+    if base.ends_with("_percent") || base.ends_with("_pct") {
+        Some("percent")
+    } else if base.ends_with("_bytes") {
+        Some("bytes")
+    } else if base.ends_with("_mb") {
+        Some("MB")
+    } else if base.ends_with("_ms") {
+        Some("ms")
+    } else if base.ends_with("_mbps") {
+        Some("Mbps")
     } else {
-        anyhow::bail!(
-            "Plugin {} execution failed: {}",
-            task.plugin_id,
-            result.message
-        )
+        None
+    }
+}
+
+/// Limits the number of concurrent task executions per server
+pub struct ServerLimiter {
+    limit: usize,
+    slots: Mutex<HashMap<i64, Arc<Semaphore>>>,
+}
+
+impl ServerLimiter {
+    /// Create a limiter allowing `limit` executions per server (0 = unlimited)
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            slots: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Wait for a free slot on a server; the slot is released when the permit is dropped
+    pub async fn acquire(&self, server_id: i64) -> Option<OwnedSemaphorePermit> {
+        if self.limit == 0 {
+            return None;
+        }
+
+        let semaphore = self
+            .slots
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(server_id)
+            .or_insert_with(|| Arc::new(Semaphore::new(self.limit)))
+            .clone();
+
+        if semaphore.available_permits() == 0 {
+            debug!(
+                "Server {} is at its concurrency limit ({}), waiting",
+                server_id, self.limit
+            );
+        }
+
+        semaphore.acquire_owned().await.ok()
     }
 }
 
 /// Result of a task execution
 #[derive(Debug, Clone)]
 pub struct TaskExecutionResult {
-    pub task_id: i64,
     pub success: bool,
     pub output: String,
     pub error: Option<String>,
     pub duration_ms: u64,
+    /// Number of attempts made (retries + 1)
+    pub attempts: u32,
+    /// What the plugin or remote command reported, if the task got that far
+    pub plugin_result: Option<PluginResult>,
+    pub status: ExecutionStatus,
+    /// One result per target server (empty for local plugin tasks)
+    pub servers: Vec<ServerResult>,
 }
 
+/// Overall outcome of an execution across its target servers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExecutionStatus {
+    Success,
+    /// Some servers succeeded and some failed
+    Partial,
+    Failed,
+    Cancelled,
+}
+
+impl ExecutionStatus {
+    /// Status name as stored in `tasks.last_status`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Partial => "partial",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// Result of a task's command on one server
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerResult {
+    pub server_id: i64,
+    pub server_name: Option<String>,
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    pub error: Option<String>,
+    pub attempts: u32,
+}
+
+impl ServerResult {
+    fn new(
+        server_id: i64,
+        server_name: Option<String>,
+        result: &Result<String>,
+        attempts: u32,
+        plugin_result: Option<&PluginResult>,
+    ) -> Self {
+        let data = plugin_result.and_then(|r| r.data.as_ref());
+        let field = |name: &str| {
+            data.and_then(|d| d.get(name))
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+
+        Self {
+            server_id,
+            server_name,
+            success: result.is_ok(),
+            exit_code: data
+                .and_then(|d| d.get("exit_code"))
+                .and_then(|v| v.as_i64())
+                .map(|c| c as i32),
+            stdout: field("stdout"),
+            stderr: field("stderr"),
+            error: result.as_ref().err().map(|e| format!("{:#}", e)),
+            attempts,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay(1), Duration::from_secs(RETRY_BASE_DELAY_SECS));
+        assert_eq!(
+            retry_delay(2),
+            Duration::from_secs(RETRY_BASE_DELAY_SECS * 2)
+        );
+        assert_eq!(
+            retry_delay(3),
+            Duration::from_secs(RETRY_BASE_DELAY_SECS * 4)
+        );
+
+        // Capped, including for retry counts that would overflow the shift
+        assert_eq!(retry_delay(10), Duration::from_secs(RETRY_MAX_DELAY_SECS));
+        assert_eq!(retry_delay(64), Duration::from_secs(RETRY_MAX_DELAY_SECS));
+        assert_eq!(
+            retry_delay(u32::MAX),
+            Duration::from_secs(RETRY_MAX_DELAY_SECS)
+        );
+        assert!((1..100).all(|retry| retry_delay(retry) <= retry_delay(retry + 1)));
+    }
+}
@@ -5,13 +5,22 @@
 #![allow(non_snake_case)]
 
 // Server-side modules
+mod alerts;
+mod audit;
+mod auth;
 mod config;
+mod executions;
 mod executor;
+mod rbac;
+#[cfg(feature = "plugin-updates")]
+mod reboots;
 mod routes;
 mod ssh;
 mod state;
 mod templates;
 mod ui_routes;
+mod updates;
+mod workflows;
 
 use config::Config;
 use state::AppState;
@@ -34,7 +43,7 @@ async fn main() -> anyhow::Result<()> {
         #[arg(short, long)]
         config: Option<String>,
     }
-    
+
     // Initialize tracing
     tracing_subscriber::fmt()
         .with_env_filter(
@@ -54,13 +63,48 @@ async fn main() -> anyhow::Result<()> {
     info!(addr = %args.addr, "Starting SvrCtlRS server");
 
     // Initialize database
-    let database_url = std::env::var("DATABASE_URL")
-        .unwrap_or_else(|_| "sqlite:data/svrctlrs.db".to_string());
+    let database_url =
+        std::env::var("DATABASE_URL").unwrap_or_else(|_| "sqlite:data/svrctlrs.db".to_string());
     info!(url = %database_url, "Connecting to database");
-    
+
     let database = svrctlrs_database::Database::new(&database_url).await?;
     database.migrate().await?;
 
+    // Executions still marked running were cut off by the last shutdown
+    let interrupted =
+        svrctlrs_database::queries::tasks::fail_interrupted_executions(database.pool()).await?;
+    if interrupted > 0 {
+        info!(
+            "Marked {} interrupted task executions as failed",
+            interrupted
+        );
+    }
+    let interrupted =
+        svrctlrs_database::queries::workflows::fail_interrupted_workflow_runs(database.pool())
+            .await?;
+    if interrupted > 0 {
+        info!("Marked {} interrupted workflow runs as failed", interrupted);
+    }
+    let interrupted =
+        svrctlrs_database::queries::updates::fail_interrupted_rollbacks(database.pool()).await?;
+    if interrupted > 0 {
+        info!(
+            "Marked {} interrupted update rollbacks as failed",
+            interrupted
+        );
+    }
+
+    // First run: create the admin account
+    auth::bootstrap_admin(&database).await?;
+
+    // Sessions are stored in the database; only send the cookie over HTTPS
+    // when that's how the server is reached
+    let secure_cookies = config
+        .public_url
+        .as_deref()
+        .is_some_and(|url| url.starts_with("https://"));
+    let session_layer = auth::session_layer(&database, secure_cookies).await?;
+
     // Initialize application state
     let state = AppState::new(config, database).await?;
 
@@ -72,18 +116,21 @@ async fn main() -> anyhow::Result<()> {
     info!("Starting scheduler");
     state.start_scheduler().await?;
 
-    // Initialize global state for compatibility
-    AppState::set_global(state.clone());
-
     // Build UI router with state
     let ui_router = ui_routes::ui_routes().with_state(state.clone());
-    
+
     // Build main router
     let app = Router::new()
         // API routes
         .nest("/api", routes::api_routes(state.clone()))
         // UI routes (HTMX + Askama)
         .merge(ui_router)
+        // Authentication (login required everywhere but the public paths)
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            auth::require_auth,
+        ))
+        .layer(session_layer)
         // Middleware
         .layer(
             tower_http::trace::TraceLayer::new_for_http().make_span_with(
@@ -103,7 +150,12 @@ async fn main() -> anyhow::Result<()> {
     let listener = tokio::net::TcpListener::bind(&args.addr).await?;
     info!(addr = %args.addr, "Server listening");
 
-    axum::serve(listener, app.into_make_service()).await?;
+    // Connection info gives the audit log each request's source address
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+    )
+    .await?;
 
     Ok(())
 }
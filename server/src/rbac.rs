@@ -0,0 +1,436 @@
+//! Role-based access control
+//!
+//! Every user has a role: viewers can only look, operators can also run
+//! tasks, workflows, and update actions, and admins can also change
+//! servers, plugins, notification backends, alert rules, tasks, workflows,
+//! and users. A user can additionally be scoped to server tags, in which
+//! case they may only act on servers carrying one of those tags; plugin
+//! tasks, workflows, and actions on the local host aren't tied to a server
+//! and need an unscoped user.
+//!
+//! Handlers check with [`authorize`] (role only) or [`authorize_on`] (role
+//! plus the servers an action touches). Denied requests get `403 Forbidden`
+//! and are recorded in the audit log.
+
+use std::convert::Infallible;
+use std::fmt;
+use std::net::IpAddr;
+
+use axum::{
+    extract::{FromRequestParts, OptionalFromRequestParts},
+    http::{request::Parts, StatusCode},
+};
+use svrctlrs_database::{
+    models::{
+        ApiTokenScope, AuditOutcome, NewAuditEntry, Server, Task, TaskTarget, User, UserRole,
+    },
+    queries, Database,
+};
+use tracing::{error, warn};
+
+use crate::state::AppState;
+
+/// Actor name of webhook calls authenticated with `WEBHOOK_SECRET`
+const WEBHOOK_ACTOR: &str = "webhook";
+
+/// Actor name of update batch decisions made from notification buttons
+const NOTIFICATION_ACTOR: &str = "notification";
+
+/// The user making a request, put into the request extensions by
+/// [`crate::auth::require_auth`]
+#[derive(Debug, Clone)]
+pub struct CurrentUser {
+    pub id: i64,
+    pub username: String,
+    pub role: UserRole,
+    /// Tags of the servers the user may act on; empty = all servers
+    pub server_tags: Vec<String>,
+    /// Address the request came from, for the audit log
+    pub source_ip: Option<IpAddr>,
+}
+
+impl CurrentUser {
+    pub fn from_user(user: &User) -> Self {
+        Self {
+            id: user.id,
+            username: user.username.clone(),
+            role: user.role(),
+            server_tags: user.get_server_tags(),
+            source_ip: None,
+        }
+    }
+
+    /// A user acting through an API token
+    ///
+    /// The token's scopes cap the user's role: without `admin` the token
+    /// allows at most operator actions, and without `operate` only viewer ones.
+    pub fn for_api_token(user: &User, scopes: &[ApiTokenScope]) -> Self {
+        let cap = if scopes.contains(&ApiTokenScope::Admin) {
+            UserRole::Admin
+        } else if scopes.contains(&ApiTokenScope::Operate) {
+            UserRole::Operator
+        } else {
+            UserRole::Viewer
+        };
+        let mut current = Self::from_user(user);
+        current.role = current.role.min(cap);
+        current
+    }
+
+    /// Caller of a webhook authenticated with the shared webhook token
+    ///
+    /// Webhooks exist to trigger tasks, so the token grants an unscoped
+    /// operator.
+    pub fn webhook(source_ip: Option<IpAddr>) -> Self {
+        Self {
+            id: 0,
+            username: WEBHOOK_ACTOR.to_string(),
+            role: UserRole::Operator,
+            server_tags: Vec::new(),
+            source_ip,
+        }
+    }
+
+    /// Caller of an update batch's notification action link
+    ///
+    /// The batch's own token authenticates the link, which can only approve
+    /// or defer that batch.
+    pub fn notification(source_ip: Option<IpAddr>) -> Self {
+        Self {
+            id: 0,
+            username: NOTIFICATION_ACTOR.to_string(),
+            role: UserRole::Operator,
+            server_tags: Vec::new(),
+            source_ip,
+        }
+    }
+
+    /// Whether the user has at least this role
+    pub fn has_role(&self, role: UserRole) -> bool {
+        self.role >= role
+    }
+
+    /// Whether the user may act on a server
+    pub fn can_access_server(&self, server: &Server) -> bool {
+        self.server_tags.is_empty()
+            || server
+                .get_tags()
+                .iter()
+                .any(|t| self.server_tags.contains(t))
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for CurrentUser {
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<CurrentUser>()
+            .cloned()
+            .ok_or((StatusCode::UNAUTHORIZED, "Authentication required"))
+    }
+}
+
+impl<S: Send + Sync> OptionalFromRequestParts<S> for CurrentUser {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> Result<Option<Self>, Self::Rejection> {
+        Ok(parts.extensions.get::<CurrentUser>().cloned())
+    }
+}
+
+/// Servers an action touches
+#[derive(Debug, Clone)]
+pub enum Targets {
+    /// These servers
+    Servers(Vec<Server>),
+    /// Not tied to particular servers: plugin tasks, workflows, the local host
+    Unrestricted,
+}
+
+impl Targets {
+    /// Servers a task runs on
+    ///
+    /// A task whose target can't be resolved counts as unrestricted.
+    pub async fn for_task(db: &Database, task: &Task) -> Self {
+        let target = match task.target() {
+            Ok(TaskTarget::Local) => return Self::Unrestricted,
+            Ok(target) => target,
+            Err(e) => {
+                warn!(task_id = task.id, error = %e, "Task has an invalid target");
+                return Self::Unrestricted;
+            }
+        };
+        match queries::servers::list_servers(db.pool()).await {
+            Ok(servers) => {
+                Self::Servers(servers.into_iter().filter(|s| target.includes(s)).collect())
+            }
+            Err(e) => {
+                error!(error = %e, "Failed to list servers");
+                Self::Unrestricted
+            }
+        }
+    }
+
+    /// Servers a stored task runs on, unrestricted if there is no such task
+    pub async fn for_task_id(db: &Database, task_id: i64) -> Self {
+        match queries::tasks::get_task(db.pool(), task_id).await {
+            Ok(task) => Self::for_task(db, &task).await,
+            Err(_) => Self::Unrestricted,
+        }
+    }
+
+    /// A registered server, or the local host for `None`
+    pub async fn for_server(db: &Database, server_id: Option<i64>) -> Self {
+        let Some(id) = server_id else {
+            return Self::Unrestricted;
+        };
+        match queries::servers::get_server(db.pool(), id).await {
+            Ok(server) => Self::Servers(vec![server]),
+            Err(e) => {
+                warn!(server_id = id, error = %e, "Failed to get server");
+                Self::Unrestricted
+            }
+        }
+    }
+}
+
+/// A request refused because the user lacks the role or server scope
+#[derive(Debug)]
+pub struct Forbidden(String);
+
+impl fmt::Display for Forbidden {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Permission denied: {}", self.0)
+    }
+}
+
+impl std::error::Error for Forbidden {}
+
+impl From<Forbidden> for (StatusCode, String) {
+    fn from(forbidden: Forbidden) -> Self {
+        (StatusCode::FORBIDDEN, forbidden.to_string())
+    }
+}
+
+/// Check the user has at least `role`
+///
+/// `action` names what is being done (e.g. `server.delete`) and `target`
+/// what it is done to, for the audit log.
+pub async fn authorize(
+    state: &AppState,
+    user: &CurrentUser,
+    role: UserRole,
+    action: &str,
+    target: &str,
+) -> Result<(), Forbidden> {
+    if user.has_role(role) {
+        return Ok(());
+    }
+    Err(deny(
+        state,
+        user,
+        action,
+        target,
+        format!("{} requires the {} role", action, role.as_str()),
+    )
+    .await)
+}
+
+/// Check the user is an operator allowed to act on all of `targets`
+pub async fn authorize_on(
+    state: &AppState,
+    user: &CurrentUser,
+    action: &str,
+    target: &str,
+    targets: &Targets,
+) -> Result<(), Forbidden> {
+    authorize(state, user, UserRole::Operator, action, target).await?;
+    if user.server_tags.is_empty() {
+        return Ok(());
+    }
+
+    let reason = match targets {
+        Targets::Unrestricted => format!(
+            "{} is not limited to servers tagged {}",
+            target,
+            user.server_tags.join(", ")
+        ),
+        Targets::Servers(servers) => match servers.iter().find(|s| !user.can_access_server(s)) {
+            Some(server) => format!(
+                "server {} is outside your scope ({})",
+                server.name,
+                user.server_tags.join(", ")
+            ),
+            None => return Ok(()),
+        },
+    };
+    Err(deny(state, user, action, target, reason).await)
+}
+
+/// Log and audit a denied action
+async fn deny(
+    state: &AppState,
+    user: &CurrentUser,
+    action: &str,
+    target: &str,
+    reason: String,
+) -> Forbidden {
+    warn!(user = %user.username, action, target, "Denied: {}", reason);
+
+    let db = state.db().await;
+    let entry = NewAuditEntry {
+        actor: user.username.clone(),
+        action: action.to_string(),
+        target: Some(target.to_string()),
+        outcome: AuditOutcome::Denied,
+        message: Some(reason.clone()),
+        source_ip: user.source_ip.map(|ip| ip.to_string()),
+        changes: None,
+    };
+    if let Err(e) = queries::audit_log::record_audit_entry(db.pool(), &entry).await {
+        error!(error = %e, "Failed to record denied action in the audit log");
+    }
+
+    Forbidden(reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use serde_json::json;
+    use svrctlrs_database::models::{CreateServer, CreateUser};
+
+    async fn test_state(name: &str) -> AppState {
+        let path =
+            std::env::temp_dir().join(format!("svrctlrs-{}-{}.db", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let url = format!("sqlite:{}", path.display());
+        let db = Database::new(&url).await.unwrap();
+        db.migrate().await.unwrap();
+
+        let config: Config = serde_json::from_value(json!({
+            "database_url": url,
+            "servers": [],
+            "ssh_key_path": null,
+        }))
+        .unwrap();
+        AppState::new(config, db).await.unwrap()
+    }
+
+    fn user(role: UserRole, server_tags: &[&str]) -> CurrentUser {
+        CurrentUser {
+            id: 1,
+            username: role.as_str().to_string(),
+            role,
+            server_tags: server_tags.iter().map(|t| t.to_string()).collect(),
+            source_ip: None,
+        }
+    }
+
+    async fn create_server(state: &AppState, name: &str, tags: &[&str]) -> Server {
+        let db = state.db().await;
+        let server: CreateServer =
+            serde_json::from_value(json!({ "name": name, "host": name, "tags": tags })).unwrap();
+        let id = queries::servers::create_server(db.pool(), &server)
+            .await
+            .unwrap();
+        queries::servers::get_server(db.pool(), id).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_authorize_on_scopes() {
+        let state = test_state("rbac-scopes").await;
+        let web1 = create_server(&state, "web1", &["web"]).await;
+        let db1 = create_server(&state, "db1", &["db"]).await;
+        let in_scope = Targets::Servers(vec![web1.clone()]);
+        let mixed = Targets::Servers(vec![web1, db1]);
+
+        // Unscoped operators may act anywhere
+        let operator = user(UserRole::Operator, &[]);
+        for targets in [&in_scope, &mixed, &Targets::Unrestricted] {
+            assert!(
+                authorize_on(&state, &operator, "task.run", "task 1", targets)
+                    .await
+                    .is_ok()
+            );
+        }
+
+        // Scoped operators only on servers carrying one of their tags
+        let scoped = user(UserRole::Operator, &["web"]);
+        assert!(
+            authorize_on(&state, &scoped, "task.run", "task 1", &in_scope)
+                .await
+                .is_ok()
+        );
+        let denied = authorize_on(&state, &scoped, "task.run", "task 1", &mixed)
+            .await
+            .unwrap_err();
+        assert!(denied
+            .to_string()
+            .contains("server db1 is outside your scope"));
+        let denied = authorize_on(
+            &state,
+            &scoped,
+            "task.run",
+            "task 1",
+            &Targets::Unrestricted,
+        )
+        .await
+        .unwrap_err();
+        assert!(denied
+            .to_string()
+            .contains("is not limited to servers tagged web"));
+
+        // The role is checked before the scope
+        let viewer = user(UserRole::Viewer, &[]);
+        let denied = authorize_on(&state, &viewer, "task.run", "task 1", &in_scope)
+            .await
+            .unwrap_err();
+        assert!(denied.to_string().contains("requires the operator role"));
+    }
+
+    #[tokio::test]
+    async fn test_api_token_scopes_cap_the_role() {
+        let state = test_state("rbac-token-scopes").await;
+        let db = state.db().await;
+        let create = |username: &str, role| CreateUser {
+            username: username.to_string(),
+            password_hash: String::new(),
+            role,
+            server_tags: vec!["web".to_string()],
+        };
+        let admin_id = queries::users::create_user(db.pool(), &create("admin", UserRole::Admin))
+            .await
+            .unwrap();
+        let admin = queries::users::get_user(db.pool(), admin_id).await.unwrap();
+        let viewer_id = queries::users::create_user(db.pool(), &create("viewer", UserRole::Viewer))
+            .await
+            .unwrap();
+        let viewer = queries::users::get_user(db.pool(), viewer_id)
+            .await
+            .unwrap();
+
+        use ApiTokenScope::{Admin, Operate, Read};
+        let role =
+            |user: &User, scopes: &[ApiTokenScope]| CurrentUser::for_api_token(user, scopes).role;
+        assert_eq!(role(&admin, &[Read]), UserRole::Viewer);
+        assert_eq!(role(&admin, &[]), UserRole::Viewer);
+        assert_eq!(role(&admin, &[Read, Operate]), UserRole::Operator);
+        assert_eq!(role(&admin, &[Operate]), UserRole::Operator);
+        assert_eq!(role(&admin, &[Read, Operate, Admin]), UserRole::Admin);
+
+        // Scopes never raise a user above their own role
+        assert_eq!(role(&viewer, &[Read, Operate, Admin]), UserRole::Viewer);
+
+        // The user's server scope still applies
+        let current = CurrentUser::for_api_token(&admin, &[Read, Operate, Admin]);
+        assert_eq!(current.server_tags, vec!["web".to_string()]);
+        assert_eq!(current.username, "admin");
+    }
+}
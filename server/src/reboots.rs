@@ -0,0 +1,405 @@
+//! Rolling reboots
+//!
+//! The updates plugin's `reboot_rollout` task reboots the servers that need
+//! it one at a time, or one tag group at a time, and waits for each to come
+//! back over SSH before moving on. A server that fails to reboot or does not
+//! come back within the boot timeout stops the rollout, and the remaining
+//! servers are left alone.
+//!
+//! Task args (all optional):
+//! - `tags`: comma-separated; only reboot servers with any of these tags
+//! - `group_by_tag`: `true` to reboot servers sharing a tag together
+//! - `force`: `true` to also reboot servers that do not report needing it
+//! - `boot_timeout`: seconds to wait for a server to come back (default 600)
+//!
+//! Only registered servers with a host are rebooted, never the machine
+//! svrctlrs runs on.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+use svrctlrs_core::{NotificationMessage, PluginResult};
+use svrctlrs_database::{models::Server as DbServer, queries};
+use svrctlrs_plugin_updates::{RebootStatus, REBOOT_CHECK_SCRIPT};
+
+use crate::{
+    ssh::{self, SshConfig},
+    state::AppState,
+    updates::UPDATES_SERVICE,
+};
+
+/// Time allowed for a server to come back when the task does not say
+const DEFAULT_BOOT_TIMEOUT_SECS: u64 = 600;
+
+/// Delay between attempts to reach a rebooting server
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Time allowed for each command run during a reboot
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Prints an ID that changes on every boot
+const BOOT_ID_COMMAND: &str = "cat /proc/sys/kernel/random/boot_id";
+
+/// Schedules a reboot and returns, so the SSH session ends cleanly
+///
+/// Fails straight away if sudo would prompt for a password.
+const REBOOT_COMMAND: &str = r#"SUDO=; [ "$(id -u)" = "0" ] || SUDO="sudo -n"; $SUDO true || exit 1; nohup sh -c "sleep 2; $SUDO systemctl reboot || $SUDO shutdown -r now" </dev/null >/dev/null 2>&1 &"#;
+
+/// What happened to a server during a rollout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum RebootOutcome {
+    Rebooted,
+    /// Did not need a reboot
+    Skipped,
+    Failed,
+    /// Left alone because the rollout stopped
+    NotAttempted,
+}
+
+/// Result of rebooting one server
+#[derive(Debug, Clone, Serialize)]
+struct ServerReboot {
+    server: String,
+    outcome: RebootOutcome,
+    /// Why the server needed a reboot
+    reasons: Vec<String>,
+    /// Time from issuing the reboot until SSH answered again
+    downtime_secs: Option<u64>,
+    /// Still reports needing a reboot after coming back
+    still_required: bool,
+    error: Option<String>,
+}
+
+impl ServerReboot {
+    fn new(server: &str, outcome: RebootOutcome) -> Self {
+        Self {
+            server: server.to_string(),
+            outcome,
+            reasons: Vec::new(),
+            downtime_secs: None,
+            still_required: false,
+            error: None,
+        }
+    }
+}
+
+/// Rollout settings from the task args
+struct RolloutOptions {
+    tags: Vec<String>,
+    group_by_tag: bool,
+    force: bool,
+    boot_timeout: Duration,
+}
+
+impl RolloutOptions {
+    fn from_config(config: &HashMap<String, String>) -> Result<Self> {
+        let flag = |key: &str| config.get(key).is_some_and(|v| v == "true");
+        let boot_timeout = match config.get("boot_timeout") {
+            Some(secs) => secs
+                .trim()
+                .parse()
+                .with_context(|| format!("Invalid boot_timeout '{}'", secs))?,
+            None => DEFAULT_BOOT_TIMEOUT_SECS,
+        };
+
+        Ok(Self {
+            tags: config
+                .get("tags")
+                .map(|tags| {
+                    tags.split(',')
+                        .map(|t| t.trim().to_string())
+                        .filter(|t| !t.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            group_by_tag: flag("group_by_tag"),
+            force: flag("force"),
+            boot_timeout: Duration::from_secs(boot_timeout.max(1)),
+        })
+    }
+
+    /// Split the servers to reboot into groups rebooted one after another
+    ///
+    /// Servers are rebooted one at a time unless grouped by tag, in which
+    /// case a server joins the group of its first (matching) tag.
+    fn plan(&self, servers: &[DbServer]) -> Vec<Vec<DbServer>> {
+        let mut groups: Vec<(Option<String>, Vec<DbServer>)> = Vec::new();
+
+        for server in servers.iter().filter(|s| s.enabled && s.host.is_some()) {
+            let tags = server.get_tags();
+            let matching: Vec<&String> = tags
+                .iter()
+                .filter(|t| self.tags.is_empty() || self.tags.contains(t))
+                .collect();
+            if !self.tags.is_empty() && matching.is_empty() {
+                continue;
+            }
+
+            let key = matching
+                .first()
+                .filter(|_| self.group_by_tag)
+                .map(|t| t.to_string());
+            match groups.iter_mut().find(|(k, _)| key.is_some() && *k == key) {
+                Some((_, group)) => group.push(server.clone()),
+                None => groups.push((key, vec![server.clone()])),
+            }
+        }
+
+        groups.into_iter().map(|(_, group)| group).collect()
+    }
+}
+
+/// Run the `reboot_rollout` task
+pub async fn run_rollout(
+    state: &AppState,
+    config: &HashMap<String, String>,
+) -> Result<PluginResult> {
+    let options = RolloutOptions::from_config(config)?;
+    let servers = {
+        let db = state.db().await;
+        queries::servers::list_servers(db.pool())
+            .await
+            .context("Failed to load servers for reboot rollout")?
+    };
+    let groups = options.plan(&servers);
+    info!("Rolling reboot over {} groups", groups.len());
+
+    let mut results = Vec::new();
+    let mut stopped_at = None;
+
+    for group in groups {
+        if stopped_at.is_some() {
+            results.extend(
+                group
+                    .iter()
+                    .map(|server| ServerReboot::new(&server.name, RebootOutcome::NotAttempted)),
+            );
+            continue;
+        }
+
+        let mut running = JoinSet::new();
+        for (index, server) in group.into_iter().enumerate() {
+            let (force, boot_timeout) = (options.force, options.boot_timeout);
+            running
+                .spawn(async move { (index, reboot_server(&server, force, boot_timeout).await) });
+        }
+
+        let mut group_results = Vec::new();
+        while let Some(joined) = running.join_next().await {
+            match joined {
+                Ok(result) => group_results.push(result),
+                Err(e) => error!("Server reboot panicked: {}", e),
+            }
+        }
+        group_results.sort_by_key(|(index, _)| *index);
+
+        for (_, result) in group_results {
+            if result.outcome == RebootOutcome::Failed && stopped_at.is_none() {
+                warn!("Stopping reboot rollout: {} failed", result.server);
+                stopped_at = Some(result.server.clone());
+            }
+            results.push(result);
+        }
+    }
+
+    let count = |outcome| results.iter().filter(|r| r.outcome == outcome).count();
+    let (rebooted, skipped, failed) = (
+        count(RebootOutcome::Rebooted),
+        count(RebootOutcome::Skipped),
+        count(RebootOutcome::Failed),
+    );
+
+    let mut message = format!(
+        "Rebooted {} servers ({} did not need it)",
+        rebooted, skipped
+    );
+    if let Some(server) = &stopped_at {
+        message.push_str(&format!(
+            "; stopped after {} failed, {} servers not attempted",
+            server,
+            count(RebootOutcome::NotAttempted)
+        ));
+    }
+
+    if rebooted > 0 || failed > 0 {
+        send_rollout_notification(state, &results, stopped_at.as_deref()).await;
+    }
+
+    Ok(PluginResult {
+        success: failed == 0,
+        message,
+        data: Some(serde_json::json!({
+            "servers": results,
+            "rebooted": rebooted,
+            "skipped": skipped,
+            "failed": failed,
+            "stopped": stopped_at.is_some(),
+        })),
+        metrics: None,
+    })
+}
+
+/// Reboot a server if it needs it and wait until it is reachable again
+async fn reboot_server(server: &DbServer, force: bool, boot_timeout: Duration) -> ServerReboot {
+    let ssh_config = SshConfig {
+        host: server.host.clone().unwrap_or_default(),
+        port: server.port as u16,
+        username: server.username.clone(),
+        key_path: server.ssh_key_path.clone(),
+        timeout: Duration::from_secs(server.connection_timeout.max(1) as u64),
+    };
+    let mut result = ServerReboot::new(&server.name, RebootOutcome::Failed);
+
+    let status = match run(&ssh_config, REBOOT_CHECK_SCRIPT).await {
+        Ok(output) => RebootStatus::parse(&output),
+        Err(e) => {
+            result.error = Some(format!(
+                "Failed to check whether a reboot is required: {:#}",
+                e
+            ));
+            return result;
+        }
+    };
+    result.reasons = status.reasons;
+    if !status.required && !force {
+        result.outcome = RebootOutcome::Skipped;
+        return result;
+    }
+
+    let started = Instant::now();
+    if let Err(e) = reboot_and_wait(&ssh_config, &server.name, boot_timeout).await {
+        result.error = Some(format!("{:#}", e));
+        return result;
+    }
+    result.downtime_secs = Some(started.elapsed().as_secs());
+    result.outcome = RebootOutcome::Rebooted;
+
+    match run(&ssh_config, REBOOT_CHECK_SCRIPT).await {
+        Ok(output) => result.still_required = RebootStatus::parse(&output).required,
+        Err(e) => warn!("Failed to re-check {} after reboot: {:#}", server.name, e),
+    }
+    if result.still_required {
+        warn!("{} still requires a reboot after rebooting", server.name);
+    }
+
+    result
+}
+
+/// Issue a reboot and wait for the server to answer SSH from a new boot
+async fn reboot_and_wait(ssh_config: &SshConfig, name: &str, boot_timeout: Duration) -> Result<()> {
+    let boot_id = run(ssh_config, BOOT_ID_COMMAND)
+        .await
+        .context("Failed to read boot ID")?;
+
+    info!("Rebooting {}", name);
+    run(ssh_config, REBOOT_COMMAND)
+        .await
+        .context("Failed to reboot")?;
+
+    let deadline = Instant::now() + boot_timeout;
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        if Instant::now() >= deadline {
+            anyhow::bail!(
+                "Did not come back within {} seconds",
+                boot_timeout.as_secs()
+            );
+        }
+        // Unreachable while it restarts; the old boot ID means it has not gone down yet
+        if ssh::test_connection(ssh_config).await.is_err() {
+            continue;
+        }
+        match run(ssh_config, BOOT_ID_COMMAND).await {
+            Ok(id) if id.trim() != boot_id.trim() => {
+                info!("{} is back up", name);
+                return Ok(());
+            }
+            _ => continue,
+        }
+    }
+}
+
+/// Run a command over SSH, failing on a non-zero exit code
+async fn run(ssh_config: &SshConfig, command: &str) -> Result<String> {
+    let cancel = CancellationToken::new();
+    let output = tokio::time::timeout(
+        COMMAND_TIMEOUT,
+        ssh::execute_command(ssh_config, command, &cancel, |_, _| {}),
+    )
+    .await
+    .map_err(|_| {
+        anyhow::anyhow!(
+            "Command timed out after {} seconds",
+            COMMAND_TIMEOUT.as_secs()
+        )
+    })??;
+
+    if !output.success {
+        anyhow::bail!(
+            "Command failed with exit code {}: {}",
+            output.exit_code,
+            output.stderr.trim()
+        );
+    }
+    Ok(output.stdout)
+}
+
+/// Report the outcome of a rollout
+async fn send_rollout_notification(
+    state: &AppState,
+    results: &[ServerReboot],
+    stopped_at: Option<&str>,
+) {
+    let rebooted = results
+        .iter()
+        .filter(|r| r.outcome == RebootOutcome::Rebooted)
+        .count();
+    let title = match stopped_at {
+        Some(server) => format!("❌ Reboot rollout stopped at {}", server),
+        None => format!("🔄 Rebooted {} servers", rebooted),
+    };
+
+    let mut body = String::new();
+    for result in results {
+        let line = match result.outcome {
+            RebootOutcome::Rebooted => format!(
+                "✅ **{}**: back after {}s{}",
+                result.server,
+                result.downtime_secs.unwrap_or_default(),
+                if result.still_required {
+                    ", still requires a reboot"
+                } else {
+                    ""
+                }
+            ),
+            RebootOutcome::Skipped => format!("➖ **{}**: no reboot needed", result.server),
+            RebootOutcome::Failed => format!(
+                "❌ **{}**: {}",
+                result.server,
+                result.error.as_deref().unwrap_or("failed")
+            ),
+            RebootOutcome::NotAttempted => format!("⏸️ **{}**: not attempted", result.server),
+        };
+        body.push_str(&line);
+        body.push('\n');
+    }
+
+    let message = NotificationMessage {
+        title,
+        body,
+        priority: if stopped_at.is_some() { 5 } else { 3 },
+        actions: vec![],
+    };
+
+    let manager = state.notification_manager().await;
+    if let Err(e) = manager.send_for_service(UPDATES_SERVICE, &message).await {
+        warn!("Failed to send reboot rollout notification: {}", e);
+    }
+}
@@ -1,10 +1,15 @@
 //! API routes
 
+mod alerts;
 mod api;
+mod executions;
 mod notifications;
 mod plugins;
 mod servers;
+mod updates;
+mod users;
 mod webhooks;
+mod workflows;
 
 use axum::Router;
 
@@ -21,6 +26,16 @@ pub fn api_routes(state: AppState) -> Router {
         .nest("/v1/plugins", plugins::routes())
         // Notification backend management routes
         .nest("/v1/notifications", notifications::routes())
+        // Alert rule management routes
+        .nest("/v1/alerts", alerts::routes())
+        // Running task execution routes
+        .nest("/v1/executions", executions::routes())
+        // Workflow management routes
+        .nest("/v1/workflows", workflows::routes())
+        // Pending package update routes
+        .nest("/v1/updates", updates::routes())
+        // User account routes
+        .nest("/v1/users", users::routes())
         // Webhook routes
         .nest("/webhooks", webhooks::routes())
         .with_state(state)
@@ -0,0 +1,328 @@
+//! Alert rule management API endpoints
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+use serde_json::json;
+use tracing::{error, info, instrument};
+
+use svrctlrs_database::{queries, CreateAlertRule, UpdateAlertRule, UserRole};
+
+use crate::{
+    alerts::validate_rule,
+    audit::Audit,
+    rbac::{authorize, CurrentUser},
+    state::AppState,
+};
+
+/// Default / maximum number of alert events returned
+const DEFAULT_EVENT_LIMIT: i64 = 100;
+const MAX_EVENT_LIMIT: i64 = 1000;
+
+/// Create alerts API router
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/rules", get(list_rules).post(create_rule))
+        .route(
+            "/rules/{id}",
+            get(get_rule).put(update_rule).delete(delete_rule),
+        )
+        .route("/active", get(list_active))
+        .route("/events", get(list_events))
+}
+
+/// List all alert rules
+#[instrument(skip(state))]
+async fn list_rules(
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let db = state.database.read().await;
+
+    let rules = queries::list_alert_rules(db.pool()).await.map_err(|e| {
+        error!(error = %e, "Failed to list alert rules");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to list alert rules: {}", e),
+        )
+    })?;
+
+    Ok(Json(json!({
+        "rules": rules
+    })))
+}
+
+/// Get alert rule by ID
+#[instrument(skip(state))]
+async fn get_rule(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let db = state.database.read().await;
+
+    let rule = queries::get_alert_rule(db.pool(), id).await.map_err(|e| {
+        error!(error = %e, id = id, "Failed to get alert rule");
+        (
+            StatusCode::NOT_FOUND,
+            format!("Alert rule not found: {}", e),
+        )
+    })?;
+
+    Ok(Json(rule))
+}
+
+/// Create a new alert rule
+#[instrument(skip(state, user))]
+async fn create_rule(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Json(rule): Json<CreateAlertRule>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "alert_rule.create",
+        &rule.name,
+    )
+    .await?;
+
+    info!(name = %rule.name, metric = %rule.metric_name, "Creating alert rule");
+
+    if rule.name.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Rule name is required".to_string()));
+    }
+    validate_rule(
+        &rule.metric_name,
+        &rule.operator,
+        rule.threshold,
+        rule.duration_secs,
+        &rule.severity,
+    )
+    .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let db = state.database.read().await;
+    let pool = db.pool();
+
+    let audit = Audit::new(
+        &state,
+        &user,
+        "alert_rule.create",
+        format!("alert rule {}", rule.name),
+    );
+    let rule_id = audit
+        .check(queries::create_alert_rule(pool, &rule).await)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to create alert rule");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to create alert rule: {}", e),
+            )
+        })?;
+
+    let created = queries::get_alert_rule(pool, rule_id).await.map_err(|e| {
+        error!(error = %e, "Failed to fetch created alert rule");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to fetch created alert rule: {}", e),
+        )
+    })?;
+
+    audit.after(&created).success().await;
+    info!(id = rule_id, "Alert rule created successfully");
+
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+/// Update an alert rule
+#[instrument(skip(state, user))]
+async fn update_rule(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path(id): Path<i64>,
+    Json(update): Json<UpdateAlertRule>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "alert_rule.update",
+        &format!("alert rule {}", id),
+    )
+    .await?;
+
+    info!(id = id, "Updating alert rule");
+
+    let db = state.database.read().await;
+    let pool = db.pool();
+
+    let existing = queries::get_alert_rule(pool, id).await.map_err(|e| {
+        error!(error = %e, id = id, "Alert rule not found");
+        (
+            StatusCode::NOT_FOUND,
+            format!("Alert rule not found: {}", e),
+        )
+    })?;
+
+    // Validate the rule as it will look after the update
+    validate_rule(
+        update
+            .metric_name
+            .as_deref()
+            .unwrap_or(&existing.metric_name),
+        update.operator.as_deref().unwrap_or(&existing.operator),
+        update.threshold.unwrap_or(existing.threshold),
+        update.duration_secs.unwrap_or(existing.duration_secs),
+        update.severity.as_deref().unwrap_or(&existing.severity),
+    )
+    .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let audit = Audit::new(
+        &state,
+        &user,
+        "alert_rule.update",
+        format!("alert rule {} ({})", id, existing.name),
+    )
+    .before(&existing);
+    audit
+        .check(queries::update_alert_rule(pool, id, &update).await)
+        .await
+        .map_err(|e| {
+            error!(error = %e, id = id, "Failed to update alert rule");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to update alert rule: {}", e),
+            )
+        })?;
+
+    let updated = queries::get_alert_rule(pool, id).await.map_err(|e| {
+        error!(error = %e, "Failed to fetch updated alert rule");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to fetch updated alert rule: {}", e),
+        )
+    })?;
+
+    audit.after(&updated).success().await;
+    info!(id = id, "Alert rule updated successfully");
+
+    Ok(Json(updated))
+}
+
+/// Delete an alert rule
+#[instrument(skip(state, user))]
+async fn delete_rule(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "alert_rule.delete",
+        &format!("alert rule {}", id),
+    )
+    .await?;
+
+    info!(id = id, "Deleting alert rule");
+
+    let db = state.database.read().await;
+    let pool = db.pool();
+
+    let rule = queries::get_alert_rule(pool, id).await.map_err(|e| {
+        error!(error = %e, id = id, "Alert rule not found");
+        (
+            StatusCode::NOT_FOUND,
+            format!("Alert rule not found: {}", e),
+        )
+    })?;
+
+    let audit = Audit::new(
+        &state,
+        &user,
+        "alert_rule.delete",
+        format!("alert rule {} ({})", id, rule.name),
+    )
+    .before(&rule);
+    audit
+        .check(queries::delete_alert_rule(pool, id).await)
+        .await
+        .map_err(|e| {
+            error!(error = %e, id = id, "Failed to delete alert rule");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to delete alert rule: {}", e),
+            )
+        })?;
+
+    audit.success().await;
+    info!(id = id, name = %rule.name, "Alert rule deleted successfully");
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "message": "Alert rule deleted successfully",
+            "id": id
+        })),
+    ))
+}
+
+/// List alerts that are currently pending or firing
+#[instrument(skip(state))]
+async fn list_active(
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let db = state.database.read().await;
+
+    let alerts = queries::list_active_alerts(db.pool()).await.map_err(|e| {
+        error!(error = %e, "Failed to list active alerts");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to list active alerts: {}", e),
+        )
+    })?;
+
+    Ok(Json(json!({
+        "alerts": alerts
+    })))
+}
+
+/// Alert event query parameters
+#[derive(Debug, Deserialize)]
+struct EventParams {
+    rule_id: Option<i64>,
+    limit: Option<i64>,
+}
+
+/// List recent firing/resolved events
+#[instrument(skip(state))]
+async fn list_events(
+    State(state): State<AppState>,
+    Query(params): Query<EventParams>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_EVENT_LIMIT)
+        .clamp(1, MAX_EVENT_LIMIT);
+    let db = state.database.read().await;
+
+    let events = queries::list_alert_events(db.pool(), params.rule_id, limit)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to list alert events");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to list alert events: {}", e),
+            )
+        })?;
+
+    Ok(Json(json!({
+        "events": events
+    })))
+}
@@ -1,18 +1,29 @@
 //! REST API endpoints
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
     routing::{get, post},
     Json, Router,
 };
+use chrono::{DateTime, Duration, Utc};
 use serde::Deserialize;
 use serde_json::json;
 use std::collections::HashMap;
+use svrctlrs_database::{
+    models::{CreateTask, MetricQuery, TaskTarget, UpdateTask, UserRole},
+    queries,
+};
 use tracing::{debug, error, info, instrument};
 
-use crate::state::AppState;
+use crate::{
+    audit::Audit,
+    executions::TaskTrigger,
+    executor::MAX_RETRY_ATTEMPTS,
+    rbac::{authorize, authorize_on, CurrentUser, Targets},
+    state::AppState,
+};
 
 /// Create API router
 pub fn routes() -> Router<AppState> {
@@ -22,10 +33,17 @@ pub fn routes() -> Router<AppState> {
         .route("/status", get(server_status))
         // Metrics
         .route("/metrics", get(get_metrics))
+        .route("/metrics/series", get(list_metric_series))
         .route("/metrics/{plugin_id}", get(plugin_metrics))
         // Tasks
-        .route("/tasks", get(list_all_tasks))
+        .route("/tasks", get(list_all_tasks).post(create_task))
         .route("/tasks/execute", post(execute_task))
+        .route(
+            "/tasks/{id}",
+            get(get_task).put(update_task).delete(delete_task),
+        )
+        .route("/tasks/{id}/history", get(get_task_history))
+        .route("/tasks/{id}/run", post(run_task))
 }
 
 /// Health check endpoint
@@ -56,39 +74,227 @@ async fn server_status(State(state): State<AppState>) -> impl IntoResponse {
     }))
 }
 
+/// Default time range when neither `since` nor `range` is given
+const DEFAULT_METRICS_RANGE_SECS: i64 = 24 * 60 * 60;
+
+/// Target number of points per series when `bucket` is omitted
+const AUTO_BUCKET_POINTS: i64 = 300;
+
+/// Default and maximum number of buckets returned
+const DEFAULT_METRICS_LIMIT: i64 = 10_000;
+const MAX_METRICS_LIMIT: i64 = 100_000;
+
+/// Time-series query parameters
+#[derive(Debug, Deserialize)]
+struct MetricsParams {
+    /// Filter by server ID
+    server_id: Option<i64>,
+    /// Filter by server name (alternative to `server_id`)
+    server: Option<String>,
+    /// Filter by plugin
+    plugin_id: Option<String>,
+    /// Exact metric name or prefix ending in `*`
+    metric: Option<String>,
+    /// Range start (RFC 3339)
+    since: Option<DateTime<Utc>>,
+    /// Range end (RFC 3339, defaults to now)
+    until: Option<DateTime<Utc>>,
+    /// Range length ending at `until` when `since` is omitted (e.g. `6h`, `7d`)
+    range: Option<String>,
+    /// Bucket width (e.g. `60`, `5m`, `1h`), `raw` for no down-sampling
+    bucket: Option<String>,
+    /// Maximum number of buckets returned
+    limit: Option<i64>,
+}
+
+/// Parse a duration like `90`, `30s`, `5m`, `1h`, or `7d` into seconds
+fn parse_duration_secs(input: &str) -> Option<i64> {
+    let input = input.trim();
+    let (number, multiplier) = match input.char_indices().last()? {
+        (i, 's') => (&input[..i], 1),
+        (i, 'm') => (&input[..i], 60),
+        (i, 'h') => (&input[..i], 60 * 60),
+        (i, 'd') => (&input[..i], 24 * 60 * 60),
+        _ => (input, 1),
+    };
+    number
+        .parse::<i64>()
+        .ok()
+        .filter(|n| *n > 0)
+        .and_then(|n| n.checked_mul(multiplier))
+}
 
-/// Get system metrics
+/// Query stored metrics as down-sampled time series
 #[instrument(skip(state))]
-async fn get_metrics(State(state): State<AppState>) -> impl IntoResponse {
-    // TODO: Fetch actual metrics from database
-    Json(json!({
-        "metrics": {
-            "plugins_loaded": state.plugins.read().await.plugin_ids().len(),
-            "servers_configured": state.config.servers.len()
-        }
-    }))
+async fn get_metrics(
+    State(state): State<AppState>,
+    Query(params): Query<MetricsParams>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    query_metrics(&state, params).await.map(Json)
 }
 
-/// Get plugin-specific metrics
+/// Query stored metrics for a single plugin
 #[instrument(skip(state))]
 async fn plugin_metrics(
     State(state): State<AppState>,
     Path(plugin_id): Path<String>,
+    Query(mut params): Query<MetricsParams>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
-    let registry = state.plugins.read().await;
+    {
+        let db = state.db().await;
+        queries::plugins::get_plugin(db.pool(), &plugin_id)
+            .await
+            .map_err(|_| {
+                (
+                    StatusCode::NOT_FOUND,
+                    format!("Plugin {} not found", plugin_id),
+                )
+            })?;
+    }
 
-    // Verify plugin exists
-    registry.get(&plugin_id).ok_or_else(|| {
-        (
-            StatusCode::NOT_FOUND,
-            format!("Plugin {} not found", plugin_id),
-        )
+    params.plugin_id = Some(plugin_id);
+    query_metrics(&state, params).await.map(Json)
+}
+
+async fn query_metrics(
+    state: &AppState,
+    params: MetricsParams,
+) -> Result<serde_json::Value, (StatusCode, String)> {
+    let bad_request = |msg: String| (StatusCode::BAD_REQUEST, msg);
+
+    let until = params.until.unwrap_or_else(Utc::now);
+    let since = match (params.since, &params.range) {
+        (Some(since), _) => since,
+        (None, Some(range)) => parse_duration_secs(range)
+            .and_then(Duration::try_seconds)
+            .and_then(|range| until.checked_sub_signed(range))
+            .ok_or_else(|| bad_request(format!("Invalid range: {}", range)))?,
+        (None, None) => until - Duration::seconds(DEFAULT_METRICS_RANGE_SECS),
+    };
+    if since >= until {
+        return Err(bad_request("'since' must be before 'until'".to_string()));
+    }
+
+    let bucket_secs = match params.bucket.as_deref() {
+        Some("raw") => 1,
+        Some(bucket) => parse_duration_secs(bucket)
+            .ok_or_else(|| bad_request(format!("Invalid bucket: {}", bucket)))?,
+        None => ((until - since).num_seconds() / AUTO_BUCKET_POINTS).max(1),
+    };
+
+    let db = state.db().await;
+    let servers = queries::servers::list_servers(db.pool())
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let server_id = match (params.server_id, &params.server) {
+        (Some(id), _) => Some(id),
+        (None, Some(name)) => Some(
+            servers
+                .iter()
+                .find(|s| &s.name == name)
+                .map(|s| s.id)
+                .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Server {} not found", name)))?,
+        ),
+        (None, None) => None,
+    };
+
+    let query = MetricQuery {
+        plugin_id: params.plugin_id,
+        server_id,
+        metric_name: params.metric,
+        since,
+        until,
+        bucket_secs,
+        limit: params
+            .limit
+            .unwrap_or(DEFAULT_METRICS_LIMIT)
+            .clamp(1, MAX_METRICS_LIMIT),
+    };
+
+    let buckets = queries::metrics::query_metric_buckets(db.pool(), &query)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to query metrics");
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+        })?;
+
+    let server_names: HashMap<i64, &str> =
+        servers.iter().map(|s| (s.id, s.name.as_str())).collect();
+
+    // Buckets arrive ordered by series, group consecutive rows
+    let mut series: Vec<serde_json::Value> = Vec::new();
+    let mut current_key: Option<(Option<i64>, String, String)> = None;
+    let mut points = Vec::new();
+
+    let mut flush = |key: Option<(Option<i64>, String, String)>, points: Vec<serde_json::Value>| {
+        if let Some((server_id, plugin_id, metric_name)) = key {
+            series.push(json!({
+                "server_id": server_id,
+                "server_name": server_id.and_then(|id| server_names.get(&id).copied()),
+                "plugin_id": plugin_id,
+                "metric_name": metric_name,
+                "points": points,
+            }));
+        }
+    };
+
+    for bucket in &buckets {
+        let key = (
+            bucket.server_id,
+            bucket.plugin_id.clone(),
+            bucket.metric_name.clone(),
+        );
+        if current_key.as_ref() != Some(&key) {
+            flush(current_key.take(), std::mem::take(&mut points));
+            current_key = Some(key);
+        }
+        points.push(json!({
+            "timestamp": bucket.start_time(),
+            "avg": bucket.avg_value,
+            "min": bucket.min_value,
+            "max": bucket.max_value,
+            "count": bucket.sample_count,
+        }));
+    }
+    flush(current_key, points);
+
+    Ok(json!({
+        "since": since,
+        "until": until,
+        "bucket_secs": bucket_secs,
+        "truncated": buckets.len() as i64 >= query.limit,
+        "series": series,
+    }))
+}
+
+/// Metric series filter parameters
+#[derive(Debug, Deserialize)]
+struct MetricSeriesParams {
+    plugin_id: Option<String>,
+    server_id: Option<i64>,
+}
+
+/// List the metric series that have been recorded
+#[instrument(skip(state))]
+async fn list_metric_series(
+    State(state): State<AppState>,
+    Query(params): Query<MetricSeriesParams>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let db = state.db().await;
+    let series = queries::metrics::list_metric_series(
+        db.pool(),
+        params.plugin_id.as_deref(),
+        params.server_id,
+    )
+    .await
+    .map_err(|e| {
+        error!(error = %e, "Failed to list metric series");
+        (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
     })?;
 
-    // TODO: Fetch actual metrics from database
     Ok(Json(json!({
-        "plugin_id": plugin_id,
-        "metrics": {}
+        "series": series
     })))
 }
 
@@ -126,44 +332,518 @@ struct ExecuteTaskRequest {
 }
 
 /// Execute a task manually
-#[instrument(skip(state))]
+#[instrument(skip(state, user))]
 async fn execute_task(
     State(state): State<AppState>,
+    user: CurrentUser,
     Json(req): Json<ExecuteTaskRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
-    info!(plugin_id = %req.plugin_id, task_id = %req.task_id, "Manual task execution requested");
+    authorize_on(
+        &state,
+        &user,
+        "plugin_task.run",
+        &format!("{}/{}", req.plugin_id, req.task_id),
+        &Targets::Unrestricted,
+    )
+    .await?;
 
-    let registry = state.plugins.read().await;
+    info!(plugin_id = %req.plugin_id, task_id = %req.task_id, "Manual task execution requested");
 
-    let plugin = registry.get(&req.plugin_id).ok_or_else(|| {
-        (
+    if state.plugins.read().await.get(&req.plugin_id).is_none() {
+        return Err((
             StatusCode::NOT_FOUND,
             format!("Plugin {} not found", req.plugin_id),
+        ));
+    }
+
+    // Run through the executor so the update approval gate applies
+    let audit = Audit::new(
+        &state,
+        &user,
+        "plugin_task.run",
+        format!("{}/{}", req.plugin_id, req.task_id),
+    );
+    let result = crate::executor::run_plugin_task(
+        &state,
+        &req.plugin_id,
+        &req.task_id,
+        HashMap::new(),
+        None,
+    )
+    .await;
+    let result = audit
+        .check(result.map_err(|e| format!("{:#}", e)))
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Task execution failed");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Task execution failed: {}", e),
+            )
+        })?;
+
+    info!(success = result.success, "Task execution completed");
+    if result.success {
+        audit.success().await;
+    } else {
+        audit.failure(&result.message).await;
+    }
+
+    Ok(Json(json!({
+        "success": result.success,
+        "message": result.message,
+        "data": result.data,
+        "metrics": result.metrics
+    })))
+}
+
+/// Validate a task's schedule, catch-up and overlap policies
+fn validate_task_schedule(
+    schedule: &str,
+    catch_up: &str,
+    overlap_policy: &str,
+) -> Result<(), (StatusCode, String)> {
+    use std::str::FromStr;
+
+    cron::Schedule::from_str(schedule).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Invalid cron expression '{}': {}", schedule, e),
         )
     })?;
+    if svrctlrs_scheduler::CatchUpPolicy::parse(catch_up).is_none() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Invalid catch-up policy '{}' (expected skip, once or all)",
+                catch_up
+            ),
+        ));
+    }
+    if svrctlrs_scheduler::OverlapPolicy::parse(overlap_policy).is_none() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Invalid overlap policy '{}' (expected skip, queue or kill)",
+                overlap_policy
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Check that a task's target fits together
+fn validate_task_target(
+    target: &str,
+    server_id: Option<i64>,
+    tags: &[String],
+) -> Result<TaskTarget, (StatusCode, String)> {
+    TaskTarget::from_parts(target, server_id, tags).map_err(|e| (StatusCode::BAD_REQUEST, e))
+}
+
+/// Get a stored task by ID
+#[instrument(skip(state))]
+async fn get_task(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let db = state.database.read().await;
 
-    // Create plugin context
-    let context = svrctlrs_core::PluginContext {
-        servers: state.config.servers.clone(),
-        config: HashMap::new(),
-        notification_manager: state.notification_manager().await,
+    let task = queries::tasks::get_task(db.pool(), id).await.map_err(|e| {
+        error!(error = %e, id = id, "Failed to get task");
+        (StatusCode::NOT_FOUND, format!("Task not found: {}", e))
+    })?;
+
+    Ok(Json(task))
+}
+
+/// Default / maximum number of task history entries returned
+const DEFAULT_HISTORY_LIMIT: i64 = 50;
+const MAX_HISTORY_LIMIT: i64 = 500;
+
+#[derive(Debug, Deserialize)]
+struct HistoryParams {
+    limit: Option<i64>,
+}
+
+/// Get a task's execution history, newest first, including command output
+#[instrument(skip(state))]
+async fn get_task_history(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Query(params): Query<HistoryParams>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_HISTORY_LIMIT)
+        .clamp(1, MAX_HISTORY_LIMIT);
+    let db = state.database.read().await;
+
+    let history = queries::tasks::get_task_history(db.pool(), id, limit)
+        .await
+        .map_err(|e| {
+            error!(error = %e, id = id, "Failed to get task history");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to get task history: {}", e),
+            )
+        })?;
+
+    Ok(Json(json!({
+        "history": history
+    })))
+}
+
+/// Create a stored task and schedule it
+#[instrument(skip(state, user))]
+async fn create_task(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Json(task): Json<CreateTask>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    authorize(&state, &user, UserRole::Admin, "task.create", &task.name).await?;
+
+    info!(name = %task.name, plugin_id = %task.plugin_id, "Creating task");
+
+    validate_task_schedule(&task.schedule, &task.catch_up, &task.overlap_policy)?;
+    validate_task_target(
+        task.target_name(),
+        task.server_id,
+        task.target_tags.as_deref().unwrap_or_default(),
+    )?;
+    if task
+        .retry_attempts
+        .is_some_and(|r| !(0..=MAX_RETRY_ATTEMPTS).contains(&r))
+    {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!(
+                "retry_attempts must be between 0 and {}",
+                MAX_RETRY_ATTEMPTS
+            ),
+        ));
+    }
+
+    let audit = Audit::new(&state, &user, "task.create", format!("task {}", task.name));
+    let db = state.database.read().await;
+    let task_id = audit
+        .check(queries::tasks::create_task(db.pool(), &task).await)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to create task");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to create task: {}", e),
+            )
+        })?;
+    drop(db);
+
+    sync_scheduler(&state, task_id).await?;
+
+    let db = state.database.read().await;
+    let created = queries::tasks::get_task(db.pool(), task_id)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to fetch created task");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to fetch created task: {}", e),
+            )
+        })?;
+
+    audit.after(&created).success().await;
+    info!(id = task_id, "Task created successfully");
+
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+/// Update a stored task; schedule and enabled changes apply immediately
+#[instrument(skip(state, user))]
+async fn update_task(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path(id): Path<i64>,
+    Json(update): Json<UpdateTask>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "task.update",
+        &format!("task {}", id),
+    )
+    .await?;
+
+    info!(id = id, "Updating task");
+
+    let db = state.database.read().await;
+    let existing = queries::tasks::get_task(db.pool(), id).await.map_err(|e| {
+        error!(error = %e, id = id, "Task not found");
+        (StatusCode::NOT_FOUND, format!("Task not found: {}", e))
+    })?;
+
+    validate_task_schedule(
+        update.schedule.as_deref().unwrap_or(&existing.schedule),
+        update.catch_up.as_deref().unwrap_or(&existing.catch_up),
+        update
+            .overlap_policy
+            .as_deref()
+            .unwrap_or(&existing.overlap_policy),
+    )?;
+
+    // Validate the target as it will look after the update
+    let server_id = match update.server_id {
+        Some(0) => None,
+        Some(server_id) => Some(server_id),
+        None => existing.server_id,
+    };
+    let tags = match &update.target_tags {
+        Some(tags) => tags.clone(),
+        None => existing.get_target_tags(),
     };
+    validate_task_target(
+        update.target.as_deref().unwrap_or(&existing.target),
+        server_id,
+        &tags,
+    )?;
+
+    if update
+        .retry_attempts
+        .is_some_and(|r| r > MAX_RETRY_ATTEMPTS)
+    {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!(
+                "retry_attempts must be at most {} (negative to use the server's setting)",
+                MAX_RETRY_ATTEMPTS
+            ),
+        ));
+    }
+
+    let audit = Audit::new(
+        &state,
+        &user,
+        "task.update",
+        format!("task {} ({})", id, existing.name),
+    )
+    .before(&existing);
+    audit
+        .check(queries::tasks::update_task(db.pool(), id, &update).await)
+        .await
+        .map_err(|e| {
+            error!(error = %e, id = id, "Failed to update task");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to update task: {}", e),
+            )
+        })?;
+    drop(db);
 
-    // Execute the task
-    let result = plugin.execute(&req.task_id, &context).await.map_err(|e| {
-        error!(error = %e, "Task execution failed");
+    sync_scheduler(&state, id).await?;
+
+    let db = state.database.read().await;
+    let updated = queries::tasks::get_task(db.pool(), id).await.map_err(|e| {
+        error!(error = %e, "Failed to fetch updated task");
         (
             StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Task execution failed: {}", e),
+            format!("Failed to fetch updated task: {}", e),
         )
     })?;
 
-    info!(success = result.success, "Task execution completed");
+    audit.after(&updated).success().await;
+    info!(id = id, "Task updated successfully");
+
+    Ok(Json(updated))
+}
+
+/// Run a stored task now and wait for it to finish
+///
+/// Tasks that fan out to several servers report each server's result.
+#[instrument(skip(state, user))]
+async fn run_task(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let db = state.database.read().await;
+    let task = queries::tasks::get_task(db.pool(), id).await.map_err(|e| {
+        error!(error = %e, id = id, "Task not found");
+        (StatusCode::NOT_FOUND, format!("Task not found: {}", e))
+    })?;
+    let targets = Targets::for_task(&db, &task).await;
+    drop(db);
+    authorize_on(
+        &state,
+        &user,
+        "task.run",
+        &format!("task {} ({})", id, task.name),
+        &targets,
+    )
+    .await?;
+
+    info!(id = id, "Running task");
+
+    let audit = Audit::new(
+        &state,
+        &user,
+        "task.run",
+        format!("task {} ({})", id, task.name),
+    );
+    let result = crate::executor::execute_task(&state, id, TaskTrigger::Manual).await;
+    let result = audit
+        .check(result.map_err(|e| format!("{:#}", e)))
+        .await
+        .map_err(|e| {
+            error!(error = %e, id = id, "Failed to run task");
+            (
+                StatusCode::BAD_REQUEST,
+                format!("Failed to run task: {}", e),
+            )
+        })?;
+    if result.success {
+        audit.success().await;
+    } else {
+        audit
+            .failure(result.error.as_deref().unwrap_or(result.status.as_str()))
+            .await;
+    }
 
     Ok(Json(json!({
         "success": result.success,
-        "message": result.message,
-        "data": result.data,
-        "metrics": result.metrics
+        "status": result.status,
+        "output": result.output,
+        "error": result.error,
+        "duration_ms": result.duration_ms,
+        "attempts": result.attempts,
+        "servers": result.servers
+    })))
+}
+
+/// Delete a stored task and unschedule it
+#[instrument(skip(state, user))]
+async fn delete_task(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "task.delete",
+        &format!("task {}", id),
+    )
+    .await?;
+
+    info!(id = id, "Deleting task");
+
+    let db = state.database.read().await;
+    let task = queries::tasks::get_task(db.pool(), id).await.map_err(|e| {
+        error!(error = %e, id = id, "Task not found");
+        (StatusCode::NOT_FOUND, format!("Task not found: {}", e))
+    })?;
+
+    let audit = Audit::new(
+        &state,
+        &user,
+        "task.delete",
+        format!("task {} ({})", id, task.name),
+    )
+    .before(&task);
+    audit
+        .check(queries::tasks::delete_task(db.pool(), id).await)
+        .await
+        .map_err(|e| {
+            error!(error = %e, id = id, "Failed to delete task");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to delete task: {}", e),
+            )
+        })?;
+    drop(db);
+
+    sync_scheduler(&state, id).await?;
+
+    audit.success().await;
+    info!(id = id, name = %task.name, "Task deleted successfully");
+
+    Ok(Json(json!({
+        "message": "Task deleted successfully",
+        "id": id
     })))
 }
+
+/// Apply a task change to the running scheduler
+async fn sync_scheduler(state: &AppState, task_id: i64) -> Result<(), (StatusCode, String)> {
+    state.sync_task(task_id).await.map_err(|e| {
+        error!(error = %e, task_id = task_id, "Failed to update scheduler");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Task saved but scheduler update failed: {}", e),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use svrctlrs_database::Database;
+
+    async fn test_state(name: &str) -> AppState {
+        let path =
+            std::env::temp_dir().join(format!("svrctlrs-{}-{}.db", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let url = format!("sqlite:{}", path.display());
+        let db = Database::new(&url).await.unwrap();
+        db.migrate().await.unwrap();
+
+        let config: Config = serde_json::from_value(json!({
+            "database_url": url,
+            "servers": [],
+            "ssh_key_path": null,
+        }))
+        .unwrap();
+        AppState::new(config, db).await.unwrap()
+    }
+
+    fn range_params(range: &str) -> MetricsParams {
+        MetricsParams {
+            server_id: None,
+            server: None,
+            plugin_id: None,
+            metric: None,
+            since: None,
+            until: None,
+            range: Some(range.to_string()),
+            bucket: None,
+            limit: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_duration_secs() {
+        assert_eq!(parse_duration_secs("90"), Some(90));
+        assert_eq!(parse_duration_secs("5m"), Some(300));
+        assert_eq!(parse_duration_secs("7d"), Some(7 * 24 * 60 * 60));
+        assert_eq!(parse_duration_secs("0"), None);
+        assert_eq!(parse_duration_secs("-1h"), None);
+        assert_eq!(parse_duration_secs("fortnight"), None);
+        assert_eq!(parse_duration_secs("9223372036854775807d"), None);
+    }
+
+    #[tokio::test]
+    async fn test_out_of_range_metrics_queries_are_rejected() {
+        let state = test_state("metrics-range").await;
+
+        for range in ["10000000000000", "9223372036854775807", "106751991167300d"] {
+            let err = query_metrics(&state, range_params(range))
+                .await
+                .unwrap_err();
+            assert_eq!(err.0, StatusCode::BAD_REQUEST, "range {}", range);
+        }
+
+        let result = query_metrics(&state, range_params("1h")).await.unwrap();
+        assert_eq!(result["series"], json!([]));
+    }
+}
@@ -0,0 +1,145 @@
+//! Running task execution API endpoints
+
+use std::{convert::Infallible, time::Duration};
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
+    },
+    routing::{get, post},
+    Json, Router,
+};
+use serde_json::json;
+use tokio_stream::{
+    wrappers::{errors::BroadcastStreamRecvError, BroadcastStream, IntervalStream},
+    Stream, StreamExt,
+};
+use tracing::{info, instrument};
+
+use crate::{
+    audit::Audit,
+    executions::OutputLine,
+    executor,
+    rbac::{authorize_on, CurrentUser, Targets},
+    state::AppState,
+};
+
+/// How often the live stream pushes a fresh snapshot
+const STREAM_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Create executions API router
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_executions))
+        .route("/stream", get(stream_executions))
+        .route("/{id}/output", get(stream_output))
+        .route("/{id}/cancel", post(cancel_execution))
+}
+
+/// List in-flight executions with their elapsed time
+#[instrument(skip(state))]
+async fn list_executions(State(state): State<AppState>) -> impl IntoResponse {
+    Json(json!({
+        "executions": state.executions.list()
+    }))
+}
+
+/// Server-sent events stream of in-flight executions
+///
+/// Emits an `executions` event with the full list every second.
+async fn stream_executions(
+    State(state): State<AppState>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let stream = IntervalStream::new(tokio::time::interval(STREAM_INTERVAL)).map(move |_| {
+        let event = Event::default()
+            .event("executions")
+            .json_data(state.executions.list())
+            .unwrap_or_else(|_| Event::default().event("executions").data("[]"));
+        Ok(event)
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+/// Server-sent events stream of a running execution's output
+///
+/// Replays the lines printed so far, then sends each new line as an `output`
+/// event. A `skipped` event reports lines dropped because the client fell
+/// behind, and a final `done` event is sent when the execution finishes.
+async fn stream_output(
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, String)> {
+    let subscription = state.executions.subscribe_output(id).ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            format!("Execution {} is not running", id),
+        )
+    })?;
+
+    let backlog = tokio_stream::iter(subscription.backlog).map(output_event);
+    let live = BroadcastStream::new(subscription.receiver).map(|line| match line {
+        Ok(line) => output_event(line),
+        Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+            Event::default().event("skipped").data(skipped.to_string())
+        }
+    });
+    let done = tokio_stream::once(Event::default().event("done").data("finished"));
+
+    let stream = backlog.chain(live).chain(done).map(Ok);
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+fn output_event(line: OutputLine) -> Event {
+    Event::default()
+        .event("output")
+        .json_data(&line)
+        .unwrap_or_else(|_| Event::default().event("output").data(line.line))
+}
+
+/// Cancel a running execution
+#[instrument(skip(state, user))]
+async fn cancel_execution(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path(id): Path<u64>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let Some(execution) = state.executions.list().into_iter().find(|e| e.id == id) else {
+        return Err((
+            StatusCode::NOT_FOUND,
+            format!("Execution {} is not running", id),
+        ));
+    };
+    let targets = Targets::for_task_id(&*state.db().await, execution.task_id).await;
+    authorize_on(
+        &state,
+        &user,
+        "execution.cancel",
+        &format!("execution {} of task {}", id, execution.task_name),
+        &targets,
+    )
+    .await?;
+
+    info!(id = id, "Cancelling execution");
+
+    let audit = Audit::new(
+        &state,
+        &user,
+        "execution.cancel",
+        format!("execution {} of task {}", id, execution.task_name),
+    );
+    if !executor::cancel_execution(&state, id).await {
+        let message = format!("Execution {} is not running", id);
+        audit.failure(&message).await;
+        return Err((StatusCode::NOT_FOUND, message));
+    }
+    audit.success().await;
+
+    Ok(Json(json!({
+        "success": true,
+        "message": format!("Execution {} cancelled", id)
+    })))
+}
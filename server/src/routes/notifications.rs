@@ -2,29 +2,49 @@ use axum::{
     extract::{Path, State},
     http::StatusCode,
     response::IntoResponse,
-    routing::{delete, get, post, put},
+    routing::get,
     Json, Router,
 };
 use tracing::{error, info, instrument};
 
+use crate::audit::Audit;
+use crate::rbac::{authorize, CurrentUser};
 use crate::state::AppState;
 use svrctlrs_database::models::notification::{
     CreateNotificationBackend, NotificationBackend, UpdateNotificationBackend,
 };
-use svrctlrs_database::queries;
+use svrctlrs_database::models::UserRole;
+use svrctlrs_database::{queries, Database};
 
 /// Create notification backend API router
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/", get(list_backends).post(create_backend))
-        .route("/{id}", get(get_backend).put(update_backend).delete(delete_backend))
+        .route("/types", get(list_backend_types))
+        .route(
+            "/{id}",
+            get(get_backend).put(update_backend).delete(delete_backend),
+        )
 }
 
 /// List all notification backends
-#[instrument(skip(state))]
+///
+/// Backend configs hold credentials (tokens, passwords, webhook URLs), so
+/// only admins may read them.
+#[instrument(skip(state, user))]
 async fn list_backends(
     State(state): State<AppState>,
+    user: CurrentUser,
 ) -> Result<Json<Vec<NotificationBackend>>, (StatusCode, String)> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "notification_backend.view",
+        "notification backends",
+    )
+    .await?;
+
     let db = state.db().await;
     let backends = queries::notifications::list_notification_backends(db.pool())
         .await
@@ -35,12 +55,35 @@ async fn list_backends(
     Ok(Json(backends))
 }
 
-/// Get a notification backend by ID
+/// List the backend types that can be configured
 #[instrument(skip(state))]
+async fn list_backend_types(State(state): State<AppState>) -> Json<Vec<String>> {
+    Json(
+        state
+            .notification_backends
+            .types()
+            .into_iter()
+            .map(String::from)
+            .collect(),
+    )
+}
+
+/// Get a notification backend by ID, admins only like [`list_backends`]
+#[instrument(skip(state, user))]
 async fn get_backend(
     State(state): State<AppState>,
+    user: CurrentUser,
     Path(id): Path<i64>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "notification_backend.view",
+        &format!("notification backend {}", id),
+    )
+    .await?;
+
     let db = state.db().await;
     let backend = queries::notifications::get_notification_backend(db.pool(), id)
         .await
@@ -52,21 +95,48 @@ async fn get_backend(
 }
 
 /// Create a new notification backend
-#[instrument(skip(state, create_backend_input))]
+#[instrument(skip(state, user, create_backend_input))]
 async fn create_backend(
     State(state): State<AppState>,
+    user: CurrentUser,
     Json(create_backend_input): Json<CreateNotificationBackend>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "notification_backend.create",
+        &create_backend_input.name,
+    )
+    .await?;
+
     info!(name = %create_backend_input.name, backend_type = %create_backend_input.backend_type, "Creating notification backend");
+    state
+        .check_notification_backend(
+            &create_backend_input.backend_type,
+            &create_backend_input.name,
+            &create_backend_input.config,
+        )
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
     let db = state.db().await;
-    
-    let id = queries::notifications::create_notification_backend(db.pool(), &create_backend_input)
+
+    let audit = Audit::new(
+        &state,
+        &user,
+        "notification_backend.create",
+        format!("notification backend {}", create_backend_input.name),
+    );
+    let id = audit
+        .check(
+            queries::notifications::create_notification_backend(db.pool(), &create_backend_input)
+                .await,
+        )
         .await
         .map_err(|e| {
             error!(error = %e, "Failed to create notification backend");
             (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
         })?;
-    
+
     // Return created backend
     let backend = queries::notifications::get_notification_backend(db.pool(), id)
         .await
@@ -74,27 +144,63 @@ async fn create_backend(
             error!(error = %e, "Failed to get created notification backend");
             (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
         })?;
-    
+    audit.after(&backend).success().await;
+
     Ok((StatusCode::CREATED, Json(backend)))
 }
 
 /// Update a notification backend
-#[instrument(skip(state, update_backend_input))]
+#[instrument(skip(state, user, update_backend_input))]
 async fn update_backend(
     State(state): State<AppState>,
+    user: CurrentUser,
     Path(id): Path<i64>,
     Json(update_backend_input): Json<UpdateNotificationBackend>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "notification_backend.update",
+        &format!("notification backend {}", id),
+    )
+    .await?;
+
     info!(backend_id = id, "Updating notification backend");
     let db = state.db().await;
-    
-    queries::notifications::update_notification_backend(db.pool(), id, &update_backend_input)
+
+    let existing = find_backend(&db, id).await?;
+    if let Some(config) = &update_backend_input.config {
+        let name = update_backend_input
+            .name
+            .as_deref()
+            .unwrap_or(&existing.name);
+        state
+            .check_notification_backend(&existing.backend_type, name, config)
+            .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    }
+    let audit = Audit::new(
+        &state,
+        &user,
+        "notification_backend.update",
+        format!("notification backend {} ({})", id, existing.name),
+    )
+    .before(&existing);
+    audit
+        .check(
+            queries::notifications::update_notification_backend(
+                db.pool(),
+                id,
+                &update_backend_input,
+            )
+            .await,
+        )
         .await
         .map_err(|e| {
             error!(error = %e, "Failed to update notification backend");
             (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
         })?;
-    
+
     // Return updated backend
     let backend = queries::notifications::get_notification_backend(db.pool(), id)
         .await
@@ -102,26 +208,132 @@ async fn update_backend(
             error!(error = %e, "Failed to get updated notification backend");
             (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
         })?;
-    
+    audit.after(&backend).success().await;
+
     Ok(Json(backend))
 }
 
 /// Delete a notification backend
-#[instrument(skip(state))]
+#[instrument(skip(state, user))]
 async fn delete_backend(
     State(state): State<AppState>,
+    user: CurrentUser,
     Path(id): Path<i64>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "notification_backend.delete",
+        &format!("notification backend {}", id),
+    )
+    .await?;
+
     info!(backend_id = id, "Deleting notification backend");
     let db = state.db().await;
-    
-    queries::notifications::delete_notification_backend(db.pool(), id)
+
+    let existing = find_backend(&db, id).await?;
+    let audit = Audit::new(
+        &state,
+        &user,
+        "notification_backend.delete",
+        format!("notification backend {} ({})", id, existing.name),
+    )
+    .before(&existing);
+    audit
+        .check(queries::notifications::delete_notification_backend(db.pool(), id).await)
         .await
         .map_err(|e| {
             error!(error = %e, "Failed to delete notification backend");
             (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
         })?;
-    
+    audit.success().await;
+
     Ok(StatusCode::NO_CONTENT)
 }
 
+async fn find_backend(db: &Database, id: i64) -> Result<NotificationBackend, (StatusCode, String)> {
+    queries::notifications::get_notification_backend(db.pool(), id)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::NOT_FOUND,
+                format!("Notification backend not found: {}", e),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use serde_json::json;
+
+    async fn test_state(name: &str) -> AppState {
+        let path =
+            std::env::temp_dir().join(format!("svrctlrs-{}-{}.db", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let url = format!("sqlite:{}", path.display());
+        let db = Database::new(&url).await.unwrap();
+        db.migrate().await.unwrap();
+
+        let config: Config = serde_json::from_value(json!({
+            "database_url": url,
+            "servers": [],
+            "ssh_key_path": null,
+        }))
+        .unwrap();
+        AppState::new(config, db).await.unwrap()
+    }
+
+    fn user(role: UserRole) -> CurrentUser {
+        CurrentUser {
+            id: 1,
+            username: role.as_str().to_string(),
+            role,
+            server_tags: Vec::new(),
+            source_ip: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_only_admins_can_read_backend_configs() {
+        let state = test_state("notification-backend-secrets").await;
+        let id = {
+            let db = state.db().await;
+            queries::notifications::create_notification_backend(
+                db.pool(),
+                &CreateNotificationBackend {
+                    backend_type: "slack".to_string(),
+                    name: "team-chat".to_string(),
+                    config: json!({ "url": "https://hooks.slack.com/services/T0/B0/secret" }),
+                    priority: 5,
+                    enabled: true,
+                },
+            )
+            .await
+            .unwrap()
+        };
+
+        for role in [UserRole::Viewer, UserRole::Operator] {
+            let err = list_backends(State(state.clone()), user(role))
+                .await
+                .unwrap_err();
+            assert_eq!(err.0, StatusCode::FORBIDDEN);
+
+            let err = get_backend(State(state.clone()), user(role), Path(id))
+                .await
+                .err()
+                .unwrap();
+            assert_eq!(err.0, StatusCode::FORBIDDEN);
+        }
+
+        let Json(backends) = list_backends(State(state.clone()), user(UserRole::Admin))
+            .await
+            .unwrap();
+        assert_eq!(
+            backends[0].get_config()["url"],
+            "https://hooks.slack.com/services/T0/B0/secret"
+        );
+    }
+}
@@ -8,9 +8,12 @@ use axum::{
 use serde_json::json;
 use tracing::{error, info, instrument};
 
+use crate::audit::Audit;
+use crate::rbac::{authorize, CurrentUser};
 use crate::state::AppState;
 use svrctlrs_database::models::plugin::{Plugin, UpdatePlugin};
-use svrctlrs_database::queries;
+use svrctlrs_database::models::UserRole;
+use svrctlrs_database::{queries, Database};
 
 /// Create plugin API router
 pub fn routes() -> Router<AppState> {
@@ -21,8 +24,14 @@ pub fn routes() -> Router<AppState> {
 }
 
 /// List all plugins
-#[instrument(skip(state))]
-async fn list_plugins(State(state): State<AppState>) -> Result<Json<Vec<Plugin>>, (StatusCode, String)> {
+#[instrument(skip(state, user))]
+async fn list_plugins(
+    State(state): State<AppState>,
+    user: CurrentUser,
+) -> Result<Json<Vec<Plugin>>, (StatusCode, String)> {
+    // Plugin configs hold API keys
+    authorize(&state, &user, UserRole::Admin, "plugin.view", "plugins").await?;
+
     let db = state.db().await;
     let plugins = queries::plugins::list_plugins(db.pool())
         .await
@@ -34,11 +43,21 @@ async fn list_plugins(State(state): State<AppState>) -> Result<Json<Vec<Plugin>>
 }
 
 /// Get a plugin by ID
-#[instrument(skip(state))]
+#[instrument(skip(state, user))]
 async fn get_plugin(
     State(state): State<AppState>,
+    user: CurrentUser,
     Path(id): Path<String>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "plugin.view",
+        &format!("plugin {}", id),
+    )
+    .await?;
+
     let db = state.db().await;
     let plugin = queries::plugins::get_plugin(db.pool(), &id)
         .await
@@ -50,22 +69,36 @@ async fn get_plugin(
 }
 
 /// Update a plugin
-#[instrument(skip(state, update_plugin_input))]
+#[instrument(skip(state, user, update_plugin_input))]
 async fn update_plugin(
     State(state): State<AppState>,
+    user: CurrentUser,
     Path(id): Path<String>,
     Json(update_plugin_input): Json<UpdatePlugin>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "plugin.update",
+        &format!("plugin {}", id),
+    )
+    .await?;
+
     info!(plugin_id = %id, "Updating plugin");
     let db = state.db().await;
-    
-    queries::plugins::update_plugin(db.pool(), &id, &update_plugin_input)
+
+    let existing = find_plugin(&db, &id).await?;
+    let audit =
+        Audit::new(&state, &user, "plugin.update", format!("plugin {}", id)).before(&existing);
+    audit
+        .check(queries::plugins::update_plugin(db.pool(), &id, &update_plugin_input).await)
         .await
         .map_err(|e| {
             error!(error = %e, "Failed to update plugin");
             (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
         })?;
-    
+
     // Return updated plugin
     let plugin = queries::plugins::get_plugin(db.pool(), &id)
         .await
@@ -73,29 +106,125 @@ async fn update_plugin(
             error!(error = %e, "Failed to get updated plugin");
             (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
         })?;
-    
+    audit.after(&plugin).success().await;
+
     Ok(Json(plugin))
 }
 
 /// Toggle plugin enabled status
-#[instrument(skip(state))]
+#[instrument(skip(state, user))]
 async fn toggle_plugin(
     State(state): State<AppState>,
+    user: CurrentUser,
     Path(id): Path<String>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "plugin.toggle",
+        &format!("plugin {}", id),
+    )
+    .await?;
+
     info!(plugin_id = %id, "Toggling plugin");
     let db = state.db().await;
-    
-    let new_status = queries::plugins::toggle_plugin(db.pool(), &id)
+
+    let existing = find_plugin(&db, &id).await?;
+    let audit =
+        Audit::new(&state, &user, "plugin.toggle", format!("plugin {}", id)).before(&existing);
+    let new_status = audit
+        .check(queries::plugins::toggle_plugin(db.pool(), &id).await)
         .await
         .map_err(|e| {
             error!(error = %e, "Failed to toggle plugin");
             (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
         })?;
-    
+    let audit = match queries::plugins::get_plugin(db.pool(), &id).await {
+        Ok(plugin) => audit.after(&plugin),
+        Err(_) => audit,
+    };
+    audit.success().await;
+
     Ok(Json(json!({
         "plugin_id": id,
         "enabled": new_status
     })))
 }
 
+async fn find_plugin(db: &Database, id: &str) -> Result<Plugin, (StatusCode, String)> {
+    queries::plugins::get_plugin(db.pool(), id)
+        .await
+        .map_err(|e| (StatusCode::NOT_FOUND, format!("Plugin not found: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    async fn test_state(name: &str) -> AppState {
+        let path =
+            std::env::temp_dir().join(format!("svrctlrs-{}-{}.db", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let url = format!("sqlite:{}", path.display());
+        let db = Database::new(&url).await.unwrap();
+        db.migrate().await.unwrap();
+
+        let config: Config = serde_json::from_value(json!({
+            "database_url": url,
+            "servers": [],
+            "ssh_key_path": null,
+        }))
+        .unwrap();
+        AppState::new(config, db).await.unwrap()
+    }
+
+    fn user(role: UserRole) -> CurrentUser {
+        CurrentUser {
+            id: 1,
+            username: role.as_str().to_string(),
+            role,
+            server_tags: Vec::new(),
+            source_ip: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_only_admins_can_read_plugin_configs() {
+        let state = test_state("plugin-secrets").await;
+        {
+            let db = state.db().await;
+            let update = UpdatePlugin {
+                enabled: None,
+                config: Some(json!({ "api_key": "owm-secret", "units": "metric" })),
+            };
+            queries::plugins::update_plugin(db.pool(), "weather", &update)
+                .await
+                .unwrap();
+        }
+
+        for role in [UserRole::Viewer, UserRole::Operator] {
+            let err = list_plugins(State(state.clone()), user(role))
+                .await
+                .unwrap_err();
+            assert_eq!(err.0, StatusCode::FORBIDDEN);
+
+            let err = get_plugin(
+                State(state.clone()),
+                user(role),
+                Path("weather".to_string()),
+            )
+            .await
+            .err()
+            .unwrap();
+            assert_eq!(err.0, StatusCode::FORBIDDEN);
+        }
+
+        let Json(plugins) = list_plugins(State(state.clone()), user(UserRole::Admin))
+            .await
+            .unwrap();
+        let weather = plugins.iter().find(|p| p.id == "weather").unwrap();
+        assert_eq!(weather.get_config()["api_key"], "owm-secret");
+    }
+}
@@ -4,36 +4,46 @@ use axum::{
     extract::{Path, State},
     http::StatusCode,
     response::IntoResponse,
-    routing::{delete, get, post, put},
+    routing::{get, post},
     Json, Router,
 };
 use serde_json::json;
 use tracing::{error, info, instrument};
 
-use svrctlrs_database::{queries, CreateServer, UpdateServer};
+use svrctlrs_database::{queries, CreateServer, UpdateServer, UserRole};
 
-use crate::state::AppState;
+use crate::{
+    audit::Audit,
+    rbac::{authorize, authorize_on, CurrentUser, Targets},
+    state::AppState,
+};
 
 /// Create servers API router
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/", get(list_servers).post(create_server))
-        .route("/{id}", get(get_server).put(update_server).delete(delete_server))
+        .route(
+            "/{id}",
+            get(get_server).put(update_server).delete(delete_server),
+        )
         .route("/{id}/test", post(test_server_connection))
 }
 
 /// List all servers
 #[instrument(skip(state))]
-async fn list_servers(State(state): State<AppState>) -> Result<impl IntoResponse, (StatusCode, String)> {
+async fn list_servers(
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
     let db = state.database.read().await;
     let pool = db.pool();
 
-    let servers = queries::list_servers(pool)
-        .await
-        .map_err(|e| {
-            error!(error = %e, "Failed to list servers");
-            (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to list servers: {}", e))
-        })?;
+    let servers = queries::list_servers(pool).await.map_err(|e| {
+        error!(error = %e, "Failed to list servers");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to list servers: {}", e),
+        )
+    })?;
 
     Ok(Json(json!({
         "servers": servers
@@ -49,115 +59,176 @@ async fn get_server(
     let db = state.database.read().await;
     let pool = db.pool();
 
-    let server = queries::get_server(pool, id)
-        .await
-        .map_err(|e| {
-            error!(error = %e, id = id, "Failed to get server");
-            (StatusCode::NOT_FOUND, format!("Server not found: {}", e))
-        })?;
+    let server = queries::get_server(pool, id).await.map_err(|e| {
+        error!(error = %e, id = id, "Failed to get server");
+        (StatusCode::NOT_FOUND, format!("Server not found: {}", e))
+    })?;
 
     Ok(Json(server))
 }
 
 /// Create a new server
-#[instrument(skip(state))]
+#[instrument(skip(state, user))]
 async fn create_server(
     State(state): State<AppState>,
+    user: CurrentUser,
     Json(server): Json<CreateServer>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "server.create",
+        &server.name,
+    )
+    .await?;
+
     info!(name = %server.name, host = %server.host, "Creating server");
 
     let db = state.database.read().await;
     let pool = db.pool();
 
-    let server_id = queries::create_server(pool, &server)
+    let audit = Audit::new(
+        &state,
+        &user,
+        "server.create",
+        format!("server {}", server.name),
+    );
+    let server_id = audit
+        .check(queries::create_server(pool, &server).await)
         .await
         .map_err(|e| {
             error!(error = %e, "Failed to create server");
-            (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to create server: {}", e))
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to create server: {}", e),
+            )
         })?;
 
     // Fetch the created server
-    let created_server = queries::get_server(pool, server_id)
-        .await
-        .map_err(|e| {
-            error!(error = %e, "Failed to fetch created server");
-            (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to fetch created server: {}", e))
-        })?;
-
+    let created_server = queries::get_server(pool, server_id).await.map_err(|e| {
+        error!(error = %e, "Failed to fetch created server");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to fetch created server: {}", e),
+        )
+    })?;
+
+    audit.after(&created_server).success().await;
     info!(id = server_id, "Server created successfully");
 
     Ok((StatusCode::CREATED, Json(created_server)))
 }
 
 /// Update a server
-#[instrument(skip(state))]
+#[instrument(skip(state, user))]
 async fn update_server(
     State(state): State<AppState>,
+    user: CurrentUser,
     Path(id): Path<i64>,
     Json(update): Json<UpdateServer>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "server.update",
+        &format!("server {}", id),
+    )
+    .await?;
+
     info!(id = id, "Updating server");
 
     let db = state.database.read().await;
     let pool = db.pool();
 
     // Verify server exists
-    queries::get_server(pool, id)
-        .await
-        .map_err(|e| {
-            error!(error = %e, id = id, "Server not found");
-            (StatusCode::NOT_FOUND, format!("Server not found: {}", e))
-        })?;
+    let existing = queries::get_server(pool, id).await.map_err(|e| {
+        error!(error = %e, id = id, "Server not found");
+        (StatusCode::NOT_FOUND, format!("Server not found: {}", e))
+    })?;
+    let audit = Audit::new(
+        &state,
+        &user,
+        "server.update",
+        format!("server {} ({})", id, existing.name),
+    )
+    .before(&existing);
 
     // Update server
-    queries::update_server(pool, id, &update)
+    audit
+        .check(queries::update_server(pool, id, &update).await)
         .await
         .map_err(|e| {
             error!(error = %e, id = id, "Failed to update server");
-            (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to update server: {}", e))
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to update server: {}", e),
+            )
         })?;
 
     // Fetch updated server
-    let updated_server = queries::get_server(pool, id)
-        .await
-        .map_err(|e| {
-            error!(error = %e, "Failed to fetch updated server");
-            (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to fetch updated server: {}", e))
-        })?;
-
+    let updated_server = queries::get_server(pool, id).await.map_err(|e| {
+        error!(error = %e, "Failed to fetch updated server");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to fetch updated server: {}", e),
+        )
+    })?;
+
+    audit.after(&updated_server).success().await;
     info!(id = id, "Server updated successfully");
 
     Ok(Json(updated_server))
 }
 
 /// Delete a server
-#[instrument(skip(state))]
+#[instrument(skip(state, user))]
 async fn delete_server(
     State(state): State<AppState>,
+    user: CurrentUser,
     Path(id): Path<i64>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "server.delete",
+        &format!("server {}", id),
+    )
+    .await?;
+
     info!(id = id, "Deleting server");
 
     let db = state.database.read().await;
     let pool = db.pool();
 
     // Verify server exists
-    let server = queries::get_server(pool, id)
-        .await
-        .map_err(|e| {
-            error!(error = %e, id = id, "Server not found");
-            (StatusCode::NOT_FOUND, format!("Server not found: {}", e))
-        })?;
+    let server = queries::get_server(pool, id).await.map_err(|e| {
+        error!(error = %e, id = id, "Server not found");
+        (StatusCode::NOT_FOUND, format!("Server not found: {}", e))
+    })?;
 
     // Delete server
-    queries::delete_server(pool, id)
+    let audit = Audit::new(
+        &state,
+        &user,
+        "server.delete",
+        format!("server {} ({})", id, server.name),
+    )
+    .before(&server);
+    audit
+        .check(queries::delete_server(pool, id).await)
         .await
         .map_err(|e| {
             error!(error = %e, id = id, "Failed to delete server");
-            (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete server: {}", e))
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to delete server: {}", e),
+            )
         })?;
 
+    audit.success().await;
     info!(id = id, name = %server.name, "Server deleted successfully");
 
     Ok((
@@ -165,14 +236,15 @@ async fn delete_server(
         Json(json!({
             "message": "Server deleted successfully",
             "id": id
-        }))
+        })),
     ))
 }
 
 /// Test server SSH connection
-#[instrument(skip(state))]
+#[instrument(skip(state, user))]
 async fn test_server_connection(
     State(state): State<AppState>,
+    user: CurrentUser,
     Path(id): Path<i64>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
     info!(id = id, "Testing server connection");
@@ -180,16 +252,23 @@ async fn test_server_connection(
     let db = state.database.read().await;
     let pool = db.pool();
 
-    let server = queries::get_server(pool, id)
-        .await
-        .map_err(|e| {
-            error!(error = %e, id = id, "Server not found");
-            (StatusCode::NOT_FOUND, format!("Server not found: {}", e))
-        })?;
+    let server = queries::get_server(pool, id).await.map_err(|e| {
+        error!(error = %e, id = id, "Server not found");
+        (StatusCode::NOT_FOUND, format!("Server not found: {}", e))
+    })?;
+
+    authorize_on(
+        &state,
+        &user,
+        "server.test_connection",
+        &format!("server {} ({})", id, server.name),
+        &Targets::Servers(vec![server.clone()]),
+    )
+    .await?;
 
     // TODO: Implement actual SSH connection test
     // For now, just return a mock response
-    
+
     info!(id = id, host = ?server.host, "Connection test completed");
 
     Ok(Json(json!({
@@ -200,4 +279,3 @@ async fn test_server_connection(
         "port": server.port
     })))
 }
-
@@ -0,0 +1,466 @@
+//! Package update API endpoints
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use serde_json::json;
+use svrctlrs_database::{models::UpdateBatchDecision, queries};
+use tracing::{error, info, instrument};
+
+use crate::{
+    audit::Audit,
+    rbac::{authorize_on, CurrentUser, Targets},
+    state::AppState,
+};
+
+/// Default / maximum number of update batches returned
+const DEFAULT_BATCH_LIMIT: i64 = 50;
+const MAX_BATCH_LIMIT: i64 = 500;
+
+/// Create updates API router
+pub fn routes() -> Router<AppState> {
+    let router = Router::new()
+        .route("/", get(list_pending_updates))
+        .route("/batches", get(list_batches))
+        .route("/batches/{id}", get(get_batch))
+        .route("/batches/{id}/{decision}", post(decide_batch))
+        .route("/transactions", get(list_transactions))
+        .route("/transactions/{id}", get(get_transaction));
+
+    #[cfg(feature = "plugin-updates")]
+    let router = router.route("/transactions/{id}/rollback", post(rollback_transaction));
+
+    router
+}
+
+/// Pending update query parameters
+#[derive(Debug, Deserialize)]
+struct PendingUpdateParams {
+    /// Filter by server ID
+    server_id: Option<i64>,
+    /// Filter by server name (alternative to `server_id`)
+    server: Option<String>,
+    /// Only security updates
+    #[serde(default)]
+    security: bool,
+}
+
+/// List the package updates found by the most recent check of each server
+#[instrument(skip(state))]
+async fn list_pending_updates(
+    State(state): State<AppState>,
+    Query(params): Query<PendingUpdateParams>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let db = state.db().await;
+    let pool = db.pool();
+
+    let server_name = match (params.server_id, params.server) {
+        (Some(id), _) => Some(
+            queries::servers::get_server(pool, id)
+                .await
+                .map_err(|e| (StatusCode::NOT_FOUND, format!("Server not found: {}", e)))?
+                .name,
+        ),
+        (None, name) => name,
+    };
+
+    let updates =
+        queries::updates::list_pending_updates(pool, server_name.as_deref(), params.security)
+            .await
+            .map_err(|e| {
+                error!(error = %e, "Failed to list pending updates");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Failed to list pending updates: {}", e),
+                )
+            })?;
+
+    let security = updates.iter().filter(|u| u.is_security).count();
+
+    Ok(Json(json!({
+        "total": updates.len(),
+        "security": security,
+        "updates": updates
+    })))
+}
+
+/// Update batch query parameters
+#[derive(Debug, Deserialize)]
+struct BatchParams {
+    /// Filter by server name
+    server: Option<String>,
+    /// Filter by status (pending, approved, deferred, superseded, applied, failed)
+    status: Option<String>,
+    limit: Option<i64>,
+}
+
+/// List update batches, newest first
+#[instrument(skip(state))]
+async fn list_batches(
+    State(state): State<AppState>,
+    Query(params): Query<BatchParams>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_BATCH_LIMIT)
+        .clamp(1, MAX_BATCH_LIMIT);
+    let db = state.db().await;
+
+    let batches = queries::updates::list_update_batches(
+        db.pool(),
+        params.server.as_deref(),
+        params.status.as_deref(),
+        limit,
+    )
+    .await
+    .map_err(|e| {
+        error!(error = %e, "Failed to list update batches");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to list update batches: {}", e),
+        )
+    })?;
+
+    Ok(Json(json!({
+        "batches": batches
+    })))
+}
+
+/// Get an update batch with its packages
+#[instrument(skip(state))]
+async fn get_batch(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let db = state.db().await;
+
+    let batch = queries::updates::get_update_batch(db.pool(), id)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::NOT_FOUND,
+                format!("Update batch not found: {}", e),
+            )
+        })?;
+    let packages = batch.get_packages();
+
+    Ok(Json(json!({
+        "batch": batch,
+        "packages": packages
+    })))
+}
+
+/// Approve or defer an update batch
+#[instrument(skip(state, user))]
+async fn decide_batch(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path((id, decision)): Path<(i64, String)>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let server_id = {
+        let db = state.db().await;
+        queries::updates::get_update_batch(db.pool(), id)
+            .await
+            .map_err(|e| {
+                (
+                    StatusCode::NOT_FOUND,
+                    format!("Update batch not found: {}", e),
+                )
+            })?
+            .server_id
+    };
+    let targets = Targets::for_server(&*state.db().await, server_id).await;
+    authorize_on(
+        &state,
+        &user,
+        &format!("update_batch.{}", decision),
+        &format!("update batch {}", id),
+        &targets,
+    )
+    .await?;
+
+    record_decision(&state, &user, id, &decision, "api").await
+}
+
+/// Apply an approve / defer decision to an open update batch
+///
+/// Shared by the API and the notification action webhook.
+pub(super) async fn record_decision(
+    state: &AppState,
+    actor: &CurrentUser,
+    id: i64,
+    decision: &str,
+    decided_via: &str,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let parsed = UpdateBatchDecision::parse(decision).ok_or_else(|| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Invalid decision '{}' (expected approve or defer)",
+                decision
+            ),
+        )
+    })?;
+
+    let db = state.db().await;
+    let pool = db.pool();
+
+    let existing = queries::updates::get_update_batch(pool, id)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::NOT_FOUND,
+                format!("Update batch not found: {}", e),
+            )
+        })?;
+    let audit = Audit::new(
+        state,
+        actor,
+        &format!("update_batch.{}", decision),
+        format!("update batch {} ({})", id, existing.server_name),
+    )
+    .before(&existing);
+
+    let decided = audit
+        .check(
+            queries::updates::decide_update_batch(pool, id, parsed, &actor.username, decided_via)
+                .await,
+        )
+        .await
+        .map_err(|e| {
+            error!(error = %e, id = id, "Failed to update update batch");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to update update batch: {}", e),
+            )
+        })?;
+
+    let batch = audit
+        .check(queries::updates::get_update_batch(pool, id).await)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::NOT_FOUND,
+                format!("Update batch not found: {}", e),
+            )
+        })?;
+
+    if !decided {
+        let reason = format!(
+            "Update batch {} is {} and can no longer be changed",
+            id, batch.status
+        );
+        audit.failure(&reason).await;
+        return Err((StatusCode::CONFLICT, reason));
+    }
+    audit.after(&batch).success().await;
+
+    info!(
+        id = id,
+        server = %batch.server_name,
+        status = %batch.status,
+        decided_by = %actor.username,
+        decided_via = %decided_via,
+        "Update batch decided"
+    );
+
+    Ok(Json(json!({
+        "batch": batch
+    })))
+}
+
+/// Update transaction query parameters
+#[derive(Debug, Deserialize)]
+struct TransactionParams {
+    /// Filter by server name
+    server: Option<String>,
+    limit: Option<i64>,
+}
+
+/// List the package transactions made by applying updates, newest first
+#[instrument(skip(state))]
+async fn list_transactions(
+    State(state): State<AppState>,
+    Query(params): Query<TransactionParams>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_BATCH_LIMIT)
+        .clamp(1, MAX_BATCH_LIMIT);
+    let db = state.db().await;
+
+    let transactions =
+        queries::updates::list_update_transactions(db.pool(), params.server.as_deref(), limit)
+            .await
+            .map_err(|e| {
+                error!(error = %e, "Failed to list update transactions");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Failed to list update transactions: {}", e),
+                )
+            })?;
+
+    Ok(Json(json!({
+        "transactions": transactions
+    })))
+}
+
+/// Get an update transaction with its packages
+#[instrument(skip(state))]
+async fn get_transaction(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let db = state.db().await;
+
+    let transaction = queries::updates::get_update_transaction(db.pool(), id)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::NOT_FOUND,
+                format!("Update transaction not found: {}", e),
+            )
+        })?;
+    let packages = transaction.get_packages();
+
+    Ok(Json(json!({
+        "transaction": transaction,
+        "packages": packages
+    })))
+}
+
+/// Roll back the packages of an update transaction
+///
+/// The rollback runs in the background; poll the transaction for its status.
+#[cfg(feature = "plugin-updates")]
+#[instrument(skip(state, user))]
+async fn rollback_transaction(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    use crate::updates::{start_rollback, RollbackStart};
+
+    let transaction = {
+        let db = state.db().await;
+        queries::updates::get_update_transaction(db.pool(), id)
+            .await
+            .map_err(|e| {
+                (
+                    StatusCode::NOT_FOUND,
+                    format!("Update transaction not found: {}", e),
+                )
+            })?
+    };
+    let targets = Targets::for_server(&*state.db().await, transaction.server_id).await;
+    authorize_on(
+        &state,
+        &user,
+        "update_transaction.rollback",
+        &format!("update transaction {} on {}", id, transaction.server_name),
+        &targets,
+    )
+    .await?;
+
+    match start_rollback(&state, transaction, &user).await {
+        Ok(RollbackStart::Started) => Ok((
+            StatusCode::ACCEPTED,
+            Json(json!({
+                "status": "rolling_back",
+                "transaction_id": id
+            })),
+        )),
+        Ok(RollbackStart::Refused(reason)) => Err((StatusCode::CONFLICT, reason)),
+        Err(e) => {
+            error!(error = %e, id = id, "Failed to start rollback");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to start rollback: {:#}", e),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use svrctlrs_database::{
+        models::{AuditFilter, NewPendingUpdate},
+        Database,
+    };
+
+    async fn test_state(name: &str) -> AppState {
+        let path =
+            std::env::temp_dir().join(format!("svrctlrs-{}-{}.db", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let url = format!("sqlite:{}", path.display());
+        let db = Database::new(&url).await.unwrap();
+        db.migrate().await.unwrap();
+
+        let config: Config = serde_json::from_value(json!({
+            "database_url": url,
+            "servers": [],
+            "ssh_key_path": null,
+        }))
+        .unwrap();
+        AppState::new(config, db).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_notification_decisions_are_audited() {
+        let state = test_state("update-batch-audit").await;
+        let id = {
+            let db = state.db().await;
+            let updates = vec![NewPendingUpdate {
+                name: "openssl".to_string(),
+                new_version: Some("3.0.13".to_string()),
+                ..Default::default()
+            }];
+            queries::updates::record_update_batch(db.pool(), None, "web1", &updates, "batch-token")
+                .await
+                .unwrap()
+                .unwrap()
+        };
+
+        let actor = CurrentUser::notification(None);
+        let Json(decided) = record_decision(&state, &actor, id, "approve", "notification")
+            .await
+            .unwrap();
+        assert_eq!(decided["batch"]["decided_by"], "notification");
+        let err = record_decision(&state, &actor, id, "skip", "notification")
+            .await
+            .unwrap_err();
+        assert_eq!(err.0, StatusCode::BAD_REQUEST);
+        let Json(decided) = record_decision(&state, &actor, id, "defer", "notification")
+            .await
+            .unwrap();
+        assert_eq!(decided["batch"]["status"], "deferred");
+
+        let db = state.db().await;
+
+        let filter = AuditFilter {
+            action: Some("update_batch.".to_string()),
+            ..Default::default()
+        };
+        let entries = queries::audit_log::list_audit_entries(db.pool(), &filter, 10)
+            .await
+            .unwrap();
+        let outcomes: Vec<_> = entries
+            .iter()
+            .map(|e| (e.actor.as_str(), e.action.as_str(), e.outcome.as_str()))
+            .collect();
+        assert_eq!(
+            outcomes,
+            vec![
+                ("notification", "update_batch.defer", "success"),
+                ("notification", "update_batch.approve", "success"),
+            ]
+        );
+    }
+}
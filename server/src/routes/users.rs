@@ -0,0 +1,299 @@
+//! User account API
+//!
+//! Only admins can manage users. The last enabled admin can't be deleted,
+//! disabled or demoted, so there is always someone left to manage the rest.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+use serde_json::json;
+use tracing::{error, info, instrument};
+
+use crate::{
+    audit::Audit,
+    auth::hash_password,
+    rbac::{authorize, CurrentUser},
+    state::AppState,
+};
+use svrctlrs_database::{
+    models::{CreateUser, UpdateUser, User, UserRole},
+    queries, Database,
+};
+
+/// Create user API router
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_users).post(create_user))
+        .route("/{id}", get(get_user).put(update_user).delete(delete_user))
+}
+
+/// Create user request
+#[derive(Debug, Deserialize)]
+struct CreateUserRequest {
+    username: String,
+    password: String,
+    #[serde(default = "default_role")]
+    role: UserRole,
+    /// Tags of the servers the user may act on; empty = all servers
+    #[serde(default)]
+    server_tags: Vec<String>,
+}
+
+fn default_role() -> UserRole {
+    UserRole::Viewer
+}
+
+/// Update user request; omitted fields are left unchanged
+#[derive(Debug, Deserialize)]
+struct UpdateUserRequest {
+    password: Option<String>,
+    role: Option<UserRole>,
+    server_tags: Option<Vec<String>>,
+    enabled: Option<bool>,
+}
+
+/// List all users
+#[instrument(skip(state, user))]
+async fn list_users(
+    State(state): State<AppState>,
+    user: CurrentUser,
+) -> Result<Json<Vec<User>>, (StatusCode, String)> {
+    authorize(&state, &user, UserRole::Admin, "user.list", "users").await?;
+
+    let db = state.db().await;
+    let users = queries::users::list_users(db.pool()).await.map_err(|e| {
+        error!(error = %e, "Failed to list users");
+        (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+    })?;
+    Ok(Json(users))
+}
+
+/// Get a user by ID
+#[instrument(skip(state, user))]
+async fn get_user(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path(id): Path<i64>,
+) -> Result<Json<User>, (StatusCode, String)> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "user.get",
+        &format!("user {}", id),
+    )
+    .await?;
+
+    let db = state.db().await;
+    Ok(Json(find_user(&db, id).await?))
+}
+
+/// Create a user
+#[instrument(skip(state, user, request), fields(username = %request.username))]
+async fn create_user(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Json(request): Json<CreateUserRequest>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "user.create",
+        &request.username,
+    )
+    .await?;
+
+    let username = request.username.trim();
+    if username.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Username is required".to_string()));
+    }
+    if request.password.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Password is required".to_string()));
+    }
+
+    let create = CreateUser {
+        username: username.to_string(),
+        password_hash: password_hash(&request.password)?,
+        role: request.role,
+        server_tags: request.server_tags,
+    };
+
+    let db = state.db().await;
+    if queries::users::get_user_by_username(db.pool(), username)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .is_some()
+    {
+        return Err((
+            StatusCode::CONFLICT,
+            format!("A user named '{}' already exists", username),
+        ));
+    }
+
+    let audit = Audit::new(&state, &user, "user.create", format!("user {}", username));
+    let id = audit
+        .check(queries::users::create_user(db.pool(), &create).await)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to create user");
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+        })?;
+
+    let created = find_user(&db, id).await?;
+    audit.after(&created).success().await;
+
+    info!(id, username, role = create.role.as_str(), "User created");
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+/// Update a user's password, role, server scope or enabled state
+#[instrument(skip(state, user, request))]
+async fn update_user(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path(id): Path<i64>,
+    Json(request): Json<UpdateUserRequest>,
+) -> Result<Json<User>, (StatusCode, String)> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "user.update",
+        &format!("user {}", id),
+    )
+    .await?;
+
+    let db = state.db().await;
+    let existing = find_user(&db, id).await?;
+
+    let loses_admin =
+        request.role.is_some_and(|r| r != UserRole::Admin) || request.enabled == Some(false);
+    if loses_admin {
+        ensure_not_last_admin(&db, &existing).await?;
+    }
+
+    let password_hash = match request.password.as_deref() {
+        Some("") => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "Password can't be empty".to_string(),
+            ));
+        }
+        Some(password) => Some(password_hash(password)?),
+        None => None,
+    };
+    let update = UpdateUser {
+        password_hash,
+        role: request.role,
+        server_tags: request.server_tags,
+        enabled: request.enabled,
+    };
+
+    let audit = Audit::new(
+        &state,
+        &user,
+        "user.update",
+        format!("user {} ({})", id, existing.username),
+    )
+    .before(&existing);
+    audit
+        .check(queries::users::update_user(db.pool(), id, &update).await)
+        .await
+        .map_err(|e| {
+            error!(error = %e, id, "Failed to update user");
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+        })?;
+
+    let updated = find_user(&db, id).await?;
+    audit.after(&updated).success().await;
+
+    info!(id, username = %existing.username, "User updated");
+    Ok(Json(updated))
+}
+
+/// Delete a user
+#[instrument(skip(state, user))]
+async fn delete_user(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "user.delete",
+        &format!("user {}", id),
+    )
+    .await?;
+
+    if id == user.id {
+        return Err((
+            StatusCode::CONFLICT,
+            "You can't delete your own account".to_string(),
+        ));
+    }
+
+    let db = state.db().await;
+    let existing = find_user(&db, id).await?;
+    ensure_not_last_admin(&db, &existing).await?;
+
+    let audit = Audit::new(
+        &state,
+        &user,
+        "user.delete",
+        format!("user {} ({})", id, existing.username),
+    )
+    .before(&existing);
+    audit
+        .check(queries::users::delete_user(db.pool(), id).await)
+        .await
+        .map_err(|e| {
+            error!(error = %e, id, "Failed to delete user");
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+        })?;
+
+    audit.success().await;
+
+    info!(id, username = %existing.username, "User deleted");
+    Ok(Json(json!({
+        "message": "User deleted successfully",
+        "id": id
+    })))
+}
+
+async fn find_user(db: &Database, id: i64) -> Result<User, (StatusCode, String)> {
+    queries::users::get_user(db.pool(), id)
+        .await
+        .map_err(|e| (StatusCode::NOT_FOUND, format!("User not found: {}", e)))
+}
+
+fn password_hash(password: &str) -> Result<String, (StatusCode, String)> {
+    hash_password(password).map_err(|e| {
+        error!(error = %e, "Failed to hash password");
+        (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+    })
+}
+
+/// Refuse to remove the last enabled admin
+async fn ensure_not_last_admin(db: &Database, user: &User) -> Result<(), (StatusCode, String)> {
+    if !user.enabled || user.role() != UserRole::Admin {
+        return Ok(());
+    }
+    let admins = queries::users::count_enabled_admins(db.pool())
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if admins <= 1 {
+        return Err((
+            StatusCode::CONFLICT,
+            format!("'{}' is the last enabled admin", user.username),
+        ));
+    }
+    Ok(())
+}
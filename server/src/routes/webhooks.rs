@@ -1,7 +1,7 @@
 //! Webhook endpoints for remote triggering
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::{HeaderMap, StatusCode},
     response::IntoResponse,
     routing::post,
@@ -12,18 +12,28 @@ use serde_json::json;
 use std::collections::HashMap;
 use tracing::{debug, error, info, instrument, warn};
 
-use crate::state::AppState;
+use crate::{
+    audit::{Audit, ClientIp},
+    executions::TaskTrigger,
+    rbac::{authorize_on, CurrentUser, Targets},
+    state::AppState,
+};
 
 /// Create webhook router
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/trigger/{plugin_id}/{task_id}", post(trigger_task))
+        .route("/workflows/{id}", post(trigger_workflow))
         .route("/docker/health", post(trigger_docker_health))
         .route("/docker/cleanup", post(trigger_docker_cleanup))
         .route("/docker/analysis", post(trigger_docker_analysis))
         .route("/updates/check", post(trigger_updates_check))
         .route("/updates/apply", post(trigger_updates_apply))
         .route("/updates/cleanup", post(trigger_os_cleanup))
+        .route(
+            "/updates/batches/{id}/{decision}",
+            post(decide_update_batch),
+        )
 }
 
 /// Webhook trigger request body
@@ -31,242 +41,341 @@ pub fn routes() -> Router<AppState> {
 struct TriggerRequest {
     #[serde(default)]
     token: Option<String>,
-    #[serde(default)]
-    payload: Option<serde_json::Value>,
 }
 
 /// Verify webhook token
-fn verify_token(headers: &HeaderMap, request_token: &Option<String>) -> bool {
-    let expected_token = std::env::var("WEBHOOK_SECRET").ok();
+///
+/// Webhooks are disabled until `WEBHOOK_SECRET` is set: without it every
+/// request is refused.
+fn verify_token(
+    headers: &HeaderMap,
+    request_token: &Option<String>,
+) -> Result<(), (StatusCode, String)> {
+    let Some(expected) = std::env::var("WEBHOOK_SECRET")
+        .ok()
+        .filter(|secret| !secret.is_empty())
+    else {
+        warn!("WEBHOOK_SECRET not configured - rejecting webhook request");
+        return Err((
+            StatusCode::FORBIDDEN,
+            "Webhooks are disabled until WEBHOOK_SECRET is set".to_string(),
+        ));
+    };
 
-    if expected_token.is_none() {
-        // No token configured, allow all requests (development mode)
-        warn!("WEBHOOK_SECRET not configured - accepting all webhook requests");
-        return true;
+    // Check Authorization header first, then the token in the request body
+    let header_token = headers
+        .get("Authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    if [header_token, request_token.as_deref()]
+        .into_iter()
+        .flatten()
+        .any(|token| constant_time_eq(token, &expected))
+    {
+        return Ok(());
     }
 
-    let expected = expected_token.as_ref().unwrap();
-
-    // Check Authorization header first
-    if let Some(auth_header) = headers.get("Authorization") {
-        if let Ok(value) = auth_header.to_str() {
-            if let Some(token) = value.strip_prefix("Bearer ") {
-                if token == expected {
-                    return true;
-                }
-            }
-        }
-    }
+    warn!("Unauthorized webhook request");
+    Err((
+        StatusCode::UNAUTHORIZED,
+        "Invalid or missing token".to_string(),
+    ))
+}
 
-    // Check token in request body
-    if let Some(token) = request_token {
-        if token == expected {
-            return true;
-        }
+/// Compare secrets without returning early on the first difference, so
+/// response times don't reveal how much of a guess was right
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |diff, (x, y)| diff | (x ^ y))
+            == 0
+}
+
+/// Who is calling a webhook
+///
+/// A logged-in user acts as themselves; otherwise the caller must present
+/// the webhook token and acts as the webhook actor.
+fn webhook_caller(
+    user: Option<CurrentUser>,
+    client_ip: ClientIp,
+    headers: &HeaderMap,
+    request_token: &Option<String>,
+) -> Result<CurrentUser, (StatusCode, String)> {
+    if let Some(user) = user {
+        return Ok(user);
     }
+    verify_token(headers, request_token)?;
+    Ok(CurrentUser::webhook(client_ip.0))
+}
 
-    false
+/// Check the caller may trigger a plugin task
+///
+/// Plugin tasks run against every server, so scoped users can't trigger them.
+async fn authorize_trigger(
+    state: &AppState,
+    caller: &CurrentUser,
+    plugin_id: &str,
+    task_id: &str,
+) -> Result<(), (StatusCode, String)> {
+    authorize_on(
+        state,
+        caller,
+        &format!("webhook.{}", task_id),
+        &format!("{}/{}", plugin_id, task_id),
+        &Targets::Unrestricted,
+    )
+    .await?;
+    Ok(())
 }
 
 /// Generic task trigger endpoint
 #[instrument(skip(state, headers))]
 async fn trigger_task(
     State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    client_ip: ClientIp,
     Path((plugin_id, task_id)): Path<(String, String)>,
     headers: HeaderMap,
     Json(req): Json<TriggerRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
     info!(plugin_id = %plugin_id, task_id = %task_id, "Webhook trigger received");
 
-    // Verify token
-    if !verify_token(&headers, &req.token) {
-        warn!("Unauthorized webhook request");
-        return Err((
-            StatusCode::UNAUTHORIZED,
-            "Invalid or missing token".to_string(),
-        ));
-    }
-
-    let registry = state.plugins.read().await;
+    let caller = webhook_caller(user, client_ip, &headers, &req.token)?;
 
-    let plugin = registry.get(&plugin_id).ok_or_else(|| {
-        (
-            StatusCode::NOT_FOUND,
-            format!("Plugin {} not found", plugin_id),
-        )
-    })?;
-
-    // Create plugin context
-    let context = svrctlrs_core::PluginContext {
-        servers: state.config.servers.clone(),
-        config: HashMap::new(),
-        notification_manager: state.notification_manager().await,
-    };
-
-    // Execute the task
-    let result = plugin.execute(&task_id, &context).await.map_err(|e| {
-        error!(error = %e, "Webhook task execution failed");
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Task execution failed: {}", e),
-        )
-    })?;
-
-    info!(success = result.success, "Webhook task execution completed");
+    trigger_specific_task(state, &caller, &plugin_id, &task_id).await
+}
 
-    Ok(Json(json!({
-        "success": result.success,
-        "message": result.message,
-        "executed_at": chrono::Utc::now().to_rfc3339()
-    })))
+/// Workflow trigger endpoint; starts a run and returns its ID
+#[instrument(skip(state, headers))]
+async fn trigger_workflow(
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    client_ip: ClientIp,
+    Path(id): Path<i64>,
+    headers: HeaderMap,
+    Json(req): Json<TriggerRequest>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    info!(workflow_id = id, "Workflow webhook received");
+
+    let caller = webhook_caller(user, client_ip, &headers, &req.token)?;
+    authorize_on(
+        &state,
+        &caller,
+        "webhook.workflow",
+        &format!("workflow {}", id),
+        &Targets::Unrestricted,
+    )
+    .await?;
+
+    let audit = Audit::new(
+        &state,
+        &caller,
+        "webhook.workflow",
+        format!("workflow {}", id),
+    );
+    let run_id = crate::workflows::start_workflow(&state, id, TaskTrigger::Webhook).await;
+    let run_id = audit
+        .check(run_id.map_err(|e| format!("{:#}", e)))
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Webhook workflow start failed");
+            (
+                StatusCode::BAD_REQUEST,
+                format!("Failed to start workflow: {}", e),
+            )
+        })?;
+    audit.success().await;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(json!({
+            "run_id": run_id,
+            "started_at": chrono::Utc::now().to_rfc3339()
+        })),
+    ))
 }
 
 /// Trigger Docker health check
 #[instrument(skip(state, headers))]
 async fn trigger_docker_health(
     State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    client_ip: ClientIp,
     headers: HeaderMap,
     Json(req): Json<TriggerRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
     debug!("Docker health check webhook triggered");
 
-    if !verify_token(&headers, &req.token) {
-        return Err((
-            StatusCode::UNAUTHORIZED,
-            "Invalid or missing token".to_string(),
-        ));
-    }
+    let caller = webhook_caller(user, client_ip, &headers, &req.token)?;
 
-    trigger_specific_task(state, "docker", "docker_health").await
+    trigger_specific_task(state, &caller, "docker", "docker_health").await
 }
 
 /// Trigger Docker cleanup
 #[instrument(skip(state, headers))]
 async fn trigger_docker_cleanup(
     State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    client_ip: ClientIp,
     headers: HeaderMap,
     Json(req): Json<TriggerRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
     debug!("Docker cleanup webhook triggered");
 
-    if !verify_token(&headers, &req.token) {
-        return Err((
-            StatusCode::UNAUTHORIZED,
-            "Invalid or missing token".to_string(),
-        ));
-    }
+    let caller = webhook_caller(user, client_ip, &headers, &req.token)?;
 
-    trigger_specific_task(state, "docker", "docker_cleanup").await
+    trigger_specific_task(state, &caller, "docker", "docker_cleanup").await
 }
 
 /// Trigger Docker analysis
 #[instrument(skip(state, headers))]
 async fn trigger_docker_analysis(
     State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    client_ip: ClientIp,
     headers: HeaderMap,
     Json(req): Json<TriggerRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
     debug!("Docker analysis webhook triggered");
 
-    if !verify_token(&headers, &req.token) {
-        return Err((
-            StatusCode::UNAUTHORIZED,
-            "Invalid or missing token".to_string(),
-        ));
-    }
+    let caller = webhook_caller(user, client_ip, &headers, &req.token)?;
 
-    trigger_specific_task(state, "docker", "docker_analysis").await
+    trigger_specific_task(state, &caller, "docker", "docker_analysis").await
 }
 
 /// Trigger updates check
 #[instrument(skip(state, headers))]
 async fn trigger_updates_check(
     State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    client_ip: ClientIp,
     headers: HeaderMap,
     Json(req): Json<TriggerRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
     debug!("Updates check webhook triggered");
 
-    if !verify_token(&headers, &req.token) {
-        return Err((
-            StatusCode::UNAUTHORIZED,
-            "Invalid or missing token".to_string(),
-        ));
-    }
+    let caller = webhook_caller(user, client_ip, &headers, &req.token)?;
 
-    trigger_specific_task(state, "updates", "updates_check").await
+    trigger_specific_task(state, &caller, "updates", "updates_check").await
 }
 
 /// Trigger updates apply
 #[instrument(skip(state, headers))]
 async fn trigger_updates_apply(
     State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    client_ip: ClientIp,
     headers: HeaderMap,
     Json(req): Json<TriggerRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
     debug!("Updates apply webhook triggered");
 
-    if !verify_token(&headers, &req.token) {
-        return Err((
-            StatusCode::UNAUTHORIZED,
-            "Invalid or missing token".to_string(),
-        ));
-    }
+    let caller = webhook_caller(user, client_ip, &headers, &req.token)?;
 
-    trigger_specific_task(state, "updates", "updates_apply").await
+    trigger_specific_task(state, &caller, "updates", "updates_apply").await
 }
 
 /// Trigger OS cleanup
 #[instrument(skip(state, headers))]
 async fn trigger_os_cleanup(
     State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    client_ip: ClientIp,
     headers: HeaderMap,
     Json(req): Json<TriggerRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
     debug!("OS cleanup webhook triggered");
 
-    if !verify_token(&headers, &req.token) {
+    let caller = webhook_caller(user, client_ip, &headers, &req.token)?;
+
+    trigger_specific_task(state, &caller, "updates", "os_cleanup").await
+}
+
+/// Update batch decision query parameters
+#[derive(Debug, Deserialize)]
+struct BatchDecisionParams {
+    /// The batch's secret, as embedded in its notification actions
+    token: String,
+}
+
+/// Approve or defer an update batch from a notification action button
+#[instrument(skip(state, client_ip, params))]
+async fn decide_update_batch(
+    State(state): State<AppState>,
+    client_ip: ClientIp,
+    Path((id, decision)): Path<(i64, String)>,
+    Query(params): Query<BatchDecisionParams>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    info!(batch_id = id, decision = %decision, "Update batch decision webhook received");
+
+    let batch = {
+        let db = state.db().await;
+        svrctlrs_database::queries::updates::get_update_batch(db.pool(), id)
+            .await
+            .map_err(|_| {
+                (
+                    StatusCode::NOT_FOUND,
+                    format!("Update batch {} not found", id),
+                )
+            })?
+    };
+    if !constant_time_eq(&batch.token, &params.token) {
+        warn!(batch_id = id, "Invalid update batch token");
         return Err((
             StatusCode::UNAUTHORIZED,
             "Invalid or missing token".to_string(),
         ));
     }
 
-    trigger_specific_task(state, "updates", "os_cleanup").await
+    let actor = CurrentUser::notification(client_ip.0);
+    super::updates::record_decision(&state, &actor, id, &decision, "notification").await
 }
 
 /// Helper function to trigger a specific task
 async fn trigger_specific_task(
     state: AppState,
+    caller: &CurrentUser,
     plugin_id: &str,
     task_id: &str,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
-    let registry = state.plugins.read().await;
+    authorize_trigger(&state, caller, plugin_id, task_id).await?;
 
-    let plugin = registry.get(plugin_id).ok_or_else(|| {
-        (
+    if state.plugins.read().await.get(plugin_id).is_none() {
+        return Err((
             StatusCode::NOT_FOUND,
             format!("Plugin {} not found", plugin_id),
-        )
-    })?;
-
-    // Create plugin context
-    let context = svrctlrs_core::PluginContext {
-        servers: state.config.servers.clone(),
-        config: HashMap::new(),
-        notification_manager: state.notification_manager().await,
-    };
+        ));
+    }
 
-    // Execute the task
-    let result = plugin.execute(task_id, &context).await.map_err(|e| {
-        error!(error = %e, "Webhook task execution failed");
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Task execution failed: {}", e),
-        )
-    })?;
+    // Same entry point as scheduled runs: registered servers, update
+    // approval, metrics, alerts and update history
+    let audit = Audit::new(
+        &state,
+        caller,
+        &format!("webhook.{}", task_id),
+        format!("{}/{}", plugin_id, task_id),
+    );
+    let result =
+        crate::executor::run_plugin_task(&state, plugin_id, task_id, HashMap::new(), None).await;
+    let result = audit
+        .check(result.map_err(|e| format!("{:#}", e)))
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Webhook task execution failed");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Task execution failed: {}", e),
+            )
+        })?;
 
     info!(success = result.success, "Webhook task execution completed");
+    if result.success {
+        audit.success().await;
+    } else {
+        audit.failure(&result.message).await;
+    }
 
     Ok(Json(json!({
         "success": result.success,
@@ -274,3 +383,61 @@ async fn trigger_specific_task(
         "executed_at": chrono::Utc::now().to_rfc3339()
     })))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq("s3cret", "s3cret"));
+        assert!(constant_time_eq("", ""));
+        assert!(!constant_time_eq("s3cret", "s3crex"));
+        assert!(!constant_time_eq("s3cret", "s3cret2"));
+        assert!(!constant_time_eq("s3cret", ""));
+    }
+
+    // The only test touching WEBHOOK_SECRET, so it can't race another one
+    #[test]
+    fn test_verify_token() {
+        let bearer = |token: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(
+                "Authorization",
+                format!("Bearer {}", token).parse().unwrap(),
+            );
+            headers
+        };
+        let status = |result: Result<(), (StatusCode, String)>| result.unwrap_err().0;
+
+        // Without a secret every request is refused, whatever token it carries
+        std::env::remove_var("WEBHOOK_SECRET");
+        assert_eq!(
+            status(verify_token(&HeaderMap::new(), &None)),
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            status(verify_token(&bearer(""), &Some(String::new()))),
+            StatusCode::FORBIDDEN
+        );
+        std::env::set_var("WEBHOOK_SECRET", "");
+        assert_eq!(
+            status(verify_token(&bearer(""), &Some(String::new()))),
+            StatusCode::FORBIDDEN
+        );
+
+        std::env::set_var("WEBHOOK_SECRET", "s3cret");
+        assert!(verify_token(&bearer("s3cret"), &None).is_ok());
+        assert!(verify_token(&HeaderMap::new(), &Some("s3cret".to_string())).is_ok());
+        assert!(verify_token(&bearer("wrong"), &Some("s3cret".to_string())).is_ok());
+        assert_eq!(
+            status(verify_token(&bearer("wrong"), &None)),
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status(verify_token(&HeaderMap::new(), &None)),
+            StatusCode::UNAUTHORIZED
+        );
+        std::env::remove_var("WEBHOOK_SECRET");
+    }
+}
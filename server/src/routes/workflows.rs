@@ -0,0 +1,398 @@
+//! Workflow management API endpoints
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use serde_json::json;
+use tracing::{error, info, instrument, warn};
+
+use svrctlrs_database::{models::UserRole, queries, CreateWorkflow, UpdateWorkflow};
+
+use crate::{
+    audit::Audit,
+    executions::TaskTrigger,
+    rbac::{authorize, authorize_on, CurrentUser, Targets},
+    state::AppState,
+    workflows::validate_workflow,
+};
+
+/// Default / maximum number of workflow runs returned
+const DEFAULT_RUN_LIMIT: i64 = 50;
+const MAX_RUN_LIMIT: i64 = 500;
+
+/// Create workflows API router
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_workflows).post(create_workflow))
+        .route(
+            "/{id}",
+            get(get_workflow)
+                .put(update_workflow)
+                .delete(delete_workflow),
+        )
+        .route("/{id}/run", post(run_workflow))
+        .route("/{id}/runs", get(list_runs))
+        .route("/runs/{run_id}", get(get_run))
+}
+
+/// List all workflows
+#[instrument(skip(state))]
+async fn list_workflows(
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let db = state.database.read().await;
+
+    let workflows = queries::list_workflows(db.pool()).await.map_err(|e| {
+        error!(error = %e, "Failed to list workflows");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to list workflows: {}", e),
+        )
+    })?;
+
+    Ok(Json(json!({
+        "workflows": workflows
+    })))
+}
+
+/// Get workflow by ID, with its parsed steps
+#[instrument(skip(state))]
+async fn get_workflow(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let db = state.database.read().await;
+
+    let workflow = queries::get_workflow(db.pool(), id).await.map_err(|e| {
+        error!(error = %e, id = id, "Failed to get workflow");
+        (StatusCode::NOT_FOUND, format!("Workflow not found: {}", e))
+    })?;
+    let steps = workflow.get_steps().unwrap_or_default();
+
+    Ok(Json(json!({
+        "workflow": workflow,
+        "steps": steps
+    })))
+}
+
+/// Create a new workflow
+#[instrument(skip(state, user))]
+async fn create_workflow(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Json(workflow): Json<CreateWorkflow>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "workflow.create",
+        &workflow.name,
+    )
+    .await?;
+
+    info!(name = %workflow.name, steps = workflow.steps.len(), "Creating workflow");
+
+    if workflow.name.trim().is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Workflow name is required".to_string(),
+        ));
+    }
+    validate_workflow(workflow.schedule.as_deref(), &workflow.steps)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let db = state.database.read().await;
+    let pool = db.pool();
+
+    let audit = Audit::new(
+        &state,
+        &user,
+        "workflow.create",
+        format!("workflow {}", workflow.name),
+    );
+    let workflow_id = audit
+        .check(queries::create_workflow(pool, &workflow).await)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to create workflow");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to create workflow: {}", e),
+            )
+        })?;
+
+    let created = queries::get_workflow(pool, workflow_id)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to fetch created workflow");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to fetch created workflow: {}", e),
+            )
+        })?;
+    drop(db);
+
+    audit.after(&created).success().await;
+    if let Err(e) = state.sync_workflow(workflow_id).await {
+        warn!(error = %e, id = workflow_id, "Failed to add workflow to scheduler");
+    }
+
+    info!(id = workflow_id, "Workflow created successfully");
+
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+/// Update a workflow
+#[instrument(skip(state, user))]
+async fn update_workflow(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path(id): Path<i64>,
+    Json(update): Json<UpdateWorkflow>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "workflow.update",
+        &format!("workflow {}", id),
+    )
+    .await?;
+
+    info!(id = id, "Updating workflow");
+
+    let db = state.database.read().await;
+    let pool = db.pool();
+
+    let existing = queries::get_workflow(pool, id).await.map_err(|e| {
+        error!(error = %e, id = id, "Workflow not found");
+        (StatusCode::NOT_FOUND, format!("Workflow not found: {}", e))
+    })?;
+
+    if update.name.as_deref().is_some_and(|n| n.trim().is_empty()) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Workflow name is required".to_string(),
+        ));
+    }
+
+    // Validate the workflow as it will look after the update
+    let steps = match &update.steps {
+        Some(steps) => steps.clone(),
+        None => existing
+            .get_steps()
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?,
+    };
+    let schedule = update.schedule.as_deref().or(existing.schedule.as_deref());
+    validate_workflow(schedule, &steps).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let audit = Audit::new(
+        &state,
+        &user,
+        "workflow.update",
+        format!("workflow {} ({})", id, existing.name),
+    )
+    .before(&existing);
+    audit
+        .check(queries::update_workflow(pool, id, &update).await)
+        .await
+        .map_err(|e| {
+            error!(error = %e, id = id, "Failed to update workflow");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to update workflow: {}", e),
+            )
+        })?;
+
+    let updated = queries::get_workflow(pool, id).await.map_err(|e| {
+        error!(error = %e, "Failed to fetch updated workflow");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to fetch updated workflow: {}", e),
+        )
+    })?;
+    drop(db);
+
+    audit.after(&updated).success().await;
+    if let Err(e) = state.sync_workflow(id).await {
+        warn!(error = %e, id = id, "Failed to sync workflow with scheduler");
+    }
+
+    info!(id = id, "Workflow updated successfully");
+
+    Ok(Json(updated))
+}
+
+/// Delete a workflow and its run history
+#[instrument(skip(state, user))]
+async fn delete_workflow(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "workflow.delete",
+        &format!("workflow {}", id),
+    )
+    .await?;
+
+    info!(id = id, "Deleting workflow");
+
+    let db = state.database.read().await;
+    let pool = db.pool();
+
+    let workflow = queries::get_workflow(pool, id).await.map_err(|e| {
+        error!(error = %e, id = id, "Workflow not found");
+        (StatusCode::NOT_FOUND, format!("Workflow not found: {}", e))
+    })?;
+
+    let audit = Audit::new(
+        &state,
+        &user,
+        "workflow.delete",
+        format!("workflow {} ({})", id, workflow.name),
+    )
+    .before(&workflow);
+    audit
+        .check(queries::delete_workflow(pool, id).await)
+        .await
+        .map_err(|e| {
+            error!(error = %e, id = id, "Failed to delete workflow");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to delete workflow: {}", e),
+            )
+        })?;
+    drop(db);
+
+    audit.success().await;
+    if let Err(e) = state.sync_workflow(id).await {
+        warn!(error = %e, id = id, "Failed to remove workflow from scheduler");
+    }
+
+    info!(id = id, name = %workflow.name, "Workflow deleted successfully");
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "message": "Workflow deleted successfully",
+            "id": id
+        })),
+    ))
+}
+
+/// Start a workflow run; returns immediately with the run ID
+#[instrument(skip(state, user))]
+async fn run_workflow(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    authorize_on(
+        &state,
+        &user,
+        "workflow.run",
+        &format!("workflow {}", id),
+        &Targets::Unrestricted,
+    )
+    .await?;
+
+    info!(id = id, "Starting workflow run");
+
+    let audit = Audit::new(&state, &user, "workflow.run", format!("workflow {}", id));
+    let run_id = crate::workflows::start_workflow(&state, id, TaskTrigger::Manual).await;
+    let run_id = audit
+        .check(run_id.map_err(|e| format!("{:#}", e)))
+        .await
+        .map_err(|e| {
+            error!(error = %e, id = id, "Failed to start workflow");
+            (
+                StatusCode::BAD_REQUEST,
+                format!("Failed to start workflow: {}", e),
+            )
+        })?;
+    audit.success().await;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(json!({
+            "run_id": run_id
+        })),
+    ))
+}
+
+/// Workflow run query parameters
+#[derive(Debug, Deserialize)]
+struct RunParams {
+    limit: Option<i64>,
+}
+
+/// List recent runs of a workflow
+#[instrument(skip(state))]
+async fn list_runs(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Query(params): Query<RunParams>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_RUN_LIMIT)
+        .clamp(1, MAX_RUN_LIMIT);
+    let db = state.database.read().await;
+
+    let runs = queries::list_workflow_runs(db.pool(), Some(id), limit)
+        .await
+        .map_err(|e| {
+            error!(error = %e, id = id, "Failed to list workflow runs");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to list workflow runs: {}", e),
+            )
+        })?;
+
+    Ok(Json(json!({
+        "runs": runs
+    })))
+}
+
+/// Get a workflow run with the outcome of each step
+#[instrument(skip(state))]
+async fn get_run(
+    State(state): State<AppState>,
+    Path(run_id): Path<i64>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let db = state.database.read().await;
+    let pool = db.pool();
+
+    let run = queries::get_workflow_run(pool, run_id).await.map_err(|e| {
+        error!(error = %e, run_id = run_id, "Failed to get workflow run");
+        (
+            StatusCode::NOT_FOUND,
+            format!("Workflow run not found: {}", e),
+        )
+    })?;
+
+    let steps = queries::list_workflow_step_runs(pool, run_id)
+        .await
+        .map_err(|e| {
+            error!(error = %e, run_id = run_id, "Failed to list workflow step runs");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to list workflow step runs: {}", e),
+            )
+        })?;
+
+    Ok(Json(json!({
+        "run": run,
+        "steps": steps
+    })))
+}
@@ -2,7 +2,10 @@
 
 use anyhow::{Context, Result};
 use async_ssh2_tokio::{client::Client, AuthMethod, ServerCheckMethod};
+use russh::ChannelMsg;
+use serde::Serialize;
 use std::time::Duration;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, info};
 
 /// SSH connection configuration
@@ -56,34 +59,98 @@ pub async fn test_connection(config: &SshConfig) -> Result<String> {
         .context("Failed to execute test command")?;
 
     let output = result.stdout.trim().to_string();
-    
+
     info!("SSH connection test successful: {}", output);
     Ok(output)
 }
 
 /// Execute a command on a remote server via SSH
-pub async fn execute_command(config: &SshConfig, command: &str) -> Result<CommandResult> {
+///
+/// Output is passed to `on_line` line by line as it arrives, and also
+/// returned in full once the command exits.
+///
+/// Cancelling the token disconnects the session, which closes the channel
+/// (and hangs up the remote command).
+pub async fn execute_command(
+    config: &SshConfig,
+    command: &str,
+    cancel: &CancellationToken,
+    mut on_line: impl FnMut(OutputStream, &str) + Send,
+) -> Result<CommandResult> {
     debug!(
         "Executing command on {}@{}:{}: {}",
         config.username, config.host, config.port, command
     );
 
-    let client = tokio::time::timeout(
-        config.timeout,
-        connect_ssh(config)
-    )
-    .await
-    .context("Connection timeout")?
-    .context("Failed to establish SSH connection")?;
+    let connect = tokio::time::timeout(config.timeout, connect_ssh(config));
+    let client = tokio::select! {
+        connected = connect => match connected {
+            Err(_) => {
+                return Err(ConnectionError {
+                    message: format!("Connection timeout after {} seconds", config.timeout.as_secs()),
+                    transient: true,
+                }
+                .into());
+            }
+            Ok(Err(e)) => return Err(ConnectionError::from_connect_error(&e).into()),
+            Ok(Ok(client)) => client,
+        },
+        _ = cancel.cancelled() => anyhow::bail!("Cancelled while connecting"),
+    };
 
-    let result = client
-        .execute(command)
+    let mut channel = client
+        .get_channel()
+        .await
+        .context("Failed to open SSH channel")?;
+    channel
+        .exec(true, command)
         .await
         .context("Failed to execute command")?;
 
-    let stdout = result.stdout;
-    let stderr = result.stderr;
-    let exit_code = result.exit_status as i32;
+    let mut stdout = LineBuffer::default();
+    let mut stderr = LineBuffer::default();
+    let mut exit_code = None;
+    let mut exit_signal = None;
+
+    loop {
+        let msg = tokio::select! {
+            msg = channel.wait() => msg,
+            _ = cancel.cancelled() => None,
+        };
+        if cancel.is_cancelled() {
+            if let Err(e) = client.disconnect().await {
+                debug!("Failed to disconnect SSH session cleanly: {}", e);
+            }
+            anyhow::bail!("Command cancelled");
+        }
+
+        // Keep reading after the exit status; output may still follow it
+        match msg {
+            Some(ChannelMsg::Data { ref data }) => {
+                stdout.push(data, |line| on_line(OutputStream::Stdout, line));
+            }
+            Some(ChannelMsg::ExtendedData { ref data, ext: 1 }) => {
+                stderr.push(data, |line| on_line(OutputStream::Stderr, line));
+            }
+            Some(ChannelMsg::ExitStatus { exit_status }) => exit_code = Some(exit_status as i32),
+            Some(ChannelMsg::ExitSignal { signal_name, .. }) => exit_signal = Some(signal_name),
+            Some(_) => {}
+            None => break,
+        }
+    }
+
+    stdout.finish(|line| on_line(OutputStream::Stdout, line));
+    stderr.finish(|line| on_line(OutputStream::Stderr, line));
+
+    let Some(exit_code) = exit_code else {
+        match exit_signal {
+            Some(signal) => anyhow::bail!("Command was killed by signal {:?}", signal),
+            None => anyhow::bail!("Command did not report an exit status"),
+        }
+    };
+
+    let stdout = stdout.into_string();
+    let stderr = stderr.into_string();
 
     debug!(
         "Command execution completed with exit code {}: stdout={}, stderr={}",
@@ -98,6 +165,127 @@ pub async fn execute_command(config: &SshConfig, command: &str) -> Result<Comman
     })
 }
 
+/// Output stream a line of command output was written to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// Collects a command's output, splitting it into lines as it arrives
+#[derive(Default)]
+struct LineBuffer {
+    all: Vec<u8>,
+    pending: Vec<u8>,
+}
+
+impl LineBuffer {
+    /// Append data, passing each completed line to `emit`
+    fn push(&mut self, data: &[u8], mut emit: impl FnMut(&str)) {
+        self.all.extend_from_slice(data);
+        self.pending.extend_from_slice(data);
+
+        while let Some(end) = self.pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=end).collect();
+            emit(String::from_utf8_lossy(&line[..end]).trim_end_matches('\r'));
+        }
+    }
+
+    /// Emit the last line if the output did not end with a newline
+    fn finish(&mut self, mut emit: impl FnMut(&str)) {
+        if !self.pending.is_empty() {
+            let line = std::mem::take(&mut self.pending);
+            emit(String::from_utf8_lossy(&line).trim_end_matches('\r'));
+        }
+    }
+
+    fn into_string(self) -> String {
+        String::from_utf8_lossy(&self.all).into_owned()
+    }
+}
+
+/// Failure to reach a server, as opposed to a command failing on it
+#[derive(Debug)]
+pub struct ConnectionError {
+    pub message: String,
+    /// The host may be reachable on a later attempt (refused, timed out,
+    /// unreachable). Authentication and key errors are not transient.
+    pub transient: bool,
+}
+
+impl ConnectionError {
+    fn from_connect_error(err: &anyhow::Error) -> Self {
+        // Keep the full cause chain; the root cause is what tells us whether to retry
+        let message = format!("Failed to establish SSH connection: {:#}", err);
+        let transient = err.chain().any(|cause| {
+            cause
+                .downcast_ref::<std::io::Error>()
+                .is_some_and(|e| is_transient_io_error(e.kind()))
+        }) || is_transient_message(&message);
+
+        Self { message, transient }
+    }
+}
+
+impl std::fmt::Display for ConnectionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ConnectionError {}
+
+/// Whether an error is worth retrying (the server could not be reached)
+///
+/// Errors raised after the connection was established - including a command
+/// exiting non-zero - are never transient, since the command may have run.
+pub fn is_transient(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        cause
+            .downcast_ref::<ConnectionError>()
+            .is_some_and(|e| e.transient)
+    })
+}
+
+fn is_transient_io_error(kind: std::io::ErrorKind) -> bool {
+    use std::io::ErrorKind;
+
+    matches!(
+        kind,
+        ErrorKind::ConnectionRefused
+            | ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::NotConnected
+            | ErrorKind::TimedOut
+            | ErrorKind::BrokenPipe
+            | ErrorKind::UnexpectedEof
+            | ErrorKind::HostUnreachable
+            | ErrorKind::NetworkUnreachable
+            | ErrorKind::NetworkDown
+    )
+}
+
+/// Fallback for errors whose I/O cause was flattened into a message by the SSH library
+fn is_transient_message(message: &str) -> bool {
+    const MARKERS: &[&str] = &[
+        "connection refused",
+        "connection reset",
+        "connection aborted",
+        "timed out",
+        "timeout",
+        "no route to host",
+        "network is unreachable",
+        "host is unreachable",
+        "broken pipe",
+        "disconnected",
+        "temporary failure in name resolution",
+    ];
+
+    let message = message.to_lowercase();
+    MARKERS.iter().any(|marker| message.contains(marker))
+}
+
 /// Result of a command execution
 #[derive(Debug, Clone)]
 pub struct CommandResult {
@@ -107,25 +295,6 @@ pub struct CommandResult {
     pub success: bool,
 }
 
-/// Check what SSH keys are available
-pub fn list_available_keys() -> Vec<String> {
-    let home = std::env::var("HOME")
-        .or_else(|_| std::env::var("USERPROFILE"))
-        .unwrap_or_else(|_| "/home/svrctlrs".to_string());
-    
-    let possible_keys = vec![
-        format!("{}/.ssh/id_ed25519", home),
-        format!("{}/.ssh/id_rsa", home),
-        format!("{}/.ssh/id_ecdsa", home),
-        format!("{}/.ssh/id_dsa", home),
-    ];
-    
-    possible_keys
-        .into_iter()
-        .filter(|path| std::path::Path::new(path).exists())
-        .collect()
-}
-
 /// Connect to SSH server
 pub async fn connect_ssh(config: &SshConfig) -> Result<Client> {
     // Determine authentication method
@@ -138,10 +307,10 @@ pub async fn connect_ssh(config: &SshConfig) -> Result<Client> {
         let auth_method = AuthMethod::with_key_file(key_path, None);
         return connect_with_auth(config, auth_method).await;
     }
-    
+
     // Try default SSH keys - check multiple possible locations
     info!("Searching for SSH keys in default locations");
-    
+
     // Get home directory - try multiple methods
     let home = std::env::var("HOME")
         .or_else(|_| std::env::var("USERPROFILE"))
@@ -149,9 +318,9 @@ pub async fn connect_ssh(config: &SshConfig) -> Result<Client> {
             // Fallback to /home/svrctlrs for Docker container
             "/home/svrctlrs".to_string()
         });
-    
+
     info!("Using home directory: {}", home);
-    
+
     let default_keys = vec![
         format!("{}/.ssh/id_ed25519", home),
         format!("{}/.ssh/id_rsa", home),
@@ -161,15 +330,15 @@ pub async fn connect_ssh(config: &SshConfig) -> Result<Client> {
 
     let mut tried_keys = Vec::new();
     let mut last_error = None;
-    
+
     // Try each key until one works
     for key_path in default_keys {
         if std::path::Path::new(&key_path).exists() {
             info!("Found SSH key: {}", key_path);
             tried_keys.push(key_path.clone());
-            
+
             let auth_method = AuthMethod::with_key_file(&key_path, None);
-            
+
             // Try to connect with this key
             match connect_with_auth(config, auth_method).await {
                 Ok(client) => {
@@ -191,7 +360,9 @@ pub async fn connect_ssh(config: &SshConfig) -> Result<Client> {
         Err(anyhow::anyhow!(
             "Connection failed with all available SSH keys. Tried: {}. Last error: {}",
             tried_keys.join(", "),
-            last_error.map(|e| e.to_string()).unwrap_or_else(|| "Unknown".to_string())
+            last_error
+                .map(|e| format!("{:#}", e))
+                .unwrap_or_else(|| "Unknown".to_string())
         ))
     } else {
         Err(anyhow::anyhow!(
@@ -215,3 +386,50 @@ async fn connect_with_auth(config: &SshConfig, auth_method: AuthMethod) -> Resul
     Ok(client)
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Error as IoError, ErrorKind};
+
+    #[test]
+    fn test_is_transient_io_error() {
+        assert!(is_transient_io_error(ErrorKind::ConnectionRefused));
+        assert!(is_transient_io_error(ErrorKind::TimedOut));
+        assert!(is_transient_io_error(ErrorKind::HostUnreachable));
+        assert!(!is_transient_io_error(ErrorKind::PermissionDenied));
+        assert!(!is_transient_io_error(ErrorKind::NotFound));
+        assert!(!is_transient_io_error(ErrorKind::InvalidData));
+    }
+
+    #[test]
+    fn test_is_transient_message() {
+        assert!(is_transient_message("Connection refused (os error 111)"));
+        assert!(is_transient_message("connect: No route to host"));
+        assert!(is_transient_message("SSH handshake TIMED OUT"));
+        assert!(is_transient_message("Temporary failure in name resolution"));
+        assert!(!is_transient_message("Authentication failed"));
+        assert!(!is_transient_message("Failed to load key: no such file"));
+    }
+
+    #[test]
+    fn test_is_transient() {
+        // Unreachable hosts are retried, whether the I/O error survives or
+        // only its message does
+        let refused = anyhow::Error::new(IoError::from(ErrorKind::ConnectionRefused))
+            .context("connecting to web1");
+        let err = anyhow::Error::new(ConnectionError::from_connect_error(&refused));
+        assert!(is_transient(&err));
+        let flattened = anyhow::anyhow!("ssh error: Connection reset by peer");
+        let err = anyhow::Error::new(ConnectionError::from_connect_error(&flattened));
+        assert!(is_transient(&err.context("running task 1")));
+
+        // Authentication failures are not
+        let auth = anyhow::anyhow!("Authentication failed for user root");
+        let err = anyhow::Error::new(ConnectionError::from_connect_error(&auth));
+        assert!(!is_transient(&err));
+
+        // Nor is anything after connecting, such as the command failing
+        let timed_out = anyhow::Error::new(IoError::from(ErrorKind::TimedOut));
+        assert!(!is_transient(&timed_out));
+    }
+}
@@ -1,15 +1,22 @@
 //! Application state
 
 use std::sync::Arc;
-use svrctlrs_core::{NotificationManager, PluginRegistry, RemoteExecutor, Result};
+use svrctlrs_core::{
+    NotificationBackendConfig, NotificationBackendRegistry, NotificationManager, PluginRegistry,
+    Result,
+};
 use svrctlrs_database::Database;
-use svrctlrs_scheduler::Scheduler;
-use tokio::sync::{OnceCell, RwLock};
+use svrctlrs_scheduler::{
+    AsyncTaskHandler, CatchUpPolicy, OverlapPolicy, Scheduler, SchedulerEvent,
+    SchedulerEventHandler, TaskOptions,
+};
+use tokio::sync::RwLock;
 
-use crate::config::Config;
-
-/// Global application state for server functions
-static APP_STATE: OnceCell<AppState> = OnceCell::const_new();
+use crate::{
+    config::Config,
+    executions::{RunningExecutions, TaskTrigger},
+    executor::ServerLimiter,
+};
 
 /// Shared application state
 ///
@@ -21,35 +28,56 @@ pub struct AppState {
     pub database: Arc<RwLock<Database>>,
     pub plugins: Arc<RwLock<PluginRegistry>>,
     pub scheduler: Arc<RwLock<Option<Scheduler>>>,
-    pub executor: Arc<RemoteExecutor>,
+    /// Per-server limit on concurrent SSH task executions
+    pub server_limiter: Arc<ServerLimiter>,
+    /// Task executions currently in flight
+    pub executions: Arc<RunningExecutions>,
+    /// Notification backend types that can be configured
+    pub notification_backends: Arc<NotificationBackendRegistry>,
 }
 
+/// Services that send notifications, for per-service Gotify keys and ntfy topics
+/// (plugins are filtered by enabled status at runtime)
+const NOTIFICATION_SERVICES: &[&str] = &[
+    "docker",
+    "updates",
+    "health",
+    "weather",
+    "speedtest",
+    "alerts",
+];
+
 impl AppState {
     /// Create new application state
     pub async fn new(config: Config, database: Database) -> Result<Self> {
-        let executor = Arc::new(RemoteExecutor::new(config.ssh_key_path.clone()));
         let plugins = Arc::new(RwLock::new(PluginRegistry::new()));
+        let server_limiter = Arc::new(ServerLimiter::new(config.max_ssh_per_server));
 
         Ok(Self {
             config: Arc::new(config),
             database: Arc::new(RwLock::new(database)),
             plugins,
             scheduler: Arc::new(RwLock::new(None)),
-            executor,
+            server_limiter,
+            executions: Arc::new(RunningExecutions::new()),
+            notification_backends: Arc::new(NotificationBackendRegistry::new()),
         })
     }
 
     /// Initialize all plugins based on database configuration
     pub async fn init_plugins(&self) -> Result<()> {
         use svrctlrs_database::queries;
-        
+
         let mut registry = self.plugins.write().await;
         let db = self.database.read().await;
 
         // Load enabled plugins from database
         let enabled_plugins = queries::plugins::list_enabled_plugins(db.pool()).await?;
-        
-        tracing::info!("Loading {} enabled plugins from database", enabled_plugins.len());
+
+        tracing::info!(
+            "Loading {} enabled plugins from database",
+            enabled_plugins.len()
+        );
 
         // Register each enabled plugin
         for db_plugin in enabled_plugins {
@@ -60,21 +88,21 @@ impl AppState {
                     let plugin = svrctlrs_plugin_docker::DockerPlugin::new();
                     registry.register(Box::new(plugin))?;
                 }
-                
+
                 #[cfg(feature = "plugin-updates")]
                 "updates" => {
                     tracing::info!("Registering Updates plugin (enabled in database)");
                     let plugin = svrctlrs_plugin_updates::UpdatesPlugin::new();
                     registry.register(Box::new(plugin))?;
                 }
-                
+
                 #[cfg(feature = "plugin-health")]
                 "health" => {
                     tracing::info!("Registering Health plugin (enabled in database)");
                     let plugin = svrctlrs_plugin_health::HealthPlugin::new();
                     registry.register(Box::new(plugin))?;
                 }
-                
+
                 #[cfg(feature = "plugin-weather")]
                 "weather" => {
                     tracing::info!("Registering Weather plugin (enabled in database)");
@@ -82,7 +110,7 @@ impl AppState {
                     let plugin = svrctlrs_plugin_weather::WeatherPlugin::from_config(config)?;
                     registry.register(Box::new(plugin))?;
                 }
-                
+
                 #[cfg(feature = "plugin-speedtest")]
                 "speedtest" => {
                     tracing::info!("Registering SpeedTest plugin (enabled in database)");
@@ -90,7 +118,7 @@ impl AppState {
                     let plugin = svrctlrs_plugin_speedtest::SpeedTestPlugin::from_config(config)?;
                     registry.register(Box::new(plugin))?;
                 }
-                
+
                 _ => {
                     tracing::warn!("Unknown plugin in database: {}", db_plugin.id);
                 }
@@ -107,65 +135,93 @@ impl AppState {
     pub async fn start_scheduler(&self) -> Result<()> {
         use svrctlrs_database::queries;
         use tracing::info;
-        
+
         let mut scheduler_lock = self.scheduler.write().await;
-        let scheduler = Scheduler::new();
+        let scheduler = Scheduler::new()
+            .with_event_handler(self.scheduler_event_handler())
+            .with_max_concurrent(self.config.max_concurrent_tasks);
 
-        // Load enabled tasks from database
+        // Load all tasks from database; disabled ones are registered paused so
+        // they can be resumed without a restart
         let db = self.database.read().await;
-        let tasks = queries::tasks::list_enabled_tasks(db.pool()).await?;
-        
-        info!("Loading {} enabled tasks into scheduler", tasks.len());
-        
+        let tasks = queries::tasks::list_tasks(db.pool()).await?;
+        drop(db);
+
+        info!(
+            "Loading {} tasks into scheduler ({} enabled)",
+            tasks.len(),
+            tasks.iter().filter(|t| t.enabled).count()
+        );
+
         // Register each task with the scheduler
         for task in tasks {
-            let task_id = task.id;
-            let schedule = task.schedule.clone();
-            let task_name = task.name.clone();
-            
-            // Clone state for the closure
-            let state = self.clone();
-            
-            // Create async handler that executes the task
-            let handler: std::sync::Arc<dyn Fn() -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<()>> + Send>> + Send + Sync> = 
-                std::sync::Arc::new(move || {
-                    let state = state.clone();
-                    Box::pin(async move {
-                        match crate::executor::execute_task(&state, task_id).await {
-                            Ok(result) => {
-                                if result.success {
-                                    tracing::info!("Scheduled task {} completed successfully", task_id);
-                                    Ok(())
-                                } else {
-                                    let err_msg = result.error.unwrap_or_else(|| "Unknown error".to_string());
-                                    tracing::error!("Scheduled task {} failed: {}", task_id, err_msg);
-                                    Err(svrctlrs_core::Error::RemoteExecutionError(err_msg))
-                                }
-                            }
-                            Err(e) => {
-                                tracing::error!("Failed to execute scheduled task {}: {}", task_id, e);
-                                Err(svrctlrs_core::Error::RemoteExecutionError(e.to_string()))
-                            }
-                        }
-                    })
-                });
-            
             // Try to add task, but don't fail if cron expression is invalid
-            match scheduler.add_task(
-                format!("task_{}", task_id),
-                &schedule,
-                handler,
-            ).await {
+            match scheduler
+                .add_task_with_options(
+                    scheduler_task_id(task.id),
+                    &task.schedule,
+                    self.task_handler(task.id),
+                    task_options(&task),
+                )
+                .await
+            {
                 Ok(_) => {
-                    tracing::info!("Registered task {} ({}) with schedule: {}", task_id, task_name, schedule);
+                    tracing::info!(
+                        "Registered task {} ({}) with schedule: {}",
+                        task.id,
+                        task.name,
+                        task.schedule
+                    );
                 }
                 Err(e) => {
-                    tracing::error!("Failed to register task {} ({}): {}. Skipping this task.", task_id, task_name, e);
+                    tracing::error!(
+                        "Failed to register task {} ({}): {}. Skipping this task.",
+                        task.id,
+                        task.name,
+                        e
+                    );
                     // Continue with other tasks instead of failing
                 }
             }
         }
-        
+
+        // Scheduled workflows run alongside tasks
+        let db = self.database.read().await;
+        let workflows = queries::workflows::list_workflows(db.pool()).await?;
+        drop(db);
+
+        for workflow in workflows {
+            let Some(schedule) = &workflow.schedule else {
+                continue;
+            };
+            match scheduler
+                .add_task_with_options(
+                    scheduler_workflow_id(workflow.id),
+                    schedule,
+                    self.workflow_handler(workflow.id),
+                    workflow_options(&workflow),
+                )
+                .await
+            {
+                Ok(_) => {
+                    tracing::info!(
+                        "Registered workflow {} ({}) with schedule: {}",
+                        workflow.id,
+                        workflow.name,
+                        schedule
+                    );
+                }
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to register workflow {} ({}): {}. Skipping this workflow.",
+                        workflow.id,
+                        workflow.name,
+                        e
+                    );
+                }
+            }
+        }
+
         // Start the scheduler
         scheduler.start().await?;
 
@@ -173,96 +229,334 @@ impl AppState {
         Ok(())
     }
 
-    /// Get database reference
-    pub async fn db(&self) -> tokio::sync::RwLockReadGuard<'_, Database> {
-        self.database.read().await
+    /// Bring the running scheduler in line with a task's database row
+    ///
+    /// Call after creating, updating, enabling/disabling or deleting a task.
+    /// Deleted tasks are removed, disabled tasks are paused, and schedule or
+    /// catch-up changes take effect immediately.
+    pub async fn sync_task(&self, task_id: i64) -> Result<()> {
+        use svrctlrs_database::queries;
+
+        let scheduler_lock = self.scheduler.read().await;
+        let Some(scheduler) = scheduler_lock.as_ref() else {
+            // Scheduler not running; tasks are loaded when it starts
+            return Ok(());
+        };
+        let id = scheduler_task_id(task_id);
+
+        let db = self.database.read().await;
+        let task = queries::tasks::find_task(db.pool(), task_id).await?;
+        drop(db);
+
+        // A missing row means the task was deleted
+        let Some(task) = task else {
+            if scheduler.remove_task(&id).await {
+                tracing::info!("Task {} removed from scheduler", task_id);
+            }
+            return Ok(());
+        };
+
+        // The stored next run belongs to the old schedule; compute a fresh one
+        let options = TaskOptions {
+            resume_from: None,
+            ..task_options(&task)
+        };
+        let paused = options.paused;
+
+        scheduler
+            .replace_task(id, &task.schedule, self.task_handler(task_id), options)
+            .await?;
+        tracing::info!(
+            "Task {} ({}) synced with scheduler: schedule '{}'{}",
+            task_id,
+            task.name,
+            task.schedule,
+            if paused { ", paused" } else { "" }
+        );
+
+        Ok(())
     }
 
-    /// Get notification manager for plugin context
-    /// Loads notification backends from database
-    pub async fn notification_manager(&self) -> NotificationManager {
-        use svrctlrs_core::{GotifyBackend, NtfyBackend};
+    /// Bring the running scheduler in line with a workflow's database row
+    ///
+    /// Call after creating, updating, enabling/disabling or deleting a
+    /// workflow. Workflows without a schedule are not registered.
+    pub async fn sync_workflow(&self, workflow_id: i64) -> Result<()> {
         use svrctlrs_database::queries;
-        use tracing::{info, warn};
 
-        let client = reqwest::Client::new();
+        let scheduler_lock = self.scheduler.read().await;
+        let Some(scheduler) = scheduler_lock.as_ref() else {
+            return Ok(());
+        };
+        let id = scheduler_workflow_id(workflow_id);
+
         let db = self.database.read().await;
+        let workflow = queries::workflows::find_workflow(db.pool(), workflow_id).await?;
+        drop(db);
 
-        // Load enabled notification backends from database
-        let backends = match queries::notifications::list_notification_backends(db.pool()).await {
-            Ok(backends) => backends.into_iter().filter(|b| b.enabled).collect::<Vec<_>>(),
-            Err(e) => {
-                warn!("Failed to load notification backends from database: {}", e);
-                Vec::new()
+        let Some((workflow, schedule)) = workflow.and_then(|w| w.schedule.clone().map(|s| (w, s)))
+        else {
+            if scheduler.remove_task(&id).await {
+                tracing::info!("Workflow {} removed from scheduler", workflow_id);
             }
+            return Ok(());
         };
 
-        // Initialize Gotify backends
-        let mut gotify_backend: Option<GotifyBackend> = None;
-        for backend in backends.iter().filter(|b| b.backend_type == "gotify") {
-            let config = backend.get_config();
-            if let (Some(url), Some(token)) = (
-                config.get("url").and_then(|v| v.as_str()),
-                config.get("token").and_then(|v| v.as_str()),
-            ) {
-                match GotifyBackend::with_url_and_key(client.clone(), url, token) {
-                    Ok(mut gb) => {
-                        // Load service-specific keys for all plugins
-                        // (plugins will be filtered by enabled status at runtime)
-                        let services = vec!["docker", "updates", "health", "weather", "speedtest"];
-                        gb.load_service_keys(&services);
-                        gotify_backend = Some(gb);
-                        info!("Initialized Gotify backend: {}", backend.name);
-                        break; // Use first enabled Gotify backend
-                    }
+        let options = TaskOptions {
+            resume_from: None,
+            ..workflow_options(&workflow)
+        };
+        let paused = options.paused;
+
+        scheduler
+            .replace_task(id, &schedule, self.workflow_handler(workflow_id), options)
+            .await?;
+        tracing::info!(
+            "Workflow {} ({}) synced with scheduler: schedule '{}'{}",
+            workflow_id,
+            workflow.name,
+            schedule,
+            if paused { ", paused" } else { "" }
+        );
+
+        Ok(())
+    }
+
+    /// Handler that runs a workflow through the workflow engine
+    fn workflow_handler(&self, workflow_id: i64) -> AsyncTaskHandler {
+        let state = self.clone();
+
+        Arc::new(move || {
+            let state = state.clone();
+            Box::pin(async move {
+                match crate::workflows::run_workflow(&state, workflow_id, TaskTrigger::Schedule)
+                    .await
+                {
+                    Ok(true) => Ok(()),
+                    Ok(false) => Err(svrctlrs_core::Error::RemoteExecutionError(format!(
+                        "Workflow {} run failed",
+                        workflow_id
+                    ))),
                     Err(e) => {
-                        warn!("Failed to initialize Gotify backend {}: {}", backend.name, e);
+                        tracing::error!("Failed to run scheduled workflow {}: {}", workflow_id, e);
+                        Err(svrctlrs_core::Error::RemoteExecutionError(e.to_string()))
                     }
                 }
-            }
-        }
+            })
+        })
+    }
 
-        // Initialize ntfy backends
-        let mut ntfy_backend: Option<NtfyBackend> = None;
-        for backend in backends.iter().filter(|b| b.backend_type == "ntfy") {
-            let config = backend.get_config();
-            if let (Some(url), Some(topic)) = (
-                config.get("url").and_then(|v| v.as_str()),
-                config.get("topic").and_then(|v| v.as_str()),
-            ) {
-                match NtfyBackend::with_url_and_topic(client.clone(), url, topic) {
-                    Ok(mut nb) => {
-                        // Load service-specific topics for all plugins
-                        // (plugins will be filtered by enabled status at runtime)
-                        let services = vec!["docker", "updates", "health", "weather", "speedtest"];
-                        nb.load_service_topics(&services);
-                        ntfy_backend = Some(nb);
-                        info!("Initialized ntfy backend: {}", backend.name);
-                        break; // Use first enabled ntfy backend
+    /// Handler that runs a database task through the executor
+    fn task_handler(&self, task_id: i64) -> AsyncTaskHandler {
+        let state = self.clone();
+
+        Arc::new(move || {
+            let state = state.clone();
+            Box::pin(async move {
+                match crate::executor::execute_task(&state, task_id, TaskTrigger::Schedule).await {
+                    Ok(result) => {
+                        if result.success {
+                            tracing::info!("Scheduled task {} completed successfully", task_id);
+                            Ok(())
+                        } else {
+                            let err_msg =
+                                result.error.unwrap_or_else(|| "Unknown error".to_string());
+                            tracing::error!("Scheduled task {} failed: {}", task_id, err_msg);
+                            Err(svrctlrs_core::Error::RemoteExecutionError(err_msg))
+                        }
                     }
                     Err(e) => {
-                        warn!("Failed to initialize ntfy backend {}: {}", backend.name, e);
+                        tracing::error!("Failed to execute scheduled task {}: {}", task_id, e);
+                        Err(svrctlrs_core::Error::RemoteExecutionError(e.to_string()))
                     }
                 }
+            })
+        })
+    }
+
+    /// Persist scheduler bookkeeping (next run times, missed runs) to the tasks table
+    fn scheduler_event_handler(&self) -> SchedulerEventHandler {
+        let database = self.database.clone();
+
+        Arc::new(move |event| {
+            let database = database.clone();
+            Box::pin(async move {
+                use svrctlrs_database::queries;
+
+                let db = database.read().await;
+                let result = match &event {
+                    SchedulerEvent::NextRun { task_id, next_run } => {
+                        match (
+                            parse_scheduler_task_id(task_id),
+                            parse_scheduler_workflow_id(task_id),
+                        ) {
+                            (Some(id), _) => {
+                                queries::tasks::set_task_next_run(db.pool(), id, *next_run).await
+                            }
+                            (_, Some(id)) => {
+                                queries::workflows::set_workflow_next_run(db.pool(), id, *next_run)
+                                    .await
+                            }
+                            _ => Ok(()),
+                        }
+                    }
+                    SchedulerEvent::Missed {
+                        task_id,
+                        missed,
+                        first,
+                        policy,
+                        catch_up_runs,
+                        ..
+                    } => match parse_scheduler_task_id(task_id) {
+                        Some(id) => {
+                            tracing::warn!(
+                                    "Task {} missed {} scheduled run(s) since {} (catch-up: {}, running {})",
+                                    id, missed, first, policy.as_str(), catch_up_runs
+                                );
+                            queries::tasks::record_missed_runs(db.pool(), id, *missed as i64).await
+                        }
+                        None => Ok(()),
+                    },
+                };
+
+                if let Err(e) = result {
+                    tracing::error!("Failed to persist scheduler event {:?}: {}", event, e);
+                }
+            })
+        })
+    }
+
+    /// Get database reference
+    pub async fn db(&self) -> tokio::sync::RwLockReadGuard<'_, Database> {
+        self.database.read().await
+    }
+
+    /// Get notification manager for plugin context
+    /// Loads enabled notification backends from database, highest priority first
+    pub async fn notification_manager(&self) -> NotificationManager {
+        use svrctlrs_database::queries;
+        use tracing::{info, warn};
+
+        let client = reqwest::Client::new();
+        let db = self.database.read().await;
+
+        let backends =
+            match queries::notifications::list_enabled_notification_backends(db.pool()).await {
+                Ok(backends) => backends,
+                Err(e) => {
+                    warn!("Failed to load notification backends from database: {}", e);
+                    Vec::new()
+                }
+            };
+
+        let mut manager = NotificationManager::default();
+        for backend in backends {
+            let config = backend.get_config();
+            let backend_config = NotificationBackendConfig {
+                name: &backend.name,
+                config: &config,
+                client: &client,
+                services: NOTIFICATION_SERVICES,
+            };
+            match self
+                .notification_backends
+                .create(&backend.backend_type, &backend_config)
+            {
+                Ok(b) => {
+                    info!(
+                        "Initialized {} backend: {}",
+                        backend.backend_type, backend.name
+                    );
+                    manager.add(b);
+                }
+                Err(e) => {
+                    warn!(
+                        "Failed to initialize {} backend {}: {}",
+                        backend.backend_type, backend.name, e
+                    );
+                }
             }
         }
 
-        // Create notification manager with database-loaded backends
-        NotificationManager::from_backends(gotify_backend, ntfy_backend)
+        manager
     }
 
-    /// Initialize the global app state (call once at startup)
-    pub fn set_global(state: AppState) {
-        if APP_STATE.set(state).is_err() {
-            panic!("AppState already initialized");
-        }
+    /// Check that a notification backend of this type can be built from this config
+    pub fn check_notification_backend(
+        &self,
+        backend_type: &str,
+        name: &str,
+        config: &serde_json::Value,
+    ) -> Result<()> {
+        let client = reqwest::Client::new();
+        let backend_config = NotificationBackendConfig {
+            name,
+            config,
+            client: &client,
+            services: &[],
+        };
+        self.notification_backends
+            .create(backend_type, &backend_config)
+            .map(|_| ())
     }
+}
+
+/// Scheduler ID of a database task
+pub fn scheduler_task_id(task_id: i64) -> String {
+    format!("task_{}", task_id)
+}
+
+/// Scheduler options for a database task
+fn task_options(task: &svrctlrs_database::models::Task) -> TaskOptions {
+    let catch_up = CatchUpPolicy::parse(&task.catch_up).unwrap_or_else(|| {
+        tracing::warn!(
+            "Task {} has unknown catch-up policy '{}', using skip",
+            task.id,
+            task.catch_up
+        );
+        CatchUpPolicy::Skip
+    });
+    let overlap = OverlapPolicy::parse(&task.overlap_policy).unwrap_or_else(|| {
+        tracing::warn!(
+            "Task {} has unknown overlap policy '{}', using skip",
+            task.id,
+            task.overlap_policy
+        );
+        OverlapPolicy::Skip
+    });
+
+    TaskOptions {
+        catch_up,
+        overlap,
+        // Pick up where the last run left off so downtime is detected
+        resume_from: task.next_run_at,
+        paused: !task.enabled,
+    }
+}
 
-    /// Get the global app state (for use in server functions)
-    pub fn global() -> AppState {
-        APP_STATE
-            .get()
-            .expect("AppState not initialized - call AppState::set_global() first")
-            .clone()
+/// Database task ID from a scheduler ID
+fn parse_scheduler_task_id(id: &str) -> Option<i64> {
+    id.strip_prefix("task_")?.parse().ok()
+}
+
+/// Scheduler ID of a workflow
+fn scheduler_workflow_id(workflow_id: i64) -> String {
+    format!("workflow_{}", workflow_id)
+}
+
+/// Workflow ID from a scheduler ID
+fn parse_scheduler_workflow_id(id: &str) -> Option<i64> {
+    id.strip_prefix("workflow_")?.parse().ok()
+}
+
+/// Scheduler options for a workflow
+///
+/// Missed runs are skipped, and a run that comes due while the previous one
+/// is still going is dropped.
+fn workflow_options(workflow: &svrctlrs_database::models::Workflow) -> TaskOptions {
+    TaskOptions {
+        resume_from: workflow.next_run_at,
+        paused: !workflow.enabled,
+        ..TaskOptions::default()
     }
 }
@@ -41,6 +41,7 @@ pub struct DashboardStats {
 pub struct ServersTemplate {
     pub user: Option<User>,
     pub servers: Vec<Server>,
+    pub transactions: Vec<UpdateTransaction>,
 }
 
 #[derive(Template)]
@@ -65,6 +66,36 @@ pub struct Server {
     pub username: Option<String>,
     pub description: Option<String>,
     pub enabled: bool,
+    pub connection_timeout: i32,
+    pub retry_attempts: i32,
+    pub docker_endpoint: Option<String>,
+    pub docker_cert_path: Option<String>,
+    pub pre_update_hook: Option<String>,
+    pub post_update_hook: Option<String>,
+    pub rollback_hook: Option<String>,
+}
+
+#[derive(Template)]
+#[template(path = "components/update_transaction_list.html")]
+pub struct UpdateTransactionListTemplate {
+    pub transactions: Vec<UpdateTransaction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateTransaction {
+    pub id: i64,
+    pub server_name: String,
+    pub package_manager: String,
+    pub transaction_id: String,
+    pub package_count: usize, // 0 for dnf/yum, which undo by ID
+    pub status: String,
+    pub post_hook_failed: bool,
+    pub rollback_hook_ran: bool,
+    pub created_at: String,
+    pub rolled_back_by: String,
+    pub rollback_output: String,
+    pub rollback_summary: String, // Last line of the rollback output
+    pub can_roll_back: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -74,6 +105,11 @@ pub struct CreateServerInput {
     pub port: Option<i32>,
     pub username: Option<String>,
     pub description: Option<String>,
+    pub docker_endpoint: Option<String>,
+    pub docker_cert_path: Option<String>,
+    pub pre_update_hook: Option<String>,
+    pub post_update_hook: Option<String>,
+    pub rollback_hook: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -84,6 +120,13 @@ pub struct UpdateServerInput {
     pub username: Option<String>,
     pub description: Option<String>,
     pub enabled: Option<bool>,
+    pub connection_timeout: Option<i32>,
+    pub retry_attempts: Option<i32>,
+    pub docker_endpoint: Option<String>,
+    pub docker_cert_path: Option<String>,
+    pub pre_update_hook: Option<String>,
+    pub post_update_hook: Option<String>,
+    pub rollback_hook: Option<String>,
 }
 
 // ============================================================================
@@ -95,6 +138,32 @@ pub struct UpdateServerInput {
 pub struct TasksTemplate {
     pub user: Option<User>,
     pub tasks: Vec<Task>,
+    pub executions: Vec<RunningExecution>,
+}
+
+#[derive(Template)]
+#[template(path = "components/running_executions.html")]
+pub struct RunningExecutionsTemplate {
+    pub executions: Vec<RunningExecution>,
+}
+
+#[derive(Template)]
+#[template(path = "pages/task_run.html")]
+pub struct TaskRunTemplate {
+    pub user: Option<User>,
+    pub execution: Option<RunningExecution>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RunningExecution {
+    pub id: u64,
+    pub task_name: String,
+    pub plugin_id: String,
+    pub triggered_by: String,
+    pub attempt: u32,
+    pub started_at: String,
+    pub elapsed: String,
+    pub cancelling: bool,
 }
 
 #[derive(Template)]
@@ -110,8 +179,14 @@ pub struct Task {
     pub description: Option<String>,
     pub plugin_id: String,
     pub schedule: String,
+    pub enabled: bool,
     pub last_run_at: Option<String>,
     pub next_run_at: Option<String>,
+    pub missed_runs: i64,
+    /// Where the task runs, e.g. "Local", a server name or "Tags: web, db"
+    pub target: String,
+    /// Outcome of the last run: success, partial, failed or cancelled
+    pub last_status: Option<String>,
 }
 
 // ============================================================================
@@ -136,12 +211,15 @@ pub struct PluginListTemplate {
 pub struct PluginConfigFormTemplate {
     pub plugin: Plugin,
     pub config_schedule: String,
+    pub config_catch_up: String,
+    pub config_overlap_policy: String,
     pub config_api_key: String,
     pub config_zip: String,
     pub config_location: String,
     pub config_units: String,
     pub config_min_down: String,
     pub config_min_up: String,
+    pub config_approval_required: bool,
     pub error: Option<String>,
 }
 
@@ -158,6 +236,8 @@ pub struct Plugin {
 pub struct PluginConfigInput {
     // Common to all plugins
     pub schedule: Option<String>,
+    pub catch_up: Option<String>,
+    pub overlap_policy: Option<String>,
     // Weather plugin
     pub api_key: Option<String>,
     pub zip: Option<String>,
@@ -166,6 +246,8 @@ pub struct PluginConfigInput {
     // Speedtest plugin
     pub min_down: Option<String>,
     pub min_up: Option<String>,
+    // Updates plugin
+    pub approval_required: Option<String>,
 }
 
 // ============================================================================
@@ -199,12 +281,28 @@ pub struct NotificationListTemplate {
 #[template(path = "components/notification_form.html")]
 pub struct NotificationFormTemplate {
     pub notification: Option<NotificationBackend>,
-    pub config_url: String,
-    pub config_token: String,
-    pub config_topic: String,
+    pub config: NotificationConfig,
     pub error: Option<String>,
 }
 
+/// Backend settings shown in the notification form; empty = not set
+#[derive(Debug, Clone, Default)]
+pub struct NotificationConfig {
+    pub url: String,
+    pub token: String,
+    pub topic: String,
+    pub host: String,
+    pub port: String,
+    pub tls: String,
+    pub username: String,
+    pub from: String,
+    pub to: String, // Comma-separated recipients
+    pub room_id: String,
+    pub method: String,
+    pub headers: String, // One "Name: value" per line
+    pub body_template: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NotificationBackend {
     pub id: i64,
@@ -221,6 +319,17 @@ pub struct CreateNotificationInput {
     pub url: Option<String>,
     pub token: Option<String>,
     pub topic: Option<String>,
+    pub host: Option<String>,
+    pub port: Option<String>,
+    pub tls: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>, // Comma-separated
+    pub room_id: Option<String>,
+    pub method: Option<String>,
+    pub headers: Option<String>, // One "Name: value" per line
+    pub body_template: Option<String>,
     pub priority: Option<i32>,
     pub enabled: Option<String>,
 }
@@ -231,10 +340,296 @@ pub struct UpdateNotificationInput {
     pub url: Option<String>,
     pub token: Option<String>,
     pub topic: Option<String>,
+    pub host: Option<String>,
+    pub port: Option<String>,
+    pub tls: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>, // Empty = keep the current password
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub room_id: Option<String>,
+    pub method: Option<String>,
+    pub headers: Option<String>,
+    pub body_template: Option<String>,
     pub priority: Option<i32>,
     pub enabled: Option<String>,
 }
 
+/// Backend settings from the create and update forms
+#[derive(Debug, Default)]
+pub struct NotificationConfigInput {
+    pub url: Option<String>,
+    pub token: Option<String>,
+    pub topic: Option<String>,
+    pub host: Option<String>,
+    pub port: Option<String>,
+    pub tls: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub room_id: Option<String>,
+    pub method: Option<String>,
+    pub headers: Option<String>,
+    pub body_template: Option<String>,
+}
+
+// ============================================================================
+// API Tokens
+// ============================================================================
+
+#[derive(Template)]
+#[template(path = "pages/api_tokens.html")]
+pub struct ApiTokensTemplate {
+    pub user: Option<User>,
+    pub tokens: Vec<ApiToken>,
+    pub new_token: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Template)]
+#[template(path = "components/api_token_list.html")]
+pub struct ApiTokenListTemplate {
+    pub tokens: Vec<ApiToken>,
+    pub new_token: Option<String>, // Shown once, right after creating it
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiToken {
+    pub id: i64,
+    pub name: String,
+    pub token_prefix: String,
+    pub scopes: String,
+    pub expires_at: String, // Empty = never
+    pub expired: bool,
+    pub last_used_at: String, // Empty = never used
+    pub created_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateApiTokenInput {
+    pub name: String,
+    pub expires_in_days: Option<String>, // Empty = never expires
+    pub scope_read: Option<String>,
+    pub scope_operate: Option<String>,
+    pub scope_admin: Option<String>,
+}
+
+// ============================================================================
+// Audit Log
+// ============================================================================
+
+#[derive(Template)]
+#[template(path = "pages/audit.html")]
+pub struct AuditTemplate {
+    pub user: Option<User>,
+    pub entries: Vec<AuditEntry>,
+    pub truncated: bool, // More entries match than are shown
+    pub error: Option<String>,
+}
+
+#[derive(Template)]
+#[template(path = "components/audit_list.html")]
+pub struct AuditListTemplate {
+    pub entries: Vec<AuditEntry>,
+    pub truncated: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub created_at: String,
+    pub actor: String,
+    pub action: String,
+    pub target: String,
+    pub outcome: String,
+    pub message: String,
+    pub source_ip: String,
+    pub changes: String, // Pretty-printed JSON; empty = no changes recorded
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct AuditFilterInput {
+    pub actor: Option<String>,
+    pub action: Option<String>,
+    pub target: Option<String>,
+    pub outcome: Option<String>,
+    pub since: Option<String>, // YYYY-MM-DD
+    pub until: Option<String>, // YYYY-MM-DD, inclusive
+}
+
+// ============================================================================
+// Alerts
+// ============================================================================
+
+#[derive(Template)]
+#[template(path = "pages/alerts.html")]
+pub struct AlertsTemplate {
+    pub user: Option<User>,
+    pub rules: Vec<AlertRule>,
+    pub active: Vec<ActiveAlert>,
+    pub events: Vec<AlertEvent>,
+}
+
+#[derive(Template)]
+#[template(path = "components/alert_rule_list.html")]
+pub struct AlertRuleListTemplate {
+    pub rules: Vec<AlertRule>,
+}
+
+#[derive(Template)]
+#[template(path = "components/alert_rule_form.html")]
+pub struct AlertRuleFormTemplate {
+    pub rule: Option<AlertRule>,
+    pub servers: Vec<Server>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub enabled: bool,
+    pub plugin_id: String, // Empty = any plugin
+    pub metric_name: String,
+    pub server_id: Option<i64>,
+    pub server_tag: String, // Empty = any tag
+    pub scope: String,      // Human-readable server filter
+    pub operator: String,
+    pub threshold: f64,
+    pub duration_secs: i64,
+    pub severity: String,
+    pub notify_resolved: bool,
+}
+
+impl AlertRule {
+    /// Whether the rule is limited to the given server (for form selection)
+    pub fn targets_server(&self, id: &i64) -> bool {
+        self.server_id == Some(*id)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiveAlert {
+    pub rule_name: String,
+    pub severity: String,
+    pub server_name: String,
+    pub metric_name: String,
+    pub state: String,
+    pub value: String,
+    pub since: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertEvent {
+    pub rule_name: String,
+    pub server_name: String,
+    pub event: String,
+    pub severity: String,
+    pub message: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AlertRuleInput {
+    pub name: String,
+    pub description: Option<String>,
+    pub plugin_id: Option<String>,
+    pub metric_name: String,
+    pub server_id: Option<String>, // Empty = any server
+    pub server_tag: Option<String>,
+    pub operator: String,
+    pub threshold: String,
+    pub duration_secs: Option<String>,
+    pub severity: String,
+    pub notify_resolved: Option<String>,
+    pub enabled: Option<String>,
+}
+
+// ============================================================================
+// Workflows
+// ============================================================================
+
+#[derive(Template)]
+#[template(path = "pages/workflows.html")]
+pub struct WorkflowsTemplate {
+    pub user: Option<User>,
+    pub workflows: Vec<Workflow>,
+    pub runs: Vec<WorkflowRun>,
+}
+
+#[derive(Template)]
+#[template(path = "components/workflow_list.html")]
+pub struct WorkflowListTemplate {
+    pub workflows: Vec<Workflow>,
+}
+
+#[derive(Template)]
+#[template(path = "components/workflow_form.html")]
+pub struct WorkflowFormTemplate {
+    pub workflow: Option<Workflow>,
+    pub error: Option<String>,
+}
+
+#[derive(Template)]
+#[template(path = "components/workflow_runs.html")]
+pub struct WorkflowRunsTemplate {
+    pub runs: Vec<WorkflowRun>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Workflow {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub enabled: bool,
+    pub schedule: String,               // Empty = manual / webhook only
+    pub steps_json: String,             // Pretty-printed steps for the edit form
+    pub levels: Vec<Vec<WorkflowStep>>, // Steps grouped by dependency depth
+    pub last_run_at: String,
+    pub next_run_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowStep {
+    pub id: String,
+    pub target: String,     // Task name or "server: command"
+    pub condition: String,  // Empty = previous steps succeeded
+    pub depends_on: String, // Comma-separated step IDs
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowRun {
+    pub id: i64,
+    pub workflow_name: String,
+    pub status: String,
+    pub triggered_by: String,
+    pub started_at: String,
+    pub finished_at: String,
+    pub error_message: String,
+    pub steps: Vec<WorkflowStepRun>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowStepRun {
+    pub step_id: String,
+    pub status: String,
+    pub message: String,
+    pub duration: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WorkflowInput {
+    pub name: String,
+    pub description: Option<String>,
+    pub schedule: Option<String>,
+    pub steps: String, // JSON array of steps
+    pub enabled: Option<String>,
+}
+
 // ============================================================================
 // Auth
 // ============================================================================
@@ -260,4 +655,3 @@ pub struct LoginForm {
 pub struct NotFoundTemplate {
     pub user: Option<User>,
 }
-
@@ -2,16 +2,28 @@
 
 use askama::Template;
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
     response::{Html, IntoResponse, Redirect},
-    routing::{get, post, put},
+    routing::{delete, get, post, put},
     Form, Router,
 };
 use serde::Deserialize;
+use svrctlrs_database::{
+    models::{server as db_server, AuditFilter, AuditOutcome, TaskTarget, UserRole},
+    queries,
+};
 use tower_http::services::ServeDir;
-use svrctlrs_database::{models::server as db_server, queries};
+use tower_sessions::Session;
 
-use crate::{state::AppState, templates::*};
+use crate::{
+    audit::Audit,
+    auth,
+    executions::TaskTrigger,
+    rbac::{self, authorize, authorize_on, CurrentUser, Targets},
+    state::AppState,
+    templates::*,
+};
 
 /// Create UI router with all page and component routes
 pub fn ui_routes() -> Router<AppState> {
@@ -22,54 +34,96 @@ pub fn ui_routes() -> Router<AppState> {
         .route("/tasks", get(tasks_page))
         .route("/plugins", get(plugins_page))
         .route("/settings", get(settings_page))
-        
         // Server CRUD
         .route("/servers/new", get(server_form_new))
         .route("/servers", post(server_create))
         .route("/servers/test", post(server_test_connection))
         .route("/servers/{id}/edit", get(server_form_edit))
         .route("/servers/{id}", put(server_update).delete(server_delete))
-        
+        .route("/servers/transactions", get(update_transaction_list))
+        .route(
+            "/servers/transactions/{id}/rollback",
+            post(update_transaction_rollback),
+        )
         // Task list (for auto-refresh) and manual execution
         .route("/tasks/list", get(task_list))
         .route("/tasks/{id}/run", post(task_run_now))
-        
+        .route("/tasks/{id}/toggle", post(task_toggle))
+        .route("/tasks/running", get(running_executions))
+        .route("/tasks/executions/{id}", get(execution_page))
+        .route("/tasks/executions/{id}/cancel", post(execution_cancel))
+        .route("/tasks/{id}", delete(task_delete))
         // Plugin toggle and configuration
         .route("/plugins/{id}/toggle", post(plugin_toggle))
-        .route("/plugins/{id}/config", get(plugin_config_form).put(plugin_config_save))
-        
+        .route(
+            "/plugins/{id}/config",
+            get(plugin_config_form).put(plugin_config_save),
+        )
+        // Alert rules
+        .route("/alerts", get(alerts_page))
+        .route("/alerts/rules/new", get(alert_rule_form_new))
+        .route("/alerts/rules", post(alert_rule_create))
+        .route("/alerts/rules/{id}/edit", get(alert_rule_form_edit))
+        .route(
+            "/alerts/rules/{id}",
+            put(alert_rule_update).delete(alert_rule_delete),
+        )
+        // Workflows
+        .route("/workflows", get(workflows_page).post(workflow_create))
+        .route("/workflows/new", get(workflow_form_new))
+        .route("/workflows/runs", get(workflow_runs))
+        .route("/workflows/{id}/edit", get(workflow_form_edit))
+        .route("/workflows/{id}/run", post(workflow_run_now))
+        .route("/workflows/{id}/toggle", post(workflow_toggle))
+        .route(
+            "/workflows/{id}",
+            put(workflow_update).delete(workflow_delete),
+        )
         // Notification settings
         .route("/settings/notifications", get(notifications_page))
         .route("/settings/notifications/new", get(notification_form_new))
         .route("/settings/notifications", post(notification_create))
-        .route("/settings/notifications/{id}/edit", get(notification_form_edit))
-        .route("/settings/notifications/{id}", put(notification_update).delete(notification_delete))
-        
+        .route(
+            "/settings/notifications/{id}/edit",
+            get(notification_form_edit),
+        )
+        .route(
+            "/settings/notifications/{id}",
+            put(notification_update).delete(notification_delete),
+        )
+        // API token settings
+        .route(
+            "/settings/tokens",
+            get(api_tokens_page).post(api_token_create),
+        )
+        .route("/settings/tokens/{id}", delete(api_token_revoke))
+        // Audit log
+        .route("/audit", get(audit_page))
+        .route("/audit/list", get(audit_list))
+        .route("/audit/export", get(audit_export))
         // Auth
         .route("/auth/login", get(login_page).post(login))
         .route("/auth/logout", post(logout))
-        
         // Static files
         .nest_service(
             "/static",
             ServeDir::new(
-                std::env::var("STATIC_DIR")
-                    .unwrap_or_else(|_| "server/static".to_string())
-            )
+                std::env::var("STATIC_DIR").unwrap_or_else(|_| "server/static".to_string()),
+            ),
         )
-        
         // 404 handler
         .fallback(not_found)
 }
 
 // ============================================================================
-// Helper: Get user from session (placeholder for now)
+// Helper: Get user from session
 // ============================================================================
 
-async fn get_user_from_session() -> Option<User> {
-    // TODO: Implement session management with tower-sessions
-    // For now, return None (no auth)
-    None
+async fn get_user_from_session(session: &Session) -> Option<User> {
+    auth::current_user(session).await.map(|u| User {
+        id: u.id,
+        username: u.username,
+    })
 }
 
 // ============================================================================
@@ -85,6 +139,13 @@ fn db_server_to_ui(db: db_server::Server) -> Server {
         username: Some(db.username),
         description: db.description,
         enabled: db.enabled,
+        connection_timeout: db.connection_timeout,
+        retry_attempts: db.retry_attempts,
+        docker_endpoint: db.docker_endpoint,
+        docker_cert_path: db.docker_cert_path,
+        pre_update_hook: db.pre_update_hook,
+        post_update_hook: db.post_update_hook,
+        rollback_hook: db.rollback_hook,
     }
 }
 
@@ -92,25 +153,28 @@ fn db_server_to_ui(db: db_server::Server) -> Server {
 // Dashboard
 // ============================================================================
 
-async fn dashboard_page(State(state): State<AppState>) -> Result<Html<String>, AppError> {
-    let user = get_user_from_session().await;
-    
+async fn dashboard_page(
+    State(state): State<AppState>,
+    session: Session,
+) -> Result<Html<String>, AppError> {
+    let user = get_user_from_session(&session).await;
+
     // Get stats
     let plugins = state.plugins.read().await;
     let enabled_plugins = plugins.plugins().len();
-    
+
     // Get server count from database
     let db = state.db().await;
     let servers = queries::servers::list_servers(db.pool()).await?;
     let total_servers = servers.len();
-    
+
     let stats = DashboardStats {
         total_servers,
-        active_tasks: 0,  // TODO: Track active tasks
+        active_tasks: 0, // TODO: Track active tasks
         enabled_plugins,
-        total_tasks: 0,   // TODO: Track total tasks
+        total_tasks: 0, // TODO: Track total tasks
     };
-    
+
     let template = DashboardTemplate { user, stats };
     Ok(Html(template.render()?))
 }
@@ -119,18 +183,125 @@ async fn dashboard_page(State(state): State<AppState>) -> Result<Html<String>, A
 // Servers
 // ============================================================================
 
-async fn servers_page(State(state): State<AppState>) -> Result<Html<String>, AppError> {
-    let user = get_user_from_session().await;
-    
+async fn servers_page(
+    State(state): State<AppState>,
+    session: Session,
+) -> Result<Html<String>, AppError> {
+    let user = get_user_from_session(&session).await;
+
     // Load servers from database
     let db = state.db().await;
     let db_servers = queries::servers::list_servers(db.pool()).await?;
     let servers = db_servers.into_iter().map(db_server_to_ui).collect();
-    
-    let template = ServersTemplate { user, servers };
+    let transactions = get_update_transactions(&db).await?;
+
+    let template = ServersTemplate {
+        user,
+        servers,
+        transactions,
+    };
+    Ok(Html(template.render()?))
+}
+
+/// Update transactions shown on the Servers page
+const UPDATE_TRANSACTIONS_SHOWN: i64 = 20;
+
+async fn get_update_transactions(
+    db: &svrctlrs_database::Database,
+) -> Result<Vec<UpdateTransaction>, AppError> {
+    let transactions =
+        queries::updates::list_update_transactions(db.pool(), None, UPDATE_TRANSACTIONS_SHOWN)
+            .await?;
+
+    Ok(transactions
+        .into_iter()
+        .map(|t| {
+            let rollback_output = t.rollback_output.clone().unwrap_or_default();
+            UpdateTransaction {
+                id: t.id,
+                package_count: t.get_packages().len(),
+                can_roll_back: cfg!(feature = "plugin-updates") && t.can_roll_back(),
+                server_name: t.server_name,
+                package_manager: t.package_manager,
+                transaction_id: t.transaction_id,
+                status: t.status,
+                post_hook_failed: t.post_hook_failed,
+                rollback_hook_ran: t.rollback_hook_ran,
+                created_at: t.created_at.to_rfc3339(),
+                rolled_back_by: t.rolled_back_by.unwrap_or_default(),
+                rollback_summary: rollback_output
+                    .lines()
+                    .last()
+                    .unwrap_or_default()
+                    .to_string(),
+                rollback_output,
+            }
+        })
+        .collect())
+}
+
+async fn update_transaction_list(State(state): State<AppState>) -> Result<Html<String>, AppError> {
+    let db = state.db().await;
+    let transactions = get_update_transactions(&db).await?;
+
+    let template = UpdateTransactionListTemplate { transactions };
     Ok(Html(template.render()?))
 }
 
+/// Downgrade the packages of an update transaction
+#[cfg_attr(not(feature = "plugin-updates"), allow(unused_variables))]
+async fn update_transaction_rollback(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path(id): Path<i64>,
+) -> Result<Html<String>, AppError> {
+    #[cfg(feature = "plugin-updates")]
+    let message = {
+        use crate::updates::{start_rollback, RollbackStart};
+
+        let transaction = {
+            let db = state.db().await;
+            queries::updates::get_update_transaction(db.pool(), id).await?
+        };
+        let targets = Targets::for_server(&*state.db().await, transaction.server_id).await;
+        authorize_on(
+            &state,
+            &user,
+            "update_transaction.rollback",
+            &format!("update transaction {} on {}", id, transaction.server_name),
+            &targets,
+        )
+        .await?;
+
+        match start_rollback(&state, transaction, &user).await {
+            Ok(RollbackStart::Started) => {
+                r#"<div class="alert alert-success">✓ Rollback started</div>"#.to_string()
+            }
+            Ok(RollbackStart::Refused(reason)) => {
+                format!(r#"<div class="alert alert-error">✗ {}</div>"#, reason)
+            }
+            Err(e) => format!(
+                r#"<div class="alert alert-error">✗ Failed to start rollback: {:#}</div>"#,
+                e
+            ),
+        }
+    };
+    #[cfg(not(feature = "plugin-updates"))]
+    let message = {
+        tracing::warn!(
+            "Cannot roll back update transaction {}: updates plugin not built",
+            id
+        );
+        r#"<div class="alert alert-error">✗ The updates plugin is not built into this server</div>"#
+            .to_string()
+    };
+
+    let db = state.db().await;
+    let transactions = get_update_transactions(&db).await?;
+    let template = UpdateTransactionListTemplate { transactions };
+    Ok(Html(format!("{}{}", message, template.render()?)))
+}
+
 async fn server_form_new() -> Result<Html<String>, AppError> {
     let template = ServerFormTemplate {
         server: None,
@@ -146,7 +317,7 @@ async fn server_form_edit(
     // Load server from database
     let db = state.db().await;
     let db_server = queries::servers::get_server(db.pool(), id).await;
-    
+
     let (server, error) = match db_server {
         Ok(s) => (Some(db_server_to_ui(s)), None),
         Err(e) => {
@@ -154,15 +325,18 @@ async fn server_form_edit(
             (None, Some(format!("Server with ID {} not found", id)))
         }
     };
-    
+
     let template = ServerFormTemplate { server, error };
     Ok(Html(template.render()?))
 }
 
 async fn server_create(
     State(state): State<AppState>,
+    user: CurrentUser,
     Form(input): Form<CreateServerInput>,
 ) -> Result<Html<String>, AppError> {
+    authorize(&state, &user, UserRole::Admin, "server.create", &input.name).await?;
+
     // Validate
     if input.name.is_empty() || input.host.is_empty() {
         let template = ServerFormTemplate {
@@ -171,11 +345,11 @@ async fn server_create(
         };
         return Ok(Html(template.render()?));
     }
-    
+
     // Save to database
     tracing::info!("Creating server: {} @ {}", input.name, input.host);
     let db = state.db().await;
-    
+
     let create_server = db_server::CreateServer {
         name: input.name.clone(),
         host: input.host.clone(),
@@ -184,17 +358,37 @@ async fn server_create(
         ssh_key_path: None,
         description: input.description,
         tags: None,
+        docker_endpoint: input.docker_endpoint.filter(|e| !e.trim().is_empty()),
+        docker_cert_path: input.docker_cert_path.filter(|p| !p.trim().is_empty()),
+        pre_update_hook: input.pre_update_hook.filter(|h| !h.trim().is_empty()),
+        post_update_hook: input.post_update_hook.filter(|h| !h.trim().is_empty()),
+        rollback_hook: input.rollback_hook.filter(|h| !h.trim().is_empty()),
     };
-    
+
     // Try to create, handle duplicate name error
-    match queries::servers::create_server(db.pool(), &create_server).await {
-        Ok(_) => {
+    let audit = Audit::new(
+        &state,
+        &user,
+        "server.create",
+        format!("server {}", input.name),
+    );
+    match audit
+        .check(queries::servers::create_server(db.pool(), &create_server).await)
+        .await
+    {
+        Ok(id) => {
+            let audit = match queries::servers::get_server(db.pool(), id).await {
+                Ok(created) => audit.after(&created),
+                Err(_) => audit,
+            };
+            audit.success().await;
+
             // Success - return updated list with success message
             let db_servers = queries::servers::list_servers(db.pool()).await?;
             let servers = db_servers.into_iter().map(db_server_to_ui).collect();
             let template = ServerListTemplate { servers };
             let list_html = template.render()?;
-            
+
             // Prepend success message
             Ok(Html(format!(
                 r#"<div class="alert alert-success">✓ Server '{}' created successfully!</div>{}"#,
@@ -219,23 +413,35 @@ async fn server_create(
 
 async fn server_update(
     State(state): State<AppState>,
+    user: CurrentUser,
     Path(id): Path<i64>,
     Form(input): Form<UpdateServerInput>,
 ) -> Result<Html<String>, AppError> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "server.update",
+        &format!("server {}", id),
+    )
+    .await?;
+
     // Update in database
     tracing::info!("Updating server {}: {:?}", id, input);
     let db = state.db().await;
-    
+
+    let existing = queries::servers::get_server(db.pool(), id).await?;
+    let audit = Audit::new(
+        &state,
+        &user,
+        "server.update",
+        format!("server {} ({})", id, existing.name),
+    )
+    .before(&existing);
+
     // Get the server name for the success message
-    let server_name = if let Some(ref name) = input.name {
-        name.clone()
-    } else {
-        // If name wasn't changed, get it from database
-        queries::servers::get_server(db.pool(), id)
-            .await?
-            .name
-    };
-    
+    let server_name = input.name.clone().unwrap_or_else(|| existing.name.clone());
+
     let update_server = db_server::UpdateServer {
         name: input.name,
         host: input.host,
@@ -245,19 +451,33 @@ async fn server_update(
         description: input.description,
         tags: None,
         enabled: input.enabled,
-        connection_timeout: None,
-        retry_attempts: None,
+        connection_timeout: input.connection_timeout,
+        retry_attempts: input.retry_attempts,
+        docker_endpoint: input.docker_endpoint,
+        docker_cert_path: input.docker_cert_path,
+        pre_update_hook: input.pre_update_hook,
+        post_update_hook: input.post_update_hook,
+        rollback_hook: input.rollback_hook,
     };
-    
+
     // Try to update, handle duplicate name error
-    match queries::servers::update_server(db.pool(), id, &update_server).await {
+    match audit
+        .check(queries::servers::update_server(db.pool(), id, &update_server).await)
+        .await
+    {
         Ok(_) => {
+            let audit = match queries::servers::get_server(db.pool(), id).await {
+                Ok(updated) => audit.after(&updated),
+                Err(_) => audit,
+            };
+            audit.success().await;
+
             // Success - return updated list with success message
             let db_servers = queries::servers::list_servers(db.pool()).await?;
             let servers = db_servers.into_iter().map(db_server_to_ui).collect();
             let template = ServerListTemplate { servers };
             let list_html = template.render()?;
-            
+
             // Prepend success message
             Ok(Html(format!(
                 r#"<div class="alert alert-success">✓ Server '{}' updated successfully!</div>{}"#,
@@ -268,9 +488,7 @@ async fn server_update(
             // Check if it's a duplicate name error
             let error_msg = e.to_string();
             if error_msg.contains("UNIQUE constraint") && error_msg.contains("servers.name") {
-                Ok(Html(format!(
-                    r#"<div class="alert alert-error">✗ A server with that name already exists. Please use a different name.</div>"#
-                )))
+                Ok(Html(r#"<div class="alert alert-error">✗ A server with that name already exists. Please use a different name.</div>"#.to_string()))
             } else {
                 // Other database error
                 Err(e.into())
@@ -281,19 +499,37 @@ async fn server_update(
 
 async fn server_delete(
     State(state): State<AppState>,
+    user: CurrentUser,
     Path(id): Path<i64>,
 ) -> Result<impl IntoResponse, AppError> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "server.delete",
+        &format!("server {}", id),
+    )
+    .await?;
+
     // Get server name before deleting
     let db = state.db().await;
-    let server_name = queries::servers::get_server(db.pool(), id)
-        .await
-        .map(|s| s.name)
-        .unwrap_or_else(|_| format!("Server {}", id));
-    
+    let existing = queries::servers::get_server(db.pool(), id).await?;
+    let server_name = existing.name.clone();
+
     // Delete from database
     tracing::info!("Deleting server {}", id);
-    queries::servers::delete_server(db.pool(), id).await?;
-    
+    let audit = Audit::new(
+        &state,
+        &user,
+        "server.delete",
+        format!("server {} ({})", id, server_name),
+    )
+    .before(&existing);
+    audit
+        .check(queries::servers::delete_server(db.pool(), id).await)
+        .await?;
+    audit.success().await;
+
     // Return success message
     Ok(Html(format!(
         r#"<div class="alert alert-success">✓ Server '{}' deleted successfully!</div>"#,
@@ -309,14 +545,45 @@ struct TestConnectionInput {
 }
 
 async fn server_test_connection(
-    State(_state): State<AppState>,
+    State(state): State<AppState>,
+    user: CurrentUser,
     Form(input): Form<TestConnectionInput>,
 ) -> Result<Html<String>, AppError> {
     let port = input.port.unwrap_or(22);
     let username = input.username.unwrap_or_else(|| "root".to_string());
-    
-    tracing::info!("Testing SSH connection to {}@{}:{}", username, input.host, port);
-    
+
+    // Scoped users may only test the registered servers in their scope;
+    // any other host needs an unscoped operator
+    let targets = {
+        let db = state.db().await;
+        let servers: Vec<_> = queries::servers::list_servers(db.pool())
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|s| s.host.as_deref() == Some(input.host.as_str()))
+            .collect();
+        if servers.is_empty() {
+            Targets::Unrestricted
+        } else {
+            Targets::Servers(servers)
+        }
+    };
+    authorize_on(
+        &state,
+        &user,
+        "server.test_connection",
+        &format!("{}@{}:{}", username, input.host, port),
+        &targets,
+    )
+    .await?;
+
+    tracing::info!(
+        "Testing SSH connection to {}@{}:{}",
+        username,
+        input.host,
+        port
+    );
+
     // Create SSH config
     let ssh_config = crate::ssh::SshConfig {
         host: input.host.clone(),
@@ -325,7 +592,7 @@ async fn server_test_connection(
         key_path: None, // Will use default SSH keys
         timeout: std::time::Duration::from_secs(10),
     };
-    
+
     // Test the connection
     match crate::ssh::test_connection(&ssh_config).await {
         Ok(message) => {
@@ -349,11 +616,19 @@ async fn server_test_connection(
 // Tasks
 // ============================================================================
 
-async fn tasks_page(State(state): State<AppState>) -> Result<Html<String>, AppError> {
-    let user = get_user_from_session().await;
+async fn tasks_page(
+    State(state): State<AppState>,
+    session: Session,
+) -> Result<Html<String>, AppError> {
+    let user = get_user_from_session(&session).await;
     let tasks = get_tasks(&state).await;
-    
-    let template = TasksTemplate { user, tasks };
+    let executions = get_running_executions(&state);
+
+    let template = TasksTemplate {
+        user,
+        tasks,
+        executions,
+    };
     Ok(Html(template.render()?))
 }
 
@@ -366,84 +641,305 @@ async fn task_list(State(state): State<AppState>) -> Result<Html<String>, AppErr
 async fn get_tasks(state: &AppState) -> Vec<Task> {
     // Load tasks from database
     let db = state.db().await;
-    let db_tasks = queries::tasks::list_tasks(db.pool()).await.unwrap_or_default();
-    
-    db_tasks.into_iter().map(|t| Task {
-        id: t.id,
-        name: t.name,
-        description: t.description,
-        plugin_id: t.plugin_id,
-        schedule: t.schedule,
-        last_run_at: t.last_run_at.map(|dt| dt.to_rfc3339()),
-        next_run_at: t.next_run_at.map(|dt| dt.to_rfc3339()),
-    }).collect()
+    let db_tasks = queries::tasks::list_tasks(db.pool())
+        .await
+        .unwrap_or_default();
+    let servers = queries::servers::list_servers(db.pool())
+        .await
+        .unwrap_or_default();
+
+    db_tasks
+        .into_iter()
+        .map(|t| Task {
+            target: match t.target() {
+                Ok(TaskTarget::Local) => "Local".to_string(),
+                Ok(TaskTarget::Server(id)) => servers
+                    .iter()
+                    .find(|s| s.id == id)
+                    .map(|s| s.name.clone())
+                    .unwrap_or_else(|| format!("Server {}", id)),
+                Ok(TaskTarget::AllServers) => "All servers".to_string(),
+                Ok(TaskTarget::Tags(tags)) => format!("Tags: {}", tags.join(", ")),
+                Err(e) => format!("Invalid: {}", e),
+            },
+            last_status: t.last_status,
+            id: t.id,
+            name: t.name,
+            description: t.description,
+            plugin_id: t.plugin_id,
+            schedule: t.schedule,
+            enabled: t.enabled,
+            last_run_at: t.last_run_at.map(|dt| dt.to_rfc3339()),
+            next_run_at: t.next_run_at.map(|dt| dt.to_rfc3339()),
+            missed_runs: t.missed_runs,
+        })
+        .collect()
 }
 
-async fn task_run_now(
+async fn task_toggle(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path(id): Path<i64>,
+) -> Result<Html<String>, AppError> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "task.update",
+        &format!("task {}", id),
+    )
+    .await?;
+
+    let db = state.db().await;
+    let task = queries::tasks::get_task(db.pool(), id).await?;
+
+    tracing::info!(
+        "{} task {}",
+        if task.enabled {
+            "Disabling"
+        } else {
+            "Enabling"
+        },
+        id
+    );
+    let update = svrctlrs_database::models::task::UpdateTask {
+        enabled: Some(!task.enabled),
+        ..Default::default()
+    };
+    let audit = Audit::new(
+        &state,
+        &user,
+        "task.update",
+        format!("task {} ({})", id, task.name),
+    )
+    .before(&task);
+    audit
+        .check(queries::tasks::update_task(db.pool(), id, &update).await)
+        .await?;
+    let audit = match queries::tasks::get_task(db.pool(), id).await {
+        Ok(updated) => audit.after(&updated),
+        Err(_) => audit,
+    };
+    audit.success().await;
+    drop(db);
+
+    // Pause or resume the task in the running scheduler
+    state.sync_task(id).await?;
+
+    task_list(State(state)).await
+}
+
+async fn task_delete(
     State(state): State<AppState>,
+    user: CurrentUser,
     Path(id): Path<i64>,
 ) -> Result<Html<String>, AppError> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "task.delete",
+        &format!("task {}", id),
+    )
+    .await?;
+
+    tracing::info!("Deleting task {}", id);
+    let db = state.db().await;
+    let task = queries::tasks::get_task(db.pool(), id).await?;
+    let audit = Audit::new(
+        &state,
+        &user,
+        "task.delete",
+        format!("task {} ({})", id, task.name),
+    )
+    .before(&task);
+    audit
+        .check(queries::tasks::delete_task(db.pool(), id).await)
+        .await?;
+    audit.success().await;
+    drop(db);
+
+    state.sync_task(id).await?;
+
+    task_list(State(state)).await
+}
+
+async fn task_run_now(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, AppError> {
     tracing::info!("Running task {} manually", id);
-    
-    // Execute task using the executor
-    match crate::executor::execute_task(&state, id).await {
-        Ok(result) => {
-            if result.success {
-                // Escape HTML in output to prevent XSS
-                let escaped_output = result.output
-                    .replace('&', "&amp;")
-                    .replace('<', "&lt;")
-                    .replace('>', "&gt;")
-                    .replace('"', "&quot;");
-                
-                Ok(Html(format!(
-                    r#"<div class="alert alert-success">✓ Task executed successfully in {}ms<br><pre>{}</pre></div>"#,
-                    result.duration_ms,
-                    escaped_output
-                )))
-            } else {
-                Ok(Html(format!(
-                    r#"<div class="alert alert-error">✗ Task execution failed: {}</div>"#,
-                    result.error.unwrap_or_else(|| "Unknown error".to_string())
-                )))
+
+    let db = state.db().await;
+    let task = queries::tasks::get_task(db.pool(), id).await?;
+    let targets = Targets::for_task(&db, &task).await;
+    drop(db);
+    authorize_on(&state, &user, "task.run", &task.name, &targets).await?;
+
+    if !task.enabled {
+        return Ok((
+            [("HX-Trigger", "executions-changed")],
+            Html(r#"<div class="alert alert-error">✗ Task is disabled</div>"#.to_string()),
+        ));
+    }
+
+    // Run in the background; progress shows up in the running executions list
+    let run_state = state.clone();
+    let target = format!("task {} ({})", id, task.name);
+    tokio::spawn(async move {
+        let audit = Audit::new(&run_state, &user, "task.run", target);
+        match crate::executor::execute_task(&run_state, id, TaskTrigger::Manual).await {
+            Ok(result) if result.success => {
+                tracing::info!(
+                    "Manual run of task {} completed in {}ms",
+                    id,
+                    result.duration_ms
+                );
+                tracing::debug!("Task {} output: {}", id, result.output);
+                audit.success().await;
+            }
+            Ok(result) => {
+                let error = result.error.unwrap_or_else(|| "Unknown error".to_string());
+                tracing::warn!(
+                    "Manual run of task {} {} after {} attempt(s): {}",
+                    id,
+                    result.status.as_str(),
+                    result.attempts,
+                    error
+                );
+                audit
+                    .failure(format!("{}: {}", result.status.as_str(), error))
+                    .await;
+            }
+            Err(e) => {
+                tracing::error!("Failed to execute task {}: {}", id, e);
+                audit.failure(format!("{:#}", e)).await;
             }
         }
-        Err(e) => {
-            tracing::error!("Failed to execute task {}: {}", id, e);
-            Ok(Html(format!(
-                r#"<div class="alert alert-error">✗ Failed to execute task: {}</div>"#,
-                e
-            )))
+    });
+
+    Ok((
+        [("HX-Trigger", "executions-changed")],
+        Html(format!(
+            r#"<div class="alert alert-info">▶ Task '{}' started</div>"#,
+            task.name
+        )),
+    ))
+}
+
+async fn running_executions(State(state): State<AppState>) -> Result<Html<String>, AppError> {
+    let template = RunningExecutionsTemplate {
+        executions: get_running_executions(&state),
+    };
+    Ok(Html(template.render()?))
+}
+
+async fn execution_page(
+    State(state): State<AppState>,
+    session: Session,
+    Path(id): Path<u64>,
+) -> Result<Html<String>, AppError> {
+    let user = get_user_from_session(&session).await;
+    let execution = get_running_executions(&state)
+        .into_iter()
+        .find(|e| e.id == id);
+
+    let template = TaskRunTemplate { user, execution };
+    Ok(Html(template.render()?))
+}
+
+async fn execution_cancel(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path(id): Path<u64>,
+) -> Result<Html<String>, AppError> {
+    if let Some(execution) = state.executions.list().into_iter().find(|e| e.id == id) {
+        let target = format!("execution {} of task {}", id, execution.task_name);
+        let targets = Targets::for_task_id(&*state.db().await, execution.task_id).await;
+        authorize_on(&state, &user, "execution.cancel", &target, &targets).await?;
+
+        let audit = Audit::new(&state, &user, "execution.cancel", target);
+        if crate::executor::cancel_execution(&state, id).await {
+            audit.success().await;
+        } else {
+            tracing::info!("Execution {} already finished, nothing to cancel", id);
+            audit.failure("Execution already finished").await;
         }
     }
+    running_executions(State(state)).await
+}
+
+fn get_running_executions(state: &AppState) -> Vec<RunningExecution> {
+    state
+        .executions
+        .list()
+        .into_iter()
+        .map(|e| RunningExecution {
+            id: e.id,
+            task_name: e.task_name,
+            plugin_id: e.plugin_id,
+            triggered_by: e.triggered_by.to_string(),
+            attempt: e.attempt,
+            started_at: e.started_at.to_rfc3339(),
+            elapsed: format_elapsed(e.elapsed_ms),
+            cancelling: e.cancelling,
+        })
+        .collect()
+}
+
+/// Format a duration as e.g. "4s", "2m 05s" or "1h 03m"
+fn format_elapsed(ms: i64) -> String {
+    let secs = ms / 1000;
+    match secs {
+        s if s < 60 => format!("{}s", s),
+        s if s < 3600 => format!("{}m {:02}s", s / 60, s % 60),
+        s => format!("{}h {:02}m", s / 3600, (s % 3600) / 60),
+    }
 }
 
 // ============================================================================
 // Plugins
 // ============================================================================
 
-async fn plugins_page(State(state): State<AppState>) -> Result<Html<String>, AppError> {
-    let user = get_user_from_session().await;
-    
+async fn plugins_page(
+    State(state): State<AppState>,
+    session: Session,
+) -> Result<Html<String>, AppError> {
+    let user = get_user_from_session(&session).await;
+
     // Load plugins from database
     let db = state.db().await;
     let db_plugins = queries::plugins::list_plugins(db.pool()).await?;
     let plugins = db_plugins.into_iter().map(db_plugin_to_ui).collect();
-    
+
     let template = PluginsTemplate { user, plugins };
     Ok(Html(template.render()?))
 }
 
 async fn plugin_toggle(
     State(state): State<AppState>,
+    user: CurrentUser,
     Path(id): Path<String>,
 ) -> Result<Html<String>, AppError> {
+    authorize(&state, &user, UserRole::Admin, "plugin.toggle", &id).await?;
+
     tracing::info!("Toggling plugin: {}", id);
-    
+
     // Toggle plugin in database
     let db = state.db().await;
-    queries::plugins::toggle_plugin(db.pool(), &id).await?;
-    
+    let existing = queries::plugins::get_plugin(db.pool(), &id).await?;
+    let audit =
+        Audit::new(&state, &user, "plugin.toggle", format!("plugin {}", id)).before(&existing);
+    audit
+        .check(queries::plugins::toggle_plugin(db.pool(), &id).await)
+        .await?;
+    let audit = match queries::plugins::get_plugin(db.pool(), &id).await {
+        Ok(updated) => audit.after(&updated),
+        Err(_) => audit,
+    };
+    audit.success().await;
+
     // Return updated plugin list
     let db_plugins = queries::plugins::list_plugins(db.pool()).await?;
     let plugins = db_plugins.into_iter().map(db_plugin_to_ui).collect();
@@ -463,40 +959,118 @@ fn db_plugin_to_ui(db: svrctlrs_database::models::plugin::Plugin) -> Plugin {
 
 async fn plugin_config_form(
     State(state): State<AppState>,
+    user: CurrentUser,
     Path(id): Path<String>,
 ) -> Result<Html<String>, AppError> {
+    // The form shows the plugin's API keys
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "plugin.view",
+        &format!("plugin {}", id),
+    )
+    .await?;
+
     // Load plugin from database
     let db = state.db().await;
     let db_plugin = queries::plugins::get_plugin(db.pool(), &id).await?;
-    
+
     // Parse config JSON
     let config = db_plugin.get_config();
-    
+
+    // Catch-up and overlap policies live on the plugin's task
+    let plugin_task = queries::tasks::list_tasks_by_plugin(db.pool(), &id)
+        .await?
+        .into_iter()
+        .next();
+    let (config_catch_up, config_overlap_policy) = match plugin_task {
+        Some(t) => (t.catch_up, t.overlap_policy),
+        None => ("skip".to_string(), "skip".to_string()),
+    };
+
     let template = PluginConfigFormTemplate {
         plugin: db_plugin_to_ui(db_plugin),
-        config_schedule: config.get("schedule").and_then(|v| v.as_str()).unwrap_or("0 */5 * * * *").to_string(),
-        config_api_key: config.get("api_key").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-        config_zip: config.get("zip").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-        config_location: config.get("location").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-        config_units: config.get("units").and_then(|v| v.as_str()).unwrap_or("imperial").to_string(),
-        config_min_down: config.get("min_down").and_then(|v| v.as_i64()).map(|v| v.to_string()).unwrap_or_else(|| "100".to_string()),
-        config_min_up: config.get("min_up").and_then(|v| v.as_i64()).map(|v| v.to_string()).unwrap_or_else(|| "20".to_string()),
+        config_schedule: config
+            .get("schedule")
+            .and_then(|v| v.as_str())
+            .unwrap_or("0 */5 * * * *")
+            .to_string(),
+        config_catch_up,
+        config_overlap_policy,
+        config_api_key: config
+            .get("api_key")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        config_zip: config
+            .get("zip")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        config_location: config
+            .get("location")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        config_units: config
+            .get("units")
+            .and_then(|v| v.as_str())
+            .unwrap_or("imperial")
+            .to_string(),
+        config_min_down: config
+            .get("min_down")
+            .and_then(|v| v.as_i64())
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "100".to_string()),
+        config_min_up: config
+            .get("min_up")
+            .and_then(|v| v.as_i64())
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "20".to_string()),
+        config_approval_required: config
+            .get("approval_required")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
         error: None,
     };
-    
+
     Ok(Html(template.render()?))
 }
 
 async fn plugin_config_save(
     State(state): State<AppState>,
+    user: CurrentUser,
     Path(id): Path<String>,
     Form(input): Form<PluginConfigInput>,
 ) -> Result<Html<String>, AppError> {
+    authorize(&state, &user, UserRole::Admin, "plugin.update", &id).await?;
+
     tracing::info!("Saving plugin config: {} {:?}", id, input);
-    
+
     // Extract schedule first to avoid move issues
-    let schedule = input.schedule.clone().unwrap_or_else(|| "0 */5 * * * *".to_string());
-    
+    let schedule = input
+        .schedule
+        .clone()
+        .unwrap_or_else(|| "0 */5 * * * *".to_string());
+    let catch_up = input.catch_up.clone().unwrap_or_else(|| "skip".to_string());
+    if svrctlrs_scheduler::CatchUpPolicy::parse(&catch_up).is_none() {
+        return Ok(Html(format!(
+            r#"<div class="alert alert-error">Invalid catch-up policy '{}'. Use skip, once or all.</div>"#,
+            catch_up
+        )));
+    }
+    let overlap_policy = input
+        .overlap_policy
+        .clone()
+        .unwrap_or_else(|| "skip".to_string());
+    if svrctlrs_scheduler::OverlapPolicy::parse(&overlap_policy).is_none() {
+        return Ok(Html(format!(
+            r#"<div class="alert alert-error">Invalid overlap policy '{}'. Use skip, queue or kill.</div>"#,
+            overlap_policy
+        )));
+    }
+
     // Validate cron expression before saving
     use cron::Schedule;
     use std::str::FromStr;
@@ -507,7 +1081,7 @@ async fn plugin_config_save(
             schedule, e
         )));
     }
-    
+
     // Build config JSON based on plugin type
     let config_json = if id == "weather" {
         serde_json::json!({
@@ -523,27 +1097,46 @@ async fn plugin_config_save(
             "min_down": input.min_down.and_then(|s| s.parse::<i64>().ok()).unwrap_or(100),
             "min_up": input.min_up.and_then(|s| s.parse::<i64>().ok()).unwrap_or(20),
         })
+    } else if id == "updates" {
+        serde_json::json!({
+            "schedule": schedule,
+            "approval_required": input.approval_required.is_some(),
+        })
     } else {
         serde_json::json!({
             "schedule": schedule,
         })
     };
-    
+
     // Update plugin in database
     let db = state.db().await;
     let update = svrctlrs_database::models::plugin::UpdatePlugin {
         enabled: None,
         config: Some(config_json.clone()),
     };
-    queries::plugins::update_plugin(db.pool(), &id, &update).await?;
-    
+    let existing = queries::plugins::get_plugin(db.pool(), &id).await?;
+    let audit =
+        Audit::new(&state, &user, "plugin.update", format!("plugin {}", id)).before(&existing);
+    audit
+        .check(queries::plugins::update_plugin(db.pool(), &id, &update).await)
+        .await?;
+    let audit = match queries::plugins::get_plugin(db.pool(), &id).await {
+        Ok(updated) => audit.after(&updated),
+        Err(_) => audit,
+    };
+    audit.success().await;
+
     // Create or update scheduled task for this plugin (schedule already extracted above)
-    
+
+    // The plugin reads its settings from the database; keep its API keys
+    // out of the task args, which every viewer can list
+    let task_args = crate::audit::without_secrets(&config_json);
+
     // Check if task already exists for this plugin
     let existing_tasks = queries::tasks::list_tasks(db.pool()).await?;
     let existing_task = existing_tasks.iter().find(|t| t.plugin_id == id);
-    
-    if let Some(task) = existing_task {
+
+    let task_id = if let Some(task) = existing_task {
         // Update existing task
         let update_task = svrctlrs_database::models::task::UpdateTask {
             name: None,
@@ -551,25 +1144,41 @@ async fn plugin_config_save(
             schedule: Some(schedule.clone()),
             enabled: Some(true),
             command: None,
-            args: None,
+            args: Some(task_args),
             timeout: None,
+            catch_up: Some(catch_up),
+            overlap_policy: Some(overlap_policy),
+            retry_attempts: None,
+            server_id: None,
+            target: None,
+            target_tags: None,
         };
         queries::tasks::update_task(db.pool(), task.id, &update_task).await?;
+        task.id
     } else {
         // Create new task
         let create_task = svrctlrs_database::models::task::CreateTask {
             name: format!("{} Task", id),
             description: Some(format!("Scheduled task for {} plugin", id)),
             plugin_id: id.clone(),
-            server_id: None, // Run on all servers
+            server_id: None, // Runs locally
             schedule: schedule.clone(),
             command: "execute".to_string(),
-            args: Some(config_json),
+            args: Some(task_args),
             timeout: 300,
+            catch_up,
+            overlap_policy,
+            retry_attempts: None,
+            target: None,
+            target_tags: None,
         };
-        queries::tasks::create_task(db.pool(), &create_task).await?;
-    }
-    
+        queries::tasks::create_task(db.pool(), &create_task).await?
+    };
+    drop(db);
+
+    // Apply the new schedule to the running scheduler
+    state.sync_task(task_id).await?;
+
     // Return success message
     Ok(Html("<div class=\"alert alert-success\">Configuration saved successfully! Task created/updated.</div>".to_string()))
 }
@@ -578,8 +1187,8 @@ async fn plugin_config_save(
 // Settings
 // ============================================================================
 
-async fn settings_page() -> Result<Html<String>, AppError> {
-    let user = get_user_from_session().await;
+async fn settings_page(session: Session) -> Result<Html<String>, AppError> {
+    let user = get_user_from_session(&session).await;
     let template = SettingsTemplate { user };
     Ok(Html(template.render()?))
 }
@@ -588,15 +1197,24 @@ async fn settings_page() -> Result<Html<String>, AppError> {
 // Notifications
 // ============================================================================
 
-async fn notifications_page(State(state): State<AppState>) -> Result<Html<String>, AppError> {
-    let user = get_user_from_session().await;
-    
+async fn notifications_page(
+    State(state): State<AppState>,
+    session: Session,
+) -> Result<Html<String>, AppError> {
+    let user = get_user_from_session(&session).await;
+
     // Load notification backends from database
     let db = state.db().await;
     let db_notifications = queries::notifications::list_notification_backends(db.pool()).await?;
-    let notifications = db_notifications.into_iter().map(db_notification_to_ui).collect();
-    
-    let template = NotificationsTemplate { user, notifications };
+    let notifications = db_notifications
+        .into_iter()
+        .map(db_notification_to_ui)
+        .collect();
+
+    let template = NotificationsTemplate {
+        user,
+        notifications,
+    };
     Ok(Html(template.render()?))
 }
 
@@ -604,9 +1222,7 @@ async fn notification_form_new() -> Result<Html<String>, AppError> {
     tracing::info!("notification_form_new called - loading add backend form");
     let template = NotificationFormTemplate {
         notification: None,
-        config_url: String::new(),
-        config_token: String::new(),
-        config_topic: String::new(),
+        config: NotificationConfig::default(),
         error: None,
     };
     Ok(Html(template.render()?))
@@ -614,40 +1230,47 @@ async fn notification_form_new() -> Result<Html<String>, AppError> {
 
 async fn notification_form_edit(
     State(state): State<AppState>,
+    user: CurrentUser,
     Path(id): Path<i64>,
 ) -> Result<Html<String>, AppError> {
+    // The form shows the backend's credentials
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "notification_backend.view",
+        &format!("notification backend {}", id),
+    )
+    .await?;
+
     // Load notification backend from database
     let db = state.db().await;
     let db_notification = queries::notifications::get_notification_backend(db.pool(), id).await;
-    
+
     let (notification, error) = match db_notification {
         Ok(n) => {
-            let config = n.get_config();
+            let config = notification_config_to_ui(&n.get_config());
             let template_notification = Some(db_notification_to_ui(n));
-            let config_url = config.get("url").and_then(|v| v.as_str()).unwrap_or("").to_string();
-            let config_token = config.get("token").and_then(|v| v.as_str()).unwrap_or("").to_string();
-            let config_topic = config.get("topic").and_then(|v| v.as_str()).unwrap_or("").to_string();
-            
+
             let template = NotificationFormTemplate {
                 notification: template_notification,
-                config_url,
-                config_token,
-                config_topic,
+                config,
                 error: None,
             };
             return Ok(Html(template.render()?));
         }
         Err(e) => {
             tracing::warn!("Failed to load notification backend {}: {}", id, e);
-            (None, Some(format!("Notification backend with ID {} not found", id)))
+            (
+                None,
+                Some(format!("Notification backend with ID {} not found", id)),
+            )
         }
     };
-    
+
     let template = NotificationFormTemplate {
         notification,
-        config_url: String::new(),
-        config_token: String::new(),
-        config_topic: String::new(),
+        config: NotificationConfig::default(),
         error,
     };
     Ok(Html(template.render()?))
@@ -655,56 +1278,110 @@ async fn notification_form_edit(
 
 async fn notification_create(
     State(state): State<AppState>,
+    user: CurrentUser,
     Form(input): Form<CreateNotificationInput>,
 ) -> Result<Html<String>, AppError> {
-    tracing::info!("notification_create called with: name={}, type={}, url={:?}, token={:?}, topic={:?}", 
-        input.name, input.backend_type, input.url, input.token, input.topic);
-    
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "notification_backend.create",
+        &input.name,
+    )
+    .await?;
+
+    tracing::info!(
+        "notification_create called with: name={}, type={}, url={:?}, token={:?}, topic={:?}",
+        input.name,
+        input.backend_type,
+        input.url,
+        input.token,
+        input.topic
+    );
+
     // Validate
     if input.name.is_empty() || input.backend_type.is_empty() {
         let template = NotificationFormTemplate {
             notification: None,
-            config_url: String::new(),
-            config_token: String::new(),
-            config_topic: String::new(),
+            config: NotificationConfig::default(),
             error: Some("Name and backend type are required".to_string()),
         };
         return Ok(Html(template.render()?));
     }
-    
+
     // Build config JSON based on backend type
-    let config_json = if input.backend_type == "gotify" {
-        serde_json::json!({
-            "url": input.url.unwrap_or_default(),
-            "token": input.token.unwrap_or_default(),
-        })
-    } else {
-        serde_json::json!({
-            "url": input.url.unwrap_or_default(),
-            "topic": input.topic.unwrap_or_default(),
-            "token": input.token.unwrap_or_default(),
-        })
+    let config_input = NotificationConfigInput {
+        url: input.url,
+        token: input.token,
+        topic: input.topic,
+        host: input.host,
+        port: input.port,
+        tls: input.tls,
+        username: input.username,
+        password: input.password,
+        from: input.from,
+        to: input.to,
+        room_id: input.room_id,
+        method: input.method,
+        headers: input.headers,
+        body_template: input.body_template,
     };
-    
+    let config_json = notification_config_json(&input.backend_type, config_input, None);
+
+    if let Err(e) = state.check_notification_backend(&input.backend_type, &input.name, &config_json)
+    {
+        return Ok(Html(format!(
+            r#"<div class="alert alert-error">✗ {}</div>"#,
+            e
+        )));
+    }
+
     // Save to database
-    tracing::info!("Creating notification backend: {} ({})", input.name, input.backend_type);
+    tracing::info!(
+        "Creating notification backend: {} ({})",
+        input.name,
+        input.backend_type
+    );
     let db = state.db().await;
-    
+
     let create_backend = svrctlrs_database::models::notification::CreateNotificationBackend {
         backend_type: input.backend_type.clone(),
         name: input.name.clone(),
         config: config_json,
         priority: input.priority.unwrap_or(5),
+        enabled: input.enabled.as_deref() == Some("on"),
     };
-    
-    match queries::notifications::create_notification_backend(db.pool(), &create_backend).await {
-        Ok(_) => {
+
+    let audit = Audit::new(
+        &state,
+        &user,
+        "notification_backend.create",
+        format!("notification backend {}", input.name),
+    );
+    match audit
+        .check(
+            queries::notifications::create_notification_backend(db.pool(), &create_backend).await,
+        )
+        .await
+    {
+        Ok(id) => {
+            let audit = match queries::notifications::get_notification_backend(db.pool(), id).await
+            {
+                Ok(backend) => audit.after(&backend),
+                Err(_) => audit,
+            };
+            audit.success().await;
+
             // Success - return updated list with success message
-            let db_notifications = queries::notifications::list_notification_backends(db.pool()).await?;
-            let notifications = db_notifications.into_iter().map(db_notification_to_ui).collect();
+            let db_notifications =
+                queries::notifications::list_notification_backends(db.pool()).await?;
+            let notifications = db_notifications
+                .into_iter()
+                .map(db_notification_to_ui)
+                .collect();
             let template = NotificationListTemplate { notifications };
             let list_html = template.render()?;
-            
+
             Ok(Html(format!(
                 r#"<div class="alert alert-success">✓ Notification backend '{}' ({}) created successfully!</div>{}"#,
                 input.name, input.backend_type, list_html
@@ -727,36 +1404,64 @@ async fn notification_create(
 
 async fn notification_update(
     State(state): State<AppState>,
+    user: CurrentUser,
     Path(id): Path<i64>,
     Form(input): Form<UpdateNotificationInput>,
 ) -> Result<Html<String>, AppError> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "notification_backend.update",
+        &format!("notification backend {}", id),
+    )
+    .await?;
+
     tracing::info!("Updating notification backend {}: {:?}", id, input);
-    
+
     // Get existing backend to determine type
     let db = state.db().await;
     let existing = queries::notifications::get_notification_backend(db.pool(), id).await?;
-    
+
     // Build config JSON based on backend type
-    let config_json = if existing.backend_type == "gotify" {
-        serde_json::json!({
-            "url": input.url.unwrap_or_default(),
-            "token": input.token.unwrap_or_default(),
-        })
-    } else {
-        serde_json::json!({
-            "url": input.url.unwrap_or_default(),
-            "topic": input.topic.unwrap_or_default(),
-            "token": input.token.unwrap_or_default(),
-        })
+    let config_input = NotificationConfigInput {
+        url: input.url,
+        token: input.token,
+        topic: input.topic,
+        host: input.host,
+        port: input.port,
+        tls: input.tls,
+        username: input.username,
+        password: input.password,
+        from: input.from,
+        to: input.to,
+        room_id: input.room_id,
+        method: input.method,
+        headers: input.headers,
+        body_template: input.body_template,
     };
-    
+    let config_json = notification_config_json(
+        &existing.backend_type,
+        config_input,
+        Some(&existing.get_config()),
+    );
+
     // Get backend name for success message
     let backend_name = if let Some(ref name) = input.name {
         name.clone()
     } else {
         existing.name.clone()
     };
-    
+
+    if let Err(e) =
+        state.check_notification_backend(&existing.backend_type, &backend_name, &config_json)
+    {
+        return Ok(Html(format!(
+            r#"<div class="alert alert-error">✗ {}</div>"#,
+            e
+        )));
+    }
+
     // Update in database
     let update_backend = svrctlrs_database::models::notification::UpdateNotificationBackend {
         name: input.name,
@@ -764,15 +1469,39 @@ async fn notification_update(
         config: Some(config_json),
         priority: input.priority,
     };
-    
-    match queries::notifications::update_notification_backend(db.pool(), id, &update_backend).await {
+
+    let audit = Audit::new(
+        &state,
+        &user,
+        "notification_backend.update",
+        format!("notification backend {} ({})", id, existing.name),
+    )
+    .before(&existing);
+    match audit
+        .check(
+            queries::notifications::update_notification_backend(db.pool(), id, &update_backend)
+                .await,
+        )
+        .await
+    {
         Ok(_) => {
+            let audit = match queries::notifications::get_notification_backend(db.pool(), id).await
+            {
+                Ok(backend) => audit.after(&backend),
+                Err(_) => audit,
+            };
+            audit.success().await;
+
             // Success - return updated list with success message
-            let db_notifications = queries::notifications::list_notification_backends(db.pool()).await?;
-            let notifications = db_notifications.into_iter().map(db_notification_to_ui).collect();
+            let db_notifications =
+                queries::notifications::list_notification_backends(db.pool()).await?;
+            let notifications = db_notifications
+                .into_iter()
+                .map(db_notification_to_ui)
+                .collect();
             let template = NotificationListTemplate { notifications };
             let list_html = template.render()?;
-            
+
             Ok(Html(format!(
                 r#"<div class="alert alert-success">✓ Notification backend '{}' updated successfully!</div>{}"#,
                 backend_name, list_html
@@ -781,9 +1510,7 @@ async fn notification_update(
         Err(e) => {
             let error_msg = e.to_string();
             if error_msg.contains("UNIQUE constraint") {
-                Ok(Html(format!(
-                    r#"<div class="alert alert-error">✗ A notification backend with that name already exists. Please use a different name.</div>"#
-                )))
+                Ok(Html(r#"<div class="alert alert-error">✗ A notification backend with that name already exists. Please use a different name.</div>"#.to_string()))
             } else {
                 Err(e.into())
             }
@@ -793,18 +1520,36 @@ async fn notification_update(
 
 async fn notification_delete(
     State(state): State<AppState>,
+    user: CurrentUser,
     Path(id): Path<i64>,
 ) -> Result<impl IntoResponse, AppError> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "notification_backend.delete",
+        &format!("notification backend {}", id),
+    )
+    .await?;
+
     // Get backend name before deleting
     let db = state.db().await;
-    let backend_name = queries::notifications::get_notification_backend(db.pool(), id)
-        .await
-        .map(|b| b.name)
-        .unwrap_or_else(|_| format!("Backend {}", id));
-    
+    let existing = queries::notifications::get_notification_backend(db.pool(), id).await?;
+    let backend_name = existing.name.clone();
+
     tracing::info!("Deleting notification backend {}", id);
-    queries::notifications::delete_notification_backend(db.pool(), id).await?;
-    
+    let audit = Audit::new(
+        &state,
+        &user,
+        "notification_backend.delete",
+        format!("notification backend {} ({})", id, backend_name),
+    )
+    .before(&existing);
+    audit
+        .check(queries::notifications::delete_notification_backend(db.pool(), id).await)
+        .await?;
+    audit.success().await;
+
     // Return success message
     Ok(Html(format!(
         r#"<div class="alert alert-success">✓ Notification backend '{}' deleted successfully!</div>"#,
@@ -812,7 +1557,137 @@ async fn notification_delete(
     )))
 }
 
-fn db_notification_to_ui(db: svrctlrs_database::models::notification::NotificationBackend) -> NotificationBackend {
+/// Backend config JSON from the form fields of its type
+///
+/// An empty email password keeps the `existing` one, so the form never has to
+/// show it.
+fn notification_config_json(
+    backend_type: &str,
+    input: NotificationConfigInput,
+    existing: Option<&serde_json::Value>,
+) -> serde_json::Value {
+    match backend_type {
+        "gotify" => serde_json::json!({
+            "url": input.url.unwrap_or_default(),
+            "token": input.token.unwrap_or_default(),
+        }),
+        "ntfy" => serde_json::json!({
+            "url": input.url.unwrap_or_default(),
+            "topic": input.topic.unwrap_or_default(),
+            "token": input.token.unwrap_or_default(),
+        }),
+        "email" => {
+            let password = input
+                .password
+                .filter(|p| !p.is_empty())
+                .or_else(|| {
+                    existing
+                        .and_then(|c| c.get("password"))
+                        .and_then(|v| v.as_str())
+                        .map(String::from)
+                })
+                .unwrap_or_default();
+            let to: Vec<String> = input
+                .to
+                .unwrap_or_default()
+                .split(',')
+                .map(|a| a.trim().to_string())
+                .filter(|a| !a.is_empty())
+                .collect();
+            serde_json::json!({
+                "host": input.host.unwrap_or_default(),
+                "port": input.port.and_then(|p| p.trim().parse::<u16>().ok()),
+                "tls": input.tls.unwrap_or_else(|| "starttls".to_string()),
+                "username": input.username.unwrap_or_default(),
+                "password": password,
+                "from": input.from.unwrap_or_default(),
+                "to": to,
+            })
+        }
+        "slack" => serde_json::json!({
+            "url": input.url.unwrap_or_default(),
+        }),
+        "discord" => serde_json::json!({
+            "url": input.url.unwrap_or_default(),
+            "username": input.username.unwrap_or_default(),
+        }),
+        "matrix" => serde_json::json!({
+            "url": input.url.unwrap_or_default(),
+            "token": input.token.unwrap_or_default(),
+            "room_id": input.room_id.unwrap_or_default(),
+        }),
+        "webhook" => {
+            let headers: serde_json::Map<String, serde_json::Value> = input
+                .headers
+                .unwrap_or_default()
+                .lines()
+                .filter_map(|line| line.split_once(':'))
+                .map(|(name, value)| {
+                    (
+                        name.trim().to_string(),
+                        serde_json::Value::String(value.trim().to_string()),
+                    )
+                })
+                .filter(|(name, _)| !name.is_empty())
+                .collect();
+            serde_json::json!({
+                "url": input.url.unwrap_or_default(),
+                "method": input.method.unwrap_or_else(|| "POST".to_string()),
+                "headers": headers,
+                "body_template": input.body_template.unwrap_or_default(),
+            })
+        }
+        _ => serde_json::json!({}),
+    }
+}
+
+/// Form fields from a backend's config JSON
+fn notification_config_to_ui(config: &serde_json::Value) -> NotificationConfig {
+    let get = |key: &str| {
+        config
+            .get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string()
+    };
+    NotificationConfig {
+        url: get("url"),
+        token: get("token"),
+        topic: get("topic"),
+        host: get("host"),
+        port: config
+            .get("port")
+            .and_then(|v| v.as_u64())
+            .map(|p| p.to_string())
+            .unwrap_or_default(),
+        tls: get("tls"),
+        username: get("username"),
+        from: get("from"),
+        to: match config.get("to") {
+            Some(serde_json::Value::Array(to)) => to
+                .iter()
+                .filter_map(|v| v.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            _ => get("to"),
+        },
+        room_id: get("room_id"),
+        method: get("method"),
+        headers: match config.get("headers") {
+            Some(serde_json::Value::Object(headers)) => headers
+                .iter()
+                .map(|(name, value)| format!("{}: {}", name, value.as_str().unwrap_or_default()))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => String::new(),
+        },
+        body_template: get("body_template"),
+    }
+}
+
+fn db_notification_to_ui(
+    db: svrctlrs_database::models::notification::NotificationBackend,
+) -> NotificationBackend {
     NotificationBackend {
         id: db.id,
         backend_type: db.backend_type,
@@ -822,37 +1697,1254 @@ fn db_notification_to_ui(db: svrctlrs_database::models::notification::Notificati
     }
 }
 
+// ============================================================================
+// API Tokens
+// ============================================================================
+
+async fn api_tokens_page(
+    State(state): State<AppState>,
+    session: Session,
+    user: CurrentUser,
+) -> Result<Html<String>, AppError> {
+    let db = state.db().await;
+    let tokens = get_api_tokens(&db, user.id).await?;
+
+    let template = ApiTokensTemplate {
+        user: get_user_from_session(&session).await,
+        tokens,
+        new_token: None,
+        error: None,
+    };
+    Ok(Html(template.render()?))
+}
+
+async fn api_token_create(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Form(input): Form<CreateApiTokenInput>,
+) -> Result<Html<String>, AppError> {
+    let (new_token, error) = match parse_api_token_input(&input) {
+        Ok((scopes, expires_at)) => {
+            let token = auth::generate_api_token();
+            let create = svrctlrs_database::models::CreateApiToken {
+                user_id: user.id,
+                name: input.name.trim().to_string(),
+                token_hash: auth::hash_api_token(&token),
+                token_prefix: auth::api_token_prefix(&token),
+                scopes,
+                expires_at,
+            };
+            let db = state.db().await;
+            let audit = Audit::new(
+                &state,
+                &user,
+                "api_token.create",
+                format!("API token {}", create.name),
+            );
+            let id = audit
+                .check(queries::api_tokens::create_api_token(db.pool(), &create).await)
+                .await?;
+            let created = queries::api_tokens::list_api_tokens(db.pool(), user.id)
+                .await?
+                .into_iter()
+                .find(|t| t.id == id);
+            let audit = match created {
+                Some(created) => audit.after(&created),
+                None => audit,
+            };
+            audit.success().await;
+            tracing::info!("{} created API token '{}'", user.username, create.name);
+            (Some(token), None)
+        }
+        Err(e) => (None, Some(e)),
+    };
+
+    let db = state.db().await;
+    let template = ApiTokenListTemplate {
+        tokens: get_api_tokens(&db, user.id).await?,
+        new_token,
+        error,
+    };
+    Ok(Html(template.render()?))
+}
+
+async fn api_token_revoke(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path(id): Path<i64>,
+) -> Result<Html<String>, AppError> {
+    let db = state.db().await;
+    // Users can only revoke their own tokens
+    let existing = queries::api_tokens::list_api_tokens(db.pool(), user.id)
+        .await?
+        .into_iter()
+        .find(|t| t.id == id);
+    let audit = Audit::new(
+        &state,
+        &user,
+        "api_token.revoke",
+        format!("API token {}", id),
+    );
+    let audit = match &existing {
+        Some(token) => audit.before(token),
+        None => audit,
+    };
+    if audit
+        .check(queries::api_tokens::delete_api_token(db.pool(), user.id, id).await)
+        .await?
+    {
+        tracing::info!("{} revoked API token {}", user.username, id);
+        audit.success().await;
+    } else {
+        audit.failure("API token not found").await;
+    }
+
+    let template = ApiTokenListTemplate {
+        tokens: get_api_tokens(&db, user.id).await?,
+        new_token: None,
+        error: None,
+    };
+    Ok(Html(template.render()?))
+}
+
+/// Validate the new token form into scopes and expiry
+fn parse_api_token_input(
+    input: &CreateApiTokenInput,
+) -> Result<
+    (
+        Vec<svrctlrs_database::models::ApiTokenScope>,
+        Option<chrono::DateTime<chrono::Utc>>,
+    ),
+    String,
+> {
+    use svrctlrs_database::models::ApiTokenScope;
+
+    if input.name.trim().is_empty() {
+        return Err("Token name is required".to_string());
+    }
+
+    let scopes: Vec<ApiTokenScope> = [
+        (ApiTokenScope::Read, &input.scope_read),
+        (ApiTokenScope::Operate, &input.scope_operate),
+        (ApiTokenScope::Admin, &input.scope_admin),
+    ]
+    .into_iter()
+    .filter(|(_, checked)| checked.is_some())
+    .map(|(scope, _)| scope)
+    .collect();
+    if scopes.is_empty() {
+        return Err("Pick at least one scope".to_string());
+    }
+
+    let expires_at = match input
+        .expires_in_days
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+    {
+        Some(days) => {
+            let days: i64 = days
+                .parse()
+                .map_err(|_| format!("Invalid expiry '{}'", days))?;
+            Some(chrono::Utc::now() + chrono::Duration::days(days))
+        }
+        None => None,
+    };
+
+    Ok((scopes, expires_at))
+}
+
+async fn get_api_tokens(
+    db: &svrctlrs_database::Database,
+    user_id: i64,
+) -> Result<Vec<ApiToken>, AppError> {
+    let tokens = queries::api_tokens::list_api_tokens(db.pool(), user_id).await?;
+
+    Ok(tokens
+        .into_iter()
+        .map(|t| ApiToken {
+            id: t.id,
+            scopes: t
+                .get_scopes()
+                .iter()
+                .map(|s| s.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            expired: t.is_expired(),
+            expires_at: t.expires_at.map(|at| at.to_rfc3339()).unwrap_or_default(),
+            last_used_at: t.last_used_at.map(|at| at.to_rfc3339()).unwrap_or_default(),
+            created_at: t.created_at.to_rfc3339(),
+            name: t.name,
+            token_prefix: t.token_prefix,
+        })
+        .collect())
+}
+
+// ============================================================================
+// Audit Log
+// ============================================================================
+
+/// Number of audit log entries shown on the audit page
+const AUDIT_ENTRIES_SHOWN: i64 = 200;
+
+/// Most audit log entries written to one export
+const AUDIT_EXPORT_LIMIT: i64 = 100_000;
+
+async fn audit_page(
+    State(state): State<AppState>,
+    session: Session,
+    user: CurrentUser,
+    Query(input): Query<AuditFilterInput>,
+) -> Result<Html<String>, AppError> {
+    authorize(&state, &user, UserRole::Admin, "audit.view", "audit log").await?;
+
+    let (entries, truncated, error) = get_audit_entries(&state, &input).await?;
+    let template = AuditTemplate {
+        user: get_user_from_session(&session).await,
+        entries,
+        truncated,
+        error,
+    };
+    Ok(Html(template.render()?))
+}
+
+async fn audit_list(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Query(input): Query<AuditFilterInput>,
+) -> Result<Html<String>, AppError> {
+    authorize(&state, &user, UserRole::Admin, "audit.view", "audit log").await?;
+
+    let (entries, truncated, error) = get_audit_entries(&state, &input).await?;
+    let template = AuditListTemplate {
+        entries,
+        truncated,
+        error,
+    };
+    Ok(Html(template.render()?))
+}
+
+/// Download the matching entries as JSON lines, newest first
+async fn audit_export(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Query(input): Query<AuditFilterInput>,
+) -> Result<axum::response::Response, AppError> {
+    authorize(&state, &user, UserRole::Admin, "audit.export", "audit log").await?;
+
+    let filter = match parse_audit_filter(&input) {
+        Ok(filter) => filter,
+        Err(e) => return Ok((StatusCode::BAD_REQUEST, e).into_response()),
+    };
+    let db = state.db().await;
+    let entries =
+        queries::audit_log::list_audit_entries(db.pool(), &filter, AUDIT_EXPORT_LIMIT).await?;
+    drop(db);
+
+    let mut body = String::new();
+    for entry in entries {
+        let changes = entry.get_changes();
+        let mut line = serde_json::to_value(&entry)?;
+        line["changes"] = changes.unwrap_or(serde_json::Value::Null);
+        body.push_str(&line.to_string());
+        body.push('\n');
+    }
+
+    let filename = format!(
+        "svrctlrs-audit-{}.jsonl",
+        chrono::Utc::now().format("%Y%m%d-%H%M%S")
+    );
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/x-ndjson".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+/// Load the entries for the audit page, and whether more match than are shown
+async fn get_audit_entries(
+    state: &AppState,
+    input: &AuditFilterInput,
+) -> Result<(Vec<AuditEntry>, bool, Option<String>), AppError> {
+    let filter = match parse_audit_filter(input) {
+        Ok(filter) => filter,
+        Err(e) => return Ok((Vec::new(), false, Some(e))),
+    };
+
+    let db = state.db().await;
+    let mut entries =
+        queries::audit_log::list_audit_entries(db.pool(), &filter, AUDIT_ENTRIES_SHOWN + 1).await?;
+    let truncated = entries.len() as i64 > AUDIT_ENTRIES_SHOWN;
+    entries.truncate(AUDIT_ENTRIES_SHOWN as usize);
+
+    let entries = entries
+        .into_iter()
+        .map(|e| AuditEntry {
+            id: e.id,
+            created_at: e.created_at.to_rfc3339(),
+            changes: e
+                .get_changes()
+                .and_then(|c| serde_json::to_string_pretty(&c).ok())
+                .unwrap_or_default(),
+            actor: e.actor,
+            action: e.action,
+            target: e.target.unwrap_or_default(),
+            outcome: e.outcome,
+            message: e.message.unwrap_or_default(),
+            source_ip: e.source_ip.unwrap_or_default(),
+        })
+        .collect();
+    Ok((entries, truncated, None))
+}
+
+fn parse_audit_filter(input: &AuditFilterInput) -> Result<AuditFilter, String> {
+    fn non_empty(value: &Option<String>) -> Option<String> {
+        value
+            .as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    }
+    fn date(value: &Option<String>) -> Result<Option<chrono::NaiveDate>, String> {
+        non_empty(value)
+            .map(|v| {
+                chrono::NaiveDate::parse_from_str(&v, "%Y-%m-%d")
+                    .map_err(|_| format!("Invalid date '{}'. Use YYYY-MM-DD.", v))
+            })
+            .transpose()
+    }
+
+    let outcome =
+        match non_empty(&input.outcome) {
+            Some(o) => Some(AuditOutcome::parse(&o).ok_or_else(|| {
+                format!("Unknown outcome '{}'. Use success, failure or denied.", o)
+            })?),
+            None => None,
+        };
+
+    Ok(AuditFilter {
+        actor: non_empty(&input.actor),
+        action: non_empty(&input.action),
+        target: non_empty(&input.target),
+        outcome,
+        since: date(&input.since)?
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .map(|t| t.and_utc()),
+        until: date(&input.until)?
+            .and_then(|d| d.and_hms_opt(23, 59, 59))
+            .map(|t| t.and_utc()),
+    })
+}
+
+// ============================================================================
+// Alerts
+// ============================================================================
+
+/// Number of recent alert events shown on the alerts page
+const ALERT_EVENTS_SHOWN: i64 = 25;
+
+async fn alerts_page(
+    State(state): State<AppState>,
+    session: Session,
+) -> Result<Html<String>, AppError> {
+    let user = get_user_from_session(&session).await;
+
+    let db = state.db().await;
+    let rules = get_alert_rules(&db).await?;
+
+    let active = queries::alerts::list_active_alerts(db.pool())
+        .await?
+        .into_iter()
+        .map(|a| ActiveAlert {
+            rule_name: a.rule_name,
+            severity: a.severity,
+            server_name: a.server_name,
+            metric_name: a.metric_name,
+            value: a.value.map(|v| format!("{:.2}", v)).unwrap_or_default(),
+            since: a
+                .firing_since
+                .or(a.pending_since)
+                .map(|dt| dt.to_rfc3339())
+                .unwrap_or_default(),
+            state: a.state,
+        })
+        .collect();
+
+    let events = queries::alerts::list_alert_events(db.pool(), None, ALERT_EVENTS_SHOWN)
+        .await?
+        .into_iter()
+        .map(|e| AlertEvent {
+            rule_name: e.rule_name,
+            server_name: e.server_name,
+            event: e.event,
+            severity: e.severity,
+            message: e.message,
+            created_at: e.created_at.to_rfc3339(),
+        })
+        .collect();
+
+    let template = AlertsTemplate {
+        user,
+        rules,
+        active,
+        events,
+    };
+    Ok(Html(template.render()?))
+}
+
+async fn alert_rule_form_new(State(state): State<AppState>) -> Result<Html<String>, AppError> {
+    let db = state.db().await;
+    let servers = queries::servers::list_servers(db.pool())
+        .await?
+        .into_iter()
+        .map(db_server_to_ui)
+        .collect();
+
+    let template = AlertRuleFormTemplate {
+        rule: None,
+        servers,
+        error: None,
+    };
+    Ok(Html(template.render()?))
+}
+
+async fn alert_rule_form_edit(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<Html<String>, AppError> {
+    let db = state.db().await;
+    let servers: Vec<Server> = queries::servers::list_servers(db.pool())
+        .await?
+        .into_iter()
+        .map(db_server_to_ui)
+        .collect();
+    let server_names = servers.iter().map(|s| (s.id, s.name.clone())).collect();
+
+    let (rule, error) = match queries::alerts::get_alert_rule(db.pool(), id).await {
+        Ok(r) => (Some(db_alert_rule_to_ui(r, &server_names)), None),
+        Err(e) => {
+            tracing::warn!("Failed to load alert rule {}: {}", id, e);
+            (None, Some(format!("Alert rule with ID {} not found", id)))
+        }
+    };
+
+    let template = AlertRuleFormTemplate {
+        rule,
+        servers,
+        error,
+    };
+    Ok(Html(template.render()?))
+}
+
+async fn alert_rule_create(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Form(input): Form<AlertRuleInput>,
+) -> Result<Html<String>, AppError> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "alert_rule.create",
+        &input.name,
+    )
+    .await?;
+
+    let rule = match parse_alert_rule_input(&input) {
+        Ok(rule) => rule,
+        Err(e) => {
+            return Ok(Html(format!(
+                r#"<div class="alert alert-error">✗ {}</div>"#,
+                e
+            )));
+        }
+    };
+
+    tracing::info!(
+        "Creating alert rule: {} ({} {} {})",
+        rule.name,
+        rule.metric_name,
+        rule.operator,
+        rule.threshold
+    );
+    let db = state.db().await;
+    let audit = Audit::new(
+        &state,
+        &user,
+        "alert_rule.create",
+        format!("alert rule {}", rule.name),
+    );
+    let id = audit
+        .check(queries::alerts::create_alert_rule(db.pool(), &rule).await)
+        .await?;
+
+    // New rules are enabled by default; honour an unchecked "Enabled" box
+    if input.enabled.is_none() {
+        let update = svrctlrs_database::models::UpdateAlertRule {
+            enabled: Some(false),
+            ..Default::default()
+        };
+        audit
+            .check(queries::alerts::update_alert_rule(db.pool(), id, &update).await)
+            .await?;
+    }
+    let audit = match queries::alerts::get_alert_rule(db.pool(), id).await {
+        Ok(created) => audit.after(&created),
+        Err(_) => audit,
+    };
+    audit.success().await;
+
+    let rules = get_alert_rules(&db).await?;
+    let list_html = AlertRuleListTemplate { rules }.render()?;
+
+    Ok(Html(format!(
+        r#"<div class="alert alert-success">✓ Alert rule '{}' created successfully!</div>{}"#,
+        input.name, list_html
+    )))
+}
+
+async fn alert_rule_update(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path(id): Path<i64>,
+    Form(input): Form<AlertRuleInput>,
+) -> Result<Html<String>, AppError> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "alert_rule.update",
+        &format!("alert rule {}", id),
+    )
+    .await?;
+
+    let rule = match parse_alert_rule_input(&input) {
+        Ok(rule) => rule,
+        Err(e) => {
+            return Ok(Html(format!(
+                r#"<div class="alert alert-error">✗ {}</div>"#,
+                e
+            )));
+        }
+    };
+
+    tracing::info!("Updating alert rule {}", id);
+    let update = svrctlrs_database::models::UpdateAlertRule {
+        name: Some(rule.name),
+        description: Some(rule.description.unwrap_or_default()),
+        enabled: Some(input.enabled.is_some()),
+        // Empty / zero values clear the filters
+        plugin_id: Some(rule.plugin_id.unwrap_or_default()),
+        metric_name: Some(rule.metric_name),
+        server_id: Some(rule.server_id.unwrap_or(0)),
+        server_tag: Some(rule.server_tag.unwrap_or_default()),
+        operator: Some(rule.operator),
+        threshold: Some(rule.threshold),
+        duration_secs: Some(rule.duration_secs),
+        severity: Some(rule.severity),
+        notify_resolved: Some(rule.notify_resolved),
+    };
+
+    let db = state.db().await;
+    let existing = queries::alerts::get_alert_rule(db.pool(), id).await?;
+    let audit = Audit::new(
+        &state,
+        &user,
+        "alert_rule.update",
+        format!("alert rule {} ({})", id, existing.name),
+    )
+    .before(&existing);
+    audit
+        .check(queries::alerts::update_alert_rule(db.pool(), id, &update).await)
+        .await?;
+    let audit = match queries::alerts::get_alert_rule(db.pool(), id).await {
+        Ok(updated) => audit.after(&updated),
+        Err(_) => audit,
+    };
+    audit.success().await;
+
+    let rules = get_alert_rules(&db).await?;
+    let list_html = AlertRuleListTemplate { rules }.render()?;
+
+    Ok(Html(format!(
+        r#"<div class="alert alert-success">✓ Alert rule '{}' updated successfully!</div>{}"#,
+        input.name, list_html
+    )))
+}
+
+async fn alert_rule_delete(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, AppError> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "alert_rule.delete",
+        &format!("alert rule {}", id),
+    )
+    .await?;
+
+    let db = state.db().await;
+    let existing = queries::alerts::get_alert_rule(db.pool(), id).await?;
+    let rule_name = existing.name.clone();
+
+    tracing::info!("Deleting alert rule {}", id);
+    let audit = Audit::new(
+        &state,
+        &user,
+        "alert_rule.delete",
+        format!("alert rule {} ({})", id, rule_name),
+    )
+    .before(&existing);
+    audit
+        .check(queries::alerts::delete_alert_rule(db.pool(), id).await)
+        .await?;
+    audit.success().await;
+
+    Ok(Html(format!(
+        r#"<div class="alert alert-success">✓ Alert rule '{}' deleted successfully!</div>"#,
+        rule_name
+    )))
+}
+
+/// Validate the alert rule form and convert it to a database input
+fn parse_alert_rule_input(
+    input: &AlertRuleInput,
+) -> Result<svrctlrs_database::models::CreateAlertRule, String> {
+    fn non_empty(value: &Option<String>) -> Option<String> {
+        value
+            .as_ref()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    }
+
+    if input.name.trim().is_empty() {
+        return Err("Rule name is required".to_string());
+    }
+
+    let threshold: f64 = input
+        .threshold
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid threshold '{}'", input.threshold))?;
+    let duration_secs: i64 = match non_empty(&input.duration_secs) {
+        Some(d) => d.parse().map_err(|_| format!("Invalid duration '{}'", d))?,
+        None => 0,
+    };
+    let server_id = match non_empty(&input.server_id) {
+        Some(id) => Some(
+            id.parse::<i64>()
+                .map_err(|_| format!("Invalid server '{}'", id))?,
+        ),
+        None => None,
+    };
+
+    crate::alerts::validate_rule(
+        &input.metric_name,
+        &input.operator,
+        threshold,
+        duration_secs,
+        &input.severity,
+    )?;
+
+    Ok(svrctlrs_database::models::CreateAlertRule {
+        name: input.name.trim().to_string(),
+        description: non_empty(&input.description),
+        plugin_id: non_empty(&input.plugin_id),
+        metric_name: input.metric_name.trim().to_string(),
+        server_id,
+        server_tag: non_empty(&input.server_tag),
+        operator: input.operator.clone(),
+        threshold,
+        duration_secs,
+        severity: input.severity.clone(),
+        notify_resolved: input.notify_resolved.is_some(),
+    })
+}
+
+async fn get_alert_rules(db: &svrctlrs_database::Database) -> Result<Vec<AlertRule>, AppError> {
+    let server_names = queries::servers::list_servers(db.pool())
+        .await?
+        .into_iter()
+        .map(|s| (s.id, s.name))
+        .collect();
+
+    Ok(queries::alerts::list_alert_rules(db.pool())
+        .await?
+        .into_iter()
+        .map(|r| db_alert_rule_to_ui(r, &server_names))
+        .collect())
+}
+
+fn db_alert_rule_to_ui(
+    db: svrctlrs_database::models::AlertRule,
+    server_names: &std::collections::HashMap<i64, String>,
+) -> AlertRule {
+    let server = db.server_id.map(|id| {
+        server_names
+            .get(&id)
+            .cloned()
+            .unwrap_or_else(|| format!("Server {}", id))
+    });
+    let scope = match (server, &db.server_tag) {
+        (Some(server), Some(tag)) => format!("{} (tag: {})", server, tag),
+        (Some(server), None) => server,
+        (None, Some(tag)) => format!("Tagged '{}'", tag),
+        (None, None) => "All".to_string(),
+    };
+
+    AlertRule {
+        id: db.id,
+        name: db.name,
+        description: db.description,
+        enabled: db.enabled,
+        plugin_id: db.plugin_id.unwrap_or_default(),
+        metric_name: db.metric_name,
+        server_id: db.server_id,
+        server_tag: db.server_tag.unwrap_or_default(),
+        scope,
+        operator: db.operator,
+        threshold: db.threshold,
+        duration_secs: db.duration_secs,
+        severity: db.severity,
+        notify_resolved: db.notify_resolved,
+    }
+}
+
+// ============================================================================
+// Workflows
+// ============================================================================
+
+/// Number of recent workflow runs shown on the workflows page
+const WORKFLOW_RUNS_SHOWN: i64 = 20;
+
+async fn workflows_page(
+    State(state): State<AppState>,
+    session: Session,
+) -> Result<Html<String>, AppError> {
+    let user = get_user_from_session(&session).await;
+
+    let db = state.db().await;
+    let workflows = get_workflows(&db).await?;
+    let runs = get_workflow_runs(&db).await?;
+
+    let template = WorkflowsTemplate {
+        user,
+        workflows,
+        runs,
+    };
+    Ok(Html(template.render()?))
+}
+
+async fn workflow_runs(State(state): State<AppState>) -> Result<Html<String>, AppError> {
+    let db = state.db().await;
+    let runs = get_workflow_runs(&db).await?;
+
+    let template = WorkflowRunsTemplate { runs };
+    Ok(Html(template.render()?))
+}
+
+async fn workflow_form_new() -> Result<Html<String>, AppError> {
+    let template = WorkflowFormTemplate {
+        workflow: None,
+        error: None,
+    };
+    Ok(Html(template.render()?))
+}
+
+async fn workflow_form_edit(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<Html<String>, AppError> {
+    let db = state.db().await;
+    let names = WorkflowNames::load(&db).await?;
+
+    let (workflow, error) = match queries::workflows::get_workflow(db.pool(), id).await {
+        Ok(w) => (Some(db_workflow_to_ui(w, &names)), None),
+        Err(e) => {
+            tracing::warn!("Failed to load workflow {}: {}", id, e);
+            (None, Some(format!("Workflow with ID {} not found", id)))
+        }
+    };
+
+    let template = WorkflowFormTemplate { workflow, error };
+    Ok(Html(template.render()?))
+}
+
+async fn workflow_create(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Form(input): Form<WorkflowInput>,
+) -> Result<Html<String>, AppError> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "workflow.create",
+        &input.name,
+    )
+    .await?;
+
+    let workflow = match parse_workflow_input(&input) {
+        Ok(workflow) => workflow,
+        Err(e) => {
+            return Ok(Html(format!(
+                r#"<div class="alert alert-error">✗ {}</div>"#,
+                e
+            )));
+        }
+    };
+
+    tracing::info!(
+        "Creating workflow: {} ({} steps)",
+        workflow.name,
+        workflow.steps.len()
+    );
+    let db = state.db().await;
+    let audit = Audit::new(
+        &state,
+        &user,
+        "workflow.create",
+        format!("workflow {}", workflow.name),
+    );
+    let id = match audit
+        .check(queries::workflows::create_workflow(db.pool(), &workflow).await)
+        .await
+    {
+        Ok(id) => id,
+        Err(e) if e.to_string().contains("UNIQUE constraint failed") => {
+            return Ok(Html(format!(
+                r#"<div class="alert alert-error">✗ A workflow with the name '{}' already exists. Please use a different name.</div>"#,
+                workflow.name
+            )));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let audit = match queries::workflows::get_workflow(db.pool(), id).await {
+        Ok(created) => audit.after(&created),
+        Err(_) => audit,
+    };
+    audit.success().await;
+    drop(db);
+
+    state.sync_workflow(id).await?;
+
+    let db = state.db().await;
+    let workflows = get_workflows(&db).await?;
+    let list_html = WorkflowListTemplate { workflows }.render()?;
+
+    Ok(Html(format!(
+        r#"<div class="alert alert-success">✓ Workflow '{}' created successfully!</div>{}"#,
+        workflow.name, list_html
+    )))
+}
+
+async fn workflow_update(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path(id): Path<i64>,
+    Form(input): Form<WorkflowInput>,
+) -> Result<Html<String>, AppError> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "workflow.update",
+        &format!("workflow {}", id),
+    )
+    .await?;
+
+    let workflow = match parse_workflow_input(&input) {
+        Ok(workflow) => workflow,
+        Err(e) => {
+            return Ok(Html(format!(
+                r#"<div class="alert alert-error">✗ {}</div>"#,
+                e
+            )));
+        }
+    };
+
+    tracing::info!("Updating workflow {}", id);
+    let update = svrctlrs_database::models::UpdateWorkflow {
+        name: Some(workflow.name.clone()),
+        // Empty values clear the description and schedule
+        description: Some(workflow.description.unwrap_or_default()),
+        enabled: Some(workflow.enabled),
+        schedule: Some(workflow.schedule.unwrap_or_default()),
+        steps: Some(workflow.steps),
+    };
+
+    let db = state.db().await;
+    let existing = queries::workflows::get_workflow(db.pool(), id).await?;
+    let audit = Audit::new(
+        &state,
+        &user,
+        "workflow.update",
+        format!("workflow {} ({})", id, existing.name),
+    )
+    .before(&existing);
+    if let Err(e) = audit
+        .check(queries::workflows::update_workflow(db.pool(), id, &update).await)
+        .await
+    {
+        if e.to_string().contains("UNIQUE constraint failed") {
+            return Ok(Html(r#"<div class="alert alert-error">✗ A workflow with that name already exists. Please use a different name.</div>"#.to_string()));
+        }
+        return Err(e.into());
+    }
+    let audit = match queries::workflows::get_workflow(db.pool(), id).await {
+        Ok(updated) => audit.after(&updated),
+        Err(_) => audit,
+    };
+    audit.success().await;
+    drop(db);
+
+    state.sync_workflow(id).await?;
+
+    let db = state.db().await;
+    let workflows = get_workflows(&db).await?;
+    let list_html = WorkflowListTemplate { workflows }.render()?;
+
+    Ok(Html(format!(
+        r#"<div class="alert alert-success">✓ Workflow '{}' updated successfully!</div>{}"#,
+        workflow.name, list_html
+    )))
+}
+
+async fn workflow_delete(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, AppError> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "workflow.delete",
+        &format!("workflow {}", id),
+    )
+    .await?;
+
+    let db = state.db().await;
+    let existing = queries::workflows::get_workflow(db.pool(), id).await?;
+    let workflow_name = existing.name.clone();
+
+    tracing::info!("Deleting workflow {}", id);
+    let audit = Audit::new(
+        &state,
+        &user,
+        "workflow.delete",
+        format!("workflow {} ({})", id, workflow_name),
+    )
+    .before(&existing);
+    audit
+        .check(queries::workflows::delete_workflow(db.pool(), id).await)
+        .await?;
+    audit.success().await;
+    drop(db);
+
+    state.sync_workflow(id).await?;
+
+    Ok(Html(format!(
+        r#"<div class="alert alert-success">✓ Workflow '{}' deleted successfully!</div>"#,
+        workflow_name
+    )))
+}
+
+async fn workflow_toggle(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path(id): Path<i64>,
+) -> Result<Html<String>, AppError> {
+    authorize(
+        &state,
+        &user,
+        UserRole::Admin,
+        "workflow.update",
+        &format!("workflow {}", id),
+    )
+    .await?;
+
+    let db = state.db().await;
+    let workflow = queries::workflows::get_workflow(db.pool(), id).await?;
+
+    tracing::info!(
+        "{} workflow {}",
+        if workflow.enabled {
+            "Disabling"
+        } else {
+            "Enabling"
+        },
+        id
+    );
+    let update = svrctlrs_database::models::UpdateWorkflow {
+        enabled: Some(!workflow.enabled),
+        ..Default::default()
+    };
+    let audit = Audit::new(
+        &state,
+        &user,
+        "workflow.update",
+        format!("workflow {} ({})", id, workflow.name),
+    )
+    .before(&workflow);
+    audit
+        .check(queries::workflows::update_workflow(db.pool(), id, &update).await)
+        .await?;
+    let audit = match queries::workflows::get_workflow(db.pool(), id).await {
+        Ok(updated) => audit.after(&updated),
+        Err(_) => audit,
+    };
+    audit.success().await;
+    drop(db);
+
+    state.sync_workflow(id).await?;
+
+    let db = state.db().await;
+    let workflows = get_workflows(&db).await?;
+    Ok(Html(WorkflowListTemplate { workflows }.render()?))
+}
+
+async fn workflow_run_now(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, AppError> {
+    authorize_on(
+        &state,
+        &user,
+        "workflow.run",
+        &format!("workflow {}", id),
+        &Targets::Unrestricted,
+    )
+    .await?;
+
+    tracing::info!("Running workflow {} manually", id);
+
+    let audit = Audit::new(&state, &user, "workflow.run", format!("workflow {}", id));
+    let run_id = crate::workflows::start_workflow(&state, id, TaskTrigger::Manual).await;
+    match audit.check(run_id.map_err(|e| format!("{:#}", e))).await {
+        Ok(run_id) => {
+            audit.success().await;
+            Ok((
+                [("HX-Trigger", "workflow-runs-changed")],
+                Html(format!(
+                    r#"<div class="alert alert-info">▶ Workflow run #{} started</div>"#,
+                    run_id
+                )),
+            )
+                .into_response())
+        }
+        Err(e) => {
+            Ok(Html(format!(r#"<div class="alert alert-error">✗ {}</div>"#, e)).into_response())
+        }
+    }
+}
+
+/// Validate the workflow form and convert it to a database input
+fn parse_workflow_input(
+    input: &WorkflowInput,
+) -> Result<svrctlrs_database::models::CreateWorkflow, String> {
+    fn non_empty(value: &Option<String>) -> Option<String> {
+        value
+            .as_ref()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    }
+
+    if input.name.trim().is_empty() {
+        return Err("Workflow name is required".to_string());
+    }
+
+    let steps: Vec<svrctlrs_database::models::WorkflowStep> =
+        serde_json::from_str(&input.steps).map_err(|e| format!("Invalid steps JSON: {}", e))?;
+    let schedule = non_empty(&input.schedule);
+    crate::workflows::validate_workflow(schedule.as_deref(), &steps)?;
+
+    Ok(svrctlrs_database::models::CreateWorkflow {
+        name: input.name.trim().to_string(),
+        description: non_empty(&input.description),
+        schedule,
+        steps,
+        enabled: input.enabled.is_some(),
+    })
+}
+
+/// Task and server names, for describing workflow steps
+struct WorkflowNames {
+    tasks: std::collections::HashMap<i64, String>,
+    servers: std::collections::HashMap<i64, String>,
+}
+
+impl WorkflowNames {
+    async fn load(db: &svrctlrs_database::Database) -> Result<Self, AppError> {
+        let tasks = queries::tasks::list_tasks(db.pool())
+            .await?
+            .into_iter()
+            .map(|t| (t.id, t.name))
+            .collect();
+        let servers = queries::servers::list_servers(db.pool())
+            .await?
+            .into_iter()
+            .map(|s| (s.id, s.name))
+            .collect();
+        Ok(Self { tasks, servers })
+    }
+}
+
+async fn get_workflows(db: &svrctlrs_database::Database) -> Result<Vec<Workflow>, AppError> {
+    let names = WorkflowNames::load(db).await?;
+
+    Ok(queries::workflows::list_workflows(db.pool())
+        .await?
+        .into_iter()
+        .map(|w| db_workflow_to_ui(w, &names))
+        .collect())
+}
+
+fn db_workflow_to_ui(db: svrctlrs_database::models::Workflow, names: &WorkflowNames) -> Workflow {
+    let steps = db.get_steps().unwrap_or_else(|e| {
+        tracing::warn!("Workflow {} has invalid steps: {}", db.id, e);
+        Vec::new()
+    });
+
+    let describe = |step: &svrctlrs_database::models::WorkflowStep| {
+        let target = match (step.task_id, step.server_id, &step.command) {
+            (Some(id), _, _) => names
+                .tasks
+                .get(&id)
+                .cloned()
+                .unwrap_or_else(|| format!("Task {}", id)),
+            (None, Some(id), Some(command)) => {
+                let server = names
+                    .servers
+                    .get(&id)
+                    .cloned()
+                    .unwrap_or_else(|| format!("Server {}", id));
+                format!("{}: {}", server, command)
+            }
+            _ => "Nothing to run".to_string(),
+        };
+        WorkflowStep {
+            id: step.id.clone(),
+            target,
+            condition: step.condition.clone().unwrap_or_default(),
+            depends_on: step.depends_on.join(", "),
+        }
+    };
+    // Fall back to one step per row if the dependencies are broken
+    let levels = match svrctlrs_database::models::workflow_levels(&steps) {
+        Ok(levels) => levels
+            .into_iter()
+            .map(|level| level.into_iter().map(describe).collect())
+            .collect(),
+        Err(_) => steps.iter().map(|step| vec![describe(step)]).collect(),
+    };
+
+    Workflow {
+        id: db.id,
+        name: db.name,
+        description: db.description,
+        enabled: db.enabled,
+        schedule: db.schedule.unwrap_or_default(),
+        steps_json: serde_json::to_string_pretty(&steps).unwrap_or_else(|_| db.steps.clone()),
+        levels,
+        last_run_at: db.last_run_at.map(|dt| dt.to_rfc3339()).unwrap_or_default(),
+        next_run_at: db.next_run_at.map(|dt| dt.to_rfc3339()).unwrap_or_default(),
+    }
+}
+
+async fn get_workflow_runs(db: &svrctlrs_database::Database) -> Result<Vec<WorkflowRun>, AppError> {
+    let runs = queries::workflows::list_workflow_runs(db.pool(), None, WORKFLOW_RUNS_SHOWN).await?;
+
+    let mut ui_runs = Vec::with_capacity(runs.len());
+    for run in runs {
+        let steps = queries::workflows::list_workflow_step_runs(db.pool(), run.id)
+            .await?
+            .into_iter()
+            .map(|step| WorkflowStepRun {
+                step_id: step.step_id,
+                status: step.status,
+                message: step.message.unwrap_or_default(),
+                duration: step.duration_ms.map(format_elapsed).unwrap_or_default(),
+            })
+            .collect();
+
+        ui_runs.push(WorkflowRun {
+            id: run.id,
+            workflow_name: run.workflow_name,
+            status: run.status,
+            triggered_by: run.triggered_by,
+            started_at: run.started_at.to_rfc3339(),
+            finished_at: run
+                .finished_at
+                .map(|dt| dt.to_rfc3339())
+                .unwrap_or_default(),
+            error_message: run.error_message.unwrap_or_default(),
+            steps,
+        });
+    }
+
+    Ok(ui_runs)
+}
+
 // ============================================================================
 // Auth
 // ============================================================================
 
-async fn login_page() -> Result<Html<String>, AppError> {
+async fn login_page(session: Session) -> Result<axum::response::Response, AppError> {
+    // Already logged in
+    if auth::current_user(&session).await.is_some() {
+        return Ok(Redirect::to("/").into_response());
+    }
+
     let template = LoginTemplate { error: None };
-    Ok(Html(template.render()?))
+    Ok(Html(template.render()?).into_response())
 }
 
 async fn login(
-    State(_state): State<AppState>,
+    State(state): State<AppState>,
+    session: Session,
     Form(creds): Form<LoginForm>,
-) -> Result<impl IntoResponse, AppError> {
-    // TODO: Implement authentication
-    tracing::info!("Login attempt: {}", creds.username);
-    
-    // For now, just redirect to dashboard
-    Ok(Redirect::to("/"))
+) -> Result<axum::response::Response, AppError> {
+    let db = state.db().await;
+    if auth::login(&db, &session, &creds.username, &creds.password)
+        .await?
+        .is_some()
+    {
+        return Ok(Redirect::to("/").into_response());
+    }
+
+    tracing::warn!(username = %creds.username, "Failed login attempt");
+    let template = LoginTemplate {
+        error: Some("Invalid username or password".to_string()),
+    };
+    Ok((
+        axum::http::StatusCode::UNAUTHORIZED,
+        Html(template.render()?),
+    )
+        .into_response())
 }
 
-async fn logout() -> Result<impl IntoResponse, AppError> {
-    // TODO: Clear session
-    Ok(Redirect::to("/auth/login"))
+async fn logout(session: Session) -> Result<impl IntoResponse, AppError> {
+    auth::logout(&session).await?;
+    Ok(Redirect::to(auth::LOGIN_PATH))
 }
 
 // ============================================================================
 // Error Handling
 // ============================================================================
 
-async fn not_found() -> Result<Html<String>, AppError> {
-    let user = get_user_from_session().await;
+async fn not_found(session: Session) -> Result<Html<String>, AppError> {
+    let user = get_user_from_session(&session).await;
     let template = NotFoundTemplate { user };
     Ok(Html(template.render()?))
 }
@@ -862,6 +2954,19 @@ pub struct AppError(anyhow::Error);
 
 impl IntoResponse for AppError {
     fn into_response(self) -> axum::response::Response {
+        // Show permission errors as a toast instead of replacing the target
+        if let Some(forbidden) = self.0.downcast_ref::<rbac::Forbidden>() {
+            return (
+                axum::http::StatusCode::FORBIDDEN,
+                [("HX-Retarget", "#toast-container"), ("HX-Reswap", "innerHTML")],
+                Html(format!(
+                    r#"<div class="alert alert-error" x-data x-init="setTimeout(() => $el.remove(), 5000)">✗ {}</div>"#,
+                    forbidden
+                )),
+            )
+                .into_response();
+        }
+
         tracing::error!("Application error: {:?}", self.0);
         (
             axum::http::StatusCode::INTERNAL_SERVER_ERROR,
@@ -879,4 +2984,3 @@ where
         Self(err.into())
     }
 }
-
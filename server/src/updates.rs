@@ -0,0 +1,577 @@
+//! Package update bookkeeping and the update approval workflow
+//!
+//! Every `updates_check` replaces the pending updates stored per server.
+//! When the updates plugin's `approval_required` setting is on, each check
+//! also opens an update batch per server with the packages it found and
+//! sends a notification with Approve / Defer buttons calling back into
+//! svrctlrs. `updates_apply` then only runs on servers whose batch was
+//! approved, upgrades only the batch's packages, and records whether
+//! applying it succeeded.
+//!
+//! Every `updates_apply` run records the package transaction it made on each
+//! server, which can be rolled back from the UI or the API.
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use rand::{distributions::Alphanumeric, Rng};
+use serde_json::Value as JsonValue;
+use tracing::{debug, error, info, warn};
+
+use svrctlrs_core::{NotificationAction, NotificationMessage, PluginResult, Server as CoreServer};
+use svrctlrs_database::{
+    models::{NewPendingUpdate, NewUpdateTransaction},
+    queries, Database,
+};
+
+use crate::state::AppState;
+#[cfg(feature = "plugin-updates")]
+use crate::{audit::Audit, rbac::CurrentUser};
+
+/// Notification service name used for approval requests (e.g. `UPDATES_NTFY_TOPIC`)
+pub const UPDATES_SERVICE: &str = "updates";
+
+/// Server name the updates plugin uses when no servers are registered
+const LOCAL_SERVER_NAME: &str = "localhost";
+
+/// Packages listed in an approval request before the rest are summarised
+const MAX_LISTED_PACKAGES: usize = 10;
+
+/// Whether updates must be approved before `updates_apply` installs them
+pub async fn approval_required(db: &Database) -> Result<bool> {
+    let plugin = queries::plugins::get_plugin(db.pool(), "updates")
+        .await
+        .context("Failed to load updates plugin settings")?;
+
+    Ok(plugin
+        .get_config()
+        .get("approval_required")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false))
+}
+
+/// Per-server entries of an updates plugin result (`data.servers`)
+fn server_entries(result: &PluginResult) -> impl Iterator<Item = &JsonValue> {
+    result
+        .data
+        .as_ref()
+        .and_then(|d| d.get("servers"))
+        .and_then(|s| s.as_array())
+        .into_iter()
+        .flatten()
+}
+
+/// Record the package updates found by an `updates_check` run
+///
+/// Each server that was checked successfully has its pending updates
+/// replaced; servers whose check failed keep the previous list. With
+/// approval required, an update batch is opened for every server whose
+/// updates changed, and approval is requested for it.
+pub async fn record_check(
+    state: &AppState,
+    db: &Database,
+    server_ids: &HashMap<String, i64>,
+    result: &PluginResult,
+    approval_required: bool,
+) {
+    for server in server_entries(result) {
+        if !flag(server, "success") {
+            continue;
+        }
+        let Some(name) = server.get("server").and_then(|n| n.as_str()) else {
+            continue;
+        };
+        let packages = server.get("packages").cloned().unwrap_or_default();
+        let updates: Vec<NewPendingUpdate> = match serde_json::from_value(packages) {
+            Ok(updates) => updates,
+            Err(e) => {
+                warn!("Ignoring malformed package updates for {}: {}", name, e);
+                continue;
+            }
+        };
+        let server_id = server_ids.get(name).copied();
+
+        match queries::updates::replace_pending_updates(db.pool(), server_id, name, &updates).await
+        {
+            Ok(count) => debug!("Stored {} pending updates for {}", count, name),
+            Err(e) => error!("Failed to store pending updates for {}: {}", name, e),
+        }
+
+        if !approval_required {
+            continue;
+        }
+
+        let token = generate_token();
+        match queries::updates::record_update_batch(db.pool(), server_id, name, &updates, &token)
+            .await
+        {
+            Ok(Some(batch_id)) => {
+                info!("Update batch {} for {} awaits approval", batch_id, name);
+                request_approval(state, batch_id, name, &updates, &token).await;
+            }
+            Ok(None) => debug!("Update batch for {} unchanged", name),
+            Err(e) => error!("Failed to record update batch for {}: {}", name, e),
+        }
+    }
+}
+
+/// Package name to the version approved for it, when known
+pub type ApprovedPackages = HashMap<String, Option<String>>;
+
+/// Limit an `updates_apply` run to the servers with an approved update batch
+///
+/// `servers` are the servers the run would otherwise target; an empty list
+/// stands for the updates plugin's local fallback. Batches are matched to
+/// servers by ID (`server_ids` maps names to IDs), so renaming a server
+/// keeps its approval. Returns the approved servers with the packages and
+/// versions approved for each, keyed by server name, so updates found after
+/// the approval are not applied with them.
+pub async fn approved_updates(
+    db: &Database,
+    servers: Vec<CoreServer>,
+    server_ids: &HashMap<String, i64>,
+) -> Result<(Vec<CoreServer>, HashMap<String, ApprovedPackages>)> {
+    let mut by_server: HashMap<Option<i64>, ApprovedPackages> = HashMap::new();
+    for batch in queries::updates::list_update_batches(db.pool(), None, Some("approved"), i64::MAX)
+        .await
+        .context("Failed to load approved update batches")?
+    {
+        // Batches without a server ID belong to the local fallback
+        if batch.server_id.is_none() && batch.server_name != LOCAL_SERVER_NAME {
+            continue;
+        }
+        by_server.entry(batch.server_id).or_default().extend(
+            batch
+                .get_packages()
+                .into_iter()
+                .map(|p| (p.name, p.new_version)),
+        );
+    }
+
+    let candidates: Vec<(CoreServer, Option<i64>)> = if servers.is_empty() {
+        vec![(CoreServer::local(LOCAL_SERVER_NAME), None)]
+    } else {
+        servers
+            .into_iter()
+            .filter_map(|server| {
+                let id = server_ids.get(&server.name).copied()?;
+                Some((server, Some(id)))
+            })
+            .collect()
+    };
+
+    let mut approved_servers = Vec::new();
+    let mut approved = HashMap::new();
+    for (server, id) in candidates {
+        if let Some(packages) = by_server.remove(&id) {
+            approved.insert(server.name.clone(), packages);
+            approved_servers.push(server);
+        }
+    }
+
+    Ok((approved_servers, approved))
+}
+
+/// Record the package transactions an `updates_apply` run made and, with
+/// approval required, mark the approved batches of the servers it covered
+/// as applied or failed
+pub async fn record_apply(
+    db: &Database,
+    server_ids: &HashMap<String, i64>,
+    result: &PluginResult,
+    approval_required: bool,
+) {
+    for server in server_entries(result) {
+        let Some(name) = server.get("server").and_then(|n| n.as_str()) else {
+            continue;
+        };
+
+        if let Some(transaction) = server.get("transaction").filter(|t| !t.is_null()) {
+            match serde_json::from_value::<NewUpdateTransaction>(transaction.clone()) {
+                Ok(mut transaction) => {
+                    transaction.post_hook_failed = flag(server, "post_hook_failed");
+                    transaction.rollback_hook_ran = flag(server, "rollback_hook_ran");
+                    let server_id = server_ids.get(name).copied();
+                    match queries::updates::record_update_transaction(
+                        db.pool(),
+                        server_id,
+                        name,
+                        &transaction,
+                    )
+                    .await
+                    {
+                        Ok(id) => info!("Recorded update transaction {} for {}", id, name),
+                        Err(e) => error!("Failed to record update transaction for {}: {}", name, e),
+                    }
+                }
+                Err(e) => warn!("Ignoring malformed update transaction for {}: {}", name, e),
+            }
+        }
+
+        if !approval_required {
+            continue;
+        }
+        let success = flag(server, "success");
+        let mut summary = server
+            .get("summary")
+            .and_then(|s| s.as_str())
+            .unwrap_or_default()
+            .to_string();
+        let errors: Vec<&str> = server
+            .get("errors")
+            .and_then(|e| e.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|e| e.as_str())
+            .collect();
+        if !errors.is_empty() {
+            summary = format!("{}: {}", summary, errors.join("; "));
+        }
+
+        let server_id = server_ids.get(name).copied();
+        match queries::updates::finish_update_batches(db.pool(), server_id, name, success, &summary)
+            .await
+        {
+            Ok(count) => debug!("Closed {} update batches for {}", count, name),
+            Err(e) => error!("Failed to close update batches for {}: {}", name, e),
+        }
+    }
+}
+
+/// Boolean field of a per-server entry, `false` when missing
+fn flag(server: &JsonValue, key: &str) -> bool {
+    server.get(key).and_then(|v| v.as_bool()) == Some(true)
+}
+
+/// Random secret that authorizes a batch's notification actions
+fn generate_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect()
+}
+
+/// Send the notification asking for a batch to be approved or deferred
+async fn request_approval(
+    state: &AppState,
+    batch_id: i64,
+    server_name: &str,
+    updates: &[NewPendingUpdate],
+    token: &str,
+) {
+    let security = updates.iter().filter(|u| u.is_security).count();
+
+    let mut body = format!("## {} updates on {}\n\n", updates.len(), server_name);
+    if security > 0 {
+        body.push_str(&format!("🔒 **Security Updates**: {}\n\n", security));
+    }
+    for (i, update) in updates.iter().take(MAX_LISTED_PACKAGES).enumerate() {
+        body.push_str(&format!("{}. {}", i + 1, update.name));
+        if let Some(version) = &update.new_version {
+            match &update.current_version {
+                Some(current) => body.push_str(&format!(" {} -> {}", current, version)),
+                None => body.push_str(&format!(" -> {}", version)),
+            }
+        }
+        if update.is_security {
+            body.push_str(" (security)");
+        }
+        body.push('\n');
+    }
+    if updates.len() > MAX_LISTED_PACKAGES {
+        body.push_str(&format!(
+            "...and {} more\n",
+            updates.len() - MAX_LISTED_PACKAGES
+        ));
+    }
+
+    let actions = match &state.config.public_url {
+        Some(base) => {
+            let url = |decision: &str| {
+                format!(
+                    "{}/api/webhooks/updates/batches/{}/{}?token={}",
+                    base, batch_id, decision, token
+                )
+            };
+            vec![
+                NotificationAction::http_post("Approve", url("approve")),
+                NotificationAction::http_post("Defer", url("defer")),
+            ]
+        }
+        None => {
+            warn!("PUBLIC_URL is not set, sending approval request without action buttons");
+            body.push_str(&format!(
+                "\nApprove or defer with POST /api/v1/updates/batches/{}/approve or /defer\n",
+                batch_id
+            ));
+            Vec::new()
+        }
+    };
+
+    let message = NotificationMessage {
+        title: format!(
+            "Approval needed: {} updates on {}",
+            updates.len(),
+            server_name
+        ),
+        body,
+        priority: if security > 0 { 4 } else { 3 },
+        actions,
+    };
+
+    let manager = state.notification_manager().await;
+    if let Err(e) = manager.send_for_service(UPDATES_SERVICE, &message).await {
+        warn!(
+            "Failed to send approval request for update batch {}: {}",
+            batch_id, e
+        );
+    }
+}
+
+/// Outcome of asking for an update transaction to be rolled back
+#[cfg(feature = "plugin-updates")]
+pub enum RollbackStart {
+    Started,
+    /// Why the transaction cannot be rolled back (already rolled back, nothing recorded to undo)
+    Refused(String),
+}
+
+/// Start rolling back an update transaction's packages in the background
+///
+/// The request is audited as `actor`'s; the outcome is recorded on the
+/// transaction and sent as a notification.
+#[cfg(feature = "plugin-updates")]
+pub async fn start_rollback(
+    state: &AppState,
+    transaction: svrctlrs_database::models::UpdateTransaction,
+    actor: &CurrentUser,
+) -> Result<RollbackStart> {
+    let audit = Audit::new(
+        state,
+        actor,
+        "update_transaction.rollback",
+        format!(
+            "update transaction {} on {}",
+            transaction.id, transaction.server_name
+        ),
+    );
+    let started = audit
+        .check(begin_rollback(state, transaction, &actor.username).await)
+        .await?;
+    match &started {
+        RollbackStart::Started => audit.success().await,
+        RollbackStart::Refused(reason) => audit.failure(reason).await,
+    }
+    Ok(started)
+}
+
+#[cfg(feature = "plugin-updates")]
+async fn begin_rollback(
+    state: &AppState,
+    transaction: svrctlrs_database::models::UpdateTransaction,
+    rolled_back_by: &str,
+) -> Result<RollbackStart> {
+    use svrctlrs_plugin_updates::{rollback_transaction, Transaction, TransactionPackage};
+
+    if !transaction.can_roll_back() {
+        return Ok(RollbackStart::Refused(format!(
+            "Transaction is {}",
+            transaction.status.replace('_', " ")
+        )));
+    }
+
+    let packages = transaction
+        .get_packages()
+        .into_iter()
+        .map(|p| TransactionPackage {
+            name: p.name,
+            from_version: p.from_version,
+            to_version: p.to_version,
+        })
+        .collect();
+    let plugin_transaction = Transaction {
+        package_manager: transaction.package_manager.clone(),
+        transaction_id: transaction.transaction_id.clone(),
+        packages,
+    };
+    if plugin_transaction.rollback_script().is_none() {
+        return Ok(RollbackStart::Refused(format!(
+            "{} transaction {} cannot be rolled back",
+            transaction.package_manager, transaction.transaction_id
+        )));
+    }
+
+    let db = state.db().await;
+    let server = match transaction.server_id {
+        Some(server_id) => {
+            let server = queries::servers::get_server(db.pool(), server_id)
+                .await
+                .context("Failed to load the transaction's server")?;
+            crate::executor::core_server(&server)
+        }
+        None => CoreServer::local(&transaction.server_name),
+    };
+
+    if !queries::updates::start_transaction_rollback(db.pool(), transaction.id, rolled_back_by)
+        .await
+        .context("Failed to start rollback")?
+    {
+        return Ok(RollbackStart::Refused(
+            "A rollback is already running".to_string(),
+        ));
+    }
+    drop(db);
+
+    info!(
+        "Rolling back {} transaction {} on {} for {}",
+        transaction.package_manager, transaction.transaction_id, server.name, rolled_back_by
+    );
+    let state = state.clone();
+    let requested_by = rolled_back_by.to_string();
+    tokio::spawn(async move {
+        let (success, output) = match rollback_transaction(
+            &server,
+            server.ssh_key_path.as_deref(),
+            &plugin_transaction,
+        )
+        .await
+        {
+            Ok(output) => (true, output),
+            Err(e) => {
+                error!(
+                    "Failed to roll back update transaction {}: {}",
+                    transaction.id, e
+                );
+                (false, e.to_string())
+            }
+        };
+
+        let db = state.db().await;
+        if let Err(e) = queries::updates::finish_transaction_rollback(
+            db.pool(),
+            transaction.id,
+            success,
+            output.trim(),
+        )
+        .await
+        {
+            error!(
+                "Failed to record rollback of update transaction {}: {}",
+                transaction.id, e
+            );
+        }
+        drop(db);
+
+        let message = NotificationMessage {
+            title: if success {
+                format!("↩️ Rolled back updates on {}", server.name)
+            } else {
+                format!("❌ Rollback failed on {}", server.name)
+            },
+            body: format!(
+                "{} transaction {} (requested by {})\n\n{}",
+                plugin_transaction.package_manager,
+                plugin_transaction.transaction_id,
+                requested_by,
+                if success {
+                    "Previous package versions reinstalled"
+                } else {
+                    output.trim()
+                }
+            ),
+            priority: if success { 3 } else { 5 },
+            actions: vec![],
+        };
+        let manager = state.notification_manager().await;
+        if let Err(e) = manager.send_for_service(UPDATES_SERVICE, &message).await {
+            warn!("Failed to send rollback notification: {}", e);
+        }
+    });
+
+    Ok(RollbackStart::Started)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use svrctlrs_database::models::UpdateBatchDecision;
+
+    async fn test_db(name: &str) -> Database {
+        let path =
+            std::env::temp_dir().join(format!("svrctlrs-{}-{}.db", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let db = Database::new(&format!("sqlite:{}", path.display()))
+            .await
+            .unwrap();
+        db.migrate().await.unwrap();
+        db
+    }
+
+    fn update(name: &str, new_version: &str) -> NewPendingUpdate {
+        NewPendingUpdate {
+            name: name.to_string(),
+            new_version: Some(new_version.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_approved_updates_follow_server_ids() {
+        let db = test_db("approved-updates").await;
+        let pool = db.pool();
+        let mut server_ids = HashMap::new();
+        for name in ["web1", "web2"] {
+            // Optional fields default, as in API requests
+            let server =
+                serde_json::from_value(serde_json::json!({ "name": name, "host": "10.0.0.1" }))
+                    .unwrap();
+            let id = queries::servers::create_server(pool, &server)
+                .await
+                .unwrap();
+            server_ids.insert(name.to_string(), id);
+        }
+
+        let batch = queries::updates::record_update_batch(
+            pool,
+            Some(server_ids["web1"]),
+            "web1",
+            &[update("openssl", "3.0.13")],
+            "token",
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        queries::updates::decide_update_batch(
+            pool,
+            batch,
+            UpdateBatchDecision::Approve,
+            "alice",
+            "api",
+        )
+        .await
+        .unwrap();
+
+        // web1 was renamed after the approval
+        let id = server_ids.remove("web1").unwrap();
+        server_ids.insert("web-1".to_string(), id);
+        let servers = vec![
+            CoreServer::remote("web-1", "root@10.0.0.1"),
+            CoreServer::remote("web2", "root@10.0.0.2"),
+        ];
+
+        let (servers, approved) = approved_updates(&db, servers, &server_ids).await.unwrap();
+        assert_eq!(servers.len(), 1);
+        assert_eq!(servers[0].name, "web-1");
+        assert_eq!(
+            approved["web-1"],
+            ApprovedPackages::from([("openssl".to_string(), Some("3.0.13".to_string()))])
+        );
+
+        // The local fallback only picks up local batches
+        let (servers, _) = approved_updates(&db, Vec::new(), &server_ids)
+            .await
+            .unwrap();
+        assert!(servers.is_empty());
+    }
+}
@@ -0,0 +1,295 @@
+//! Workflow engine
+//!
+//! Runs a workflow's steps as a DAG: once every step a step depends on has
+//! finished, its condition decides whether it runs or is skipped. Steps whose
+//! dependencies are done run concurrently. Each run and step outcome is
+//! recorded in the workflow run history.
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::time::Instant;
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+use svrctlrs_core::PluginResult;
+use svrctlrs_database::{
+    models::{
+        validate_workflow_steps, StepCondition, StepStatus, WorkflowStep, WorkflowStepOutcome,
+    },
+    queries,
+};
+
+use crate::{executions::TaskTrigger, state::AppState};
+
+/// Check a workflow definition before it is saved
+///
+/// The schedule, if any, must be a valid cron expression and the steps must
+/// form a valid DAG.
+pub fn validate_workflow(schedule: Option<&str>, steps: &[WorkflowStep]) -> Result<(), String> {
+    use std::str::FromStr;
+
+    if let Some(schedule) = schedule.filter(|s| !s.trim().is_empty()) {
+        cron::Schedule::from_str(schedule)
+            .map_err(|e| format!("Invalid cron expression '{}': {}", schedule, e))?;
+    }
+    validate_workflow_steps(steps)
+}
+
+/// Start a workflow run in the background, returning the run ID
+///
+/// Fails if the workflow does not exist, is disabled, or its steps are invalid.
+pub async fn start_workflow(
+    state: &AppState,
+    workflow_id: i64,
+    trigger: TaskTrigger,
+) -> Result<i64> {
+    let (run_id, steps) = prepare_run(state, workflow_id, trigger).await?;
+
+    let run_state = state.clone();
+    tokio::spawn(async move {
+        drive_run(&run_state, run_id, &steps).await;
+    });
+
+    Ok(run_id)
+}
+
+/// Run a workflow to completion, returning whether every step that ran succeeded
+pub async fn run_workflow(
+    state: &AppState,
+    workflow_id: i64,
+    trigger: TaskTrigger,
+) -> Result<bool> {
+    let (run_id, steps) = prepare_run(state, workflow_id, trigger).await?;
+    Ok(drive_run(state, run_id, &steps).await == StepStatus::Success)
+}
+
+/// Load and check a workflow, then record a new run with every step pending
+async fn prepare_run(
+    state: &AppState,
+    workflow_id: i64,
+    trigger: TaskTrigger,
+) -> Result<(i64, Vec<WorkflowStep>)> {
+    let db = state.db().await;
+    let workflow = queries::workflows::get_workflow(db.pool(), workflow_id)
+        .await
+        .context("Failed to load workflow")?;
+
+    if !workflow.enabled {
+        anyhow::bail!("Workflow '{}' is disabled", workflow.name);
+    }
+
+    let steps = workflow.get_steps().map_err(|e| anyhow::anyhow!(e))?;
+    validate_workflow_steps(&steps).map_err(|e| anyhow::anyhow!("Invalid workflow: {}", e))?;
+
+    let step_ids: Vec<String> = steps.iter().map(|s| s.id.clone()).collect();
+    let run_id = queries::workflows::create_workflow_run(
+        db.pool(),
+        workflow_id,
+        trigger.as_str(),
+        &step_ids,
+    )
+    .await
+    .context("Failed to record workflow run")?;
+
+    info!(
+        "Starting workflow {} ({}) run {} ({})",
+        workflow_id,
+        workflow.name,
+        run_id,
+        trigger.as_str()
+    );
+
+    Ok((run_id, steps))
+}
+
+/// Execute the steps of a run in dependency order and record the outcome
+async fn drive_run(state: &AppState, run_id: i64, steps: &[WorkflowStep]) -> StepStatus {
+    let mut statuses: HashMap<String, StepStatus> = steps
+        .iter()
+        .map(|s| (s.id.clone(), StepStatus::Pending))
+        .collect();
+    let mut results: HashMap<String, PluginResult> = HashMap::new();
+    let mut running: JoinSet<WorkflowStepOutcome> = JoinSet::new();
+    let mut running_ids: HashMap<tokio::task::Id, String> = HashMap::new();
+
+    loop {
+        // Start (or skip) every pending step whose dependencies have finished;
+        // skipping a step can make others ready, so repeat until nothing changes
+        let mut progressed = true;
+        while progressed {
+            progressed = false;
+            for step in steps {
+                if statuses[&step.id] != StepStatus::Pending {
+                    continue;
+                }
+                if !step.depends_on.iter().all(|d| statuses[d].is_finished()) {
+                    continue;
+                }
+                progressed = true;
+
+                let run = StepCondition::parse(step.condition.as_deref())
+                    .map(|condition| condition.should_run(&step.depends_on, &statuses, &results))
+                    .unwrap_or(false);
+
+                if run {
+                    statuses.insert(step.id.clone(), StepStatus::Running);
+                    record_step_started(state, run_id, &step.id).await;
+                    let step_state = state.clone();
+                    let step_def = step.clone();
+                    let handle =
+                        running.spawn(async move { run_step(&step_state, &step_def).await });
+                    running_ids.insert(handle.id(), step.id.clone());
+                } else {
+                    statuses.insert(step.id.clone(), StepStatus::Skipped);
+                    let outcome = WorkflowStepOutcome {
+                        status: StepStatus::Skipped,
+                        message: Some(format!(
+                            "Condition '{}' not met",
+                            step.condition.as_deref().unwrap_or("success")
+                        )),
+                        duration_ms: None,
+                        result: None,
+                    };
+                    record_step_outcome(state, run_id, &step.id, &outcome).await;
+                }
+            }
+        }
+
+        let Some(joined) = running.join_next_with_id().await else {
+            break;
+        };
+        let (step_id, outcome) = match joined {
+            Ok((task_id, outcome)) => (running_ids.remove(&task_id).unwrap_or_default(), outcome),
+            Err(e) => {
+                let step_id = running_ids.remove(&e.id()).unwrap_or_default();
+                let outcome = WorkflowStepOutcome {
+                    status: StepStatus::Failed,
+                    message: Some(format!("Step panicked: {}", e)),
+                    duration_ms: None,
+                    result: None,
+                };
+                (step_id, outcome)
+            }
+        };
+
+        info!(
+            "Workflow run {} step '{}' finished: {}",
+            run_id,
+            step_id,
+            outcome.status.as_str()
+        );
+        statuses.insert(step_id.clone(), outcome.status);
+        if let Some(result) = &outcome.result {
+            results.insert(step_id.clone(), result.clone());
+        }
+        record_step_outcome(state, run_id, &step_id, &outcome).await;
+    }
+
+    // Report failures in definition order
+    let failed: Vec<&str> = steps
+        .iter()
+        .filter(|s| statuses[&s.id] == StepStatus::Failed)
+        .map(|s| s.id.as_str())
+        .collect();
+    let (status, error_message) = if failed.is_empty() {
+        info!("Workflow run {} succeeded", run_id);
+        (StepStatus::Success, None)
+    } else {
+        let message = format!("Step(s) failed: {}", failed.join(", "));
+        warn!("Workflow run {} failed: {}", run_id, message);
+        (StepStatus::Failed, Some(message))
+    };
+
+    let db = state.db().await;
+    if let Err(e) =
+        queries::workflows::finish_workflow_run(db.pool(), run_id, status, error_message.as_deref())
+            .await
+    {
+        error!("Failed to record workflow run outcome: {}", e);
+    }
+
+    status
+}
+
+/// Run one step: a task through the executor, or an ad-hoc command over SSH
+async fn run_step(state: &AppState, step: &WorkflowStep) -> WorkflowStepOutcome {
+    let started = Instant::now();
+
+    let (status, message, result) = match (step.task_id, step.server_id, &step.command) {
+        (Some(task_id), _, _) => {
+            match crate::executor::execute_task(state, task_id, TaskTrigger::Workflow).await {
+                Ok(execution) => {
+                    let message = match execution.error {
+                        Some(error) => error,
+                        None => execution.output,
+                    };
+                    (
+                        succeeded(execution.success),
+                        message,
+                        execution.plugin_result,
+                    )
+                }
+                Err(e) => (StepStatus::Failed, format!("{:#}", e), None),
+            }
+        }
+        (None, Some(server_id), Some(command)) => {
+            match crate::executor::run_server_command(
+                state,
+                server_id,
+                command,
+                &CancellationToken::new(),
+            )
+            .await
+            {
+                Ok(result) => (
+                    succeeded(result.success),
+                    result.message.clone(),
+                    Some(result),
+                ),
+                Err(e) => (StepStatus::Failed, format!("{:#}", e), None),
+            }
+        }
+        _ => (
+            StepStatus::Failed,
+            "Step has nothing to run".to_string(),
+            None,
+        ),
+    };
+
+    WorkflowStepOutcome {
+        status,
+        message: Some(message),
+        duration_ms: Some(started.elapsed().as_millis() as i64),
+        result,
+    }
+}
+
+fn succeeded(success: bool) -> StepStatus {
+    if success {
+        StepStatus::Success
+    } else {
+        StepStatus::Failed
+    }
+}
+
+async fn record_step_started(state: &AppState, run_id: i64, step_id: &str) {
+    let db = state.db().await;
+    if let Err(e) = queries::workflows::start_workflow_step(db.pool(), run_id, step_id).await {
+        error!("Failed to record workflow step start: {}", e);
+    }
+}
+
+async fn record_step_outcome(
+    state: &AppState,
+    run_id: i64,
+    step_id: &str,
+    outcome: &WorkflowStepOutcome,
+) {
+    let db = state.db().await;
+    if let Err(e) =
+        queries::workflows::finish_workflow_step(db.pool(), run_id, step_id, outcome).await
+    {
+        error!("Failed to record workflow step outcome: {}", e);
+    }
+}